@@ -0,0 +1,105 @@
+//! Tolerant comparisons of floating point values.
+//!
+//! Distances and similarities are computed with floating point arithmetic, so two
+//! mathematically equal results may differ in their last bits. Compare them with
+//! [`close_to`] or [`assert_close`] instead of `==`:
+//!
+//! ```
+//! use aabel_rs::approx::{assert_close, close_to, DEFAULT_EPSILON};
+//!
+//! let x = 0.1 + 0.2;
+//! assert_ne!(x, 0.3);
+//! assert!(close_to(x, 0.3, DEFAULT_EPSILON));
+//! assert_close(x, 0.3, DEFAULT_EPSILON);
+//! ```
+
+/// The tolerance used by the library for its own comparisons.
+pub const DEFAULT_EPSILON: f64 = 1e-6;
+
+/// Returns true if two values are equal within a relative tolerance `eps`.
+///
+/// The values are close when `|a - b| <= eps * max(1, |a|, |b|)`, so the tolerance is
+/// relative for large values and absolute for the values smaller than one.
+/// `NaN` is not close to anything, the infinities are close only to themselves.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::approx::close_to;
+///
+/// assert!(close_to(1_000_000., 1_000_001., 1e-5));
+/// assert!(!close_to(1., 1.1, 1e-5));
+/// assert!(close_to(0.5_f32, 0.500_000_1_f32, 1e-6));
+/// ```
+pub fn close_to<T: Into<f64>>(a: T, b: T, eps: f64) -> bool {
+    let (a, b) = (a.into(), b.into());
+
+    if a == b {
+        return true;
+    }
+
+    if !a.is_finite() || !b.is_finite() {
+        return false;
+    }
+
+    let scale = 1_f64.max(a.abs()).max(b.abs());
+    (a - b).abs() <= eps * scale
+}
+
+/// Asserts that two values are equal within a relative tolerance `eps`, see [`close_to`].
+///
+/// # Panics
+///
+/// Panics if the values are not close.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::approx::assert_close;
+///
+/// assert_close(1. / 3., 0.333_333_3, 1e-6);
+/// ```
+#[track_caller]
+pub fn assert_close<T: Into<f64>>(a: T, b: T, eps: f64) {
+    let (a, b) = (a.into(), b.into());
+    assert!(
+        close_to(a, b, eps),
+        "values are not close: left is {} but right is {} (eps {})",
+        a,
+        b,
+        eps
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn close_to_() {
+        assert!(close_to(0.1 + 0.2, 0.3, DEFAULT_EPSILON));
+        assert!(close_to(0., 1e-7, DEFAULT_EPSILON));
+        assert!(!close_to(0., 1e-5, DEFAULT_EPSILON));
+        assert!(close_to(1e10, 1e10 + 1., DEFAULT_EPSILON));
+        assert!(!close_to(1e10, 1.1e10, DEFAULT_EPSILON));
+    }
+
+    #[test]
+    fn close_to_special_() {
+        assert!(!close_to(f64::NAN, f64::NAN, 1.));
+        assert!(close_to(f64::INFINITY, f64::INFINITY, DEFAULT_EPSILON));
+        assert!(!close_to(f64::INFINITY, f64::NEG_INFINITY, DEFAULT_EPSILON));
+        assert!(!close_to(f64::INFINITY, 1e300, DEFAULT_EPSILON));
+    }
+
+    #[test]
+    fn assert_close_() {
+        assert_close(2_f32.sqrt() * 2_f32.sqrt(), 2., DEFAULT_EPSILON);
+    }
+
+    #[test]
+    #[should_panic]
+    fn assert_close_fails_() {
+        assert_close(1., 1.01, DEFAULT_EPSILON);
+    }
+}
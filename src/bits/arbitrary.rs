@@ -0,0 +1,54 @@
+use super::{BVec, Bit, Byte};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+impl<'a> Arbitrary<'a> for Bit {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        bool::arbitrary(u).map(Bit::from)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        bool::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Byte {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u8::arbitrary(u).map(Byte::from)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u8::size_hint(depth)
+    }
+}
+
+impl<'a> Arbitrary<'a> for BVec {
+    /// Creates a vector from a run of raw bytes, truncated to an arbitrary number of bits,
+    /// so the fuzzer controls the bits directly.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let bytes = Vec::<u8>::arbitrary(u)?;
+        let len = u.int_in_range(0..=bytes.len() * 8)?;
+        Ok(BVec::from_bytes(&bytes, len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary_() {
+        let data: Vec<u8> = (0..=255).collect();
+        let mut u = Unstructured::new(&data);
+
+        let bit = Bit::arbitrary(&mut u).unwrap();
+        let byte = Byte::arbitrary(&mut u).unwrap();
+        let bvec = BVec::arbitrary(&mut u).unwrap();
+        assert_eq!(bit, Bit::Zero);
+        assert_eq!(byte, Byte::from(1));
+        assert!(bvec.len() <= bvec.as_bytes().len() * 8);
+
+        // an exhausted input still gives values
+        let mut empty = Unstructured::new(&[]);
+        assert!(BVec::arbitrary(&mut empty).unwrap().is_empty());
+    }
+}
@@ -0,0 +1,224 @@
+use super::{BVec, Bit, Position};
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// A vector of bits which can be set from several threads at once.
+///
+/// The bits are stored MSB-first in atomic bytes, like in a [`BVec`]. The operations use
+/// relaxed atomics: each bit update is atomic, but it is not ordered with the other memory
+/// accesses. Join the threads, or otherwise synchronize, before reading the final state.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{AtomicBVec, Bit};
+///
+/// let bits = AtomicBVec::with_length(1_000);
+///
+/// std::thread::scope(|s| {
+///     for t in 0..4 {
+///         let bits = &bits;
+///         s.spawn(move || {
+///             for i in (t..1_000).step_by(4 * 3) {
+///                 bits.set_bit(i);
+///             }
+///         });
+///     }
+/// });
+///
+/// let bvec = bits.into_bvec();
+/// assert_eq!(bvec.get_bit(3), Bit::One);
+/// assert_eq!(bvec.get_bit(4), Bit::Zero);
+/// ```
+#[derive(Debug)]
+pub struct AtomicBVec {
+    vec: Vec<AtomicU8>,
+    len: usize,
+}
+
+impl AtomicBVec {
+    /// Creates a vector of `len` bits set to zero.
+    pub fn with_length(len: usize) -> Self {
+        Self {
+            vec: (0..len.div_ceil(super::U8SIZE))
+                .map(|_| AtomicU8::new(0))
+                .collect(),
+            len,
+        }
+    }
+
+    /// Returns the length of the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the vector has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn get_bit(&self, bit: usize) -> Bit {
+        let (pos, mask) = self.locate(bit);
+        Bit::from(self.vec[pos.idx].load(Ordering::Relaxed) & mask != 0)
+    }
+
+    /// Sets the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn set_bit(&self, bit: usize) {
+        let _ = self.fetch_set(bit);
+    }
+
+    /// Resets the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn reset_bit(&self, bit: usize) {
+        let (pos, mask) = self.locate(bit);
+        self.vec[pos.idx].fetch_and(!mask, Ordering::Relaxed);
+    }
+
+    /// Sets the bit value from a given position and returns its previous value.
+    /// Exactly one of the threads setting the same bit concurrently sees [`Bit::Zero`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{AtomicBVec, Bit};
+    ///
+    /// let bits = AtomicBVec::with_length(10);
+    /// assert_eq!(bits.fetch_set(3), Bit::Zero);
+    /// assert_eq!(bits.fetch_set(3), Bit::One);
+    /// ```
+    pub fn fetch_set(&self, bit: usize) -> Bit {
+        let (pos, mask) = self.locate(bit);
+        let previous = self.vec[pos.idx].fetch_or(mask, Ordering::Relaxed);
+        Bit::from(previous & mask != 0)
+    }
+
+    /// Returns the number of bits set to one.
+    pub fn count_ones(&self) -> usize {
+        self.vec
+            .iter()
+            .map(|byte| byte.load(Ordering::Relaxed).count_ones() as usize)
+            .sum()
+    }
+
+    /// Returns a snapshot of the bits as a [`BVec`].
+    pub fn to_bvec(&self) -> BVec {
+        let bytes: Vec<u8> = self
+            .vec
+            .iter()
+            .map(|byte| byte.load(Ordering::Relaxed))
+            .collect();
+        BVec::from_bytes(&bytes, self.len)
+    }
+
+    /// Consumes the vector and returns its bits as a [`BVec`].
+    pub fn into_bvec(self) -> BVec {
+        let bytes: Vec<u8> = self.vec.into_iter().map(AtomicU8::into_inner).collect();
+        BVec::from_bytes(&bytes, self.len)
+    }
+
+    /// Returns the position of a bit and its mask inside the byte.
+    #[inline]
+    fn locate(&self, bit: usize) -> (Position, u8) {
+        assert!(
+            bit < self.len,
+            "bit index out of bounds: the len is {} but the index is {}",
+            self.len,
+            bit
+        );
+
+        let pos = Position::from(bit);
+        let mask = 0x80 >> pos.bit;
+        (pos, mask)
+    }
+}
+
+impl From<BVec> for AtomicBVec {
+    fn from(bvec: BVec) -> Self {
+        let len = bvec.len();
+        Self {
+            vec: bvec.into_bytes().into_iter().map(AtomicU8::new).collect(),
+            len,
+        }
+    }
+}
+
+impl From<AtomicBVec> for BVec {
+    #[inline]
+    fn from(bits: AtomicBVec) -> Self {
+        bits.into_bvec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_get_() {
+        let bits = AtomicBVec::with_length(12);
+        assert_eq!(bits.len(), 12);
+        assert!(!bits.is_empty());
+
+        bits.set_bit(0);
+        bits.set_bit(11);
+        assert_eq!(bits.get_bit(0), Bit::One);
+        assert_eq!(bits.get_bit(1), Bit::Zero);
+        assert_eq!(bits.count_ones(), 2);
+
+        bits.reset_bit(0);
+        assert_eq!(bits.to_bvec().as_bytes(), [0, 0b0001_0000]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds_() {
+        let bits = AtomicBVec::with_length(12);
+        bits.set_bit(12);
+    }
+
+    #[test]
+    fn from_bvec_() {
+        let bvec = BVec::from_bytes(&[0xA5, 0xF0], 12);
+        let bits = AtomicBVec::from(bvec.clone());
+        assert_eq!(bits.get_bit(0), Bit::One);
+        assert_eq!(BVec::from(bits), bvec);
+    }
+
+    #[test]
+    fn concurrent_() {
+        let bits = AtomicBVec::with_length(10_000);
+
+        let firsts: usize = std::thread::scope(|s| {
+            let handles: Vec<_> = (0..4)
+                .map(|_| {
+                    let bits = &bits;
+                    s.spawn(move || {
+                        (0..10_000)
+                            .filter(|i| bits.fetch_set(*i) == Bit::Zero)
+                            .count()
+                    })
+                })
+                .collect();
+            handles.into_iter().map(|h| h.join().unwrap()).sum()
+        });
+
+        // each bit was seen unset exactly once
+        assert_eq!(firsts, 10_000);
+        assert_eq!(bits.count_ones(), 10_000);
+    }
+}
@@ -1,6 +1,6 @@
 use std::{
     fmt::{Debug, Display},
-    ops::{BitAnd, BitOr},
+    ops::{Add, BitAnd, BitAndAssign, BitOr, BitOrAssign, BitXor, BitXorAssign, Mul, Not},
 };
 
 /// Representation of a bit value.
@@ -88,9 +88,7 @@ impl BitAnd<Bit> for Bit {
 
     #[inline]
     fn bitand(self, rhs: Bit) -> Self::Output {
-        if self == Bit::Zero {
-            Bit::Zero
-        } else if rhs == Bit::Zero {
+        if self == Bit::Zero || rhs == Bit::Zero {
             Bit::Zero
         } else {
             Bit::One
@@ -103,9 +101,7 @@ impl BitAnd<u8> for Bit {
 
     #[inline]
     fn bitand(self, rhs: u8) -> Self::Output {
-        if self == Bit::Zero {
-            Bit::Zero
-        } else if rhs == 0 {
+        if self == Bit::Zero || rhs == 0 {
             Bit::Zero
         } else {
             Bit::One
@@ -118,9 +114,7 @@ impl BitOr<Bit> for Bit {
 
     #[inline]
     fn bitor(self, rhs: Bit) -> Self::Output {
-        if self == Bit::One {
-            Bit::One
-        } else if rhs == Bit::One {
+        if self == Bit::One || rhs == Bit::One {
             Bit::One
         } else {
             Bit::Zero
@@ -133,9 +127,7 @@ impl BitOr<u8> for Bit {
 
     #[inline]
     fn bitor(self, rhs: u8) -> Self::Output {
-        if self == Bit::One {
-            Bit::One
-        } else if rhs != 0 {
+        if self == Bit::One || rhs != 0 {
             Bit::One
         } else {
             Bit::Zero
@@ -143,6 +135,92 @@ impl BitOr<u8> for Bit {
     }
 }
 
+impl BitXor<Bit> for Bit {
+    type Output = Bit;
+
+    #[inline]
+    fn bitxor(self, rhs: Bit) -> Self::Output {
+        if self == rhs {
+            Bit::Zero
+        } else {
+            Bit::One
+        }
+    }
+}
+
+impl Not for Bit {
+    type Output = Bit;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        match self {
+            Bit::Zero => Bit::One,
+            Bit::One => Bit::Zero,
+        }
+    }
+}
+
+impl BitAndAssign<Bit> for Bit {
+    #[inline]
+    fn bitand_assign(&mut self, rhs: Bit) {
+        *self = *self & rhs;
+    }
+}
+
+impl BitOrAssign<Bit> for Bit {
+    #[inline]
+    fn bitor_assign(&mut self, rhs: Bit) {
+        *self = *self | rhs;
+    }
+}
+
+impl BitXorAssign<Bit> for Bit {
+    #[inline]
+    fn bitxor_assign(&mut self, rhs: Bit) {
+        *self = *self ^ rhs;
+    }
+}
+
+/// The addition in GF(2), modulo 2, which is the same as the exclusive or.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::Bit;
+///
+/// assert_eq!(Bit::One + Bit::One, Bit::Zero);
+/// assert_eq!(Bit::One + Bit::Zero, Bit::One);
+/// ```
+impl Add<Bit> for Bit {
+    type Output = Bit;
+
+    #[inline]
+    fn add(self, rhs: Bit) -> Self::Output {
+        BitXor::bitxor(self, rhs)
+    }
+}
+
+/// The multiplication in GF(2), which is the same as the and.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::Bit;
+///
+/// // the inner product of two code words
+/// let (x, y) = ([Bit::One, Bit::One, Bit::Zero], [Bit::One, Bit::One, Bit::One]);
+/// let product = x.iter().zip(y).fold(Bit::Zero, |acc, (a, b)| acc + *a * b);
+/// assert_eq!(product, Bit::Zero);
+/// ```
+impl Mul<Bit> for Bit {
+    type Output = Bit;
+
+    #[inline]
+    fn mul(self, rhs: Bit) -> Self::Output {
+        BitAnd::bitand(self, rhs)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -194,10 +272,10 @@ mod tests {
     #[test]
     fn bool_from_bit_() {
         let b: bool = Bit::Zero.into();
-        assert_eq!(false, b);
+        assert!(!b);
 
         let b: bool = Bit::One.into();
-        assert_eq!(true, b);
+        assert!(b);
     }
 
     #[test]
@@ -237,6 +315,7 @@ mod tests {
     }
 
     #[test]
+    #[allow(clippy::erasing_op)]
     fn bit_and_u8_() {
         let bit = Bit::Zero & 1_u8;
         assert_eq!(bit, Bit::Zero);
@@ -247,4 +326,29 @@ mod tests {
         let bit = Bit::One & 1_u8;
         assert_eq!(bit, Bit::One);
     }
+
+    #[test]
+    fn gf2_() {
+        let bits = [Bit::Zero, Bit::One];
+        for a in bits {
+            assert_eq!(!!a, a);
+            for b in bits {
+                let (x, y) = (u8::from(a), u8::from(b));
+                assert_eq!(a ^ b, Bit::from(x ^ y));
+                assert_eq!(a + b, Bit::from((x + y) % 2));
+                assert_eq!(a * b, Bit::from(x * y));
+
+                let mut c = a;
+                c ^= b;
+                assert_eq!(c, a ^ b);
+                c = a;
+                c &= b;
+                assert_eq!(c, a & b);
+                c = a;
+                c |= b;
+                assert_eq!(c, a | b);
+            }
+        }
+        assert_eq!(!Bit::One, Bit::Zero);
+    }
 }
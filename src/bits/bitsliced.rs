@@ -0,0 +1,245 @@
+use super::{BVec, Bit};
+
+const LANES: usize = u64::BITS as usize;
+
+/// Stores fixed-width fingerprints in a transposed (bit-sliced) layout.
+///
+/// The `j`-th slice holds the `j`-th bit of every fingerprint, 64 fingerprints per word,
+/// so a query is scored against 64 fingerprints at once with word-parallel operations.
+/// The hamming distances are accumulated in vertical counters (one word per counter bit).
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{BVec, BitSlicedIndex};
+///
+/// let fingerprint = |bits: &[usize]| {
+///     let mut bvec = BVec::with_length(8);
+///     bits.iter().for_each(|b| bvec.set_bit(*b));
+///     bvec
+/// };
+///
+/// let mut index = BitSlicedIndex::new(8);
+/// index.push(&fingerprint(&[0, 1, 2]));
+/// index.push(&fingerprint(&[5, 6, 7]));
+/// index.push(&fingerprint(&[0, 1, 7]));
+///
+/// let top = index.top_k(&fingerprint(&[0, 1]), 2);
+/// assert_eq!(top, [(0, 1), (2, 1)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct BitSlicedIndex {
+    width: usize,
+    len: usize,
+    slices: Vec<Vec<u64>>,
+}
+
+impl BitSlicedIndex {
+    /// Creates an empty index for fingerprints of `width` bits.
+    pub fn new(width: usize) -> Self {
+        Self {
+            width,
+            len: 0,
+            slices: vec![Vec::new(); width],
+        }
+    }
+
+    /// Returns the number of bits of the fingerprints.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of fingerprints stored in the index.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the index has no fingerprints.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Adds a fingerprint to the index. The fingerprints are identified by their insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the fingerprint is not the width of the index.
+    pub fn push(&mut self, fingerprint: &BVec) {
+        assert_eq!(
+            fingerprint.len(),
+            self.width,
+            "the fingerprint length must be the index width"
+        );
+
+        let block = self.len / LANES;
+        let lane = self.len % LANES;
+
+        for (j, slice) in self.slices.iter_mut().enumerate() {
+            if lane == 0 {
+                slice.push(0);
+            }
+            if fingerprint.get_bit(j) == Bit::One {
+                slice[block] |= 1 << lane;
+            }
+        }
+
+        self.len += 1;
+    }
+
+    /// Returns the hamming distances between the query and all the fingerprints, in insertion order.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the query is not the width of the index.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BVec, BitSlicedIndex};
+    ///
+    /// let mut index = BitSlicedIndex::new(4);
+    /// index.push(&BVec::with_length(4));
+    /// index.push(&!BVec::with_length(4));
+    ///
+    /// let mut query = BVec::with_length(4);
+    /// query.set_bit(0);
+    /// assert_eq!(index.hamming_all(&query), [1, 3]);
+    /// ```
+    pub fn hamming_all(&self, query: &BVec) -> Vec<usize> {
+        assert_eq!(
+            query.len(),
+            self.width,
+            "the query length must be the index width"
+        );
+
+        let query: Vec<u64> = (0..self.width)
+            .map(|j| match query.get_bit(j) {
+                Bit::One => u64::MAX,
+                Bit::Zero => 0,
+            })
+            .collect();
+
+        // number of bits needed to count up to the width
+        let planes = (usize::BITS - self.width.leading_zeros()) as usize;
+        let mut counters = vec![0_u64; planes];
+        let mut distances = Vec::with_capacity(self.len);
+
+        for block in 0..self.len.div_ceil(LANES) {
+            counters.iter_mut().for_each(|c| *c = 0);
+
+            for (slice, q) in self.slices.iter().zip(&query) {
+                // ripple-carry addition of the mismatches into the vertical counters
+                let mut carry = slice[block] ^ q;
+                for counter in counters.iter_mut() {
+                    if carry == 0 {
+                        break;
+                    }
+                    let sum = *counter ^ carry;
+                    carry &= *counter;
+                    *counter = sum;
+                }
+            }
+
+            let lanes = LANES.min(self.len - block * LANES);
+            distances.extend((0..lanes).map(|lane| {
+                counters
+                    .iter()
+                    .enumerate()
+                    .map(|(p, c)| (((c >> lane) & 1) as usize) << p)
+                    .sum::<usize>()
+            }));
+        }
+
+        distances
+    }
+
+    /// Returns the `k` fingerprints closest to the query as `(index, distance)` pairs,
+    /// sorted by ascending hamming distance and then by index.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the query is not the width of the index.
+    pub fn top_k(&self, query: &BVec, k: usize) -> Vec<(usize, usize)> {
+        let mut scored: Vec<(usize, usize)> =
+            self.hamming_all(query).into_iter().enumerate().collect();
+        let by_distance = |x: &(usize, usize), y: &(usize, usize)| (x.1, x.0).cmp(&(y.1, y.0));
+
+        if k < scored.len() {
+            if k > 0 {
+                scored.select_nth_unstable_by(k - 1, by_distance);
+            }
+            scored.truncate(k);
+        }
+
+        scored.sort_unstable_by(by_distance);
+        scored
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn random_bvec(rng: &mut StdRng, width: usize) -> BVec {
+        let mut bvec = BVec::with_length(width);
+        for j in 0..width {
+            if rng.gen::<bool>() {
+                bvec.set_bit(j);
+            }
+        }
+        bvec
+    }
+
+    #[test]
+    fn empty_() {
+        let index = BitSlicedIndex::new(16);
+        assert!(index.is_empty());
+        assert_eq!(index.width(), 16);
+        assert!(index.hamming_all(&BVec::with_length(16)).is_empty());
+        assert!(index.top_k(&BVec::with_length(16), 3).is_empty());
+    }
+
+    #[test]
+    fn hamming_all_() {
+        let mut rng = StdRng::seed_from_u64(7);
+        let width = 100;
+        let fingerprints: Vec<BVec> = (0..150).map(|_| random_bvec(&mut rng, width)).collect();
+
+        let mut index = BitSlicedIndex::new(width);
+        fingerprints.iter().for_each(|f| index.push(f));
+        assert_eq!(index.len(), 150);
+
+        let query = random_bvec(&mut rng, width);
+        let expected: Vec<usize> = fingerprints.iter().map(|f| f.hamming(&query)).collect();
+        assert_eq!(index.hamming_all(&query), expected);
+    }
+
+    #[test]
+    fn top_k_() {
+        let width = 64;
+        let fingerprints: Vec<BVec> = (0..70)
+            .map(|i| random_bvec(&mut StdRng::seed_from_u64(i), width))
+            .collect();
+
+        let mut index = BitSlicedIndex::new(width);
+        fingerprints.iter().for_each(|f| index.push(f));
+
+        let query = random_bvec(&mut StdRng::seed_from_u64(42), width);
+        let top = index.top_k(&query, 5);
+        assert_eq!(top.len(), 5);
+        assert_eq!(top[0], (42, 0));
+        assert!(top.windows(2).all(|w| w[0].1 <= w[1].1));
+
+        let all = index.top_k(&query, 100);
+        assert_eq!(all.len(), 70);
+        assert!(index.top_k(&query, 0).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_width_() {
+        let mut index = BitSlicedIndex::new(8);
+        index.push(&BVec::with_length(9));
+    }
+}
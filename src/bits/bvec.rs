@@ -1,7 +1,14 @@
-use super::{Bit, Byte, Position};
+use super::{check_index, Bit, BitsError, Byte, Position};
+use std::{
+    fmt::{Binary, Debug, Display, LowerHex, UpperHex},
+    hash::{Hash, Hasher},
+    iter::{FusedIterator, Rev},
+    ops::{BitAnd, BitOr, BitXor, Deref, DerefMut, Index, Not, Range, Shl, Shr},
+};
 
 /// A vector of bits. Each bit can be accessed and written individually.
 pub struct BVec {
+    // exactly the bytes needed to store `len` bits, with the padding bits set to zero
     vec: Vec<u8>,
     len: usize,
 }
@@ -12,6 +19,11 @@ impl BVec {
         self.len
     }
 
+    /// Returns true if the vector has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
     /// Creates a new instance of the bit-vector with a given length.
     ///
     /// # Examples
@@ -23,11 +35,54 @@ impl BVec {
     /// assert_eq!(10, bvec.len());
     /// ```
     pub fn with_length(len: usize) -> Self {
-        let capacity = len / super::U8SIZE + (if len % super::U8SIZE == 0 { 0 } else { 1 });
-        let mut vec = Vec::with_capacity(capacity);
-        let _x: usize = (0..capacity).inspect(|_| vec.push(0)).sum();
+        Self {
+            vec: vec![0; len.div_ceil(super::U8SIZE)],
+            len,
+        }
+    }
+
+    /// Creates an empty vector which can hold at least `bits` bits without reallocating.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::with_capacity(100);
+    /// assert!(bvec.is_empty());
+    /// assert!(bvec.capacity() >= 100);
+    /// ```
+    pub fn with_capacity(bits: usize) -> Self {
+        Self {
+            vec: Vec::with_capacity(bits.div_ceil(super::U8SIZE)),
+            len: 0,
+        }
+    }
+
+    /// Returns the number of bits the vector can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.vec.capacity().saturating_mul(super::U8SIZE)
+    }
 
-        Self { vec, len }
+    /// Reserves capacity for at least `additional` more bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(3);
+    /// bvec.reserve(20);
+    /// assert!(bvec.capacity() >= 23);
+    /// assert_eq!(bvec.len(), 3);
+    /// ```
+    pub fn reserve(&mut self, additional: usize) {
+        let bytes = self
+            .len
+            .checked_add(additional)
+            .expect("capacity overflow")
+            .div_ceil(super::U8SIZE);
+        self.vec.reserve(bytes - self.vec.len());
     }
 
     /// Returns the bit value from a given position.
@@ -43,12 +98,56 @@ impl BVec {
     /// assert_eq!(bvec.get_bit(0), Bit::Zero);
     /// assert_eq!(bvec.get_bit(4), Bit::One);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
     pub fn get_bit(&self, bit: usize) -> Bit {
+        self.check_bounds(bit);
+
         let pos = Position::from(bit);
         let byte: Byte = self.vec[pos.idx].into();
         byte.get_bit(pos.bit)
     }
 
+    /// Returns the bit value from a given position, or `None` if the position is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(4);
+    /// assert_eq!(bvec.get(4), Some(Bit::One));
+    /// assert_eq!(bvec.get(10), None);
+    /// ```
+    pub fn get(&self, bit: usize) -> Option<Bit> {
+        if bit < self.len {
+            Some(self.get_bit(bit))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the bit value from a given position, or an error if the position is out of range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BitsError, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.try_set_bit(4).unwrap();
+    /// assert_eq!(bvec.try_get_bit(4), Ok(Bit::One));
+    /// assert_eq!(bvec.try_get_bit(10), Err(BitsError::OutOfRange { index: 10, len: 10 }));
+    /// assert!(bvec.try_set_bit(10).is_err());
+    /// ```
+    pub fn try_get_bit(&self, bit: usize) -> Result<Bit, BitsError> {
+        check_index(bit, self.len)?;
+        Ok(self.get_bit(bit))
+    }
+
     /// Sets the bit value from a given position.
     ///
     /// # Examples
@@ -62,7 +161,13 @@ impl BVec {
     /// assert_eq!(bvec.get_bit(0), Bit::Zero);
     /// assert_eq!(bvec.get_bit(4), Bit::One);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
     pub fn set_bit(&mut self, bit: usize) {
+        self.check_bounds(bit);
+
         let pos = Position::from(bit);
         let byte: Byte = self.vec[pos.idx].into();
         let byte: u8 = byte.set_bit(pos.bit).into();
@@ -70,6 +175,13 @@ impl BVec {
         let _ = std::mem::replace(&mut self.vec[pos.idx], byte);
     }
 
+    /// Sets the bit value from a given position, or returns an error if the position is out of range.
+    pub fn try_set_bit(&mut self, bit: usize) -> Result<(), BitsError> {
+        check_index(bit, self.len)?;
+        self.set_bit(bit);
+        Ok(())
+    }
+
     /// Resets the bit value from a given position.
     ///
     /// # Examples
@@ -84,7 +196,13 @@ impl BVec {
     /// bvec.reset_bit(4);
     /// assert_eq!(bvec.get_bit(4), Bit::Zero);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
     pub fn reset_bit(&mut self, bit: usize) {
+        self.check_bounds(bit);
+
         let pos = Position::from(bit);
         let byte: Byte = self.vec[pos.idx].into();
 
@@ -93,6 +211,13 @@ impl BVec {
         let _ = std::mem::replace(&mut self.vec[pos.idx], byte);
     }
 
+    /// Resets the bit value from a given position, or returns an error if the position is out of range.
+    pub fn try_reset_bit(&mut self, bit: usize) -> Result<(), BitsError> {
+        check_index(bit, self.len)?;
+        self.reset_bit(bit);
+        Ok(())
+    }
+
     /// Toggles the bit value from a given position.
     ///
     /// # Examples
@@ -104,7 +229,13 @@ impl BVec {
     /// bvec.toggle_bit(4);
     /// bvec.toggle_bit(6);
     /// ```
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
     pub fn toggle_bit(&mut self, bit: usize) {
+        self.check_bounds(bit);
+
         let pos = Position::from(bit);
         let byte: Byte = self.vec[pos.idx].into();
         let byte: u8 = byte.toggle_bit(pos.bit).into();
@@ -113,136 +244,2225 @@ impl BVec {
     }
 }
 
-impl Extend<Bit> for BVec {
-    fn extend<T: IntoIterator<Item = Bit>>(&mut self, iter: T) {
-        for bit in iter {
-            if self.len == self.vec.capacity() {
-                self.vec.extend([0, 0, 0, 0]);
-                self.vec.push(0);
-                self.vec.push(0);
-                self.vec.push(0);
-                self.vec.push(0);
-            }
+impl BVec {
+    /// Appends a bit to the end of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(0);
+    /// bvec.push(Bit::One);
+    /// bvec.push(Bit::Zero);
+    /// assert_eq!(bvec.len(), 2);
+    /// assert_eq!(bvec.get_bit(0), Bit::One);
+    /// ```
+    pub fn push(&mut self, bit: Bit) {
+        if (self.len + 1).div_ceil(super::U8SIZE) > self.vec.len() {
+            self.vec.push(0);
+        }
 
-            if bit == Bit::One {
-                self.set_bit(self.len);
-            }
+        self.len += 1;
 
-            self.len += 1;
+        if bit == Bit::One {
+            self.set_bit(self.len - 1);
         }
     }
-}
 
-pub struct Iter {
-    bvec: BVec,
-    current: usize,
-}
+    /// Removes the last bit of the vector and returns it, or `None` if the vector is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec: BVec = [Bit::Zero, Bit::One].into_iter().collect();
+    /// assert_eq!(bvec.pop(), Some(Bit::One));
+    /// assert_eq!(bvec.pop(), Some(Bit::Zero));
+    /// assert_eq!(bvec.pop(), None);
+    /// ```
+    pub fn pop(&mut self) -> Option<Bit> {
+        if self.len == 0 {
+            return None;
+        }
 
-impl Iterator for Iter {
-    type Item = Bit;
+        let bit = self.get_bit(self.len - 1);
+        self.reset_bit(self.len - 1);
+        self.len -= 1;
+        Some(bit)
+    }
 
-    fn next(&mut self) -> Option<Self::Item> {
-        if self.current >= self.bvec.len {
-            None
-        } else {
-            let bit = self.bvec.get_bit(self.current);
-            self.current += 1;
-            Some(bit)
+    /// Shortens the vector to the first `len` bits. It has no effect
+    /// if `len` is not smaller than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::from_bytes(&[0xFF], 8);
+    /// bvec.truncate(3);
+    /// assert_eq!(bvec.len(), 3);
+    /// assert_eq!(bvec.as_bytes(), [0b1110_0000]);
+    /// ```
+    pub fn truncate(&mut self, len: usize) {
+        if len < self.len {
+            self.len = len;
+            self.mask_trailing();
+            self.vec.truncate(self.bytes_len());
         }
     }
-}
 
-impl IntoIterator for BVec {
-    type Item = Bit;
+    /// Resizes the vector to `len` bits. A longer vector is filled with the given bit,
+    /// a shorter one is truncated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(2);
+    /// bvec.resize(12, Bit::One);
+    /// assert_eq!(bvec.len(), 12);
+    /// assert_eq!(bvec.as_bytes(), [0b0011_1111, 0b1111_0000]);
+    ///
+    /// bvec.resize(1, Bit::One);
+    /// assert_eq!(bvec.as_bytes(), [0]);
+    /// ```
+    pub fn resize(&mut self, len: usize, bit: Bit) {
+        if len <= self.len {
+            self.truncate(len);
+            return;
+        }
 
-    type IntoIter = Iter;
+        let old = self.len;
+        self.len = len;
 
-    fn into_iter(self) -> Self::IntoIter {
-        Iter {
-            bvec: self,
-            current: 0,
+        let n = self.bytes_len();
+        if self.vec.len() < n {
+            self.vec.resize(n, 0);
+        }
+
+        if bit == Bit::One {
+            self.set_range(old..len);
         }
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Sets to one the bits in a range of positions, a whole byte at a time where possible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end,
+    /// or if the end is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(20);
+    /// bvec.set_range(3..13);
+    /// assert_eq!(bvec.to_string(), "00011111111110000000");
+    /// ```
+    pub fn set_range(&mut self, range: Range<usize>) {
+        self.apply_range(range, |byte, mask| byte | mask);
+    }
 
-    #[test]
-    fn with_length_() {
-        let bvec = BVec::with_length(10);
-        assert_eq!(10, bvec.len());
-        assert_eq!(2, bvec.vec.capacity());
+    /// Resets to zero the bits in a range of positions, a whole byte at a time where possible.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the start of the range is greater than its end,
+    /// or if the end is greater than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::from_bytes(&[0xFF, 0xFF], 12);
+    /// bvec.reset_range(2..10);
+    /// assert_eq!(bvec.to_string(), "110000000011");
+    /// ```
+    pub fn reset_range(&mut self, range: Range<usize>) {
+        self.apply_range(range, |byte, mask| byte & !mask);
     }
 
-    #[test]
-    fn set_bit_() {
-        let mut bvec = BVec::with_length(10);
-        bvec.set_bit(4);
-        bvec.set_bit(6);
-        assert_eq!(10, bvec.vec[0]);
+    /// Sets all the bits of the vector to the given value.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.fill(Bit::One);
+    /// assert_eq!(bvec.count_ones(), 10);
+    ///
+    /// bvec.fill(Bit::Zero);
+    /// assert_eq!(bvec.count_ones(), 0);
+    /// ```
+    pub fn fill(&mut self, bit: Bit) {
+        match bit {
+            Bit::One => self.set_range(0..self.len),
+            Bit::Zero => self.reset_range(0..self.len),
+        }
     }
 
-    #[test]
-    fn reset_bit_() {
-        let mut bvec = BVec::with_length(10);
-        bvec.set_bit(4);
-        bvec.set_bit(6);
-        assert_eq!(10, bvec.vec[0]);
+    /// Updates the bytes overlapping a range of positions with `f(byte, mask)`,
+    /// where the mask selects the bits of the byte inside the range.
+    fn apply_range<F>(&mut self, range: Range<usize>, f: F)
+    where
+        F: Fn(u8, u8) -> u8,
+    {
+        assert!(
+            range.start <= range.end && range.end <= self.len,
+            "bit range {}..{} out of bounds: the len is {}",
+            range.start,
+            range.end,
+            self.len
+        );
 
-        bvec.reset_bit(6);
-        assert_eq!(8, bvec.vec[0]);
-    }
+        if range.is_empty() {
+            return;
+        }
 
-    #[test]
-    fn get_bit_() {
-        let mut bvec = BVec::with_length(10);
-        bvec.set_bit(4);
-        bvec.set_bit(6);
+        let first = range.start / super::U8SIZE;
+        let last = (range.end - 1) / super::U8SIZE;
 
-        assert_eq!(bvec.get_bit(0), Bit::Zero);
-        assert_eq!(bvec.get_bit(4), Bit::One);
+        for idx in first..=last {
+            let lo = range.start.max(idx * super::U8SIZE) - idx * super::U8SIZE;
+            let hi = range.end.min((idx + 1) * super::U8SIZE) - idx * super::U8SIZE;
+            let mask = (u8::MAX >> lo) & !u8::MAX.checked_shr(hi as u32).unwrap_or(0);
+
+            self.vec[idx] = f(self.vec[idx], mask);
+        }
     }
 
-    #[test]
-    fn toggle_bit_() {
-        let mut bvec = BVec::with_length(10);
-        bvec.toggle_bit(4);
-        bvec.toggle_bit(6);
+    /// Rotates the bits in place by `n` positions towards position 0.
+    /// The bits which leave position 0 re-enter at the end of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::from_bytes(&[0b1100_1000], 5);
+    /// bvec.rotate_left(2);
+    /// assert_eq!(bvec.to_string(), "00111");
+    /// ```
+    pub fn rotate_left(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
 
-        assert_eq!(10, bvec.vec[0]);
+        let n = n % self.len;
+        if n != 0 {
+            *self = &(&*self << n) | &(&*self >> (self.len - n));
+        }
     }
 
-    #[test]
-    fn bvec_into_iter_() {
-        let mut bvec = BVec::with_length(10);
-        bvec.toggle_bit(4);
-        bvec.toggle_bit(6);
-
-        let mut iter = bvec.into_iter();
+    /// Rotates the bits in place by `n` positions away from position 0.
+    /// The bits which leave the end of the vector re-enter at position 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::from_bytes(&[0b1100_1000], 5);
+    /// bvec.rotate_right(2);
+    /// assert_eq!(bvec.to_string(), "01110");
+    /// ```
+    pub fn rotate_right(&mut self, n: usize) {
+        if self.len == 0 {
+            return;
+        }
 
-        assert_eq!(iter.next(), Some(Bit::Zero));
-        assert_eq!(iter.next(), Some(Bit::Zero));
-        assert_eq!(iter.next(), Some(Bit::Zero));
-        assert_eq!(iter.next(), Some(Bit::Zero));
+        let n = n % self.len;
+        if n != 0 {
+            self.rotate_left(self.len - n);
+        }
+    }
 
-        assert_eq!(iter.next(), Some(Bit::One));
-        assert_eq!(iter.next(), Some(Bit::Zero));
-        assert_eq!(iter.next(), Some(Bit::One));
-        assert_eq!(iter.next(), Some(Bit::Zero));
+    /// Reverses the order of the bits in place, so the bit at position 0 moves to the end.
+    /// Only the `len` bits of the vector are reversed, not the padding of the last byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::from_bytes(&[0b1101_0000, 0b1000_0000], 10);
+    /// bvec.reverse();
+    /// assert_eq!(bvec.to_string(), "0100001011");
+    /// ```
+    pub fn reverse(&mut self) {
+        let n = self.bytes_len();
+        let pad = (n * super::U8SIZE - self.len) as u32;
 
-        assert_eq!(iter.next(), Some(Bit::Zero));
-        assert_eq!(iter.next(), Some(Bit::Zero));
+        self.vec[..n].reverse();
+        for byte in self.vec[..n].iter_mut() {
+            *byte = byte.reverse_bits();
+        }
 
-        assert_eq!(iter.next(), None);
+        // the padding bits are now in front, shift them back to the end
+        if pad != 0 {
+            for idx in 0..n {
+                let next = self.vec.get(idx + 1).filter(|_| idx + 1 < n).copied();
+                self.vec[idx] =
+                    (self.vec[idx] << pad) | next.map_or(0, |next| next >> (u8::BITS - pad));
+            }
+        }
     }
+}
 
-    #[test]
-    fn extend_() {
-        let mut bvec = BVec::with_length(0);
-        assert_eq!(0, bvec.len);
-        assert_eq!(0, bvec.vec.capacity());
+impl BVec {
+    /// Returns an iterator over the bits which borrows the vector, starting with the bit at position 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(3);
+    /// bvec.set_bit(1);
+    ///
+    /// assert_eq!(bvec.iter().filter(|b| *b == Bit::One).count(), 1);
+    /// assert_eq!(bvec.iter().position(|b| b == Bit::One), Some(1));
+    /// ```
+    #[inline]
+    pub fn iter(&self) -> BVecIter<'_> {
+        BVecIter {
+            bvec: self,
+            current: 0,
+            end: self.len,
+        }
+    }
+
+    /// Returns an iterator over the bits, starting with the bit at position 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(3);
+    /// bvec.set_bit(0);
+    ///
+    /// let bits: Vec<Bit> = bvec.iter_msb().collect();
+    /// assert_eq!(bits, [Bit::One, Bit::Zero, Bit::Zero]);
+    /// ```
+    pub fn iter_msb(&self) -> BVecIter<'_> {
+        self.iter()
+    }
+
+    /// Returns an iterator over the bits, starting with the last bit of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let mut bvec = BVec::with_length(3);
+    /// bvec.set_bit(0);
+    ///
+    /// let bits: Vec<Bit> = bvec.iter_lsb().collect();
+    /// assert_eq!(bits, [Bit::Zero, Bit::Zero, Bit::One]);
+    /// ```
+    pub fn iter_lsb(&self) -> Rev<BVecIter<'_>> {
+        self.iter().rev()
+    }
+
+    /// Creates a vector from a sequence of bits, the first bit being stored at position 0.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let bvec = BVec::from_bits_msb([Bit::One, Bit::Zero, Bit::Zero]);
+    /// assert_eq!(bvec.len(), 3);
+    /// assert_eq!(bvec.get_bit(0), Bit::One);
+    /// ```
+    #[inline]
+    pub fn from_bits_msb<T: IntoIterator<Item = Bit>>(bits: T) -> Self {
+        BVec::from_iter(bits)
+    }
+
+    /// Creates a vector from a sequence of bits, the first bit being stored at the last position.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let bvec = BVec::from_bits_lsb([Bit::One, Bit::Zero, Bit::Zero]);
+    /// assert_eq!(bvec.len(), 3);
+    /// assert_eq!(bvec.get_bit(2), Bit::One);
+    /// ```
+    pub fn from_bits_lsb<T: IntoIterator<Item = Bit>>(bits: T) -> Self {
+        let bits: Vec<Bit> = bits.into_iter().collect();
+        BVec::from_bits_msb(bits.into_iter().rev())
+    }
+
+    /// Returns the number of bits set to one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(4);
+    /// bvec.set_bit(9);
+    /// assert_eq!(bvec.count_ones(), 2);
+    /// ```
+    pub fn count_ones(&self) -> usize {
+        count_ones_with(self, self, |x, _| x)
+    }
+
+    /// Returns the number of bits set to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(4);
+    /// bvec.set_bit(9);
+    /// assert_eq!(bvec.count_zeros(), 8);
+    /// ```
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns the [Hamming](https://en.wikipedia.org/wiki/Hamming_distance) distance between two vectors,
+    /// computed a byte at a time, or a word at a time with the `simd` feature.
+    /// A shorter vector is padded with zero bits, so the distance also counts
+    /// the bits set to one beyond its end in the longer vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let xs = BVec::from_bytes(&[0b1010_1010, 0b1100_0000], 10);
+    /// let ys = BVec::from_bytes(&[0b1010_0101, 0b0100_0000], 10);
+    /// assert_eq!(xs.hamming(&ys), 5);
+    ///
+    /// let zs = BVec::from_bytes(&[0b1010_1010], 8);
+    /// assert_eq!(xs.hamming(&zs), 2);
+    /// ```
+    pub fn hamming(&self, other: &BVec) -> usize {
+        count_ones_with(self, other, |x, y| x ^ y)
+    }
+
+    /// Returns the number of bits set in both vectors, the popcount of their bitwise AND,
+    /// without allocating the combined vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let xs = BVec::from_bytes(&[0b1110_0000, 0b1000_0000], 10);
+    /// let ys = BVec::from_bytes(&[0b0111_0000], 8);
+    /// assert_eq!(xs.intersection_count(&ys), 2);
+    /// assert_eq!(xs.intersection_count(&ys), (&xs & &ys).count_ones());
+    /// ```
+    pub fn intersection_count(&self, other: &BVec) -> usize {
+        count_ones_with(self, other, |x, y| x & y)
+    }
+
+    /// Returns the number of bits set in either vector, the popcount of their bitwise OR,
+    /// without allocating the combined vector. A shorter vector is padded with zero bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let xs = BVec::from_bytes(&[0b1110_0000, 0b1000_0000], 10);
+    /// let ys = BVec::from_bytes(&[0b0111_0000], 8);
+    /// assert_eq!(xs.union_count(&ys), 5);
+    /// assert_eq!(xs.union_count(&ys), (&xs | &ys).count_ones());
+    /// ```
+    pub fn union_count(&self, other: &BVec) -> usize {
+        count_ones_with(self, other, |x, y| x | y)
+    }
+
+    /// Returns the Tanimoto (Jaccard) similarity between the vectors, the number of bits set
+    /// in both divided by the number of bits set in either, computed a byte at a time.
+    /// A shorter vector is padded with zero bits. Two vectors with no bit set have a similarity of zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let xs = BVec::from_bytes(&[0b1110_0000, 0b1000_0000], 10);
+    /// let ys = BVec::from_bytes(&[0b0111_0000, 0b1100_0000], 10);
+    /// assert_eq!(xs.tanimoto(&ys), 0.5);
+    /// assert_eq!(xs.tanimoto(&xs), 1.);
+    /// assert_eq!(BVec::with_length(10).tanimoto(&BVec::with_length(10)), 0.);
+    /// ```
+    pub fn tanimoto(&self, other: &BVec) -> f32 {
+        let (and, or) = (0..self.bytes_len().max(other.bytes_len()))
+            .map(|i| (self.byte_at(i), other.byte_at(i)))
+            .fold((0_usize, 0_usize), |(and, or), (x, y)| {
+                (
+                    and + (x & y).count_ones() as usize,
+                    or + (x | y).count_ones() as usize,
+                )
+            });
+
+        if or == 0 {
+            0.
+        } else {
+            and as f32 / or as f32
+        }
+    }
+
+    /// Returns the number of bits set to one in the range `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is larger than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(1);
+    /// bvec.set_bit(8);
+    /// assert_eq!(bvec.rank1(1), 0);
+    /// assert_eq!(bvec.rank1(2), 1);
+    /// assert_eq!(bvec.rank1(10), 2);
+    /// ```
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(
+            i <= self.len,
+            "rank index out of bounds: the len is {} but the index is {}",
+            self.len,
+            i
+        );
+
+        let pos = Position::from(i);
+        let full: usize = (0..pos.idx)
+            .map(|idx| self.byte_at(idx).count_ones() as usize)
+            .sum();
+
+        if pos.bit == 0 {
+            full
+        } else {
+            let mask = u8::MAX << (super::U8SIZE - pos.bit as usize);
+            full + (self.byte_at(pos.idx) & mask).count_ones() as usize
+        }
+    }
+
+    /// Returns the number of bits set to zero in the range `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is larger than the length of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(1);
+    /// assert_eq!(bvec.rank0(1), 1);
+    /// assert_eq!(bvec.rank0(10), 9);
+    /// ```
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// Returns the position of the `k`-th (zero-based) bit set to one,
+    /// or `None` if there are not enough bits set to one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(1);
+    /// bvec.set_bit(8);
+    /// assert_eq!(bvec.select1(0), Some(1));
+    /// assert_eq!(bvec.select1(1), Some(8));
+    /// assert_eq!(bvec.select1(2), None);
+    /// ```
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        self.select(k, |byte| byte)
+    }
+
+    /// Returns the position of the `k`-th (zero-based) bit set to zero,
+    /// or `None` if there are not enough bits set to zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(4);
+    /// bvec.set_bit(0);
+    /// bvec.set_bit(1);
+    /// assert_eq!(bvec.select0(0), Some(2));
+    /// assert_eq!(bvec.select0(1), Some(3));
+    /// assert_eq!(bvec.select0(2), None);
+    /// ```
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        self.select(k, |byte| !byte)
+    }
+
+    /// Finds the `k`-th bit set to one after mapping each byte.
+    fn select<F>(&self, mut k: usize, f: F) -> Option<usize>
+    where
+        F: Fn(u8) -> u8,
+    {
+        for idx in 0..self.bytes_len() {
+            let byte = f(self.byte_at(idx));
+            let ones = byte.count_ones() as usize;
+
+            if k < ones {
+                let bit = (0..super::U8SIZE)
+                    .filter(|b| byte & (1 << (super::U8SIZE - 1 - b)) != 0)
+                    .nth(k)?;
+                let pos = idx * super::U8SIZE + bit;
+                return if pos < self.len { Some(pos) } else { None };
+            }
+
+            k -= ones;
+        }
+
+        None
+    }
+
+    /// Returns the position of the first bit set to one, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0, 0b0010_0000], 16);
+    /// assert_eq!(bvec.first_one(), Some(10));
+    /// assert_eq!(BVec::with_length(16).first_one(), None);
+    /// ```
+    #[inline]
+    pub fn first_one(&self) -> Option<usize> {
+        self.next_one(0)
+    }
+
+    /// Returns the position of the first bit set to zero, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0xFF, 0b1110_0000], 12);
+    /// assert_eq!(bvec.first_zero(), Some(11));
+    /// ```
+    #[inline]
+    pub fn first_zero(&self) -> Option<usize> {
+        self.next_zero(0)
+    }
+
+    /// Returns the position of the first bit set to one at or after `from`, or `None` if there is none.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1000_0001, 0b1000_0000], 16);
+    /// assert_eq!(bvec.next_one(0), Some(0));
+    /// assert_eq!(bvec.next_one(1), Some(7));
+    /// assert_eq!(bvec.next_one(8), Some(8));
+    /// assert_eq!(bvec.next_one(9), None);
+    /// ```
+    #[inline]
+    pub fn next_one(&self, from: usize) -> Option<usize> {
+        self.next_from(from, 0)
+    }
+
+    /// Returns the position of the first bit set to zero at or after `from`, or `None` if there is none.
+    /// It finds the free slots of a vector used as an allocation bitmap.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0b0111_1111, 0xFF], 12);
+    /// assert_eq!(bvec.next_zero(0), Some(0));
+    /// assert_eq!(bvec.next_zero(1), None);
+    /// ```
+    #[inline]
+    pub fn next_zero(&self, from: usize) -> Option<usize> {
+        self.next_from(from, u8::MAX)
+    }
+
+    /// Finds the first bit at or after `from` which is one after xor-ing its byte with `flip`.
+    fn next_from(&self, from: usize, flip: u8) -> Option<usize> {
+        if from >= self.len {
+            return None;
+        }
+
+        let n = self.bytes_len();
+        let mut idx = from / super::U8SIZE;
+        let mut byte = (self.vec[idx] ^ flip) & (u8::MAX >> (from % super::U8SIZE));
+
+        loop {
+            if idx + 1 == n {
+                byte &= self.last_byte_mask();
+            }
+
+            if byte != 0 {
+                return Some(idx * super::U8SIZE + byte.leading_zeros() as usize);
+            }
+
+            idx += 1;
+            if idx >= n {
+                return None;
+            }
+
+            byte = self.vec[idx] ^ flip;
+        }
+    }
+
+    /// Returns an iterator over the positions of the bits set to one, in increasing order.
+    /// The bytes with no bit set are skipped at once, which makes the scan of sparse vectors fast.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(100);
+    /// bvec.set_bit(3);
+    /// bvec.set_bit(97);
+    ///
+    /// let ones: Vec<usize> = bvec.ones().collect();
+    /// assert_eq!(ones, [3, 97]);
+    /// ```
+    #[inline]
+    pub fn ones(&self) -> BitPositions<'_> {
+        BitPositions::new(self, 0)
+    }
+
+    /// Returns an iterator over the positions of the bits set to zero, in increasing order.
+    /// The bytes with all the bits set are skipped at once.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1011_1111, 0b1000_0000], 10);
+    ///
+    /// let zeros: Vec<usize> = bvec.zeros().collect();
+    /// assert_eq!(zeros, [1, 9]);
+    /// ```
+    #[inline]
+    pub fn zeros(&self) -> BitPositions<'_> {
+        BitPositions::new(self, u8::MAX)
+    }
+
+    /// Creates a vector with `len` bits from a slice of bytes.
+    /// The bits are read MSB-first and the bits of the last byte beyond `len` are ignored.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice has fewer than `len` bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1000_0000, 0b1111_1111], 10);
+    /// assert_eq!(bvec.len(), 10);
+    /// assert_eq!(bvec.get_bit(0), Bit::One);
+    /// assert_eq!(bvec.count_ones(), 3);
+    /// assert_eq!(bvec.as_bytes(), [0b1000_0000, 0b1100_0000]);
+    /// ```
+    pub fn from_bytes(bytes: &[u8], len: usize) -> Self {
+        Self::try_from_bytes(bytes, len).unwrap_or_else(|err| panic!("{err}"))
+    }
+
+    /// Creates a vector with the first `len` bits of a slice of bytes, MSB-first,
+    /// or returns an error if the slice has fewer than `len` bits, for example
+    /// when the length comes from untrusted input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BitsError, BVec};
+    ///
+    /// assert_eq!(BVec::try_from_bytes(&[0xFF, 0xFF], 10).unwrap().count_ones(), 10);
+    /// assert_eq!(
+    ///     BVec::try_from_bytes(&[0xFF], 10),
+    ///     Err(BitsError::NotEnoughBytes { bits: 10, bytes: 1 })
+    /// );
+    /// ```
+    pub fn try_from_bytes(bytes: &[u8], len: usize) -> Result<Self, BitsError> {
+        let n = len.div_ceil(super::U8SIZE);
+        if n > bytes.len() {
+            return Err(BitsError::NotEnoughBytes {
+                bits: len,
+                bytes: bytes.len(),
+            });
+        }
+
+        let mut bvec = BVec {
+            vec: bytes[..n].to_vec(),
+            len,
+        };
+        bvec.mask_trailing();
+        Ok(bvec)
+    }
+
+    /// Returns the bytes which store the bits of the vector, MSB-first.
+    /// The bits of the last byte beyond the length of the vector are zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(4);
+    /// bvec.set_bit(9);
+    /// assert_eq!(bvec.as_bytes(), [0b0000_1000, 0b0100_0000]);
+    /// ```
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.vec[..self.bytes_len()]
+    }
+
+    /// Consumes the vector and returns the bytes which store its bits, MSB-first.
+    /// The bits of the last byte beyond the length of the vector are zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// bvec.set_bit(4);
+    /// assert_eq!(bvec.into_bytes(), vec![0b0000_1000, 0]);
+    /// ```
+    pub fn into_bytes(mut self) -> Vec<u8> {
+        self.mask_trailing();
+        self.vec.truncate(self.bytes_len());
+        self.vec
+    }
+
+    /// Returns a guard which gives mutable access to the bytes storing the bits of the vector,
+    /// MSB-first, so external routines can process them in place. The length of the vector
+    /// cannot change, and the padding bits of the last byte are cleared when the guard is dropped.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(10);
+    /// {
+    ///     let mut bytes = bvec.bytes_mut();
+    ///     bytes.iter_mut().for_each(|b| *b = 0xFF);
+    /// }
+    ///
+    /// assert_eq!(bvec.count_ones(), 10);
+    /// assert_eq!(bvec.as_bytes(), [0xFF, 0b1100_0000]);
+    /// ```
+    pub fn bytes_mut(&mut self) -> BytesMut<'_> {
+        BytesMut { bvec: self }
+    }
+
+    /// Panics if the position is not smaller than the length of the vector.
+    #[inline]
+    fn check_bounds(&self, bit: usize) {
+        if let Err(err) = check_index(bit, self.len) {
+            panic!("{err}");
+        }
+    }
+
+    /// Returns the number of bytes needed to store the bits of the vector.
+    #[inline]
+    fn bytes_len(&self) -> usize {
+        self.len.div_ceil(super::U8SIZE)
+    }
+
+    /// Returns the byte at a given index, with the bits beyond the length of the vector cleared.
+    #[inline]
+    fn byte_at(&self, idx: usize) -> u8 {
+        let n = self.bytes_len();
+        if idx + 1 < n {
+            self.vec[idx]
+        } else if idx + 1 == n {
+            self.vec[idx] & self.last_byte_mask()
+        } else {
+            0
+        }
+    }
+
+    /// Returns the mask of the valid bits in the last byte.
+    #[inline]
+    fn last_byte_mask(&self) -> u8 {
+        match self.len % super::U8SIZE {
+            0 => u8::MAX,
+            bits => u8::MAX << (super::U8SIZE - bits),
+        }
+    }
+
+    /// Clears the padding bits beyond the length of the vector.
+    fn mask_trailing(&mut self) {
+        let n = self.bytes_len();
+        if n > 0 {
+            self.vec[n - 1] &= self.last_byte_mask();
+        }
+        for byte in self.vec.iter_mut().skip(n) {
+            *byte = 0;
+        }
+    }
+
+    /// Combines two vectors byte by byte. The result has the length of the longer vector,
+    /// the shorter one being padded with zero bits.
+    fn combine<F>(&self, other: &BVec, f: F) -> BVec
+    where
+        F: Fn(u64, u64) -> u64,
+    {
+        let len = self.len.max(other.len);
+        let mut res = BVec {
+            vec: combine_with(self, other, f),
+            len,
+        };
+        res.mask_trailing();
+        res
+    }
+}
+
+/// Returns the number of bits set to one in the bitwise `f` of two vectors.
+/// The shorter vector is padded with zero bits.
+fn count_ones_with<F: Fn(u64, u64) -> u64>(xs: &BVec, ys: &BVec, f: F) -> usize {
+    let start = bulk_len(xs, ys);
+    let rest = (start..xs.bytes_len().max(ys.bytes_len()))
+        .map(|i| f(xs.byte_at(i) as u64, ys.byte_at(i) as u64).count_ones() as usize)
+        .sum::<usize>();
+
+    bulk_count_ones(&xs.vec[..start], &ys.vec[..start], &f) + rest
+}
+
+/// Returns the bytes of the bitwise `f` of two vectors.
+/// The shorter vector is padded with zero bits.
+fn combine_with<F: Fn(u64, u64) -> u64>(xs: &BVec, ys: &BVec, f: F) -> Vec<u8> {
+    let start = bulk_len(xs, ys);
+    let mut vec = bulk_combine(&xs.vec[..start], &ys.vec[..start], &f);
+    vec.extend(
+        (start..xs.bytes_len().max(ys.bytes_len()))
+            .map(|i| f(xs.byte_at(i) as u64, ys.byte_at(i) as u64) as u8),
+    );
+    vec
+}
+
+/// Returns the number of leading bytes which are processed a word at a time: with the `simd`
+/// feature, the full bytes of both vectors, as the last ones go through [`BVec::byte_at`]
+/// which masks their padding bits. Without it, everything is processed a byte at a time.
+#[cfg(feature = "simd")]
+fn bulk_len(xs: &BVec, ys: &BVec) -> usize {
+    xs.bytes_len().min(ys.bytes_len()).saturating_sub(1)
+}
+
+#[cfg(feature = "simd")]
+fn bulk_count_ones<F: Fn(u64, u64) -> u64>(xs: &[u8], ys: &[u8], f: F) -> usize {
+    super::simd::count_ones_with(xs, ys, f)
+}
+
+#[cfg(feature = "simd")]
+fn bulk_combine<F: Fn(u64, u64) -> u64>(xs: &[u8], ys: &[u8], f: F) -> Vec<u8> {
+    super::simd::combine_with(xs, ys, f)
+}
+
+#[cfg(not(feature = "simd"))]
+fn bulk_len(_: &BVec, _: &BVec) -> usize {
+    0
+}
+
+#[cfg(not(feature = "simd"))]
+fn bulk_count_ones<F: Fn(u64, u64) -> u64>(_: &[u8], _: &[u8], _: F) -> usize {
+    0
+}
+
+#[cfg(not(feature = "simd"))]
+fn bulk_combine<F: Fn(u64, u64) -> u64>(_: &[u8], _: &[u8], _: F) -> Vec<u8> {
+    vec![]
+}
+
+/// Lazy bitwise combinations, for when the combined vector is only consumed once.
+impl BVec {
+    /// Returns an iterator over the bitwise AND of two vectors, without allocating the result.
+    /// The iteration has the length of the longer vector, the shorter one being padded with zero bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let xs = BVec::from_bytes(&[0b1100_0000], 4);
+    /// let ys = BVec::from_bytes(&[0b1010_0000], 3);
+    /// let bits: Vec<Bit> = xs.and_iter(&ys).collect();
+    /// assert_eq!(bits, [Bit::One, Bit::Zero, Bit::Zero, Bit::Zero]);
+    /// assert!(xs.and_iter(&ys).eq((&xs & &ys).iter()));
+    /// ```
+    pub fn and_iter<'a>(&'a self, other: &'a BVec) -> CombineIter<'a> {
+        CombineIter::new(self, other, |x, y| x & y)
+    }
+
+    /// Returns an iterator over the bitwise OR of two vectors, without allocating the result.
+    /// The iteration has the length of the longer vector, the shorter one being padded with zero bits.
+    pub fn or_iter<'a>(&'a self, other: &'a BVec) -> CombineIter<'a> {
+        CombineIter::new(self, other, |x, y| x | y)
+    }
+
+    /// Returns an iterator over the bitwise XOR of two vectors, without allocating the result.
+    /// The iteration has the length of the longer vector, the shorter one being padded with zero bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let xs = BVec::from_bytes(&[0b1100_0000], 4);
+    /// let ys = BVec::from_bytes(&[0b1010_0000], 4);
+    /// assert_eq!(xs.xor_iter(&ys).filter(|b| *b == Bit::One).count(), xs.hamming(&ys));
+    /// ```
+    pub fn xor_iter<'a>(&'a self, other: &'a BVec) -> CombineIter<'a> {
+        CombineIter::new(self, other, |x, y| x ^ y)
+    }
+}
+
+/// An iterator over the bitwise combination of two vectors.
+///
+/// The `struct` is created by the [`BVec::and_iter`], [`BVec::or_iter`] and [`BVec::xor_iter`]
+/// methods. See their documentation for more.
+pub struct CombineIter<'a> {
+    xs: &'a BVec,
+    ys: &'a BVec,
+    // the range of the remaining positions
+    current: usize,
+    len: usize,
+    op: fn(Bit, Bit) -> Bit,
+}
+
+impl<'a> CombineIter<'a> {
+    fn new(xs: &'a BVec, ys: &'a BVec, op: fn(Bit, Bit) -> Bit) -> Self {
+        Self {
+            xs,
+            ys,
+            current: 0,
+            len: xs.len.max(ys.len),
+            op,
+        }
+    }
+}
+
+impl Iterator for CombineIter<'_> {
+    type Item = Bit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current >= self.len {
+            return None;
+        }
+
+        let x = self.xs.get(self.current).unwrap_or(Bit::Zero);
+        let y = self.ys.get(self.current).unwrap_or(Bit::Zero);
+        self.current += 1;
+        Some((self.op)(x, y))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.len - self.current;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for CombineIter<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current >= self.len {
+            return None;
+        }
+
+        self.len -= 1;
+        let x = self.xs.get(self.len).unwrap_or(Bit::Zero);
+        let y = self.ys.get(self.len).unwrap_or(Bit::Zero);
+        Some((self.op)(x, y))
+    }
+}
+
+impl ExactSizeIterator for CombineIter<'_> {}
+
+impl FusedIterator for CombineIter<'_> {}
+
+/// Bitwise AND between two vectors. The result has the length of the longer vector,
+/// the shorter one being padded with zero bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let mut xs = BVec::with_length(10);
+/// xs.set_bit(1);
+/// xs.set_bit(9);
+///
+/// let mut ys = BVec::with_length(4);
+/// ys.set_bit(1);
+///
+/// let zs = &xs & &ys;
+/// assert_eq!(zs.len(), 10);
+/// assert_eq!(zs.get_bit(1), Bit::One);
+/// assert_eq!(zs.get_bit(9), Bit::Zero);
+/// ```
+impl BitAnd<&BVec> for &BVec {
+    type Output = BVec;
+
+    fn bitand(self, rhs: &BVec) -> Self::Output {
+        self.combine(rhs, |x, y| x & y)
+    }
+}
+
+impl BitAnd<BVec> for BVec {
+    type Output = BVec;
+
+    #[inline]
+    fn bitand(self, rhs: BVec) -> Self::Output {
+        &self & &rhs
+    }
+}
+
+/// Bitwise OR between two vectors. The result has the length of the longer vector,
+/// the shorter one being padded with zero bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let mut xs = BVec::with_length(10);
+/// xs.set_bit(9);
+///
+/// let mut ys = BVec::with_length(4);
+/// ys.set_bit(1);
+///
+/// let zs = xs | ys;
+/// assert_eq!(zs.len(), 10);
+/// assert_eq!(zs.get_bit(1), Bit::One);
+/// assert_eq!(zs.get_bit(9), Bit::One);
+/// ```
+impl BitOr<&BVec> for &BVec {
+    type Output = BVec;
+
+    fn bitor(self, rhs: &BVec) -> Self::Output {
+        self.combine(rhs, |x, y| x | y)
+    }
+}
+
+impl BitOr<BVec> for BVec {
+    type Output = BVec;
+
+    #[inline]
+    fn bitor(self, rhs: BVec) -> Self::Output {
+        &self | &rhs
+    }
+}
+
+/// Bitwise XOR between two vectors. The result has the length of the longer vector,
+/// the shorter one being padded with zero bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let mut xs = BVec::with_length(10);
+/// xs.set_bit(1);
+/// xs.set_bit(9);
+///
+/// let mut ys = BVec::with_length(4);
+/// ys.set_bit(1);
+///
+/// let zs = xs ^ ys;
+/// assert_eq!(zs.get_bit(1), Bit::Zero);
+/// assert_eq!(zs.get_bit(9), Bit::One);
+/// ```
+impl BitXor<&BVec> for &BVec {
+    type Output = BVec;
+
+    fn bitxor(self, rhs: &BVec) -> Self::Output {
+        self.combine(rhs, |x, y| x ^ y)
+    }
+}
+
+impl BitXor<BVec> for BVec {
+    type Output = BVec;
+
+    #[inline]
+    fn bitxor(self, rhs: BVec) -> Self::Output {
+        &self ^ &rhs
+    }
+}
+
+/// Flips all the bits of the vector. The padding bits beyond the length of the vector stay cleared.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let mut xs = BVec::with_length(10);
+/// xs.set_bit(1);
+///
+/// let ys = !&xs;
+/// assert_eq!(ys.len(), 10);
+/// assert_eq!(ys.get_bit(0), Bit::One);
+/// assert_eq!(ys.get_bit(1), Bit::Zero);
+/// assert_eq!(ys.get_bit(9), Bit::One);
+/// ```
+impl Not for &BVec {
+    type Output = BVec;
+
+    fn not(self) -> Self::Output {
+        let vec = (0..self.bytes_len()).map(|i| !self.byte_at(i)).collect();
+
+        let mut res = BVec { vec, len: self.len };
+        res.mask_trailing();
+        res
+    }
+}
+
+impl Not for BVec {
+    type Output = BVec;
+
+    #[inline]
+    fn not(self) -> Self::Output {
+        !&self
+    }
+}
+
+/// Shifts the bits towards position 0, keeping the length of the vector.
+/// The bits shifted past position 0 are dropped and the vacated positions at the end are zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0b1011_0000, 0b1100_0000], 10);
+/// assert_eq!((&bvec << 2).to_string(), "1100001100");
+/// assert_eq!((&bvec << 10).count_ones(), 0);
+/// ```
+impl Shl<usize> for &BVec {
+    type Output = BVec;
+
+    fn shl(self, rhs: usize) -> Self::Output {
+        let (q, r) = (rhs / super::U8SIZE, rhs % super::U8SIZE);
+
+        let vec = (0..self.bytes_len())
+            .map(|i| {
+                let hi = self.byte_at(i.saturating_add(q));
+                let lo = self.byte_at(i.saturating_add(q).saturating_add(1));
+                if r == 0 {
+                    hi
+                } else {
+                    (hi << r) | (lo >> (super::U8SIZE - r))
+                }
+            })
+            .collect();
+
+        let mut res = BVec { vec, len: self.len };
+        res.mask_trailing();
+        res
+    }
+}
+
+impl Shl<usize> for BVec {
+    type Output = BVec;
+
+    #[inline]
+    fn shl(self, rhs: usize) -> Self::Output {
+        &self << rhs
+    }
+}
+
+/// Shifts the bits away from position 0, keeping the length of the vector.
+/// The bits shifted past the end are dropped and the vacated positions at the start are zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0b1011_0000, 0b1100_0000], 10);
+/// assert_eq!((&bvec >> 3).to_string(), "0001011000");
+/// assert_eq!((&bvec >> 12).count_ones(), 0);
+/// ```
+impl Shr<usize> for &BVec {
+    type Output = BVec;
+
+    fn shr(self, rhs: usize) -> Self::Output {
+        let (q, r) = (rhs / super::U8SIZE, rhs % super::U8SIZE);
+
+        let vec = (0..self.bytes_len())
+            .map(|i| {
+                let hi = i.checked_sub(q).map_or(0, |j| self.byte_at(j));
+                let lo = i.checked_sub(q + 1).map_or(0, |j| self.byte_at(j));
+                if r == 0 {
+                    hi
+                } else {
+                    (hi >> r) | (lo << (super::U8SIZE - r))
+                }
+            })
+            .collect();
+
+        let mut res = BVec { vec, len: self.len };
+        res.mask_trailing();
+        res
+    }
+}
+
+impl Shr<usize> for BVec {
+    type Output = BVec;
+
+    #[inline]
+    fn shr(self, rhs: usize) -> Self::Output {
+        &self >> rhs
+    }
+}
+
+/// Returns the bit value from a given position.
+///
+/// # Panics
+///
+/// Panics if the position is not smaller than the length of the vector.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let mut bvec = BVec::with_length(10);
+/// bvec.set_bit(4);
+/// assert_eq!(bvec[4], Bit::One);
+/// assert_eq!(bvec[5], Bit::Zero);
+/// ```
+impl Index<usize> for BVec {
+    type Output = Bit;
+
+    fn index(&self, index: usize) -> &Self::Output {
+        match self.get_bit(index) {
+            Bit::Zero => &Bit::Zero,
+            Bit::One => &Bit::One,
+        }
+    }
+}
+
+/// Collects the bits into a vector, the first bit being stored at position 0.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let bvec: BVec = [Bit::One, Bit::Zero, Bit::One].into_iter().collect();
+/// assert_eq!(bvec.len(), 3);
+/// assert_eq!(bvec.get_bit(2), Bit::One);
+/// ```
+impl FromIterator<Bit> for BVec {
+    fn from_iter<T: IntoIterator<Item = Bit>>(iter: T) -> Self {
+        let mut bvec = BVec::with_length(0);
+        bvec.extend(iter);
+        bvec
+    }
+}
+
+impl FromIterator<bool> for BVec {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
+        BVec::from_iter(iter.into_iter().map(Bit::from))
+    }
+}
+
+impl FromIterator<u8> for BVec {
+    #[inline]
+    fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Self {
+        BVec::from_iter(iter.into_iter().map(Bit::from))
+    }
+}
+
+/// Appends the bits, growing the backing bytes geometrically.
+impl Extend<Bit> for BVec {
+    fn extend<T: IntoIterator<Item = Bit>>(&mut self, iter: T) {
+        let iter = iter.into_iter();
+        self.reserve(iter.size_hint().0);
+
+        for bit in iter {
+            self.push(bit);
+        }
+    }
+}
+
+/// A guard giving mutable access to the bytes of a [`BVec`], created by [`BVec::bytes_mut`].
+/// The padding bits beyond the length of the vector are cleared on drop.
+pub struct BytesMut<'a> {
+    bvec: &'a mut BVec,
+}
+
+impl Deref for BytesMut<'_> {
+    type Target = [u8];
+
+    #[inline]
+    fn deref(&self) -> &Self::Target {
+        self.bvec.as_bytes()
+    }
+}
+
+impl DerefMut for BytesMut<'_> {
+    #[inline]
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        let n = self.bvec.bytes_len();
+        &mut self.bvec.vec[..n]
+    }
+}
+
+impl Drop for BytesMut<'_> {
+    fn drop(&mut self) {
+        self.bvec.mask_trailing();
+    }
+}
+
+/// An owning iterator over the bits of a [`BVec`].
+pub struct BVecIntoIter {
+    bvec: BVec,
+    current: usize,
+    end: usize,
+}
+
+impl Iterator for BVecIntoIter {
+    type Item = Bit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current >= self.end {
+            None
+        } else {
+            let bit = self.bvec.get_bit(self.current);
+            self.current += 1;
+            Some(bit)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.current;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for BVecIntoIter {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current >= self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(self.bvec.get_bit(self.end))
+        }
+    }
+}
+
+impl ExactSizeIterator for BVecIntoIter {}
+
+impl FusedIterator for BVecIntoIter {}
+
+impl BVec {
+    /// Writes the digits produced by `digit` for the chunks of `bits` bits of the vector,
+    /// separating the bytes with `_` when `grouped` is true.
+    fn fmt_digits<F>(
+        &self,
+        f: &mut std::fmt::Formatter<'_>,
+        bits: usize,
+        grouped: bool,
+        digit: F,
+    ) -> std::fmt::Result
+    where
+        F: Fn(u8) -> char,
+    {
+        let per_byte = super::U8SIZE / bits;
+        let mask = (1u8 << bits) - 1;
+
+        for i in 0..self.len.div_ceil(bits) {
+            if grouped && i > 0 && i % per_byte == 0 {
+                write!(f, "_")?;
+            }
+
+            let byte = self.byte_at(i / per_byte);
+            let shift = super::U8SIZE - bits * (i % per_byte + 1);
+            write!(f, "{}", digit((byte >> shift) & mask))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Clones only the bytes which store the bits of the vector.
+impl Clone for BVec {
+    fn clone(&self) -> Self {
+        Self {
+            vec: (0..self.bytes_len()).map(|i| self.byte_at(i)).collect(),
+            len: self.len,
+        }
+    }
+}
+
+/// Two vectors are equal when they have the same length and the same bits,
+/// regardless of their capacity or of how they were built.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BVec};
+///
+/// let mut xs = BVec::from_bytes(&[0xFF], 8);
+/// xs.truncate(3);
+///
+/// let ys: BVec = [Bit::One, Bit::One, Bit::One].into_iter().collect();
+/// assert_eq!(xs, ys);
+/// assert_ne!(xs, BVec::from_bytes(&[0xFF], 4));
+/// ```
+impl PartialEq for BVec {
+    fn eq(&self, other: &Self) -> bool {
+        self.len == other.len && (0..self.bytes_len()).all(|i| self.byte_at(i) == other.byte_at(i))
+    }
+}
+
+impl Eq for BVec {}
+
+/// Hashes the length and the bits of the vector, consistently with the equality.
+impl Hash for BVec {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.len.hash(state);
+        for i in 0..self.bytes_len() {
+            self.byte_at(i).hash(state);
+        }
+    }
+}
+
+/// Formats the bits of the vector, starting with the bit at position 0.
+/// The alternate flag (`{:#}`) separates the bytes with `_`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0b1010_0000, 0b1100_0000], 10);
+/// assert_eq!(format!("{}", bvec), "1010000011");
+/// assert_eq!(format!("{:#}", bvec), "10100000_11");
+/// ```
+impl Display for BVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        Binary::fmt(self, f)
+    }
+}
+
+/// Formats the length and the bits of the vector, separating the bytes with `_`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0b1010_0000, 0b1100_0000], 10);
+/// assert_eq!(format!("{:?}", bvec), "BVec(10:10100000_11)");
+/// ```
+impl Debug for BVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BVec({}:", self.len)?;
+        self.fmt_digits(f, 1, true, |d| char::from(b'0' + d))?;
+        write!(f, ")")
+    }
+}
+
+/// Formats the bits of the vector, starting with the bit at position 0.
+/// The alternate flag (`{:#b}`) separates the bytes with `_`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0b1010_0000, 0b1100_0000], 10);
+/// assert_eq!(format!("{:b}", bvec), "1010000011");
+/// assert_eq!(format!("{:#b}", bvec), "10100000_11");
+/// ```
+impl Binary for BVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_digits(f, 1, f.alternate(), |d| char::from(b'0' + d))
+    }
+}
+
+/// Formats the bits of the vector as hexadecimal digits, each digit covering four bits.
+/// A last incomplete digit is padded with zero bits.
+/// The alternate flag (`{:#x}`) separates the bytes with `_`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0xAB, 0xC0], 10);
+/// assert_eq!(format!("{:x}", bvec), "abc");
+/// assert_eq!(format!("{:#x}", bvec), "ab_c");
+/// ```
+impl LowerHex for BVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_digits(f, 4, f.alternate(), |d| {
+            char::from_digit(d as u32, 16).unwrap_or('0')
+        })
+    }
+}
+
+/// Formats the bits of the vector as uppercase hexadecimal digits, each digit covering four bits.
+/// A last incomplete digit is padded with zero bits.
+/// The alternate flag (`{:#X}`) separates the bytes with `_`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::BVec;
+///
+/// let bvec = BVec::from_bytes(&[0xAB, 0xC0], 10);
+/// assert_eq!(format!("{:X}", bvec), "ABC");
+/// assert_eq!(format!("{:#X}", bvec), "AB_C");
+/// ```
+impl UpperHex for BVec {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.fmt_digits(f, 4, f.alternate(), |d| {
+            char::from_digit(d as u32, 16)
+                .unwrap_or('0')
+                .to_ascii_uppercase()
+        })
+    }
+}
+
+/// An iterator over the positions of the bits of a [`BVec`] with a given value.
+///
+/// The `struct` is created by the [`ones`] and [`zeros`] methods on [`BVec`]. See their documentation for more.
+///
+/// [`ones`]: BVec::ones
+/// [`zeros`]: BVec::zeros
+pub struct BitPositions<'a> {
+    bvec: &'a BVec,
+    // xor-ed with each byte, so the searched bits become ones
+    flip: u8,
+    // the index of the next byte to load
+    next_idx: usize,
+    // the searched bits of the current byte which were not yielded yet
+    byte: u8,
+    // the position of the first bit of the current byte
+    base: usize,
+}
+
+impl<'a> BitPositions<'a> {
+    fn new(bvec: &'a BVec, flip: u8) -> Self {
+        Self {
+            bvec,
+            flip,
+            next_idx: 0,
+            byte: 0,
+            base: 0,
+        }
+    }
+}
+
+impl Iterator for BitPositions<'_> {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.byte == 0 {
+            let n = self.bvec.bytes_len();
+            if self.next_idx >= n {
+                return None;
+            }
+
+            let mask = if self.next_idx + 1 == n {
+                self.bvec.last_byte_mask()
+            } else {
+                u8::MAX
+            };
+
+            self.byte = (self.bvec.vec[self.next_idx] ^ self.flip) & mask;
+            self.base = self.next_idx * super::U8SIZE;
+            self.next_idx += 1;
+        }
+
+        let bit = self.byte.leading_zeros() as usize;
+        self.byte &= !(0x80 >> bit);
+        Some(self.base + bit)
+    }
+}
+
+/// A borrowing iterator over the bits of a [`BVec`].
+///
+/// The `struct` is created by the [`iter`] method on [`BVec`]. See its documentation for more.
+///
+/// [`iter`]: BVec::iter
+pub struct BVecIter<'a> {
+    bvec: &'a BVec,
+    // the range of the remaining positions
+    current: usize,
+    end: usize,
+}
+
+impl Iterator for BVecIter<'_> {
+    type Item = Bit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.current >= self.end {
+            None
+        } else {
+            let bit = self.bvec.get_bit(self.current);
+            self.current += 1;
+            Some(bit)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.current;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for BVecIter<'_> {
+    /// Returns the bits from the end of the vector.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let bvec = BVec::from_bits_msb([Bit::One, Bit::Zero, Bit::Zero]);
+    /// let mut iter = bvec.iter();
+    /// assert_eq!(iter.len(), 3);
+    /// assert_eq!(iter.next_back(), Some(Bit::Zero));
+    /// assert_eq!(iter.next(), Some(Bit::One));
+    /// assert_eq!(iter.len(), 1);
+    ///
+    /// let reversed: Vec<Bit> = bvec.iter().rev().collect();
+    /// assert_eq!(reversed, [Bit::Zero, Bit::Zero, Bit::One]);
+    /// ```
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.current >= self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(self.bvec.get_bit(self.end))
+        }
+    }
+}
+
+impl ExactSizeIterator for BVecIter<'_> {}
+
+impl FusedIterator for BVecIter<'_> {}
+
+impl<'a> IntoIterator for &'a BVec {
+    type Item = Bit;
+
+    type IntoIter = BVecIter<'a>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl IntoIterator for BVec {
+    type Item = Bit;
+
+    type IntoIter = BVecIntoIter;
+
+    fn into_iter(self) -> Self::IntoIter {
+        BVecIntoIter {
+            end: self.len,
+            bvec: self,
+            current: 0,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn with_length_() {
+        let bvec = BVec::with_length(10);
+        assert_eq!(10, bvec.len());
+        assert_eq!(2, bvec.vec.capacity());
+    }
+
+    #[test]
+    fn set_bit_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(4);
+        bvec.set_bit(6);
+        assert_eq!(10, bvec.vec[0]);
+    }
+
+    #[test]
+    fn reset_bit_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(4);
+        bvec.set_bit(6);
+        assert_eq!(10, bvec.vec[0]);
+
+        bvec.reset_bit(6);
+        assert_eq!(8, bvec.vec[0]);
+    }
+
+    #[test]
+    fn get_bit_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(4);
+        bvec.set_bit(6);
+
+        assert_eq!(bvec.get_bit(0), Bit::Zero);
+        assert_eq!(bvec.get_bit(4), Bit::One);
+    }
+
+    #[test]
+    #[should_panic(expected = "bit index out of bounds")]
+    fn get_bit_out_of_bounds_() {
+        let bvec = BVec::with_length(10);
+        let _ = bvec.get_bit(12);
+    }
+
+    #[test]
+    fn get_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(9);
+
+        assert_eq!(bvec.get(0), Some(Bit::Zero));
+        assert_eq!(bvec.get(9), Some(Bit::One));
+        assert_eq!(bvec.get(10), None);
+        assert_eq!(bvec.get(usize::MAX), None);
+    }
+
+    #[test]
+    fn try_bit_() {
+        let mut bvec = BVec::with_length(10);
+        assert_eq!(bvec.try_set_bit(9), Ok(()));
+        assert_eq!(bvec.try_get_bit(9), Ok(Bit::One));
+        assert_eq!(bvec.try_reset_bit(9), Ok(()));
+        assert_eq!(bvec.try_get_bit(9), Ok(Bit::Zero));
+
+        let err = BitsError::OutOfRange {
+            index: usize::MAX,
+            len: 10,
+        };
+        assert_eq!(bvec.try_get_bit(usize::MAX), Err(err));
+        assert_eq!(bvec.try_set_bit(usize::MAX), Err(err));
+        assert_eq!(bvec.try_reset_bit(usize::MAX), Err(err));
+        assert_eq!(bvec.count_ones(), 0);
+
+        assert_eq!(BVec::try_from_bytes(&[], 0), Ok(BVec::with_length(0)));
+        assert!(BVec::try_from_bytes(&[1], 9)
+            .unwrap_err()
+            .to_string()
+            .starts_with("not enough bytes"));
+    }
+
+    #[test]
+    fn index_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(4);
+
+        assert_eq!(bvec[0], Bit::Zero);
+        assert_eq!(bvec[4], Bit::One);
+    }
+
+    #[test]
+    #[should_panic]
+    fn index_out_of_bounds_() {
+        let bvec = BVec::with_length(10);
+        let _ = bvec[10];
+    }
+
+    #[test]
+    #[should_panic(expected = "bit index out of bounds")]
+    fn set_bit_out_of_bounds_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(10);
+    }
+
+    #[test]
+    fn from_bytes_() {
+        let bvec = BVec::from_bytes(&[10, 255, 1], 12);
+        assert_eq!(12, bvec.len());
+        assert_eq!(bvec.vec, [10, 240]);
+        assert_eq!(bvec.count_ones(), 6);
+    }
+
+    #[test]
+    fn from_bytes_empty_() {
+        let bvec = BVec::from_bytes(&[], 0);
+        assert!(bvec.is_empty());
+        assert!(bvec.as_bytes().is_empty());
+    }
+
+    #[test]
+    #[should_panic(expected = "not enough bytes")]
+    fn from_bytes_short_() {
+        let _ = BVec::from_bytes(&[1], 9);
+    }
+
+    #[test]
+    fn as_into_bytes_() {
+        let bytes = [1, 2, 3, 4];
+        let bvec = BVec::from_bytes(&bytes, 32);
+        assert_eq!(bvec.as_bytes(), bytes);
+        assert_eq!(bvec.into_bytes(), bytes);
+
+        let bvec: BVec = [Bit::One; 9].into_iter().collect();
+        assert_eq!(bvec.as_bytes(), [255, 128]);
+        assert_eq!(bvec.into_bytes(), [255, 128]);
+    }
+
+    #[test]
+    fn toggle_bit_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.toggle_bit(4);
+        bvec.toggle_bit(6);
+
+        assert_eq!(10, bvec.vec[0]);
+    }
+
+    #[test]
+    fn bvec_into_iter_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.toggle_bit(4);
+        bvec.toggle_bit(6);
+
+        let mut iter = bvec.into_iter();
+
+        assert_eq!(iter.next(), Some(Bit::Zero));
+        assert_eq!(iter.next(), Some(Bit::Zero));
+        assert_eq!(iter.next(), Some(Bit::Zero));
+        assert_eq!(iter.next(), Some(Bit::Zero));
+
+        assert_eq!(iter.next(), Some(Bit::One));
+        assert_eq!(iter.next(), Some(Bit::Zero));
+        assert_eq!(iter.next(), Some(Bit::One));
+        assert_eq!(iter.next(), Some(Bit::Zero));
+
+        assert_eq!(iter.next(), Some(Bit::Zero));
+        assert_eq!(iter.next(), Some(Bit::Zero));
+
+        assert_eq!(iter.next(), None);
+    }
+
+    #[test]
+    fn bitand_() {
+        let mut xs = BVec::with_length(12);
+        xs.set_bit(0);
+        xs.set_bit(4);
+        xs.set_bit(11);
+
+        let mut ys = BVec::with_length(12);
+        ys.set_bit(4);
+        ys.set_bit(11);
+
+        let zs = &xs & &ys;
+        assert_eq!(12, zs.len());
+        assert_eq!(zs.vec, [8, 16]);
+
+        let zs = xs & ys;
+        assert_eq!(zs.vec, [8, 16]);
+    }
+
+    #[test]
+    fn bitor_() {
+        let mut xs = BVec::with_length(12);
+        xs.set_bit(0);
+
+        let mut ys = BVec::with_length(12);
+        ys.set_bit(4);
+        ys.set_bit(11);
+
+        let zs = &xs | &ys;
+        assert_eq!(zs.vec, [136, 16]);
+
+        let zs = xs | ys;
+        assert_eq!(zs.vec, [136, 16]);
+    }
+
+    #[test]
+    fn bitxor_() {
+        let mut xs = BVec::with_length(12);
+        xs.set_bit(0);
+        xs.set_bit(4);
+
+        let mut ys = BVec::with_length(12);
+        ys.set_bit(4);
+        ys.set_bit(11);
+
+        let zs = &xs ^ &ys;
+        assert_eq!(zs.vec, [128, 16]);
+
+        let zs = xs ^ ys;
+        assert_eq!(zs.vec, [128, 16]);
+    }
+
+    #[test]
+    fn bitops_different_lengths_() {
+        let mut xs = BVec::with_length(3);
+        xs.set_bit(0);
+        xs.set_bit(2);
+
+        let mut ys = BVec::with_length(10);
+        ys.set_bit(2);
+        ys.set_bit(9);
+
+        let zs = &xs & &ys;
+        assert_eq!(10, zs.len());
+        assert_eq!(zs.vec, [32, 0]);
+
+        let zs = &xs | &ys;
+        assert_eq!(10, zs.len());
+        assert_eq!(zs.vec, [160, 64]);
+
+        let zs = &ys ^ &xs;
+        assert_eq!(10, zs.len());
+        assert_eq!(zs.vec, [128, 64]);
+    }
+
+    #[test]
+    fn bitops_padding_() {
+        // bits set beyond the length of the vector are ignored
+        let mut xs = BVec::with_length(4);
+        xs.vec[0] |= 2;
+
+        let ys = BVec::with_length(4);
+        let zs = &xs | &ys;
+        assert_eq!(zs.vec, [0]);
+    }
+
+    #[test]
+    fn not_() {
+        let mut xs = BVec::with_length(10);
+        xs.set_bit(0);
+        xs.set_bit(9);
+
+        let ys = !&xs;
+        assert_eq!(10, ys.len());
+        assert_eq!(ys.vec, [127, 128]);
+
+        let zs = !ys;
+        assert_eq!(zs.vec, xs.vec);
+    }
+
+    #[test]
+    fn not_empty_() {
+        let xs = !BVec::with_length(0);
+        assert!(xs.is_empty());
+        assert!(xs.vec.is_empty());
+    }
+
+    #[test]
+    fn not_full_bytes_() {
+        let xs = !BVec::with_length(16);
+        assert_eq!(xs.vec, [255, 255]);
+    }
+
+    #[test]
+    fn count_ones_() {
+        let mut bvec = BVec::with_length(20);
+        assert_eq!(bvec.count_ones(), 0);
+        assert_eq!(bvec.count_zeros(), 20);
+
+        bvec.set_bit(0);
+        bvec.set_bit(8);
+        bvec.set_bit(19);
+        assert_eq!(bvec.count_ones(), 3);
+        assert_eq!(bvec.count_zeros(), 17);
+    }
+
+    #[test]
+    fn count_ones_padding_() {
+        let mut bvec = BVec::with_length(4);
+        bvec.set_bit(3);
+        bvec.vec[0] |= 4;
+        assert_eq!(bvec.count_ones(), 1);
+        assert_eq!(bvec.count_zeros(), 3);
+
+        let bvec = !BVec::with_length(13);
+        assert_eq!(bvec.count_ones(), 13);
+    }
+
+    #[test]
+    fn rank_() {
+        let mut bvec = BVec::with_length(20);
+        bvec.set_bit(0);
+        bvec.set_bit(7);
+        bvec.set_bit(8);
+        bvec.set_bit(19);
+
+        let ranks: Vec<usize> = (0..=20).map(|i| bvec.rank1(i)).collect();
+        assert_eq!(
+            ranks,
+            [0, 1, 1, 1, 1, 1, 1, 1, 2, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 3, 4]
+        );
+        assert_eq!(bvec.rank0(20), 16);
+    }
+
+    #[test]
+    #[should_panic]
+    fn rank_out_of_bounds_() {
+        let bvec = BVec::with_length(4);
+        let _ = bvec.rank1(5);
+    }
+
+    #[test]
+    fn select_() {
+        let mut bvec = BVec::with_length(20);
+        bvec.set_bit(0);
+        bvec.set_bit(7);
+        bvec.set_bit(8);
+        bvec.set_bit(19);
+
+        let ones: Vec<usize> = (0..4).filter_map(|k| bvec.select1(k)).collect();
+        assert_eq!(ones, [0, 7, 8, 19]);
+        assert_eq!(bvec.select1(4), None);
+
+        assert_eq!(bvec.select0(0), Some(1));
+        assert_eq!(bvec.select0(6), Some(9));
+        assert_eq!(bvec.select0(15), Some(18));
+        assert_eq!(bvec.select0(16), None);
+    }
+
+    #[test]
+    fn select_rank_() {
+        let mut bvec = BVec::with_length(50);
+        for i in (0..50).step_by(3) {
+            bvec.set_bit(i);
+        }
+
+        for k in 0..bvec.count_ones() {
+            let pos = bvec.select1(k).unwrap();
+            assert_eq!(bvec.rank1(pos), k);
+        }
+        for k in 0..bvec.count_zeros() {
+            let pos = bvec.select0(k).unwrap();
+            assert_eq!(bvec.rank0(pos), k);
+        }
+    }
+
+    #[test]
+    fn iter_msb_lsb_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_bit(0);
+        bvec.set_bit(8);
+
+        let msb: Vec<u8> = bvec.iter_msb().map(u8::from).collect();
+        assert_eq!(msb, [1, 0, 0, 0, 0, 0, 0, 0, 1, 0]);
+
+        let lsb: Vec<u8> = bvec.iter_lsb().map(u8::from).collect();
+        assert_eq!(lsb, [0, 1, 0, 0, 0, 0, 0, 0, 0, 1]);
+    }
+
+    #[test]
+    fn from_bits_msb_lsb_() {
+        let bits = [1, 1, 0, 0, 0, 0, 0, 0, 0, 1].map(Bit::from);
+
+        let bvec = BVec::from_bits_msb(bits);
+        assert_eq!(10, bvec.len());
+        assert_eq!(bvec.vec[0], 192);
+
+        let bvec = BVec::from_bits_lsb(bits);
+        assert_eq!(10, bvec.len());
+        assert_eq!(bvec.get_bit(0), Bit::One);
+        assert_eq!(bvec.get_bit(8), Bit::One);
+        assert_eq!(bvec.get_bit(9), Bit::One);
+        assert_eq!(bvec.count_ones(), 3);
+
+        let roundtrip: Vec<Bit> = bvec.iter_lsb().collect();
+        assert_eq!(roundtrip, bits);
+    }
+
+    #[quickcheck]
+    fn double_ended_(bits: Vec<bool>, front: usize) -> bool {
+        let bvec: BVec = bits.iter().copied().collect();
+        let front = front % (bits.len() + 1);
+
+        // some bits from the front, the others from the back
+        let mut iter = bvec.iter();
+        let head: Vec<Bit> = iter.by_ref().take(front).collect();
+        let len = iter.len();
+        let mut tail: Vec<Bit> = iter.rev().collect();
+        tail.reverse();
+
+        let owned: Vec<Bit> = bvec.clone().into_iter().rev().collect();
+        len == bits.len() - front
+            && [head, tail].concat() == bvec.iter().collect::<Vec<Bit>>()
+            && owned == bvec.iter_lsb().collect::<Vec<Bit>>()
+            && bvec.xor_iter(&bvec).rev().len() == bits.len()
+    }
+
+    #[test]
+    fn from_iter_bits_() {
+        let bvec: BVec = [Bit::Zero, Bit::One, Bit::One].into_iter().collect();
+        assert_eq!(3, bvec.len());
+        assert_eq!(bvec.vec[0], 96);
+    }
+
+    #[test]
+    fn from_iter_bools_() {
+        let bvec = BVec::from_iter([false, false, false, false, true, false, true, false, true]);
+        assert_eq!(9, bvec.len());
+        assert_eq!(bvec.vec[0], 10);
+        assert_eq!(bvec.get_bit(8), Bit::One);
+    }
+
+    #[test]
+    fn from_iter_u8s_() {
+        let bvec = BVec::from_iter([0_u8, 0, 0, 0, 1, 0, 1, 0, 0, 7]);
+        assert_eq!(10, bvec.len());
+        assert_eq!(bvec.vec[0], 10);
+        assert_eq!(bvec.get_bit(9), Bit::One);
+    }
+
+    #[test]
+    fn from_iter_empty_() {
+        let bvec: BVec = std::iter::empty::<Bit>().collect();
+        assert!(bvec.is_empty());
+    }
+
+    #[test]
+    fn extend_() {
+        let mut bvec = BVec::with_length(0);
+        assert_eq!(0, bvec.len);
+        assert_eq!(0, bvec.vec.capacity());
 
         let elements = [Bit::One, Bit::Zero, Bit::One, Bit::Zero];
         bvec.extend(elements);
@@ -271,4 +2491,462 @@ mod tests {
         assert_eq!(bvec.get_bit(10), Bit::One);
         assert_eq!(bvec.get_bit(11), Bit::Zero);
     }
+
+    #[test]
+    fn push_pop_() {
+        let mut bvec = BVec::with_length(0);
+        for i in 0..20 {
+            bvec.push(Bit::from(i % 3 == 0));
+        }
+
+        assert_eq!(bvec.len(), 20);
+        assert_eq!(bvec.as_bytes().len(), 3);
+        assert_eq!(bvec.count_ones(), 7);
+
+        for i in (0..20).rev() {
+            assert_eq!(bvec.pop(), Some(Bit::from(i % 3 == 0)));
+        }
+
+        assert_eq!(bvec.pop(), None);
+        assert!(bvec.vec.iter().all(|b| *b == 0));
+    }
+
+    #[test]
+    fn pop_push_padding_() {
+        let mut bvec = BVec::from_bytes(&[0xFF], 8);
+        assert_eq!(bvec.pop(), Some(Bit::One));
+        bvec.push(Bit::Zero);
+        assert_eq!(bvec.as_bytes(), [0b1111_1110]);
+    }
+
+    #[test]
+    fn truncate_() {
+        let mut bvec = BVec::from_bytes(&[0xFF, 0xFF, 0xFF], 24);
+        bvec.truncate(30);
+        assert_eq!(bvec.len(), 24);
+
+        bvec.truncate(10);
+        assert_eq!(bvec.len(), 10);
+        assert_eq!(bvec.vec, [0xFF, 0b1100_0000]);
+
+        bvec.push(Bit::Zero);
+        assert_eq!(bvec.as_bytes(), [0xFF, 0b1100_0000]);
+
+        bvec.truncate(0);
+        assert!(bvec.is_empty());
+        assert!(bvec.vec.is_empty());
+    }
+
+    #[test]
+    fn resize_() {
+        let mut bvec = BVec::with_length(3);
+        bvec.set_bit(0);
+
+        bvec.resize(5, Bit::One);
+        assert_eq!(bvec.as_bytes(), [0b1001_1000]);
+
+        bvec.resize(20, Bit::Zero);
+        assert_eq!(bvec.as_bytes(), [0b1001_1000, 0, 0]);
+
+        bvec.resize(30, Bit::One);
+        assert_eq!(bvec.as_bytes(), [0b1001_1000, 0, 0b0000_1111, 0b1111_1100]);
+        assert_eq!(bvec.count_ones(), 13);
+
+        bvec.resize(4, Bit::One);
+        assert_eq!(bvec.vec, [0b1001_0000]);
+    }
+
+    #[test]
+    fn iter_() {
+        let bvec = BVec::from_bytes(&[0b1010_0000, 0b1000_0000], 9);
+
+        let mut iter = bvec.iter();
+        assert_eq!(iter.size_hint(), (9, Some(9)));
+        assert_eq!(iter.next(), Some(Bit::One));
+        assert_eq!(iter.size_hint(), (8, Some(8)));
+
+        // the vector can be scanned repeatedly
+        assert_eq!(bvec.iter().filter(|b| *b == Bit::One).count(), 3);
+
+        let mut ones = 0;
+        for bit in &bvec {
+            if bit == Bit::One {
+                ones += 1;
+            }
+        }
+        assert_eq!(ones, 3);
+
+        let bits: Vec<Bit> = bvec.iter().collect();
+        assert_eq!(bits, bvec.into_iter().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn ones_zeros_() {
+        let bvec = BVec::from_bytes(&[0, 0b0100_0001, 0, 0xFF, 0b1000_0000], 33);
+
+        let ones: Vec<usize> = bvec.ones().collect();
+        assert_eq!(ones, [9, 15, 24, 25, 26, 27, 28, 29, 30, 31, 32]);
+
+        let zeros: Vec<usize> = bvec.zeros().collect();
+        assert_eq!(zeros.len(), 33 - ones.len());
+        assert!(zeros.iter().all(|i| bvec.get_bit(*i) == Bit::Zero));
+        assert_eq!(zeros.last(), Some(&23));
+    }
+
+    #[test]
+    fn ones_zeros_empty_() {
+        let bvec = BVec::with_length(0);
+        assert_eq!(bvec.ones().count(), 0);
+        assert_eq!(bvec.zeros().count(), 0);
+
+        let bvec = BVec::with_length(5);
+        assert_eq!(bvec.ones().count(), 0);
+        assert_eq!(bvec.zeros().collect::<Vec<_>>(), [0, 1, 2, 3, 4]);
+    }
+
+    #[quickcheck]
+    fn ones_select_(bytes: Vec<u8>) -> bool {
+        let bvec = BVec::from_bytes(&bytes, bytes.len() * 8);
+        bvec.ones()
+            .enumerate()
+            .all(|(k, i)| bvec.select1(k) == Some(i))
+            && bvec.zeros().count() == bvec.count_zeros()
+    }
+
+    #[test]
+    fn fmt_() {
+        let bvec = BVec::from_bytes(&[0x0F, 0xF0, 0x80], 17);
+        assert_eq!(format!("{}", bvec), "00001111111100001");
+        assert_eq!(format!("{:#b}", bvec), "00001111_11110000_1");
+        assert_eq!(format!("{:x}", bvec), "0ff08");
+        assert_eq!(format!("{:#X}", bvec), "0F_F0_8");
+        assert_eq!(format!("{:?}", bvec), "BVec(17:00001111_11110000_1)");
+    }
+
+    #[test]
+    fn fmt_empty_() {
+        let bvec = BVec::with_length(0);
+        assert_eq!(format!("{}", bvec), "");
+        assert_eq!(format!("{:#x}", bvec), "");
+        assert_eq!(format!("{:?}", bvec), "BVec(0:)");
+    }
+
+    #[test]
+    fn fmt_full_bytes_() {
+        let bvec = BVec::from_bytes(&[0x12, 0x34], 16);
+        assert_eq!(format!("{:#x}", bvec), "12_34");
+        assert_eq!(format!("{:#b}", bvec), "00010010_00110100");
+    }
+
+    fn hash_of(bvec: &BVec) -> u64 {
+        use std::collections::hash_map::DefaultHasher;
+
+        let mut hasher = DefaultHasher::new();
+        bvec.hash(&mut hasher);
+        hasher.finish()
+    }
+
+    #[test]
+    fn eq_hash_() {
+        let mut xs = BVec::with_length(0);
+        xs.extend([Bit::One, Bit::Zero, Bit::One]);
+
+        let mut ys = BVec::from_bytes(&[0b1011_0000], 5);
+        ys.truncate(3);
+
+        assert_eq!(xs.vec.len(), 1);
+        assert_eq!(xs, ys);
+        assert_eq!(hash_of(&xs), hash_of(&ys));
+
+        let zs = BVec::from_bytes(&[0b1010_0000], 4);
+        assert_ne!(xs, zs);
+        assert_ne!(xs, BVec::from_bytes(&[0b1000_0000], 3));
+    }
+
+    #[test]
+    fn clone_() {
+        let mut xs = BVec::with_length(0);
+        xs.extend([Bit::One, Bit::Zero, Bit::One]);
+
+        let ys = xs.clone();
+        assert_eq!(xs, ys);
+        assert_eq!(ys.vec, [0b1010_0000]);
+
+        xs.toggle_bit(1);
+        assert_ne!(xs, ys);
+    }
+
+    #[quickcheck]
+    fn eq_from_bits_(bits: Vec<bool>) -> bool {
+        let xs: BVec = bits.iter().copied().collect();
+        let mut ys = BVec::with_length(0);
+        for bit in &bits {
+            ys.push(Bit::from(*bit));
+        }
+
+        xs == ys && hash_of(&xs) == hash_of(&ys) && xs.clone() == ys
+    }
+
+    #[test]
+    fn shl_shr_() {
+        let bvec = BVec::from_bytes(&[0xFF, 0x0F, 0xF0], 20);
+
+        assert_eq!(&bvec << 0, bvec);
+        assert_eq!(&bvec >> 0, bvec);
+        assert_eq!((&bvec << 4).vec, [0xF0, 0xFF, 0x00]);
+        assert_eq!((&bvec << 8).vec, [0x0F, 0xF0, 0x00]);
+        assert_eq!((&bvec >> 4).vec, [0x0F, 0xF0, 0xF0]);
+        assert_eq!((&bvec >> 12).vec, [0x00, 0x0F, 0xF0]);
+        assert_eq!((bvec.clone() << 25).count_ones(), 0);
+        assert_eq!((bvec >> usize::MAX).count_ones(), 0);
+    }
+
+    #[test]
+    fn shl_padding_() {
+        // the padding bits of the source are never shifted in
+        let bvec = BVec::from_bytes(&[0xFF, 0xFF], 12);
+        assert_eq!((&bvec >> 2).vec, [0x3F, 0xF0]);
+        assert_eq!((&bvec << 2).vec, [0xFF, 0xC0]);
+    }
+
+    #[quickcheck]
+    fn shl_shr_bits_(bytes: Vec<u8>, n: u8) -> bool {
+        let n = n as usize;
+        let bvec = BVec::from_bytes(&bytes, bytes.len() * 8);
+        let left = &bvec << n;
+        let right = &bvec >> n;
+
+        (0..bvec.len()).all(|i| {
+            left.get_bit(i) == bvec.get(i + n).unwrap_or(Bit::Zero)
+                && right.get_bit(i) == i.checked_sub(n).map_or(Bit::Zero, |j| bvec.get_bit(j))
+        })
+    }
+
+    #[test]
+    fn rotate_() {
+        let mut bvec = BVec::from_bytes(&[0xF0, 0x0F, 0xA0], 20);
+        let original = bvec.clone();
+
+        bvec.rotate_left(20);
+        assert_eq!(bvec, original);
+
+        bvec.rotate_left(4);
+        assert_eq!(bvec.to_string(), "00000000111110101111");
+
+        bvec.rotate_right(24);
+        assert_eq!(bvec, original);
+
+        let mut empty = BVec::with_length(0);
+        empty.rotate_left(3);
+        empty.rotate_right(3);
+        assert!(empty.is_empty());
+    }
+
+    #[quickcheck]
+    fn rotate_bits_(bits: Vec<bool>, n: usize) -> bool {
+        let mut bvec: BVec = bits.iter().copied().collect();
+        bvec.rotate_left(n);
+
+        let len = bits.len();
+        let left = (0..len).all(|i| bvec.get_bit(i) == Bit::from(bits[(i + n % len) % len]));
+
+        bvec.rotate_right(n);
+        left && bvec == bits.into_iter().collect()
+    }
+
+    #[quickcheck]
+    fn reverse_(bits: Vec<bool>) -> bool {
+        let mut bvec: BVec = bits.iter().copied().collect();
+        bvec.reverse();
+
+        let expected: BVec = bits.into_iter().rev().collect();
+        bvec == expected && bvec.as_bytes() == expected.as_bytes()
+    }
+
+    #[test]
+    fn set_reset_range_() {
+        let mut bvec = BVec::with_length(30);
+        bvec.set_range(5..5);
+        assert_eq!(bvec.count_ones(), 0);
+
+        bvec.set_range(5..7);
+        assert_eq!(bvec.vec[..4], [0b0000_0110, 0, 0, 0]);
+
+        bvec.set_range(6..30);
+        assert_eq!(bvec.vec[..4], [0b0000_0111, 0xFF, 0xFF, 0b1111_1100]);
+
+        bvec.reset_range(8..16);
+        assert_eq!(bvec.vec[..4], [0b0000_0111, 0, 0xFF, 0b1111_1100]);
+
+        bvec.reset_range(0..30);
+        assert_eq!(bvec.count_ones(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn set_range_out_of_bounds_() {
+        let mut bvec = BVec::with_length(10);
+        bvec.set_range(5..11);
+    }
+
+    #[test]
+    fn fill_() {
+        let mut bvec = BVec::with_length(13);
+        bvec.fill(Bit::One);
+        assert_eq!(bvec.as_bytes(), [0xFF, 0b1111_1000]);
+
+        bvec.fill(Bit::Zero);
+        assert_eq!(bvec.as_bytes(), [0, 0]);
+
+        let mut empty = BVec::with_length(0);
+        empty.fill(Bit::One);
+        assert!(empty.is_empty());
+    }
+
+    #[quickcheck]
+    fn set_range_bits_(bits: Vec<bool>, a: usize, b: usize) -> bool {
+        let mut bvec: BVec = bits.iter().copied().collect();
+        let len = bits.len() + 1;
+        let (start, end) = ((a % len).min(b % len), (a % len).max(b % len));
+
+        bvec.set_range(start..end);
+        (0..bits.len()).all(|i| {
+            let expected = bits[i] || (start..end).contains(&i);
+            bvec.get_bit(i) == Bit::from(expected)
+        })
+    }
+
+    #[test]
+    fn first_next_() {
+        let bvec = BVec::from_bytes(&[0, 0, 0b0001_0000, 0xFF, 0b1100_0000], 34);
+        assert_eq!(bvec.first_one(), Some(19));
+        assert_eq!(bvec.next_one(20), Some(24));
+        assert_eq!(bvec.next_one(33), Some(33));
+        assert_eq!(bvec.next_one(34), None);
+
+        assert_eq!(bvec.first_zero(), Some(0));
+        assert_eq!(bvec.next_zero(19), Some(20));
+        assert_eq!(bvec.next_zero(24), None);
+
+        let empty = BVec::with_length(0);
+        assert_eq!(empty.first_one(), None);
+        assert_eq!(empty.first_zero(), None);
+    }
+
+    #[quickcheck]
+    fn next_one_zero_(bytes: Vec<u8>, from: usize) -> bool {
+        let bvec = BVec::from_bytes(&bytes, bytes.len() * 8);
+        let from = from % (bvec.len() + 1);
+
+        bvec.next_one(from) == bvec.ones().find(|i| *i >= from)
+            && bvec.next_zero(from) == bvec.zeros().find(|i| *i >= from)
+    }
+
+    #[test]
+    fn hamming_() {
+        let xs = BVec::from_bytes(&[0xFF, 0xFF, 0xFF], 20);
+        let ys = BVec::with_length(20);
+        assert_eq!(xs.hamming(&ys), 20);
+        assert_eq!(xs.hamming(&xs), 0);
+        assert_eq!(ys.hamming(&BVec::with_length(0)), 0);
+        assert_eq!(xs.hamming(&BVec::with_length(0)), 20);
+    }
+
+    #[quickcheck]
+    fn set_counts_(xs: Vec<bool>, ys: Vec<bool>) -> bool {
+        let xs: BVec = xs.into_iter().collect();
+        let ys: BVec = ys.into_iter().collect();
+        let (and, or) = (xs.intersection_count(&ys), xs.union_count(&ys));
+
+        and == (&xs & &ys).count_ones()
+            && or == (&xs | &ys).count_ones()
+            && or - and == xs.hamming(&ys)
+            && and + or == xs.count_ones() + ys.count_ones()
+    }
+
+    #[quickcheck]
+    fn combine_iter_(xs: Vec<bool>, ys: Vec<bool>) -> bool {
+        let xs: BVec = xs.into_iter().collect();
+        let ys: BVec = ys.into_iter().collect();
+
+        xs.and_iter(&ys).eq((&xs & &ys).iter())
+            && xs.or_iter(&ys).eq((&xs | &ys).iter())
+            && ys.xor_iter(&xs).eq((&xs ^ &ys).iter())
+            && xs.or_iter(&ys).count() == xs.len().max(ys.len())
+    }
+
+    #[quickcheck]
+    fn hamming_bits_(xs: Vec<bool>, ys: Vec<bool>) -> bool {
+        let len = xs.len().min(ys.len());
+        let (xs, ys) = (&xs[..len], &ys[..len]);
+        let expected = xs.iter().zip(ys).filter(|(x, y)| x != y).count();
+
+        let xs: BVec = xs.iter().copied().collect();
+        let ys: BVec = ys.iter().copied().collect();
+        xs.hamming(&ys) == expected && ys.hamming(&xs) == expected
+    }
+
+    #[quickcheck]
+    fn tanimoto_bits_(xs: Vec<bool>, ys: Vec<bool>) -> bool {
+        let both = xs.iter().zip(&ys).filter(|(x, y)| **x && **y).count();
+        let either = xs.iter().filter(|x| **x).count() + ys.iter().filter(|y| **y).count() - both;
+        let expected = if either == 0 {
+            0.
+        } else {
+            both as f32 / either as f32
+        };
+
+        let xs: BVec = xs.into_iter().collect();
+        let ys: BVec = ys.into_iter().collect();
+        xs.tanimoto(&ys) == expected && ys.tanimoto(&xs) == expected
+    }
+
+    #[test]
+    fn bytes_mut_() {
+        // the guard sees only the bytes of the bits, not the spare capacity
+        let mut bvec = BVec::with_capacity(100);
+        bvec.extend([Bit::One; 3]);
+        {
+            let mut bytes = bvec.bytes_mut();
+            assert_eq!(bytes.len(), 1);
+            assert_eq!(bytes[0], 0b1110_0000);
+            bytes[0] = 0b0101_1111;
+        }
+
+        assert_eq!(bvec.len(), 3);
+        assert_eq!(bvec.as_bytes(), [0b0100_0000]);
+        assert_eq!(bvec, BVec::from_bytes(&[0b0100_0000], 3));
+
+        let mut empty = BVec::with_length(0);
+        assert!(empty.bytes_mut().is_empty());
+    }
+
+    #[test]
+    fn capacity_() {
+        let mut bvec = BVec::with_capacity(20);
+        assert_eq!(bvec.len(), 0);
+        assert!(bvec.capacity() >= 20);
+
+        bvec.extend((0..20).map(|i| Bit::from(i % 2 == 0)));
+        assert_eq!(bvec.vec.len(), 3);
+        assert!(bvec.capacity() >= 24);
+
+        bvec.reserve(100);
+        assert!(bvec.capacity() >= 120);
+        assert_eq!(bvec.vec.len(), 3);
+        assert_eq!(bvec.count_ones(), 10);
+    }
+
+    #[test]
+    fn extend_bookkeeping_() {
+        // the capacity is larger than the tracked bytes
+        let mut bvec = BVec::with_capacity(1_000);
+        bvec.extend([Bit::One; 9]);
+        assert_eq!(bvec.vec, [0xFF, 0x80]);
+
+        let mut bvec = BVec::from_bytes(&[0xFF], 3);
+        bvec.extend(std::iter::repeat_n(Bit::Zero, 10));
+        assert_eq!(bvec.len(), 13);
+        assert_eq!(bvec.vec, [0b1110_0000, 0]);
+    }
 }
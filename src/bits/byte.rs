@@ -1,5 +1,7 @@
-use super::Bit;
+use super::{check_index, Bit, BitsError};
 use std::fmt::{Binary, Debug, Display, LowerHex, UpperHex};
+use std::iter::FusedIterator;
+use std::ops::{Shl, Shr};
 
 const MASKS_SET: [u8; 8] = [1 << 7, 1 << 6, 1 << 5, 1 << 4, 1 << 3, 1 << 2, 1 << 1, 1];
 const MASKS_RESET: [u8; 8] = [
@@ -78,6 +80,40 @@ impl From<Byte> for u8 {
     }
 }
 
+/// Shifts the bits towards the most significant one, filling with zeros.
+/// Shifting by 8 or more bits gives a zero byte.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, Byte};
+///
+/// let byte = Byte::from(0b0100_0001) << 1;
+/// assert_eq!(byte.get_bit(0), Bit::One);
+/// assert_eq!(byte, Byte::from(0b1000_0010));
+/// assert_eq!(byte >> 7, Byte::from(1));
+/// assert!((byte << 8).is_zero());
+/// ```
+impl Shl<u32> for Byte {
+    type Output = Self;
+
+    #[inline]
+    fn shl(self, n: u32) -> Self::Output {
+        Self(self.0.checked_shl(n).unwrap_or(0))
+    }
+}
+
+/// Shifts the bits towards the least significant one, filling with zeros.
+/// Shifting by 8 or more bits gives a zero byte.
+impl Shr<u32> for Byte {
+    type Output = Self;
+
+    #[inline]
+    fn shr(self, n: u32) -> Self::Output {
+        Self(self.0.checked_shr(n).unwrap_or(0))
+    }
+}
+
 impl FromIterator<Bit> for Byte {
     fn from_iter<T: IntoIterator<Item = Bit>>(iter: T) -> Self {
         iter.into_iter()
@@ -95,14 +131,14 @@ impl FromIterator<Bit> for Byte {
 impl FromIterator<bool> for Byte {
     #[inline]
     fn from_iter<T: IntoIterator<Item = bool>>(iter: T) -> Self {
-        Byte::from_iter(iter.into_iter().map(|b| Bit::from(b)))
+        Byte::from_iter(iter.into_iter().map(Bit::from))
     }
 }
 
 impl FromIterator<u8> for Byte {
     #[inline]
     fn from_iter<T: IntoIterator<Item = u8>>(iter: T) -> Self {
-        Byte::from_iter(iter.into_iter().map(|x| Bit::from(x)))
+        Byte::from_iter(iter.into_iter().map(Bit::from))
     }
 }
 
@@ -141,61 +177,288 @@ impl Byte {
         Self(self.0 ^ mask)
     }
 
+    /// Returns the bit value from a given position, or an error if the position is not smaller than 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BitsError, Byte};
+    ///
+    /// let byte = Byte::from(0b1000_0000);
+    /// assert_eq!(byte.try_get_bit(0), Ok(Bit::One));
+    /// assert_eq!(byte.try_get_bit(8), Err(BitsError::OutOfRange { index: 8, len: 8 }));
+    /// assert_eq!(byte.try_set_bit(7), Ok(Byte::from(0b1000_0001)));
+    /// ```
+    #[inline]
+    pub fn try_get_bit(&self, bit: u8) -> Result<Bit, BitsError> {
+        check_index(bit as usize, super::U8SIZE)?;
+        Ok(self.get_bit(bit))
+    }
+
+    /// Sets the bit value from a given position, or returns an error if the position is not smaller than 8.
+    #[inline]
+    pub fn try_set_bit(self, bit: u8) -> Result<Self, BitsError> {
+        check_index(bit as usize, super::U8SIZE)?;
+        Ok(self.set_bit(bit))
+    }
+
+    /// Resets the bit value from a given position, or returns an error if the position is not smaller than 8.
+    #[inline]
+    pub fn try_reset_bit(self, bit: u8) -> Result<Self, BitsError> {
+        check_index(bit as usize, super::U8SIZE)?;
+        Ok(self.reset_bit(bit))
+    }
+
+    /// Returns the number of bits set to one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::Byte;
+    ///
+    /// let byte = Byte::from(0b0010_1100);
+    /// assert_eq!(byte.count_ones(), 3);
+    /// assert_eq!(byte.count_zeros(), 5);
+    /// assert_eq!(byte.leading_zeros(), 2);
+    /// assert_eq!(byte.trailing_zeros(), 2);
+    /// ```
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns the number of bits set to zero.
+    #[inline]
+    pub fn count_zeros(&self) -> u32 {
+        self.0.count_zeros()
+    }
+
+    /// Returns the number of zero bits before the first one, starting with the bit `0`,
+    /// which is the most significant bit. A zero byte has 8 leading zeros.
     #[inline]
-    pub fn iter(&self) -> Iter {
-        Iter {
-            byte: self.clone(),
+    pub fn leading_zeros(&self) -> u32 {
+        self.0.leading_zeros()
+    }
+
+    /// Returns the number of zero bits after the last one, up to the bit `7`,
+    /// which is the least significant bit. A zero byte has 8 trailing zeros.
+    #[inline]
+    pub fn trailing_zeros(&self) -> u32 {
+        self.0.trailing_zeros()
+    }
+
+    /// Returns the byte with the order of its bits reversed, so the bit `0` becomes the bit `7`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::Byte;
+    ///
+    /// assert_eq!(Byte::from(0b1100_0010).reverse_bits(), Byte::from(0b0100_0011));
+    /// ```
+    #[inline]
+    pub fn reverse_bits(self) -> Self {
+        Self(self.0.reverse_bits())
+    }
+
+    /// Returns the byte with its bits rotated towards the most significant one,
+    /// so the bit `0` wraps around to the bit `7`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::Byte;
+    ///
+    /// assert_eq!(Byte::from(0b1000_0011).rotate_left(1), Byte::from(0b0000_0111));
+    /// assert_eq!(Byte::from(0b1000_0011).rotate_right(2), Byte::from(0b1110_0000));
+    /// ```
+    #[inline]
+    pub fn rotate_left(self, n: u32) -> Self {
+        Self(self.0.rotate_left(n))
+    }
+
+    /// Returns the byte with its bits rotated towards the least significant one,
+    /// so the bit `7` wraps around to the bit `0`.
+    #[inline]
+    pub fn rotate_right(self, n: u32) -> Self {
+        Self(self.0.rotate_right(n))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> ByteIter {
+        self.iter_msb()
+    }
+
+    /// Returns an iterator over the bits, starting with the most significant one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Byte};
+    ///
+    /// let mut iter = Byte::from(1).iter_msb();
+    /// assert_eq!(iter.next(), Some(Bit::Zero));
+    /// assert_eq!(iter.last(), Some(Bit::One));
+    /// ```
+    #[inline]
+    pub fn iter_msb(&self) -> ByteIter {
+        ByteIter {
+            byte: *self,
             crnt: 0,
+            end: super::U8SIZE as u8,
+            lsb_first: false,
         }
     }
+
+    /// Returns an iterator over the bits, starting with the least significant one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Byte};
+    ///
+    /// let mut iter = Byte::from(1).iter_lsb();
+    /// assert_eq!(iter.next(), Some(Bit::One));
+    /// assert_eq!(iter.last(), Some(Bit::Zero));
+    /// ```
+    #[inline]
+    pub fn iter_lsb(&self) -> ByteIter {
+        ByteIter {
+            byte: *self,
+            crnt: 0,
+            end: super::U8SIZE as u8,
+            lsb_first: true,
+        }
+    }
+
+    /// Creates a byte from at most 8 bits, starting with the most significant one.
+    /// This is the same as collecting the bits with [`FromIterator`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Byte};
+    ///
+    /// let byte = Byte::from_bits_msb([Bit::One, Bit::Zero, Bit::One]);
+    /// assert_eq!(byte, Byte::from(0b1010_0000));
+    /// ```
+    #[inline]
+    pub fn from_bits_msb<T: IntoIterator<Item = Bit>>(bits: T) -> Self {
+        Byte::from_iter(bits)
+    }
+
+    /// Creates a byte from at most 8 bits, starting with the least significant one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Byte};
+    ///
+    /// let byte = Byte::from_bits_lsb([Bit::One, Bit::Zero, Bit::One]);
+    /// assert_eq!(byte, Byte::from(0b0000_0101));
+    /// ```
+    pub fn from_bits_lsb<T: IntoIterator<Item = Bit>>(bits: T) -> Self {
+        bits.into_iter()
+            .take(super::U8SIZE)
+            .enumerate()
+            .fold(0.into(), |acc, (bit, item)| {
+                if item == Bit::One {
+                    acc.set_bit((super::U8SIZE - 1 - bit) as u8)
+                } else {
+                    acc
+                }
+            })
+    }
 }
 
 impl IntoIterator for Byte {
     type Item = Bit;
 
-    type IntoIter = Iter;
+    type IntoIter = ByteIter;
 
     #[inline]
     fn into_iter(self) -> Self::IntoIter {
-        Iter {
-            byte: self,
-            crnt: 0,
-        }
+        self.iter_msb()
     }
 }
 
 /// Represents an iterator over a byte.
 /// The elements of the iteration are [`Bit`] instances.
-pub struct Iter {
+pub struct ByteIter {
     byte: Byte,
+    // the range of the remaining steps of the iteration
     crnt: u8,
+    end: u8,
+    lsb_first: bool,
 }
 
-impl Iterator for Iter {
+impl ByteIter {
+    /// Returns the bit visited at a given step of the iteration.
+    #[inline]
+    fn bit_at(&self, step: u8) -> Bit {
+        if self.lsb_first {
+            self.byte.get_bit(7 - step)
+        } else {
+            self.byte.get_bit(step)
+        }
+    }
+}
+
+impl Iterator for ByteIter {
     type Item = Bit;
 
     fn next(&mut self) -> Option<Self::Item> {
-        if self.crnt > 7 {
+        if self.crnt >= self.end {
             None
         } else {
-            let res = self.byte.get_bit(self.crnt);
+            let res = self.bit_at(self.crnt);
             self.crnt += 1;
             Some(res)
         }
     }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.crnt) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for ByteIter {
+    /// Returns the bits from the end of the iteration.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Byte};
+    ///
+    /// let byte = Byte::from(0b1000_0001);
+    /// let bits: Vec<u8> = byte.iter().rev().map(u8::from).collect();
+    /// assert_eq!(bits, [1, 0, 0, 0, 0, 0, 0, 1]);
+    ///
+    /// let mut iter = byte.iter();
+    /// assert_eq!(iter.next_back(), Some(Bit::One));
+    /// assert_eq!(iter.len(), 7);
+    /// ```
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.crnt >= self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(self.bit_at(self.end))
+        }
+    }
 }
 
+impl ExactSizeIterator for ByteIter {}
+
+impl FusedIterator for ByteIter {}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use quickcheck_macros::quickcheck;
 
-    impl quickcheck::Arbitrary for Byte {
-        fn arbitrary(g: &mut quickcheck::Gen) -> Self {
-            u8::arbitrary(g).into()
-        }
-    }
-
     /// Argument for building the Byte from a list of u8 elements.
     #[derive(Clone, Copy, Debug)]
     pub struct Elements01 {
@@ -235,7 +498,7 @@ mod tests {
             let u6 = u8::arbitrary(g) % 2;
             let u7 = u8::arbitrary(g) % 2;
 
-            let xs = [u0, u1, u2, u3, u4, u5, u6, u7].map(|u| if u == 0 { false } else { true });
+            let xs = [u0, u1, u2, u3, u4, u5, u6, u7].map(|u| u != 0);
             ElementsBool { xs }
         }
     }
@@ -243,7 +506,7 @@ mod tests {
     #[quickcheck]
     fn prop_from_into_(x: u8) -> bool {
         let byte = Byte::from(x);
-        x == byte.into()
+        x == u8::from(byte)
     }
 
     #[quickcheck]
@@ -278,7 +541,7 @@ mod tests {
 
     #[quickcheck]
     fn prop_eq_(byte: Byte) -> bool {
-        let byte1 = byte.clone();
+        let byte1 = byte;
         byte1 == byte
     }
 
@@ -299,7 +562,7 @@ mod tests {
     #[quickcheck]
     fn prop_from_u8_(elements: Elements01) -> bool {
         let byte = Byte::from_iter(elements.xs);
-        let iter = byte.iter().map(|b| u8::from(b));
+        let iter = byte.iter().map(u8::from);
         iter.zip(elements.xs).all(|(i, x)| i == x)
     }
 
@@ -411,6 +674,15 @@ mod tests {
         assert_eq!(byte, 8.into());
     }
 
+    #[test]
+    fn try_bit_() {
+        let byte = Byte::from(10);
+        assert_eq!(byte.try_get_bit(6), Ok(Bit::One));
+        assert_eq!(byte.try_reset_bit(6), Ok(Byte::from(8)));
+        assert!(byte.try_set_bit(8).is_err());
+        assert!(byte.try_reset_bit(u8::MAX).is_err());
+    }
+
     #[test]
     fn byte_iter_() {
         let byte = Byte::from(10);
@@ -429,6 +701,53 @@ mod tests {
         assert_eq!(iter.next(), None);
     }
 
+    #[test]
+    fn byte_iter_lsb_() {
+        let byte = Byte::from(10);
+        let bits: Vec<u8> = byte.iter_lsb().map(u8::from).collect();
+        assert_eq!(bits, [0, 1, 0, 1, 0, 0, 0, 0]);
+
+        let bits: Vec<u8> = byte.iter_msb().map(u8::from).collect();
+        assert_eq!(bits, [0, 0, 0, 0, 1, 0, 1, 0]);
+    }
+
+    #[test]
+    fn byte_from_bits_lsb_() {
+        let bits = [0, 1, 0, 1].map(Bit::from);
+        assert_eq!(Byte::from_bits_lsb(bits), Byte::from(10));
+
+        // the extra bits are ignored
+        let bits = [1; 10].map(Bit::from);
+        assert_eq!(Byte::from_bits_lsb(bits), Byte::from(255));
+    }
+
+    #[quickcheck]
+    fn prop_lsb_roundtrip_(byte: Byte) -> bool {
+        Byte::from_bits_lsb(byte.iter_lsb()) == byte
+    }
+
+    #[quickcheck]
+    fn prop_lsb_msb_(byte: Byte) -> bool {
+        let mut msb: Vec<Bit> = byte.iter_msb().collect();
+        msb.reverse();
+        msb == byte.iter_lsb().collect::<Vec<Bit>>()
+    }
+
+    #[quickcheck]
+    fn prop_double_ended_(byte: Byte, front: u8) -> bool {
+        // some bits from the front, the others from the back
+        let front = front as usize % 9;
+        let mut iter = byte.iter();
+        let head: Vec<Bit> = iter.by_ref().take(front).collect();
+        let len = iter.len();
+        let mut tail: Vec<Bit> = iter.rev().collect();
+        tail.reverse();
+
+        len == 8 - front
+            && [head, tail].concat() == byte.iter().collect::<Vec<Bit>>()
+            && byte.iter_lsb().rev().eq(byte.iter_msb())
+    }
+
     #[test]
     fn byte_into_iter_() {
         let byte = Byte::from(10);
@@ -446,4 +765,54 @@ mod tests {
 
         assert_eq!(iter.next(), None);
     }
+
+    #[quickcheck]
+    fn prop_rotate_(byte: Byte, n: u32) -> bool {
+        let bits: Vec<Bit> = byte.iter().collect();
+        let k = (n % 8) as usize;
+        let mut rotated = bits.clone();
+        rotated.rotate_left(k);
+
+        byte.rotate_left(n).iter().eq(rotated) && byte.rotate_left(n).rotate_right(n) == byte
+    }
+
+    #[quickcheck]
+    fn prop_shift_(byte: Byte, n: u8) -> bool {
+        let n = (n % 10) as u32;
+        let shifted_left = (0..8).map(|i| {
+            let src = i + n;
+            if src < 8 {
+                byte.get_bit(src as u8)
+            } else {
+                Bit::Zero
+            }
+        });
+        let shifted_right = (0..8).map(|i| {
+            if i >= n {
+                byte.get_bit((i - n) as u8)
+            } else {
+                Bit::Zero
+            }
+        });
+
+        (byte << n).iter().eq(shifted_left) && (byte >> n).iter().eq(shifted_right)
+    }
+
+    #[quickcheck]
+    fn prop_counts_(byte: Byte) -> bool {
+        let bits: Vec<Bit> = byte.iter().collect();
+        let ones = bits.iter().filter(|b| **b == Bit::One).count() as u32;
+        let leading = bits.iter().take_while(|b| **b == Bit::Zero).count() as u32;
+        let trailing = bits.iter().rev().take_while(|b| **b == Bit::Zero).count() as u32;
+
+        byte.count_ones() == ones
+            && byte.count_zeros() == 8 - ones
+            && byte.leading_zeros() == leading
+            && byte.trailing_zeros() == trailing
+    }
+
+    #[quickcheck]
+    fn prop_reverse_bits_(byte: Byte) -> bool {
+        byte.reverse_bits().iter().eq(byte.iter_lsb()) && byte.reverse_bits().reverse_bits() == byte
+    }
 }
@@ -0,0 +1,405 @@
+use super::{BVec, Bit};
+use std::mem::size_of;
+
+/// The number of bits covered by a container.
+const CHUNK_BITS: usize = 1 << 16;
+/// The number of words of a bitmap container.
+const BITMAP_WORDS: usize = CHUNK_BITS / u64::BITS as usize;
+/// The largest number of ones stored in an array container.
+const ARRAY_MAX: usize = 4096;
+
+/// A vector of bits compressed for sparse content, in the style of
+/// [roaring bitmaps](https://roaringbitmap.org/).
+///
+/// The vector is split into chunks of `2^16` bits and only the chunks with ones are stored,
+/// either as a sorted array of the positions of their ones or, once they hold more than
+/// 4096 ones, as a plain bitmap. A vector with a low density of ones takes about two bytes
+/// per one, instead of one byte per eight bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{BVec, Bit, CompressedBVec};
+///
+/// let mut bits = CompressedBVec::with_length(100_000_000);
+/// bits.set_bit(7);
+/// bits.set_bit(99_999_999);
+///
+/// assert_eq!(bits.get_bit(7), Bit::One);
+/// assert_eq!(bits.get_bit(8), Bit::Zero);
+/// assert_eq!(bits.count_ones(), 2);
+/// assert!(bits.size_in_bytes() < 1_000);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Default)]
+pub struct CompressedBVec {
+    len: usize,
+    // the non-empty containers, sorted by their chunk index
+    containers: Vec<(usize, Container)>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Container {
+    Array(Vec<u16>),
+    Bitmap(Box<[u64; BITMAP_WORDS]>, usize),
+}
+
+impl Container {
+    fn contains(&self, low: u16) -> bool {
+        match self {
+            Container::Array(values) => values.binary_search(&low).is_ok(),
+            Container::Bitmap(words, _) => words[low as usize / 64] & (1 << (low % 64)) != 0,
+        }
+    }
+
+    fn count_ones(&self) -> usize {
+        match self {
+            Container::Array(values) => values.len(),
+            Container::Bitmap(_, count) => *count,
+        }
+    }
+
+    fn insert(&mut self, low: u16) {
+        match self {
+            Container::Array(values) => {
+                if let Err(idx) = values.binary_search(&low) {
+                    values.insert(idx, low);
+                    if values.len() > ARRAY_MAX {
+                        *self = Self::bitmap_from(values);
+                    }
+                }
+            }
+            Container::Bitmap(words, count) => {
+                let (word, mask) = (low as usize / 64, 1 << (low % 64));
+                if words[word] & mask == 0 {
+                    words[word] |= mask;
+                    *count += 1;
+                }
+            }
+        }
+    }
+
+    fn remove(&mut self, low: u16) {
+        match self {
+            Container::Array(values) => {
+                if let Ok(idx) = values.binary_search(&low) {
+                    values.remove(idx);
+                }
+            }
+            Container::Bitmap(words, count) => {
+                let (word, mask) = (low as usize / 64, 1 << (low % 64));
+                if words[word] & mask != 0 {
+                    words[word] &= !mask;
+                    *count -= 1;
+                    if *count <= ARRAY_MAX {
+                        *self = Container::Array(self.values().collect());
+                    }
+                }
+            }
+        }
+    }
+
+    fn bitmap_from(values: &[u16]) -> Self {
+        let mut words = Box::new([0; BITMAP_WORDS]);
+        for low in values {
+            words[*low as usize / 64] |= 1 << (low % 64);
+        }
+        Container::Bitmap(words, values.len())
+    }
+
+    /// Returns the positions of the ones, in increasing order.
+    fn values(&self) -> Box<dyn Iterator<Item = u16> + '_> {
+        match self {
+            Container::Array(values) => Box::new(values.iter().copied()),
+            Container::Bitmap(words, _) => Box::new(
+                words
+                    .iter()
+                    .enumerate()
+                    .flat_map(|(i, word)| {
+                        (0..u64::BITS)
+                            .filter(move |b| word & (1 << b) != 0)
+                            .map(move |b| i as u32 * u64::BITS + b)
+                    })
+                    .map(|low| low as u16),
+            ),
+        }
+    }
+
+    fn size_in_bytes(&self) -> usize {
+        match self {
+            Container::Array(values) => values.capacity() * size_of::<u16>(),
+            Container::Bitmap(..) => BITMAP_WORDS * size_of::<u64>(),
+        }
+    }
+}
+
+impl CompressedBVec {
+    /// Creates a vector of `len` bits set to zero, without allocating.
+    pub fn with_length(len: usize) -> Self {
+        Self {
+            len,
+            containers: Vec::new(),
+        }
+    }
+
+    /// Returns the length of the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the vector has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    #[inline]
+    fn check_bounds(&self, bit: usize) {
+        assert!(
+            bit < self.len,
+            "bit index out of bounds: the len is {} but the index is {}",
+            self.len,
+            bit
+        );
+    }
+
+    /// Returns the chunk index and the position inside the chunk of a bit.
+    #[inline]
+    fn split(bit: usize) -> (usize, u16) {
+        (bit / CHUNK_BITS, (bit % CHUNK_BITS) as u16)
+    }
+
+    fn find(&self, chunk: usize) -> Result<usize, usize> {
+        self.containers.binary_search_by_key(&chunk, |(c, _)| *c)
+    }
+
+    /// Returns the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn get_bit(&self, bit: usize) -> Bit {
+        self.check_bounds(bit);
+
+        let (chunk, low) = Self::split(bit);
+        let one = self
+            .find(chunk)
+            .is_ok_and(|idx| self.containers[idx].1.contains(low));
+        Bit::from(one)
+    }
+
+    /// Sets the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn set_bit(&mut self, bit: usize) {
+        self.check_bounds(bit);
+
+        let (chunk, low) = Self::split(bit);
+        match self.find(chunk) {
+            Ok(idx) => self.containers[idx].1.insert(low),
+            Err(idx) => self
+                .containers
+                .insert(idx, (chunk, Container::Array(vec![low]))),
+        }
+    }
+
+    /// Resets the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn reset_bit(&mut self, bit: usize) {
+        self.check_bounds(bit);
+
+        let (chunk, low) = Self::split(bit);
+        if let Ok(idx) = self.find(chunk) {
+            let container = &mut self.containers[idx].1;
+            container.remove(low);
+            if container.count_ones() == 0 {
+                self.containers.remove(idx);
+            }
+        }
+    }
+
+    /// Returns the number of bits set to one.
+    pub fn count_ones(&self) -> usize {
+        self.containers.iter().map(|(_, c)| c.count_ones()).sum()
+    }
+
+    /// Returns the number of bits set to zero.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.count_ones()
+    }
+
+    /// Returns an iterator over the positions of the bits set to one, in increasing order.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::CompressedBVec;
+    ///
+    /// let mut bits = CompressedBVec::with_length(200_000);
+    /// bits.set_bit(150_000);
+    /// bits.set_bit(3);
+    /// assert_eq!(bits.ones().collect::<Vec<_>>(), [3, 150_000]);
+    /// ```
+    pub fn ones(&self) -> impl Iterator<Item = usize> + '_ {
+        self.containers.iter().flat_map(|(chunk, container)| {
+            container
+                .values()
+                .map(move |low| chunk * CHUNK_BITS + low as usize)
+        })
+    }
+
+    /// Returns the approximate number of heap bytes used by the vector.
+    pub fn size_in_bytes(&self) -> usize {
+        self.containers.capacity() * size_of::<(usize, Container)>()
+            + self
+                .containers
+                .iter()
+                .map(|(_, c)| c.size_in_bytes())
+                .sum::<usize>()
+    }
+
+    /// Returns the vector as an uncompressed [`BVec`].
+    pub fn to_bvec(&self) -> BVec {
+        let mut bvec = BVec::with_length(self.len);
+        self.ones().for_each(|bit| bvec.set_bit(bit));
+        bvec
+    }
+}
+
+impl From<&BVec> for CompressedBVec {
+    fn from(bvec: &BVec) -> Self {
+        let mut containers: Vec<(usize, Container)> = Vec::new();
+        let mut values = Vec::new();
+
+        let mut flush = |chunk: usize, values: &mut Vec<u16>| {
+            if !values.is_empty() {
+                let container = if values.len() > ARRAY_MAX {
+                    Container::bitmap_from(values)
+                } else {
+                    Container::Array(values.clone())
+                };
+                containers.push((chunk, container));
+                values.clear();
+            }
+        };
+
+        let mut current = 0;
+        for bit in bvec.ones() {
+            let (chunk, low) = Self::split(bit);
+            if chunk != current {
+                flush(current, &mut values);
+                current = chunk;
+            }
+            values.push(low);
+        }
+        flush(current, &mut values);
+
+        Self {
+            len: bvec.len(),
+            containers,
+        }
+    }
+}
+
+impl From<BVec> for CompressedBVec {
+    #[inline]
+    fn from(bvec: BVec) -> Self {
+        Self::from(&bvec)
+    }
+}
+
+impl From<&CompressedBVec> for BVec {
+    #[inline]
+    fn from(bits: &CompressedBVec) -> Self {
+        bits.to_bvec()
+    }
+}
+
+impl From<CompressedBVec> for BVec {
+    #[inline]
+    fn from(bits: CompressedBVec) -> Self {
+        bits.to_bvec()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn empty_() {
+        let bits = CompressedBVec::with_length(1_000);
+        assert_eq!(bits.len(), 1_000);
+        assert!(!bits.is_empty());
+        assert_eq!(bits.count_ones(), 0);
+        assert_eq!(bits.count_zeros(), 1_000);
+        assert_eq!(bits.size_in_bytes(), 0);
+        assert!(CompressedBVec::default().is_empty());
+    }
+
+    #[test]
+    fn set_reset_() {
+        let mut bits = CompressedBVec::with_length(300_000);
+        bits.set_bit(70_000);
+        bits.set_bit(70_000);
+        bits.set_bit(5);
+        assert_eq!(bits.count_ones(), 2);
+        assert_eq!(bits.get_bit(70_000), Bit::One);
+
+        bits.reset_bit(70_000);
+        bits.reset_bit(70_001);
+        assert_eq!(bits.get_bit(70_000), Bit::Zero);
+        assert_eq!(bits.ones().collect::<Vec<_>>(), [5]);
+    }
+
+    #[test]
+    fn dense_chunk_() {
+        let mut bits = CompressedBVec::with_length(CHUNK_BITS * 2);
+        (0..CHUNK_BITS).step_by(3).for_each(|b| bits.set_bit(b));
+        assert!(matches!(bits.containers[0].1, Container::Bitmap(..)));
+        assert_eq!(bits.count_ones(), CHUNK_BITS.div_ceil(3));
+        assert!(bits.ones().eq((0..CHUNK_BITS).step_by(3)));
+
+        // removing ones turns the bitmap back into an array
+        (0..CHUNK_BITS)
+            .step_by(3)
+            .skip(10)
+            .for_each(|b| bits.reset_bit(b));
+        assert!(matches!(bits.containers[0].1, Container::Array(_)));
+        assert_eq!(bits.count_ones(), 10);
+    }
+
+    #[test]
+    fn bvec_roundtrip_() {
+        let mut rng = StdRng::seed_from_u64(11);
+        let len = 500_000;
+        let mut bvec = BVec::with_length(len);
+        for _ in 0..2_000 {
+            bvec.set_bit(rng.gen_range(0..len));
+        }
+        // a dense chunk among sparse ones
+        bvec.set_range(CHUNK_BITS * 3..CHUNK_BITS * 3 + 20_000);
+
+        let bits = CompressedBVec::from(&bvec);
+        assert_eq!(bits.count_ones(), bvec.count_ones());
+        assert!(bits.ones().eq(bvec.ones()));
+        assert_eq!(BVec::from(&bits), bvec);
+        assert!(bits.size_in_bytes() < bvec.as_bytes().len() / 2);
+
+        for _ in 0..1_000 {
+            let bit = rng.gen_range(0..len);
+            assert_eq!(bits.get_bit(bit), bvec.get_bit(bit));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds_() {
+        let mut bits = CompressedBVec::with_length(10);
+        bits.set_bit(10);
+    }
+}
@@ -0,0 +1,411 @@
+//! Packing of DNA sequences with two bits per nucleotide.
+//!
+//! The nucleotides `A`, `C`, `G` and `T` are the codes `0` to `3`, so the complement of a
+//! nucleotide is its code XOR `3`. A sequence is packed into a [`BVec`], the first nucleotide
+//! in the first two bits, and a k-mer of at most [`MAX_K`] nucleotides into a `u64` word, the
+//! first nucleotide in the most significant bits, so the order of the words is the
+//! lexicographic order of the k-mers.
+//!
+//! A k-mer and its reverse complement are the same fragment read from the two strands of
+//! the DNA, and the [`canonical`] k-mer, the smaller of the two codes, counts them together.
+//!
+//! # Examples
+//!
+//! ```
+//! use aabel_rs::bits::dna;
+//!
+//! let bvec = dna::pack(b"GATTACA").unwrap();
+//! assert_eq!(bvec.len(), 14);
+//! assert_eq!(dna::unpack(&bvec).unwrap(), b"GATTACA");
+//!
+//! let code = dna::encode_kmer(b"GAT").unwrap();
+//! assert_eq!(code, 0b10_00_11);
+//! assert_eq!(dna::decode_kmer(dna::reverse_complement(code, 3), 3), b"ATC");
+//! assert_eq!(dna::canonical(code, 3), dna::encode_kmer(b"ATC").unwrap());
+//! ```
+
+use super::{unpack as unpack_bits, BVec, Bit, BitsError};
+use std::{iter::FusedIterator, slice};
+
+/// The largest number of nucleotides of a k-mer packed into a `u64`.
+pub const MAX_K: usize = 32;
+
+/// The nucleotides in the order of their codes.
+const NUCLEOTIDES: &[u8; 4] = b"ACGT";
+
+/// Returns the 2-bit code of a nucleotide, in either case, or `None` for any other byte,
+/// such as the `N` of an unknown nucleotide.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::dna;
+///
+/// assert_eq!(dna::encode(b'g'), Some(2));
+/// assert_eq!(dna::encode(b'N'), None);
+/// ```
+#[inline]
+pub fn encode(base: u8) -> Option<u64> {
+    match base {
+        b'A' | b'a' => Some(0),
+        b'C' | b'c' => Some(1),
+        b'G' | b'g' => Some(2),
+        b'T' | b't' => Some(3),
+        _ => None,
+    }
+}
+
+/// Returns the uppercase nucleotide of the two least significant bits of a code.
+#[inline]
+pub fn decode(code: u64) -> u8 {
+    NUCLEOTIDES[(code & 3) as usize]
+}
+
+/// Packs a sequence of nucleotides into a vector of two bits per nucleotide.
+///
+/// # Errors
+///
+/// Returns [`BitsError::InvalidChar`] with the index of the first byte which is not a nucleotide.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{dna, BitsError};
+///
+/// assert_eq!(dna::pack(b"ACGT").unwrap().as_bytes(), [0b00_01_10_11]);
+/// assert_eq!(dna::pack(b"ACNT"), Err(BitsError::InvalidChar { index: 2 }));
+/// ```
+pub fn pack(seq: &[u8]) -> Result<BVec, BitsError> {
+    let mut bvec = BVec::with_capacity(2 * seq.len());
+    for (index, base) in seq.iter().enumerate() {
+        let code = encode(*base).ok_or(BitsError::InvalidChar { index })?;
+        bvec.push(Bit::from(code & 2 != 0));
+        bvec.push(Bit::from(code & 1 != 0));
+    }
+
+    Ok(bvec)
+}
+
+/// Returns the uppercase nucleotides packed with [`pack`].
+///
+/// # Errors
+///
+/// Returns [`BitsError::InvalidLength`] if the length of the vector is odd.
+pub fn unpack(bvec: &BVec) -> Result<Vec<u8>, BitsError> {
+    if !bvec.len().is_multiple_of(2) {
+        return Err(BitsError::InvalidLength { len: bvec.len() });
+    }
+
+    Ok(unpack_bits(bvec, 2).map(decode).collect())
+}
+
+/// Packs a k-mer into a word, the first nucleotide in the most significant bits.
+///
+/// # Errors
+///
+/// Returns [`BitsError::InvalidLength`] if the k-mer has more than [`MAX_K`] nucleotides,
+/// and [`BitsError::InvalidChar`] with the index of the first byte which is not a nucleotide.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::dna;
+///
+/// assert_eq!(dna::encode_kmer(b"CAT"), Ok(0b01_00_11));
+/// assert!(dna::encode_kmer(&[b'A'; 33]).is_err());
+/// ```
+pub fn encode_kmer(kmer: &[u8]) -> Result<u64, BitsError> {
+    if kmer.len() > MAX_K {
+        return Err(BitsError::InvalidLength { len: kmer.len() });
+    }
+
+    kmer.iter().enumerate().try_fold(0, |code, (index, base)| {
+        let bits = encode(*base).ok_or(BitsError::InvalidChar { index })?;
+        Ok((code << 2) | bits)
+    })
+}
+
+/// Returns the uppercase nucleotides of a k-mer of length `k` packed with [`encode_kmer`].
+///
+/// # Panics
+///
+/// Panics if `k` is greater than [`MAX_K`].
+pub fn decode_kmer(code: u64, k: usize) -> Vec<u8> {
+    assert!(k <= MAX_K, "the k-mer length must be at most {MAX_K}");
+    (0..k).rev().map(|i| decode(code >> (2 * i))).collect()
+}
+
+/// Returns the code of the reverse complement of a k-mer of length `k`, the k-mer read
+/// backwards on the other strand, computed with a few word operations.
+///
+/// # Panics
+///
+/// Panics if `k` is not in the `1..=32` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::dna;
+///
+/// let code = dna::encode_kmer(b"AACG").unwrap();
+/// assert_eq!(dna::reverse_complement(code, 4), dna::encode_kmer(b"CGTT").unwrap());
+/// ```
+pub fn reverse_complement(code: u64, k: usize) -> u64 {
+    check_k(k);
+
+    // complement the nucleotides, then reverse the order of the 2-bit groups of the word
+    let x = !code;
+    let x = ((x >> 2) & 0x3333_3333_3333_3333) | ((x & 0x3333_3333_3333_3333) << 2);
+    let x = ((x >> 4) & 0x0F0F_0F0F_0F0F_0F0F) | ((x & 0x0F0F_0F0F_0F0F_0F0F) << 4);
+    x.swap_bytes() >> (64 - 2 * k)
+}
+
+/// Returns the canonical code of a k-mer of length `k`, the smaller of its code and the
+/// code of its reverse complement, so both strands give the same k-mer.
+///
+/// # Panics
+///
+/// Panics if `k` is not in the `1..=32` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::dna;
+///
+/// let (xs, ys) = (dna::encode_kmer(b"TTG").unwrap(), dna::encode_kmer(b"CAA").unwrap());
+/// assert_eq!(dna::canonical(xs, 3), ys);
+/// assert_eq!(dna::canonical(ys, 3), ys);
+/// ```
+#[inline]
+pub fn canonical(code: u64, k: usize) -> u64 {
+    code.min(reverse_complement(code, k))
+}
+
+/// Returns an iterator over the codes of the k-mers of a sequence, in order, rolling the
+/// code of each window from the previous one. The windows which contain a byte other than
+/// a nucleotide, such as an `N`, are skipped.
+///
+/// # Panics
+///
+/// Panics if `k` is not in the `1..=32` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::dna;
+///
+/// let kmers: Vec<u64> = dna::kmers(b"ACGNGT", 2).collect();
+/// assert_eq!(kmers, [0b00_01, 0b01_10, 0b10_11]);
+/// ```
+pub fn kmers(seq: &[u8], k: usize) -> Kmers<'_> {
+    Kmers::new(seq, k, false)
+}
+
+/// Returns an iterator over the [`canonical`] codes of the k-mers of a sequence, in order,
+/// rolling both the code of each window and the code of its reverse complement.
+///
+/// # Panics
+///
+/// Panics if `k` is not in the `1..=32` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::dna;
+///
+/// // both strands of the same fragment
+/// let xs: Vec<u64> = dna::canonical_kmers(b"AACGT", 3).collect();
+/// let mut ys: Vec<u64> = dna::canonical_kmers(b"ACGTT", 3).collect();
+/// ys.reverse();
+/// assert_eq!(xs, ys);
+/// ```
+pub fn canonical_kmers(seq: &[u8], k: usize) -> Kmers<'_> {
+    Kmers::new(seq, k, true)
+}
+
+/// An iterator over the codes of the k-mers of a sequence.
+///
+/// The `struct` is created by the [`kmers`] and [`canonical_kmers`] functions. See their
+/// documentation for more.
+#[derive(Clone, Debug)]
+pub struct Kmers<'a> {
+    bases: slice::Iter<'a, u8>,
+    k: usize,
+    mask: u64,
+    canonical: bool,
+    // the codes of the last window and of its reverse complement
+    code: u64,
+    rc: u64,
+    // the number of consecutive nucleotides read, up to the last one
+    valid: usize,
+}
+
+impl<'a> Kmers<'a> {
+    fn new(seq: &'a [u8], k: usize, canonical: bool) -> Self {
+        check_k(k);
+
+        Self {
+            bases: seq.iter(),
+            k,
+            mask: u64::MAX >> (64 - 2 * k),
+            canonical,
+            code: 0,
+            rc: 0,
+            valid: 0,
+        }
+    }
+}
+
+impl Iterator for Kmers<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        for base in self.bases.by_ref() {
+            let Some(bits) = encode(*base) else {
+                self.valid = 0;
+                continue;
+            };
+
+            self.code = ((self.code << 2) | bits) & self.mask;
+            self.rc = (self.rc >> 2) | ((bits ^ 3) << (2 * (self.k - 1)));
+            self.valid += 1;
+
+            if self.valid >= self.k {
+                return Some(if self.canonical {
+                    self.code.min(self.rc)
+                } else {
+                    self.code
+                });
+            }
+        }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.bases.len();
+        let needed = self.k.saturating_sub(self.valid);
+        (0, Some((remaining + 1).saturating_sub(needed.max(1))))
+    }
+}
+
+impl FusedIterator for Kmers<'_> {}
+
+fn check_k(k: usize) {
+    assert!(
+        (1..=MAX_K).contains(&k),
+        "the k-mer length must be between 1 and {MAX_K}"
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    /// Maps arbitrary bytes to nucleotides.
+    fn dna(xs: &[u8]) -> Vec<u8> {
+        xs.iter().map(|x| NUCLEOTIDES[*x as usize % 4]).collect()
+    }
+
+    /// Reverse complements a sequence of nucleotides, one byte at a time.
+    fn reverse_complement_seq(seq: &[u8]) -> Vec<u8> {
+        seq.iter()
+            .rev()
+            .map(|base| decode(encode(*base).unwrap() ^ 3))
+            .collect()
+    }
+
+    #[test]
+    fn empty_() {
+        assert!(pack(b"").unwrap().is_empty());
+        assert_eq!(unpack(&BVec::with_length(0)), Ok(vec![]));
+        assert_eq!(encode_kmer(b""), Ok(0));
+        assert!(decode_kmer(0, 0).is_empty());
+        assert_eq!(kmers(b"ACG", 4).count(), 0);
+    }
+
+    #[test]
+    fn errors_() {
+        assert_eq!(
+            unpack(&BVec::with_length(3)),
+            Err(BitsError::InvalidLength { len: 3 })
+        );
+        assert_eq!(
+            encode_kmer(b"ACX"),
+            Err(BitsError::InvalidChar { index: 2 })
+        );
+        assert_eq!(
+            encode_kmer(&[b'A'; 33]),
+            Err(BitsError::InvalidLength { len: 33 })
+        );
+    }
+
+    #[test]
+    fn full_word_() {
+        let kmer = dna(&(0..32).collect::<Vec<_>>());
+        let code = encode_kmer(&kmer).unwrap();
+        assert_eq!(decode_kmer(code, 32), kmer);
+        assert_eq!(
+            reverse_complement(code, 32),
+            encode_kmer(&reverse_complement_seq(&kmer)).unwrap()
+        );
+        assert_eq!(kmers(&kmer, 32).collect::<Vec<_>>(), [code]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_k_() {
+        kmers(b"ACGT", 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn large_k_() {
+        reverse_complement(0, 33);
+    }
+
+    #[quickcheck]
+    fn roundtrip_(seq: Vec<u8>) -> bool {
+        let seq = dna(&seq);
+        let bvec = pack(&seq).unwrap();
+
+        bvec.len() == 2 * seq.len()
+            && unpack(&bvec).unwrap() == seq
+            && pack(&seq.to_ascii_lowercase()).unwrap() == bvec
+    }
+
+    #[quickcheck]
+    fn reverse_complement_(kmer: Vec<u8>) -> bool {
+        let kmer = dna(&kmer[..kmer.len().min(MAX_K)]);
+        let k = kmer.len();
+        if k == 0 {
+            return true;
+        }
+
+        let code = encode_kmer(&kmer).unwrap();
+        let rc = reverse_complement(code, k);
+
+        decode_kmer(rc, k) == reverse_complement_seq(&kmer)
+            && reverse_complement(rc, k) == code
+            && canonical(code, k) == canonical(rc, k)
+    }
+
+    #[quickcheck]
+    fn kmers_(seq: Vec<u8>, k: u8) -> bool {
+        // some of the bytes are not nucleotides
+        let seq: Vec<u8> = seq.iter().map(|x| b"ACGTN"[*x as usize % 5]).collect();
+        let k = k as usize % MAX_K + 1;
+
+        let windows = seq.windows(k).filter_map(|kmer| encode_kmer(kmer).ok());
+        let canonicals = windows.clone().map(|code| canonical(code, k));
+
+        let iter = kmers(&seq, k);
+        let (_, upper) = iter.size_hint();
+
+        iter.clone().eq(windows)
+            && canonical_kmers(&seq, k).eq(canonicals)
+            && upper.is_some_and(|upper| upper >= iter.count())
+    }
+}
@@ -0,0 +1,50 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// The error returned by the checked bit accessors, such as [`BVec::try_get_bit`],
+/// when an index or a length does not fit the bits.
+///
+/// [`BVec::try_get_bit`]: super::BVec::try_get_bit
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum BitsError {
+    /// The bit index is not smaller than the number of bits.
+    OutOfRange { index: usize, len: usize },
+    /// The bytes cannot hold the requested number of bits.
+    NotEnoughBytes { bits: usize, bytes: usize },
+    /// The text has a character which is not a digit of its encoding, at a byte index.
+    InvalidChar { index: usize },
+    /// The text has a length which is not valid for its encoding.
+    InvalidLength { len: usize },
+}
+
+impl Display for BitsError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::OutOfRange { index, len } => write!(
+                f,
+                "bit index out of bounds: the len is {len} but the index is {index}"
+            ),
+            Self::NotEnoughBytes { bits, bytes } => write!(
+                f,
+                "not enough bytes: {bits} bits need {} bytes but the slice has {bytes}",
+                bits.div_ceil(super::U8SIZE)
+            ),
+            Self::InvalidChar { index } => write!(f, "invalid character at index {index}"),
+            Self::InvalidLength { len } => write!(f, "invalid length of the text: {len}"),
+        }
+    }
+}
+
+impl Error for BitsError {}
+
+/// Checks that a bit index is smaller than the number of bits.
+#[inline]
+pub(crate) fn check_index(index: usize, len: usize) -> Result<(), BitsError> {
+    if index < len {
+        Ok(())
+    } else {
+        Err(BitsError::OutOfRange { index, len })
+    }
+}
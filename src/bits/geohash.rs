@@ -0,0 +1,165 @@
+//! [Geohash](https://en.wikipedia.org/wiki/Geohash) encoding of geographic coordinates.
+//!
+//! The latitude and the longitude are quantized to 32 bits each and interleaved
+//! with [`morton_encode`], the longitude providing the most significant bit.
+//! The resulting bits are written five at a time using the geohash base32 alphabet.
+
+use super::{morton_decode, morton_encode};
+
+const ALPHABET: &[u8; 32] = b"0123456789bcdefghjkmnpqrstuvwxyz";
+
+/// The maximum number of characters of a geohash.
+pub const GEOHASH_MAX_PRECISION: usize = 12;
+
+/// The cell of a decoded geohash.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct GeoCell {
+    /// The latitude of the center of the cell.
+    pub lat: f64,
+    /// The longitude of the center of the cell.
+    pub lon: f64,
+    /// Half of the height of the cell, in degrees.
+    pub lat_err: f64,
+    /// Half of the width of the cell, in degrees.
+    pub lon_err: f64,
+}
+
+/// Returns the geohash with `precision` characters of a given position.
+///
+/// The latitude is clamped to `[-90, 90]` and the longitude to `[-180, 180]`.
+///
+/// # Panics
+///
+/// Panics if the precision is zero or larger than [`GEOHASH_MAX_PRECISION`].
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::geohash_encode;
+///
+/// assert_eq!(geohash_encode(57.64911, 10.40744, 11), "u4pruydqqvj");
+/// ```
+pub fn geohash_encode(lat: f64, lon: f64, precision: usize) -> String {
+    assert!(
+        (1..=GEOHASH_MAX_PRECISION).contains(&precision),
+        "precision must be between 1 and {GEOHASH_MAX_PRECISION}"
+    );
+
+    let code = morton_encode([quantize(lat, 90.), quantize(lon, 180.)]) as u64;
+
+    (0..precision)
+        .map(|i| {
+            let idx = (code >> (64 - 5 * (i + 1))) & 0x1f;
+            ALPHABET[idx as usize] as char
+        })
+        .collect()
+}
+
+/// Returns the cell described by a geohash,
+/// or `None` if the geohash is empty, too long or contains invalid characters.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::geohash_decode;
+///
+/// let cell = geohash_decode("ezs42").unwrap();
+/// assert!((cell.lat - 42.605).abs() < 0.001);
+/// assert!((cell.lon + 5.603).abs() < 0.001);
+/// ```
+pub fn geohash_decode(hash: &str) -> Option<GeoCell> {
+    if hash.is_empty() || hash.len() > GEOHASH_MAX_PRECISION {
+        return None;
+    }
+
+    let mut code = 0_u64;
+    for (i, c) in hash.bytes().enumerate() {
+        let idx = ALPHABET.iter().position(|a| *a == c.to_ascii_lowercase())?;
+        code |= (idx as u64) << (64 - 5 * (i + 1));
+    }
+
+    let [lat, lon] = morton_decode::<2>(code as u128);
+
+    // the longitude gets the extra bit when the number of bits is odd
+    let bits = 5 * hash.len() as i32;
+    let lon_bits = (bits + 1) / 2;
+    let lat_bits = bits / 2;
+
+    let lat_err = 90. / 2_f64.powi(lat_bits);
+    let lon_err = 180. / 2_f64.powi(lon_bits);
+
+    Some(GeoCell {
+        lat: dequantize(lat, 90.) + lat_err,
+        lon: dequantize(lon, 180.) + lon_err,
+        lat_err,
+        lon_err,
+    })
+}
+
+/// Maps a value from `[-max, max]` to the full `u32` range.
+fn quantize(x: f64, max: f64) -> u32 {
+    let scaled = (x.clamp(-max, max) + max) / (2. * max) * 2_f64.powi(32);
+    scaled.min(u32::MAX as f64) as u32
+}
+
+fn dequantize(x: u32, max: f64) -> f64 {
+    x as f64 / 2_f64.powi(32) * 2. * max - max
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encode_() {
+        assert_eq!(geohash_encode(42.6, -5.6, 5), "ezs42");
+        assert_eq!(geohash_encode(57.64911, 10.40744, 11), "u4pruydqqvj");
+        assert_eq!(geohash_encode(-90., -180., 3), "000");
+        assert_eq!(geohash_encode(90., 180., 3), "zzz");
+    }
+
+    #[test]
+    fn decode_() {
+        let cell = geohash_decode("u4pruydqqvj").unwrap();
+        assert!((cell.lat - 57.64911).abs() <= cell.lat_err);
+        assert!((cell.lon - 10.40744).abs() <= cell.lon_err);
+    }
+
+    #[test]
+    fn decode_err_() {
+        let cell = geohash_decode("e").unwrap();
+        assert_eq!(cell.lat_err, 22.5);
+        assert_eq!(cell.lon_err, 22.5);
+
+        let cell = geohash_decode("ez").unwrap();
+        assert_eq!(cell.lat_err, 2.8125);
+        assert_eq!(cell.lon_err, 5.625);
+    }
+
+    #[test]
+    fn decode_invalid_() {
+        assert!(geohash_decode("").is_none());
+        assert!(geohash_decode("ezs4a").is_none());
+        assert!(geohash_decode("0123456789bcd").is_none());
+    }
+
+    #[test]
+    fn decode_uppercase_() {
+        assert_eq!(geohash_decode("EZS42"), geohash_decode("ezs42"));
+    }
+
+    #[test]
+    fn roundtrip_() {
+        for (lat, lon) in [(0., 0.), (48.8584, 2.2945), (-33.8568, 151.2153)] {
+            let hash = geohash_encode(lat, lon, 9);
+            let cell = geohash_decode(&hash).unwrap();
+            assert_eq!(geohash_encode(cell.lat, cell.lon, 9), hash);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn precision_() {
+        let _ = geohash_encode(0., 0., 13);
+    }
+}
@@ -0,0 +1,168 @@
+//! [Gray code](https://en.wikipedia.org/wiki/Gray_code) conversions.
+//!
+//! In the reflected binary Gray code, consecutive integers have codes which differ in a single bit.
+
+use super::Byte;
+use std::ops::{BitXor, Shr};
+
+/// Unsigned integers which can be converted to and from their reflected binary Gray code.
+pub trait GrayCode: Copy + Eq + BitXor<Output = Self> + Shr<u32, Output = Self> {
+    /// The number of bits of the integer.
+    const BITS: u32;
+}
+
+macro_rules! impl_gray_code {
+    ($($t:ty),*) => {
+        $(
+            impl GrayCode for $t {
+                const BITS: u32 = <$t>::BITS;
+            }
+        )*
+    };
+}
+
+impl_gray_code!(u8, u16, u32, u64, u128, usize);
+
+/// Returns the Gray code of an integer.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::gray_encode;
+///
+/// assert_eq!(gray_encode(5_u32), 0b111);
+/// assert_eq!(gray_encode(6_u64), 0b101);
+/// ```
+#[inline]
+pub fn gray_encode<T: GrayCode>(x: T) -> T {
+    x ^ (x >> 1)
+}
+
+/// Returns the integer of a Gray code.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{gray_decode, gray_encode};
+///
+/// assert_eq!(gray_decode(0b111_u32), 5);
+/// assert_eq!(gray_decode(gray_encode(u128::MAX - 7)), u128::MAX - 7);
+/// ```
+#[inline]
+pub fn gray_decode<T: GrayCode>(code: T) -> T {
+    // prefix xor of the bits, in log(BITS) steps
+    let mut x = code;
+    let mut shift = 1;
+    while shift < T::BITS {
+        x = x ^ (x >> shift);
+        shift <<= 1;
+    }
+    x
+}
+
+impl Byte {
+    /// Returns the Gray code of the byte.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::Byte;
+    ///
+    /// assert_eq!(Byte::from(2).to_gray(), Byte::from(3));
+    /// assert_eq!(Byte::from(3).from_gray(), Byte::from(2));
+    /// ```
+    #[inline]
+    pub fn to_gray(self) -> Self {
+        Byte::from(gray_encode(u8::from(self)))
+    }
+
+    /// Returns the byte whose Gray code this byte is.
+    #[inline]
+    pub fn from_gray(self) -> Self {
+        Byte::from(gray_decode(u8::from(self)))
+    }
+}
+
+/// Returns an iterator over all the Gray codes of `bits` bits, in order,
+/// so each code differs from the previous one in a single bit.
+///
+/// # Panics
+///
+/// Panics if `bits` is larger than 64.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::gray_codes;
+///
+/// let codes: Vec<u64> = gray_codes(3).collect();
+/// assert_eq!(codes, [0b000, 0b001, 0b011, 0b010, 0b110, 0b111, 0b101, 0b100]);
+/// ```
+pub fn gray_codes(bits: u32) -> GrayCodes {
+    assert!(bits <= u64::BITS, "the number of bits must be at most 64");
+
+    GrayCodes {
+        next: Some(0),
+        last: if bits == 0 {
+            0
+        } else {
+            u64::MAX >> (u64::BITS - bits)
+        },
+    }
+}
+
+/// An iterator over the successive Gray codes of a given number of bits,
+/// created by [`gray_codes`].
+#[derive(Clone, Debug)]
+pub struct GrayCodes {
+    next: Option<u64>,
+    last: u64,
+}
+
+impl Iterator for GrayCodes {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let n = self.next?;
+        self.next = if n == self.last { None } else { Some(n + 1) };
+        Some(gray_encode(n))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn gray_codes_() {
+        assert_eq!(gray_codes(0).collect::<Vec<_>>(), [0]);
+        assert_eq!(gray_codes(1).collect::<Vec<_>>(), [0, 1]);
+
+        let codes: Vec<u64> = gray_codes(10).collect();
+        assert_eq!(codes.len(), 1 << 10);
+        assert!(codes.windows(2).all(|w| (w[0] ^ w[1]).count_ones() == 1));
+
+        let mut sorted = codes.clone();
+        sorted.sort_unstable();
+        assert!(sorted.into_iter().eq(0..1 << 10));
+
+        let mut wide = gray_codes(64);
+        assert_eq!(wide.next(), Some(0));
+    }
+
+    #[test]
+    fn bytes_() {
+        for x in 0..=u8::MAX {
+            let byte = Byte::from(x);
+            assert_eq!(byte.to_gray().from_gray(), byte);
+        }
+    }
+
+    #[quickcheck]
+    fn roundtrip_(x: u64, y: u16) -> bool {
+        gray_decode(gray_encode(x)) == x
+            && gray_decode(gray_encode(y)) == y
+            && (gray_encode(x) ^ gray_encode(x.wrapping_add(1))).count_ones() == 1
+    }
+}
@@ -0,0 +1,201 @@
+//! [Hilbert curve](https://en.wikipedia.org/wiki/Hilbert_curve) encoding.
+//!
+//! Like the Morton order, the Hilbert order maps multidimensional points to integers,
+//! but consecutive indices are always neighbouring points, which gives a better locality.
+//! The implementation follows J. Skilling, "Programming the Hilbert curve" (2004):
+//! the coordinates are transformed in place and then interleaved with [`morton_encode`].
+
+use super::{morton_decode, morton_encode};
+
+const ORDER: u32 = u32::BITS;
+
+/// Returns the Hilbert index of a point with `D` coordinates, where `D` is between 2 and 4.
+///
+/// # Panics
+///
+/// Panics if `D` is smaller than 2 or larger than 4.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{hilbert_decode, hilbert_encode};
+///
+/// assert_eq!(hilbert_encode([0, 0]), 0);
+///
+/// let idx = hilbert_encode([3, 5]);
+/// assert_eq!(hilbert_decode::<2>(idx), [3, 5]);
+/// ```
+pub fn hilbert_encode<const D: usize>(point: [u32; D]) -> u128 {
+    assert!((2..=4).contains(&D), "dimension must be between 2 and 4");
+
+    let mut xs = point;
+    axes_to_transpose(&mut xs);
+
+    // the first coordinate holds the most significant bit of each group
+    xs.reverse();
+    morton_encode(xs)
+}
+
+/// Returns the point with `D` coordinates, where `D` is between 2 and 4, for a given Hilbert index.
+///
+/// # Panics
+///
+/// Panics if `D` is smaller than 2 or larger than 4.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::hilbert_decode;
+///
+/// // consecutive indices are neighbouring points
+/// let [x0, y0] = hilbert_decode::<2>(41);
+/// let [x1, y1] = hilbert_decode::<2>(42);
+/// assert_eq!(x0.abs_diff(x1) + y0.abs_diff(y1), 1);
+/// ```
+pub fn hilbert_decode<const D: usize>(index: u128) -> [u32; D] {
+    assert!((2..=4).contains(&D), "dimension must be between 2 and 4");
+
+    let mut xs = morton_decode::<D>(index);
+    xs.reverse();
+    transpose_to_axes(&mut xs);
+    xs
+}
+
+fn axes_to_transpose(xs: &mut [u32]) {
+    let n = xs.len();
+    let m = 1_u32 << (ORDER - 1);
+
+    // inverse undo
+    let mut q = m;
+    while q > 1 {
+        let p = q - 1;
+        for i in 0..n {
+            if xs[i] & q != 0 {
+                xs[0] ^= p;
+            } else {
+                let t = (xs[0] ^ xs[i]) & p;
+                xs[0] ^= t;
+                xs[i] ^= t;
+            }
+        }
+        q >>= 1;
+    }
+
+    // gray encode
+    for i in 1..n {
+        xs[i] ^= xs[i - 1];
+    }
+
+    let mut t = 0;
+    let mut q = m;
+    while q > 1 {
+        if xs[n - 1] & q != 0 {
+            t ^= q - 1;
+        }
+        q >>= 1;
+    }
+
+    for x in xs.iter_mut() {
+        *x ^= t;
+    }
+}
+
+fn transpose_to_axes(xs: &mut [u32]) {
+    let n = xs.len();
+
+    // gray decode
+    let t = xs[n - 1] >> 1;
+    for i in (1..n).rev() {
+        xs[i] ^= xs[i - 1];
+    }
+    xs[0] ^= t;
+
+    // undo excess work
+    let mut q = 2_u64;
+    while q != 1 << ORDER {
+        let p = (q - 1) as u32;
+        for i in (0..n).rev() {
+            if xs[i] as u64 & q != 0 {
+                xs[0] ^= p;
+            } else {
+                let t = (xs[0] ^ xs[i]) & p;
+                xs[0] ^= t;
+                xs[i] ^= t;
+            }
+        }
+        q <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    fn manhattan<const D: usize>(xs: [u32; D], ys: [u32; D]) -> u32 {
+        xs.iter().zip(ys).map(|(x, y)| x.abs_diff(y)).sum()
+    }
+
+    #[test]
+    fn origin_() {
+        assert_eq!(hilbert_encode([0, 0]), 0);
+        assert_eq!(hilbert_encode([0, 0, 0]), 0);
+        assert_eq!(hilbert_encode([0, 0, 0, 0]), 0);
+    }
+
+    #[test]
+    fn first_cells_() {
+        // the first 4 indices cover the 2x2 square at the origin
+        let mut cells: Vec<[u32; 2]> = (0..4).map(hilbert_decode::<2>).collect();
+        cells.sort();
+        assert_eq!(cells, [[0, 0], [0, 1], [1, 0], [1, 1]]);
+    }
+
+    #[test]
+    fn adjacency_2_() {
+        for i in 0..4096 {
+            let xs = hilbert_decode::<2>(i);
+            let ys = hilbert_decode::<2>(i + 1);
+            assert_eq!(manhattan(xs, ys), 1);
+        }
+    }
+
+    #[test]
+    fn adjacency_3_() {
+        for i in 0..4096 {
+            let xs = hilbert_decode::<3>(i);
+            let ys = hilbert_decode::<3>(i + 1);
+            assert_eq!(manhattan(xs, ys), 1);
+        }
+    }
+
+    #[test]
+    fn adjacency_4_() {
+        for i in 0..4096 {
+            let xs = hilbert_decode::<4>(i);
+            let ys = hilbert_decode::<4>(i + 1);
+            assert_eq!(manhattan(xs, ys), 1);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn dimension_() {
+        let _ = hilbert_encode([1]);
+    }
+
+    #[quickcheck]
+    fn prop_roundtrip_2_(x: u32, y: u32) -> bool {
+        hilbert_decode::<2>(hilbert_encode([x, y])) == [x, y]
+    }
+
+    #[quickcheck]
+    fn prop_roundtrip_3_(x: u32, y: u32, z: u32) -> bool {
+        hilbert_decode::<3>(hilbert_encode([x, y, z])) == [x, y, z]
+    }
+
+    #[quickcheck]
+    fn prop_roundtrip_4_(x: u32, y: u32, z: u32, w: u32) -> bool {
+        hilbert_decode::<4>(hilbert_encode([x, y, z, w])) == [x, y, z, w]
+    }
+}
@@ -0,0 +1,269 @@
+use super::{BVec, Bit};
+
+/// A two dimensional matrix of bits, stored as one [`BVec`] per row, such as the incidence
+/// matrix of documents and terms.
+///
+/// The rows are bit vectors of the same length, the number of columns, so the row operations
+/// work on whole bytes. Column access goes through [`transpose`](BitMatrix::transpose), which
+/// is cheaper than reading the columns bit by bit when many of them are needed.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BitMatrix};
+///
+/// // three documents over four terms
+/// let mut docs = BitMatrix::new(3, 4);
+/// docs.set_bit(0, 0);
+/// docs.set_bit(0, 2);
+/// docs.set_bit(1, 2);
+/// docs.set_bit(2, 0);
+/// docs.set_bit(2, 2);
+///
+/// assert_eq!(docs.get_bit(0, 2), Bit::One);
+/// assert_eq!(docs.row_hamming(0, 2), 0);
+/// assert_eq!(docs.row_and(0, 1).ones().collect::<Vec<_>>(), [2]);
+///
+/// // the documents of each term
+/// let terms = docs.transpose();
+/// assert_eq!(terms.row(2).count_ones(), 3);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    cols: usize,
+    rows: Vec<BVec>,
+}
+
+impl BitMatrix {
+    /// Creates a matrix of `rows` by `cols` bits set to zero.
+    pub fn new(rows: usize, cols: usize) -> Self {
+        Self {
+            cols,
+            rows: vec![BVec::with_length(cols); rows],
+        }
+    }
+
+    /// Creates a matrix from its rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the rows have different lengths.
+    pub fn from_rows<I: IntoIterator<Item = BVec>>(rows: I) -> Self {
+        let rows: Vec<BVec> = rows.into_iter().collect();
+        let cols = rows.first().map_or(0, |row| row.len());
+        assert!(
+            rows.iter().all(|row| row.len() == cols),
+            "the rows must have the same length"
+        );
+
+        Self { cols, rows }
+    }
+
+    /// Returns the number of rows.
+    pub fn num_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    /// Returns the number of columns.
+    pub fn num_cols(&self) -> usize {
+        self.cols
+    }
+
+    /// Returns true if the matrix has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.rows.is_empty() || self.cols == 0
+    }
+
+    /// Appends a row to the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the row is not the number of columns.
+    pub fn push_row(&mut self, row: BVec) {
+        assert_eq!(
+            row.len(),
+            self.cols,
+            "the row length must be the number of columns"
+        );
+        self.rows.push(row);
+    }
+
+    /// Returns a row of the matrix.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row is out of range.
+    pub fn row(&self, row: usize) -> &BVec {
+        &self.rows[row]
+    }
+
+    /// Returns an iterator over the rows of the matrix.
+    pub fn rows(&self) -> impl ExactSizeIterator<Item = &BVec> + '_ {
+        self.rows.iter()
+    }
+
+    /// Returns a column of the matrix, reading one bit of each row.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the column is out of range.
+    pub fn column(&self, col: usize) -> BVec {
+        self.rows.iter().map(|row| row.get_bit(col)).collect()
+    }
+
+    /// Returns the bit value at a row and a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row or the column is out of range.
+    pub fn get_bit(&self, row: usize, col: usize) -> Bit {
+        self.rows[row].get_bit(col)
+    }
+
+    /// Sets the bit at a row and a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row or the column is out of range.
+    pub fn set_bit(&mut self, row: usize, col: usize) {
+        self.rows[row].set_bit(col);
+    }
+
+    /// Resets the bit at a row and a column.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the row or the column is out of range.
+    pub fn reset_bit(&mut self, row: usize, col: usize) {
+        self.rows[row].reset_bit(col);
+    }
+
+    /// Returns the number of bits set to one.
+    pub fn count_ones(&self) -> usize {
+        self.rows.iter().map(BVec::count_ones).sum()
+    }
+
+    /// Returns the bitwise AND of two rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row is out of range.
+    pub fn row_and(&self, i: usize, j: usize) -> BVec {
+        &self.rows[i] & &self.rows[j]
+    }
+
+    /// Returns the bitwise OR of two rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row is out of range.
+    pub fn row_or(&self, i: usize, j: usize) -> BVec {
+        &self.rows[i] | &self.rows[j]
+    }
+
+    /// Returns the bitwise XOR of two rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row is out of range.
+    pub fn row_xor(&self, i: usize, j: usize) -> BVec {
+        &self.rows[i] ^ &self.rows[j]
+    }
+
+    /// Returns the Hamming distance between two rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a row is out of range.
+    pub fn row_hamming(&self, i: usize, j: usize) -> usize {
+        self.rows[i].hamming(&self.rows[j])
+    }
+
+    /// Returns the Hamming distances of all the rows to a query, in the order of the rows.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the length of the query is not the number of columns.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BVec, BitMatrix};
+    ///
+    /// let matrix = BitMatrix::from_rows([BVec::from(0b1100_u8), BVec::from(0b0011_u8)]);
+    /// assert_eq!(matrix.hamming_all(&BVec::from(0b1000_u8)), [1, 3]);
+    /// ```
+    pub fn hamming_all(&self, query: &BVec) -> Vec<usize> {
+        assert_eq!(
+            query.len(),
+            self.cols,
+            "the query length must be the number of columns"
+        );
+        self.rows.iter().map(|row| row.hamming(query)).collect()
+    }
+
+    /// Returns the transposed matrix, whose rows are the columns of this one.
+    pub fn transpose(&self) -> BitMatrix {
+        let mut transposed = BitMatrix::new(self.cols, self.rows.len());
+        for (i, row) in self.rows.iter().enumerate() {
+            row.ones().for_each(|j| transposed.rows[j].set_bit(i));
+        }
+        transposed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn empty_() {
+        let matrix = BitMatrix::new(0, 5);
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.num_cols(), 5);
+        assert_eq!(matrix.transpose(), BitMatrix::new(5, 0));
+        assert_eq!(BitMatrix::from_rows([]).num_cols(), 0);
+    }
+
+    #[test]
+    fn rows_() {
+        let mut matrix = BitMatrix::new(2, 10);
+        matrix.set_bit(0, 9);
+        matrix.set_bit(1, 9);
+        matrix.set_bit(1, 3);
+        matrix.reset_bit(1, 9);
+        matrix.push_row(BVec::with_length(10));
+
+        assert_eq!(matrix.num_rows(), 3);
+        assert_eq!(matrix.count_ones(), 2);
+        assert_eq!(matrix.row_or(0, 1).ones().collect::<Vec<_>>(), [3, 9]);
+        assert_eq!(matrix.row_xor(0, 2), *matrix.row(0));
+        assert_eq!(matrix.row_and(0, 1).count_ones(), 0);
+        assert_eq!(matrix.row_hamming(0, 1), 2);
+        assert_eq!(matrix.column(9).ones().collect::<Vec<_>>(), [0]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn ragged_rows_() {
+        let _ = BitMatrix::from_rows([BVec::with_length(3), BVec::with_length(4)]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn push_row_() {
+        BitMatrix::new(1, 3).push_row(BVec::with_length(2));
+    }
+
+    #[quickcheck]
+    fn transpose_(rows: Vec<u16>) -> bool {
+        let matrix = BitMatrix::from_rows(rows.iter().map(|row| BVec::from(*row)));
+        let transposed = matrix.transpose();
+
+        transposed.num_rows() == matrix.num_cols()
+            && (0..matrix.num_cols()).all(|j| *transposed.row(j) == matrix.column(j))
+            && transposed.count_ones() == matrix.count_ones()
+            && (rows.is_empty() || transposed.transpose() == matrix)
+    }
+}
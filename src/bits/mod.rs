@@ -1,13 +1,97 @@
 //! Bit-wise functionality
+//!
+//! # Bit ordering
+//!
+//! The bits are numbered from the most significant one: bit `0` of a [`Byte`] is its
+//! most significant bit, and bit `0` of a [`BVec`] is the most significant bit of its
+//! first byte. Iteration and the `FromIterator` constructors follow this MSB-first order.
+//! The `iter_lsb` and `from_bits_lsb` methods use the opposite, LSB-first, order,
+//! which is the natural one for little-endian bit streams. When the order is only known at
+//! runtime, [`BitOrder`] selects it for the `iter_in` and `from_bits_in` methods.
+//!
+//! # Serialization
+//!
+//! With the `serde` feature enabled, [`Bit`] is serialized as a boolean, [`Byte`] as an
+//! integer, [`Position`] as the index of the bit, and [`BVec`] as its length together
+//! with the bytes which store its bits.
+//!
+//! # Text encodings
+//!
+//! [`BVec::to_hex`] and [`BVec::from_hex`] round-trip the bits of a vector, such as a
+//! signature, through hexadecimal text for logs and configuration files. With the `base64`
+//! feature enabled, `to_base64` and `from_base64` do the same with the shorter base64 text.
+//!
+//! # DNA sequences
+//!
+//! The [`dna`] module packs the nucleotides of a DNA sequence with two bits each, into a
+//! [`BVec`] for a whole sequence or into a `u64` for a k-mer, and computes the reverse
+//! complement and the canonical form of the k-mers.
+//!
+//! # Bulk operations
+//!
+//! With the `simd` feature enabled, the population count, the Hamming distance and the
+//! bitwise AND, OR and XOR of [`BVec`] process 64 bit words, with unrolled loops, instead
+//! of single bytes, which pays off for long vectors such as large signatures.
+//!
+//! # Property testing
+//!
+//! With the `test-util` feature enabled, [`Bit`], [`Byte`] and [`BVec`] implement
+//! `quickcheck::Arbitrary`, and failing vectors shrink to shorter and sparser ones.
+//! With the `arbitrary` feature enabled, they implement `arbitrary::Arbitrary`, so the
+//! decoders can be fuzzed with structured inputs, for example with `cargo fuzz`.
 
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod atomic;
 mod bit;
+mod bitsliced;
 mod bvec;
 mod byte;
+mod compressed;
+pub mod dna;
+mod error;
+mod geohash;
+mod gray;
+mod hilbert;
+mod matrix;
+mod morton;
+mod order;
+mod packing;
+mod parity;
 mod position;
+#[cfg(any(test, feature = "test-util"))]
+mod quickcheck;
+mod rle;
+mod search;
+#[cfg(feature = "serde")]
+mod serde;
+#[cfg(feature = "simd")]
+mod simd;
+mod sparse;
+mod succinct;
+mod text;
+mod uint;
+mod word;
 
+pub use atomic::*;
 pub use bit::*;
+pub use bitsliced::*;
 pub use bvec::*;
 pub use byte::*;
+pub use compressed::*;
+pub use error::*;
+pub use geohash::*;
+pub use gray::*;
+pub use hilbert::*;
+pub use matrix::*;
+pub use morton::*;
+pub use order::*;
+pub use packing::*;
+pub use parity::*;
 pub use position::*;
+pub use rle::*;
+pub use sparse::*;
+pub use succinct::*;
+pub use word::*;
 
 const U8SIZE: usize = 8;
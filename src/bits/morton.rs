@@ -0,0 +1,197 @@
+//! Locality preserving [Z-order](https://en.wikipedia.org/wiki/Z-order_curve) (Morton) encoding.
+//!
+//! The bits of the coordinates of a point are interleaved into a single integer,
+//! so points which are close in space tend to be close in the Morton order.
+//! Bit `b` of coordinate `i` becomes bit `b * D + i` of the code.
+
+/// Returns the Morton code of a point with `D` coordinates, where `D` is between 1 and 4.
+///
+/// On x86-64 processors supporting BMI2, two dimensional points are encoded with the `pdep` instruction.
+///
+/// # Panics
+///
+/// Panics if `D` is zero or larger than 4.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::morton_encode;
+///
+/// assert_eq!(morton_encode([0b11, 0b00]), 0b0101);
+/// assert_eq!(morton_encode([0b00, 0b11]), 0b1010);
+/// assert_eq!(morton_encode([1, 1, 1]), 0b111);
+/// ```
+pub fn morton_encode<const D: usize>(point: [u32; D]) -> u128 {
+    assert!((1..=4).contains(&D), "dimension must be between 1 and 4");
+
+    if D == 2 {
+        return encode2(point[0], point[1]) as u128;
+    }
+
+    let mut code = 0_u128;
+    for b in 0..32 {
+        for (i, x) in point.iter().enumerate() {
+            code |= (((x >> b) & 1) as u128) << (b * D + i);
+        }
+    }
+    code
+}
+
+/// Returns the point with `D` coordinates, where `D` is between 1 and 4, for a given Morton code.
+///
+/// # Panics
+///
+/// Panics if `D` is zero or larger than 4.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{morton_decode, morton_encode};
+///
+/// let code = morton_encode([7, 42, 1_000]);
+/// assert_eq!(morton_decode::<3>(code), [7, 42, 1_000]);
+/// ```
+pub fn morton_decode<const D: usize>(code: u128) -> [u32; D] {
+    assert!((1..=4).contains(&D), "dimension must be between 1 and 4");
+
+    let mut point = [0_u32; D];
+
+    if D == 2 {
+        let (x, y) = decode2(code as u64);
+        point[0] = x;
+        point[1] = y;
+        return point;
+    }
+
+    for b in 0..32 {
+        for (i, x) in point.iter_mut().enumerate() {
+            *x |= (((code >> (b * D + i)) & 1) as u32) << b;
+        }
+    }
+    point
+}
+
+const EVEN_BITS: u64 = 0x5555_5555_5555_5555;
+const ODD_BITS: u64 = 0xAAAA_AAAA_AAAA_AAAA;
+
+fn encode2(x: u32, y: u32) -> u64 {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::is_x86_feature_detected!("bmi2") {
+            // SAFETY: the processor supports the bmi2 instructions.
+            return unsafe { bmi2::encode2(x, y) };
+        }
+    }
+
+    spread(x) | (spread(y) << 1)
+}
+
+fn decode2(code: u64) -> (u32, u32) {
+    #[cfg(target_arch = "x86_64")]
+    {
+        if std::is_x86_feature_detected!("bmi2") {
+            // SAFETY: the processor supports the bmi2 instructions.
+            return unsafe { bmi2::decode2(code) };
+        }
+    }
+
+    (compact(code), compact(code >> 1))
+}
+
+/// Inserts a zero bit between each bit of the value.
+fn spread(x: u32) -> u64 {
+    let mut x = x as u64;
+    x = (x | (x << 16)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x << 8)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x << 2)) & 0x3333_3333_3333_3333;
+    x = (x | (x << 1)) & EVEN_BITS;
+    x
+}
+
+/// Keeps the even bits of the value, the inverse of [`spread`].
+fn compact(x: u64) -> u32 {
+    let mut x = x & EVEN_BITS;
+    x = (x | (x >> 1)) & 0x3333_3333_3333_3333;
+    x = (x | (x >> 2)) & 0x0F0F_0F0F_0F0F_0F0F;
+    x = (x | (x >> 4)) & 0x00FF_00FF_00FF_00FF;
+    x = (x | (x >> 8)) & 0x0000_FFFF_0000_FFFF;
+    x = (x | (x >> 16)) & 0x0000_0000_FFFF_FFFF;
+    x as u32
+}
+
+#[cfg(target_arch = "x86_64")]
+mod bmi2 {
+    use super::{EVEN_BITS, ODD_BITS};
+    use std::arch::x86_64::{_pdep_u64, _pext_u64};
+
+    #[target_feature(enable = "bmi2")]
+    pub(super) unsafe fn encode2(x: u32, y: u32) -> u64 {
+        _pdep_u64(x as u64, EVEN_BITS) | _pdep_u64(y as u64, ODD_BITS)
+    }
+
+    #[target_feature(enable = "bmi2")]
+    pub(super) unsafe fn decode2(code: u64) -> (u32, u32) {
+        (
+            _pext_u64(code, EVEN_BITS) as u32,
+            _pext_u64(code, ODD_BITS) as u32,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn encode_1_() {
+        assert_eq!(morton_encode([42]), 42);
+        assert_eq!(morton_decode::<1>(42), [42]);
+    }
+
+    #[test]
+    fn encode_2_() {
+        assert_eq!(morton_encode([0, 0]), 0);
+        assert_eq!(morton_encode([1, 0]), 1);
+        assert_eq!(morton_encode([0, 1]), 2);
+        assert_eq!(morton_encode([2, 3]), 0b1110);
+        assert_eq!(morton_encode([u32::MAX, u32::MAX]), u64::MAX as u128);
+    }
+
+    #[test]
+    fn encode_4_() {
+        assert_eq!(morton_encode([1, 0, 0, 1]), 0b1001);
+        assert_eq!(morton_encode([u32::MAX; 4]), u128::MAX);
+    }
+
+    #[test]
+    fn portable_() {
+        for (x, y) in [(0, 0), (5, 9), (u32::MAX, 0), (0x1234_5678, 0x9abc_def0)] {
+            let code = spread(x) | (spread(y) << 1);
+            assert_eq!(code, encode2(x, y));
+            assert_eq!((compact(code), compact(code >> 1)), (x, y));
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn encode_5_() {
+        let _ = morton_encode([0; 5]);
+    }
+
+    #[quickcheck]
+    fn prop_roundtrip_2_(x: u32, y: u32) -> bool {
+        morton_decode::<2>(morton_encode([x, y])) == [x, y]
+    }
+
+    #[quickcheck]
+    fn prop_roundtrip_3_(x: u32, y: u32, z: u32) -> bool {
+        morton_decode::<3>(morton_encode([x, y, z])) == [x, y, z]
+    }
+
+    #[quickcheck]
+    fn prop_roundtrip_4_(x: u32, y: u32, z: u32, w: u32) -> bool {
+        morton_decode::<4>(morton_encode([x, y, z, w])) == [x, y, z, w]
+    }
+}
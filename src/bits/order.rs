@@ -0,0 +1,128 @@
+use super::{BVec, Bit, Byte, ByteIter, Word, WordInt, WordIter};
+use itertools::Either;
+
+/// The order in which the bits of a [`Byte`], a [`Word`] or a [`BVec`] are iterated or collected,
+/// for when it is only known at runtime, such as the bit order of a file format.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{BitOrder, Byte};
+///
+/// let order = BitOrder::LsbFirst;
+/// let byte = Byte::from(0b0000_0110);
+/// assert_eq!(Byte::from_bits_in(order, byte.iter_in(order)), byte);
+/// assert_eq!(Byte::from_bits_in(BitOrder::MsbFirst, byte.iter_in(order)), Byte::from(0b0110_0000));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum BitOrder {
+    /// The most significant bit, at position 0, comes first.
+    #[default]
+    MsbFirst,
+    /// The least significant bit, at the last position, comes first.
+    LsbFirst,
+}
+
+impl Byte {
+    /// Returns an iterator over the bits in a given order.
+    /// See [`iter_msb`](Byte::iter_msb) and [`iter_lsb`](Byte::iter_lsb).
+    #[inline]
+    pub fn iter_in(&self, order: BitOrder) -> ByteIter {
+        match order {
+            BitOrder::MsbFirst => self.iter_msb(),
+            BitOrder::LsbFirst => self.iter_lsb(),
+        }
+    }
+
+    /// Creates a byte from at most 8 bits in a given order.
+    /// See [`from_bits_msb`](Byte::from_bits_msb) and [`from_bits_lsb`](Byte::from_bits_lsb).
+    #[inline]
+    pub fn from_bits_in<T: IntoIterator<Item = Bit>>(order: BitOrder, bits: T) -> Self {
+        match order {
+            BitOrder::MsbFirst => Byte::from_bits_msb(bits),
+            BitOrder::LsbFirst => Byte::from_bits_lsb(bits),
+        }
+    }
+}
+
+impl<T: WordInt> Word<T> {
+    /// Returns an iterator over the bits in a given order.
+    #[inline]
+    pub fn iter_in(&self, order: BitOrder) -> WordIter<T> {
+        match order {
+            BitOrder::MsbFirst => self.iter_msb(),
+            BitOrder::LsbFirst => self.iter_lsb(),
+        }
+    }
+
+    /// Creates a word from at most `BITS` bits in a given order.
+    #[inline]
+    pub fn from_bits_in<I: IntoIterator<Item = Bit>>(order: BitOrder, bits: I) -> Self {
+        match order {
+            BitOrder::MsbFirst => Word::from_bits_msb(bits),
+            BitOrder::LsbFirst => Word::from_bits_lsb(bits),
+        }
+    }
+}
+
+impl BVec {
+    /// Returns an iterator over the bits in a given order.
+    /// See [`iter_msb`](BVec::iter_msb) and [`iter_lsb`](BVec::iter_lsb).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BitOrder, BVec};
+    ///
+    /// let bvec = BVec::from_bits_msb([Bit::One, Bit::Zero, Bit::Zero]);
+    /// let bits: Vec<Bit> = bvec.iter_in(BitOrder::LsbFirst).collect();
+    /// assert_eq!(bits, [Bit::Zero, Bit::Zero, Bit::One]);
+    /// ```
+    pub fn iter_in(
+        &self,
+        order: BitOrder,
+    ) -> impl DoubleEndedIterator<Item = Bit> + ExactSizeIterator + '_ {
+        match order {
+            BitOrder::MsbFirst => Either::Left(self.iter_msb()),
+            BitOrder::LsbFirst => Either::Right(self.iter_lsb()),
+        }
+    }
+
+    /// Creates a vector from a sequence of bits in a given order.
+    /// See [`from_bits_msb`](BVec::from_bits_msb) and [`from_bits_lsb`](BVec::from_bits_lsb).
+    pub fn from_bits_in<T: IntoIterator<Item = Bit>>(order: BitOrder, bits: T) -> Self {
+        match order {
+            BitOrder::MsbFirst => BVec::from_bits_msb(bits),
+            BitOrder::LsbFirst => BVec::from_bits_lsb(bits),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bits::Word32;
+    use quickcheck_macros::quickcheck;
+
+    const ORDERS: [BitOrder; 2] = [BitOrder::MsbFirst, BitOrder::LsbFirst];
+
+    #[quickcheck]
+    fn roundtrip_(x: u8, y: u32, bits: Vec<bool>) -> bool {
+        let (byte, word) = (Byte::from(x), Word32::from(y));
+        let bvec: BVec = bits.into_iter().collect();
+
+        ORDERS.iter().all(|order| {
+            Byte::from_bits_in(*order, byte.iter_in(*order)) == byte
+                && Word32::from_bits_in(*order, word.iter_in(*order)) == word
+                && BVec::from_bits_in(*order, bvec.iter_in(*order)) == bvec
+        })
+    }
+
+    #[quickcheck]
+    fn lsb_is_reversed_msb_(y: u16) -> bool {
+        let word = Word::from(y);
+        let mut msb: Vec<Bit> = word.iter_in(BitOrder::MsbFirst).collect();
+        msb.reverse();
+        word.iter_in(BitOrder::LsbFirst).eq(msb)
+    }
+}
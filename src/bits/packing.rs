@@ -0,0 +1,270 @@
+use super::{BVec, Bit};
+use crate::compress::{varint, VarintError};
+use std::iter::FusedIterator;
+
+/// Packs integers using a fixed number of bits for each of them, most significant bit first.
+///
+/// # Panics
+///
+/// Panics if the width is not in the `1..=64` range, or if a value does not fit in `width` bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{pack, unpack};
+///
+/// let bvec = pack([5, 0, 7, 2], 3);
+/// assert_eq!(bvec.len(), 12);
+/// assert_eq!(bvec.as_bytes(), [0b1010_0011, 0b1010_0000]);
+/// assert_eq!(unpack(&bvec, 3).collect::<Vec<_>>(), [5, 0, 7, 2]);
+/// ```
+pub fn pack<I>(values: I, width: u32) -> BVec
+where
+    I: IntoIterator<Item = u64>,
+{
+    check_width(width);
+
+    let values = values.into_iter();
+    let mut bvec = BVec::with_capacity(values.size_hint().0 * width as usize);
+    for value in values {
+        assert!(
+            width == u64::BITS || value >> width == 0,
+            "the value {value} does not fit in {width} bits"
+        );
+
+        for b in (0..width).rev() {
+            bvec.push(Bit::from((value >> b) & 1 == 1));
+        }
+    }
+
+    bvec
+}
+
+/// Returns an iterator over the integers packed with [`pack`] using `width` bits each.
+///
+/// # Panics
+///
+/// Panics if the width is not in the `1..=64` range, or if the length of the vector
+/// is not a multiple of the width.
+pub fn unpack(bvec: &BVec, width: u32) -> impl Iterator<Item = u64> + '_ {
+    check_width(width);
+
+    let width = width as usize;
+    assert!(
+        bvec.len().is_multiple_of(width),
+        "the length {} is not a multiple of the width {}",
+        bvec.len(),
+        width
+    );
+
+    bvec.chunks_exact_bits(width as u32)
+}
+
+impl BVec {
+    /// Returns an iterator over consecutive chunks of `n` bits, each as an integer whose
+    /// most significant of the `n` bits is the first bit of the chunk. The chunks may cross
+    /// byte boundaries. The last bits, when the length of the vector is not a multiple of `n`,
+    /// are not returned.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `n` is not in the `1..=64` range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1010_0011, 0b1010_0000], 14);
+    /// let chunks: Vec<u64> = bvec.chunks_exact_bits(3).collect();
+    /// assert_eq!(chunks, [0b101, 0b000, 0b111, 0b010]);
+    /// assert_eq!(bvec.chunks_exact_bits(5).len(), 2);
+    /// ```
+    pub fn chunks_exact_bits(&self, n: u32) -> ChunksExactBits<'_> {
+        check_width(n);
+
+        let width = n as usize;
+        ChunksExactBits {
+            bytes: self.as_bytes(),
+            width,
+            start: 0,
+            end: self.len() - self.len() % width,
+        }
+    }
+}
+
+/// An iterator over the chunks of a fixed number of bits of a [`BVec`], as integers.
+///
+/// The `struct` is created by the [`BVec::chunks_exact_bits`] method. See its documentation for more.
+pub struct ChunksExactBits<'a> {
+    bytes: &'a [u8],
+    width: usize,
+    // the range of the bits of the remaining chunks
+    start: usize,
+    end: usize,
+}
+
+impl ChunksExactBits<'_> {
+    /// Returns the `width` bits starting at bit `start`, which span at most 9 bytes.
+    fn chunk_at(&self, start: usize) -> u64 {
+        let (first, last) = (start / 8, (start + self.width - 1) / 8);
+        let value = self.bytes[first..=last]
+            .iter()
+            .fold(0_u128, |value, byte| (value << 8) | *byte as u128);
+
+        let shift = (last + 1) * 8 - (start + self.width);
+        ((value >> shift) & (u128::MAX >> (128 - self.width))) as u64
+    }
+}
+
+impl Iterator for ChunksExactBits<'_> {
+    type Item = u64;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            None
+        } else {
+            let chunk = self.chunk_at(self.start);
+            self.start += self.width;
+            Some(chunk)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.start) / self.width;
+        (remaining, Some(remaining))
+    }
+}
+
+impl DoubleEndedIterator for ChunksExactBits<'_> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            None
+        } else {
+            self.end -= self.width;
+            Some(self.chunk_at(self.end))
+        }
+    }
+}
+
+impl ExactSizeIterator for ChunksExactBits<'_> {}
+
+impl FusedIterator for ChunksExactBits<'_> {}
+
+fn check_width(width: u32) {
+    assert!(
+        (1..=u64::BITS).contains(&width),
+        "the width must be between 1 and 64 bits"
+    );
+}
+
+/// Appends the [LEB128](https://en.wikipedia.org/wiki/LEB128) encoding of a value to a buffer:
+/// seven bits per byte, least significant group first, with the high bit set on all but the last byte.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{decode_leb128, encode_leb128};
+///
+/// let mut bytes = Vec::new();
+/// encode_leb128(300, &mut bytes);
+/// assert_eq!(bytes, [0b1010_1100, 0b0000_0010]);
+/// assert_eq!(decode_leb128(&bytes), Ok((300, 2)));
+/// ```
+#[inline]
+pub fn encode_leb128(value: u64, out: &mut Vec<u8>) {
+    varint::encode_u64(value, out)
+}
+
+/// Decodes a LEB128 value from the start of a slice and returns it with the number of bytes read.
+#[inline]
+pub fn decode_leb128(bytes: &[u8]) -> Result<(u64, usize), VarintError> {
+    varint::decode_u64(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn full_width_() {
+        let values = [u64::MAX, 0, 1 << 63];
+        let bvec = pack(values, 64);
+        assert_eq!(bvec.len(), 192);
+        assert!(unpack(&bvec, 64).eq(values));
+    }
+
+    #[test]
+    fn empty_() {
+        let bvec = pack([], 5);
+        assert!(bvec.is_empty());
+        assert_eq!(unpack(&bvec, 5).count(), 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_large_() {
+        pack([8], 3);
+    }
+
+    #[test]
+    #[should_panic]
+    fn bad_length_() {
+        let _ = unpack(&BVec::with_length(10), 3);
+    }
+
+    #[quickcheck]
+    fn pack_roundtrip_(values: Vec<u64>, width: u8) -> bool {
+        let width = width as u32 % 64 + 1;
+        let values: Vec<u64> = values
+            .into_iter()
+            .map(|v| {
+                if width == 64 {
+                    v
+                } else {
+                    v & ((1 << width) - 1)
+                }
+            })
+            .collect();
+
+        let bvec = pack(values.iter().copied(), width);
+        bvec.len() == values.len() * width as usize && unpack(&bvec, width).eq(values)
+    }
+
+    #[quickcheck]
+    fn chunks_exact_bits_(bits: Vec<bool>, width: u8) -> bool {
+        let width = width as usize % 64 + 1;
+        let bvec: BVec = bits.iter().copied().collect();
+        let expected: Vec<u64> = bits
+            .chunks_exact(width)
+            .map(|chunk| {
+                chunk
+                    .iter()
+                    .fold(0, |value, bit| (value << 1) | *bit as u64)
+            })
+            .collect();
+
+        let chunks = bvec.chunks_exact_bits(width as u32);
+        chunks.len() == expected.len()
+            && chunks.eq(expected.iter().copied())
+            && bvec
+                .chunks_exact_bits(width as u32)
+                .rev()
+                .eq(expected.iter().rev().copied())
+    }
+
+    #[test]
+    #[should_panic]
+    fn chunks_too_wide_() {
+        let _ = BVec::with_length(100).chunks_exact_bits(65);
+    }
+
+    #[quickcheck]
+    fn leb128_roundtrip_(value: u64) -> bool {
+        let mut bytes = Vec::new();
+        encode_leb128(value, &mut bytes);
+        decode_leb128(&bytes) == Ok((value, bytes.len()))
+    }
+}
@@ -0,0 +1,112 @@
+use super::{BVec, Bit, Byte};
+use std::iter::FusedIterator;
+
+impl Byte {
+    /// Returns the parity of the byte: [`Bit::One`] if it has an odd number of bits set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Byte};
+    ///
+    /// assert_eq!(Byte::from(0b1011_0000).parity(), Bit::One);
+    /// assert_eq!(Byte::from(0b1001_0000).parity(), Bit::Zero);
+    /// ```
+    #[inline]
+    pub fn parity(&self) -> Bit {
+        Bit::from(!self.count_ones().is_multiple_of(2))
+    }
+}
+
+impl BVec {
+    /// Returns the parity of the vector: [`Bit::One`] if it has an odd number of bits set.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, BVec};
+    ///
+    /// let bvec = BVec::from_bytes(&[0xFF, 0b1000_0000], 9);
+    /// assert_eq!(bvec.parity(), Bit::One);
+    /// assert_eq!(BVec::with_length(3).parity(), Bit::Zero);
+    /// ```
+    pub fn parity(&self) -> Bit {
+        let parity = self.as_bytes().iter().fold(0, |acc, byte| acc ^ byte);
+        Bit::from(!parity.count_ones().is_multiple_of(2))
+    }
+}
+
+/// Extension methods for streams of bits.
+///
+/// ```
+/// use aabel_rs::bits::{Bit, BitAdaptors};
+///
+/// let bits = [Bit::One, Bit::Zero, Bit::One];
+/// let parities: Vec<Bit> = bits.into_iter().running_parity().collect();
+/// assert_eq!(parities, [Bit::One, Bit::One, Bit::Zero]);
+/// ```
+pub trait BitAdaptors: Iterator<Item = Bit> {
+    /// Returns an iterator over the parities of the prefixes of the stream,
+    /// so each item is the parity of the bits seen so far, including the current one.
+    /// The last item is the parity of the whole stream, which can serve as a checksum.
+    fn running_parity(self) -> RunningParity<Self>
+    where
+        Self: Sized,
+    {
+        RunningParity {
+            iter: self,
+            odd: false,
+        }
+    }
+}
+
+impl<I: Iterator<Item = Bit>> BitAdaptors for I {}
+
+/// An iterator over the running parity of a stream of bits.
+///
+/// The `struct` is created by the [`BitAdaptors::running_parity`] method. See its documentation for more.
+pub struct RunningParity<I> {
+    iter: I,
+    odd: bool,
+}
+
+impl<I: Iterator<Item = Bit>> Iterator for RunningParity<I> {
+    type Item = Bit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let bit = self.iter.next()?;
+        self.odd ^= bool::from(bit);
+        Some(Bit::from(self.odd))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+impl<I: ExactSizeIterator<Item = Bit>> ExactSizeIterator for RunningParity<I> {}
+
+impl<I: FusedIterator<Item = Bit>> FusedIterator for RunningParity<I> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn byte_parity_(x: u8) -> bool {
+        let byte = Byte::from(x);
+        byte.parity() == byte.iter().running_parity().last().unwrap()
+    }
+
+    #[quickcheck]
+    fn bvec_parity_(bits: Vec<bool>) -> bool {
+        let bvec: BVec = bits.iter().copied().collect();
+        let ones = bits.iter().filter(|b| **b).count();
+
+        let parities: Vec<Bit> = bvec.iter().running_parity().collect();
+        bvec.parity() == Bit::from(ones % 2 == 1)
+            && parities.len() == bits.len()
+            && parities.last().copied().unwrap_or(Bit::Zero) == bvec.parity()
+    }
+}
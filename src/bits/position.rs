@@ -1,3 +1,4 @@
+use super::{check_index, BitsError};
 use std::fmt::{Debug, Display};
 
 /// Represents a position in the array of bits.
@@ -49,6 +50,23 @@ impl From<Position> for usize {
 }
 
 impl Position {
+    /// Creates the position of a bit inside a byte of an array, or returns
+    /// an error if the bit is not smaller than 8.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BitsError, Position};
+    ///
+    /// let pos = Position::try_new(2, 3).unwrap();
+    /// assert_eq!(usize::from(pos), 19);
+    /// assert_eq!(Position::try_new(2, 8), Err(BitsError::OutOfRange { index: 8, len: 8 }));
+    /// ```
+    pub fn try_new(idx: usize, bit: u8) -> Result<Self, BitsError> {
+        check_index(bit as usize, super::U8SIZE)?;
+        Ok(Self { idx, bit })
+    }
+
     pub fn increment(self) -> Self {
         let bit = self.bit + 1;
         if bit as usize >= super::U8SIZE {
@@ -99,6 +117,12 @@ mod tests {
         assert_ne!(pos1, pos2);
     }
 
+    #[test]
+    fn try_new_() {
+        assert_eq!(Position::try_new(1, 2), Ok(Position::from(10)));
+        assert!(Position::try_new(0, u8::MAX).is_err());
+    }
+
     #[test]
     fn incr_() {
         let pos = Position::from(6);
@@ -0,0 +1,72 @@
+use super::{BVec, Bit, Byte};
+use quickcheck::{Arbitrary, Gen};
+
+impl Arbitrary for Bit {
+    fn arbitrary(g: &mut Gen) -> Self {
+        Bit::from(bool::arbitrary(g))
+    }
+
+    /// A one shrinks to a zero.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(bool::from(*self).shrink().map(Bit::from))
+    }
+}
+
+impl Arbitrary for Byte {
+    fn arbitrary(g: &mut Gen) -> Self {
+        u8::arbitrary(g).into()
+    }
+
+    /// Shrinks towards the zero byte.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        Box::new(u8::from(*self).shrink().map(Byte::from))
+    }
+}
+
+impl Arbitrary for BVec {
+    /// Creates a vector of up to `g.size()` random bits.
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<bool>::arbitrary(g).into_iter().collect()
+    }
+
+    /// Shrinks towards shorter vectors, by dropping runs of bits,
+    /// and towards sparser vectors, by clearing single bits.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let bits: Vec<bool> = self.iter().map(bool::from).collect();
+        Box::new(bits.shrink().map(|bits| bits.into_iter().collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{QuickCheck, TestResult};
+
+    #[test]
+    fn shrink_bvec_() {
+        let bvec: BVec = [true, false, true, true].into_iter().collect();
+        let shrunk: Vec<BVec> = bvec.shrink().collect();
+
+        assert!(shrunk.iter().any(BVec::is_empty));
+        assert!(shrunk.iter().any(|s| s.len() == 4 && s.count_ones() == 2));
+        assert!(shrunk
+            .iter()
+            .all(|s| s.len() < 4 || s.count_ones() < bvec.count_ones()));
+    }
+
+    #[test]
+    fn shrink_minimal_() {
+        // a property failing for any vector with two ones shrinks to two ones
+        fn prop(bvec: BVec) -> TestResult {
+            TestResult::from_bool(bvec.count_ones() < 2)
+        }
+
+        let result = std::panic::catch_unwind(|| {
+            QuickCheck::new()
+                .tests(1_000)
+                .quickcheck(prop as fn(BVec) -> TestResult)
+        });
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("(BVec(2:11))"), "{message}");
+    }
+}
@@ -0,0 +1,261 @@
+use super::{BVec, Bit};
+use crate::compress::{varint, VarintError};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// The largest number of bits decoded by [`BVec::from_rle_bytes`], 2 MiB of bits.
+pub const RLE_MAX_LEN: usize = 1 << 24;
+
+/// The error returned when run-length encoded bytes cannot be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RleError {
+    /// The number of runs or the length of a run is not a valid varint.
+    Varint(VarintError),
+    /// The vector is longer than the limit of the decoder.
+    LimitExceeded,
+}
+
+impl Display for RleError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Varint(err) => write!(f, "the run-length encoding is not valid: {err}"),
+            Self::LimitExceeded => write!(f, "the vector exceeds the length limit"),
+        }
+    }
+}
+
+impl Error for RleError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Varint(err) => Some(err),
+            Self::LimitExceeded => None,
+        }
+    }
+}
+
+impl From<VarintError> for RleError {
+    fn from(err: VarintError) -> Self {
+        Self::Varint(err)
+    }
+}
+
+/// Run-length encoding of the vectors with long runs of equal bits, such as coverage masks.
+impl BVec {
+    /// Returns the runs of equal bits of the vector, as `(bit, length)` pairs in order.
+    /// Consecutive runs have different bits and no run is empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BVec, Bit};
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1110_0000, 0b0011_0000], 12);
+    /// assert_eq!(
+    ///     bvec.to_rle(),
+    ///     [(Bit::One, 3), (Bit::Zero, 7), (Bit::One, 2)]
+    /// );
+    /// assert!(BVec::with_length(0).to_rle().is_empty());
+    /// ```
+    pub fn to_rle(&self) -> Vec<(Bit, usize)> {
+        let mut runs = Vec::new();
+        let mut start = 0;
+
+        while start < self.len() {
+            let bit = self.get_bit(start);
+            let end = match bit {
+                Bit::One => self.next_zero(start),
+                Bit::Zero => self.next_one(start),
+            }
+            .unwrap_or(self.len());
+
+            runs.push((bit, end - start));
+            start = end;
+        }
+
+        runs
+    }
+
+    /// Creates a vector from its runs of equal bits. The runs do not need to alternate
+    /// and may be empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BVec, Bit};
+    ///
+    /// let bvec = BVec::from_rle([(Bit::Zero, 2), (Bit::One, 3), (Bit::One, 1)]);
+    /// assert_eq!(bvec.len(), 6);
+    /// assert_eq!(bvec.to_rle(), [(Bit::Zero, 2), (Bit::One, 4)]);
+    /// ```
+    pub fn from_rle<I>(runs: I) -> Self
+    where
+        I: IntoIterator<Item = (Bit, usize)>,
+    {
+        let mut bvec = BVec::with_length(0);
+        for (bit, run) in runs {
+            bvec.resize(bvec.len() + run, bit);
+        }
+        bvec
+    }
+
+    /// Returns the run-length encoding of the vector as bytes: the number of runs and the
+    /// value of the first bit, followed by the lengths of the alternating runs, all as varints.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let mut bvec = BVec::with_length(100_000);
+    /// bvec.set_range(1_000..2_000);
+    ///
+    /// let bytes = bvec.to_rle_bytes();
+    /// assert_eq!(bytes.len(), 8);
+    /// assert_eq!(BVec::from_rle_bytes(&bytes).unwrap(), (bvec, 8));
+    /// ```
+    pub fn to_rle_bytes(&self) -> Vec<u8> {
+        let runs = self.to_rle();
+        let first = runs.first().map_or(0, |(bit, _)| (*bit == Bit::One) as u64);
+
+        let mut bytes = Vec::new();
+        varint::encode_u64(((runs.len() as u64) << 1) | first, &mut bytes);
+        for (_, run) in runs {
+            varint::encode_u64(run as u64, &mut bytes);
+        }
+        bytes
+    }
+
+    /// Decodes a vector written by [`BVec::to_rle_bytes`] from the start of a slice,
+    /// and returns it with the number of bytes read.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`RleError::LimitExceeded`] instead of allocating a vector longer than
+    /// [`RLE_MAX_LEN`] bits. Use [`BVec::from_rle_bytes_limited`] for longer vectors.
+    pub fn from_rle_bytes(bytes: &[u8]) -> Result<(Self, usize), RleError> {
+        Self::from_rle_bytes_limited(bytes, RLE_MAX_LEN)
+    }
+
+    /// Decodes a vector written by [`BVec::to_rle_bytes`] from the start of a slice, like
+    /// [`BVec::from_rle_bytes`], but fails with [`RleError::LimitExceeded`] instead of
+    /// allocating a vector longer than `max_len` bits. A few bytes can encode runs of any
+    /// length, so the limit should fit the input when it is untrusted, and when fuzzing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BVec, RleError};
+    ///
+    /// // a single run of 2^62 zeros
+    /// let bytes = [0b10, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x40];
+    /// assert_eq!(
+    ///     BVec::from_rle_bytes_limited(&bytes, 1 << 20),
+    ///     Err(RleError::LimitExceeded)
+    /// );
+    ///
+    /// let bvec = BVec::with_length(1_000);
+    /// let bytes = bvec.to_rle_bytes();
+    /// assert_eq!(BVec::from_rle_bytes_limited(&bytes, 1 << 20), Ok((bvec, bytes.len())));
+    /// ```
+    pub fn from_rle_bytes_limited(bytes: &[u8], max_len: usize) -> Result<(Self, usize), RleError> {
+        let (header, mut read) = varint::decode_u64(bytes)?;
+        let mut one = header & 1 == 1;
+
+        let mut bvec = BVec::with_length(0);
+        for _ in 0..header >> 1 {
+            let (run, n) = varint::decode_u64(&bytes[read..])?;
+            let len = usize::try_from(run)
+                .ok()
+                .and_then(|run| bvec.len().checked_add(run))
+                .filter(|len| *len <= max_len)
+                .ok_or(RleError::LimitExceeded)?;
+
+            bvec.resize(len, Bit::from(one));
+            one = !one;
+            read += n;
+        }
+
+        Ok((bvec, read))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn runs_() {
+        let bvec = BVec::from_bytes(&[0xFF, 0xFF, 0x00, 0x01], 32);
+        assert_eq!(
+            bvec.to_rle(),
+            [(Bit::One, 16), (Bit::Zero, 15), (Bit::One, 1)]
+        );
+        assert_eq!(BVec::with_length(5).to_rle(), [(Bit::Zero, 5)]);
+    }
+
+    #[test]
+    fn bytes_() {
+        let empty = BVec::with_length(0);
+        assert_eq!(empty.to_rle_bytes(), [0]);
+        assert_eq!(BVec::from_rle_bytes(&[0]).unwrap(), (empty, 1));
+
+        // a trailing byte is left unread
+        let bvec = BVec::from_bytes(&[0b1100_0000], 3);
+        let mut bytes = bvec.to_rle_bytes();
+        assert_eq!(bytes, [0b101, 2, 1]);
+        bytes.push(42);
+        assert_eq!(BVec::from_rle_bytes(&bytes).unwrap(), (bvec, 3));
+
+        assert_eq!(
+            BVec::from_rle_bytes(&[0b101, 2]),
+            Err(RleError::Varint(VarintError::Truncated))
+        );
+    }
+
+    #[test]
+    fn limit_() {
+        // a single run of 2^62 zeros, and two runs which only exceed the limit together
+        let huge = [0b10, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x40];
+        assert_eq!(BVec::from_rle_bytes(&huge), Err(RleError::LimitExceeded));
+        assert_eq!(
+            BVec::from_rle_bytes_limited(&[0b100, 5, 6], 10),
+            Err(RleError::LimitExceeded)
+        );
+
+        let bvec = BVec::from_rle([(Bit::Zero, 4), (Bit::One, 6)]);
+        let bytes = bvec.to_rle_bytes();
+        assert_eq!(
+            BVec::from_rle_bytes_limited(&bytes, 10),
+            Ok((bvec, bytes.len()))
+        );
+
+        let longest = BVec::with_length(RLE_MAX_LEN);
+        assert!(BVec::from_rle_bytes(&longest.to_rle_bytes()).is_ok());
+    }
+
+    #[quickcheck]
+    fn rle_roundtrip_(bits: Vec<bool>) -> bool {
+        let bvec = BVec::from_iter(bits);
+        let runs = bvec.to_rle();
+
+        let alternating = runs.windows(2).all(|w| w[0].0 != w[1].0);
+        let bytes = bvec.to_rle_bytes();
+
+        alternating
+            && runs.iter().all(|(_, run)| *run > 0)
+            && BVec::from_rle(runs) == bvec
+            && BVec::from_rle_bytes(&bytes).unwrap() == (bvec, bytes.len())
+    }
+
+    #[quickcheck]
+    fn rle_garbage_(bytes: Vec<u8>) -> bool {
+        // arbitrary input is rejected, or decodes to a bounded vector
+        match BVec::from_rle_bytes_limited(&bytes, 1 << 16) {
+            Ok((bvec, read)) => read <= bytes.len() && bvec.len() <= 1 << 16,
+            Err(_) => true,
+        }
+    }
+}
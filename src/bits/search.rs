@@ -0,0 +1,114 @@
+use super::{BVec, U8SIZE};
+
+/// Search of bit patterns which are not aligned to bytes, such as the sync words of a bitstream.
+impl BVec {
+    /// Returns the position of the first occurrence of a pattern, or `None` if it does not occur.
+    /// An empty pattern is found at position zero.
+    ///
+    /// Each candidate position is checked a byte at a time, by shifting the bytes of the vector
+    /// into alignment with the bytes of the pattern.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0b0001_0110, 0b1000_0000], 12);
+    /// let pattern = BVec::from_bytes(&[0b1011_0100], 6);
+    /// assert_eq!(bvec.find(&pattern), Some(3));
+    /// assert_eq!(bvec.find(&BVec::from_bytes(&[0xFF], 3)), None);
+    /// ```
+    pub fn find(&self, pattern: &BVec) -> Option<usize> {
+        self.matches(pattern).next()
+    }
+
+    /// Returns an iterator over the positions of all the occurrences of a pattern, in increasing
+    /// order. Overlapping occurrences are all reported.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1111_0000], 8);
+    /// let pattern = BVec::from_bytes(&[0b1100_0000], 2);
+    /// assert_eq!(bvec.matches(&pattern).collect::<Vec<_>>(), [0, 1, 2]);
+    /// ```
+    pub fn matches<'a>(&'a self, pattern: &'a BVec) -> impl Iterator<Item = usize> + 'a {
+        let last = (self.len() + 1).saturating_sub(pattern.len());
+        (0..last).filter(move |start| self.matches_at(pattern, *start))
+    }
+
+    /// Returns true if the pattern occurs at a given position, which must leave room for it.
+    fn matches_at(&self, pattern: &BVec, start: usize) -> bool {
+        let bytes = self.as_bytes();
+        let tail = pattern.len() % U8SIZE;
+
+        pattern.as_bytes().iter().enumerate().all(|(k, expected)| {
+            let actual = bits_at(bytes, start + k * U8SIZE);
+            if k + 1 == pattern.as_bytes().len() && tail != 0 {
+                // the padding bits of the pattern are zero
+                actual & (u8::MAX << (U8SIZE - tail)) == *expected
+            } else {
+                actual == *expected
+            }
+        })
+    }
+}
+
+/// Returns the eight bits starting at a given bit position, with zeros beyond the end of the bytes.
+#[inline]
+fn bits_at(bytes: &[u8], start: usize) -> u8 {
+    let (idx, shift) = (start / U8SIZE, start % U8SIZE);
+    let high = bytes.get(idx).copied().unwrap_or(0);
+    if shift == 0 {
+        high
+    } else {
+        let low = bytes.get(idx + 1).copied().unwrap_or(0);
+        (high << shift) | (low >> (U8SIZE - shift))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bits::Bit;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn empty_() {
+        let bvec = BVec::from_bytes(&[0xAA], 3);
+        let empty = BVec::with_length(0);
+        assert_eq!(bvec.find(&empty), Some(0));
+        assert_eq!(bvec.matches(&empty).count(), 4);
+        assert_eq!(empty.find(&bvec), None);
+    }
+
+    #[test]
+    fn long_pattern_() {
+        // a 20 bit pattern straddling three bytes at an odd offset
+        let pattern = BVec::from_bytes(&[0xDE, 0xAD, 0xB0], 20);
+        let mut bvec = BVec::with_length(5);
+        bvec.extend(pattern.iter());
+        bvec.extend(std::iter::repeat_n(Bit::One, 7));
+
+        assert_eq!(bvec.find(&pattern), Some(5));
+        assert_eq!(bvec.matches(&pattern).collect::<Vec<_>>(), [5]);
+
+        // the padding bits of the vector do not match
+        let zeros = BVec::with_length(4);
+        assert_eq!(BVec::from_bytes(&[0xF0], 6).find(&zeros), None);
+    }
+
+    #[quickcheck]
+    fn matches_naive_(xs: Vec<bool>, pattern: Vec<bool>) -> bool {
+        let pattern = &pattern[..pattern.len().min(12)];
+        let expected: Vec<usize> = (0..(xs.len() + 1).saturating_sub(pattern.len()))
+            .filter(|i| xs[*i..*i + pattern.len()] == *pattern)
+            .collect();
+
+        let bvec: BVec = xs.into_iter().collect();
+        let pattern: BVec = pattern.iter().copied().collect();
+        bvec.matches(&pattern).collect::<Vec<_>>() == expected
+    }
+}
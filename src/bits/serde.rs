@@ -0,0 +1,118 @@
+use super::{BVec, Bit, Byte, Position};
+use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+impl Serialize for Bit {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        bool::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Bit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        bool::deserialize(deserializer).map(Bit::from)
+    }
+}
+
+impl Serialize for Byte {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        u8::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Byte {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        u8::deserialize(deserializer).map(Byte::from)
+    }
+}
+
+impl Serialize for Position {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        usize::from(*self).serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for Position {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        usize::deserialize(deserializer).map(Position::from)
+    }
+}
+
+/// The serialized form of a [`BVec`].
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "BVec")]
+struct BVecRepr<B> {
+    len: usize,
+    bytes: B,
+}
+
+impl Serialize for BVec {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        BVecRepr {
+            len: self.len(),
+            bytes: self.as_bytes(),
+        }
+        .serialize(serializer)
+    }
+}
+
+impl<'de> Deserialize<'de> for BVec {
+    /// Fails if the number of bytes does not match the length.
+    /// The bits of the last byte beyond the length are ignored.
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let repr = BVecRepr::<Vec<u8>>::deserialize(deserializer)?;
+
+        let expected = repr.len.div_ceil(super::U8SIZE);
+        if repr.bytes.len() != expected {
+            return Err(D::Error::custom(format!(
+                "{} bits need {} bytes but there are {}",
+                repr.len,
+                expected,
+                repr.bytes.len()
+            )));
+        }
+
+        Ok(BVec::from_bytes(&repr.bytes, repr.len))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bit_byte_position_() {
+        assert_eq!(serde_json::to_string(&Bit::One).unwrap(), "true");
+        assert_eq!(serde_json::from_str::<Bit>("false").unwrap(), Bit::Zero);
+
+        assert_eq!(serde_json::to_string(&Byte::from(7)).unwrap(), "7");
+        assert_eq!(
+            serde_json::from_str::<Byte>("200").unwrap(),
+            Byte::from(200)
+        );
+        assert!(serde_json::from_str::<Byte>("256").is_err());
+
+        let pos = Position::from(19);
+        assert_eq!(serde_json::to_string(&pos).unwrap(), "19");
+        assert_eq!(serde_json::from_str::<Position>("19").unwrap(), pos);
+    }
+
+    #[test]
+    fn bvec_() {
+        let mut bvec = BVec::from_bytes(&[0xAB, 0xFF], 12);
+        bvec.truncate(10);
+
+        let json = serde_json::to_string(&bvec).unwrap();
+        assert_eq!(json, r#"{"len":10,"bytes":[171,192]}"#);
+        assert_eq!(serde_json::from_str::<BVec>(&json).unwrap(), bvec);
+    }
+
+    #[test]
+    fn bvec_invalid_() {
+        assert!(serde_json::from_str::<BVec>(r#"{"len":10,"bytes":[1]}"#).is_err());
+        assert!(serde_json::from_str::<BVec>(r#"{"len":10,"bytes":[1,2,3]}"#).is_err());
+
+        // the padding bits are cleared
+        let bvec = serde_json::from_str::<BVec>(r#"{"len":4,"bytes":[255]}"#).unwrap();
+        assert_eq!(bvec.as_bytes(), [0xF0]);
+    }
+}
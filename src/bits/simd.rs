@@ -0,0 +1,115 @@
+//! Bulk operations over the bytes of bit vectors, a 64 bit word at a time
+//! instead of a byte at a time, enabled by the `simd` feature.
+//!
+//! The operations are bitwise, so the words are read in the native byte order.
+
+/// The number of bytes of a word.
+const WORD: usize = 8;
+/// The number of words processed by one iteration of the unrolled loops.
+const UNROLL: usize = 4;
+/// The number of bytes processed by one iteration of the unrolled loops.
+const BLOCK: usize = WORD * UNROLL;
+
+#[inline]
+fn word(bytes: &[u8]) -> u64 {
+    u64::from_ne_bytes(bytes.try_into().expect("a word has 8 bytes"))
+}
+
+/// Returns the number of bits set to one in `f(x, y)` over the bytes of two slices,
+/// the shorter one being padded with zeros. The operation must be bitwise, with `f(0, 0) == 0`.
+pub(super) fn count_ones_with<F>(xs: &[u8], ys: &[u8], f: F) -> usize
+where
+    F: Fn(u64, u64) -> u64,
+{
+    let common = xs.len().min(ys.len());
+    let (xs_head, xs_tail) = xs.split_at(common);
+    let (ys_head, ys_tail) = ys.split_at(common);
+
+    // independent counters, so the popcounts of a block do not wait on each other
+    let mut counts = [0_usize; UNROLL];
+    let blocks = xs_head.chunks_exact(BLOCK).zip(ys_head.chunks_exact(BLOCK));
+    for (x, y) in blocks {
+        for (k, count) in counts.iter_mut().enumerate() {
+            let range = k * WORD..(k + 1) * WORD;
+            *count += f(word(&x[range.clone()]), word(&y[range])).count_ones() as usize;
+        }
+    }
+
+    let done = common - common % BLOCK;
+    let bytes = xs_head[done..]
+        .iter()
+        .zip(&ys_head[done..])
+        .map(|(x, y)| (*x, *y))
+        .chain(xs_tail.iter().map(|x| (*x, 0)))
+        .chain(ys_tail.iter().map(|y| (0, *y)))
+        .map(|(x, y)| f(x as u64, y as u64).count_ones() as usize);
+
+    counts.iter().sum::<usize>() + bytes.sum::<usize>()
+}
+
+/// Returns the bytes of `f(x, y)` over the bytes of two slices, the shorter one being
+/// padded with zeros. The operation must be bitwise.
+pub(super) fn combine_with<F>(xs: &[u8], ys: &[u8], f: F) -> Vec<u8>
+where
+    F: Fn(u64, u64) -> u64,
+{
+    let common = xs.len().min(ys.len());
+    let len = xs.len().max(ys.len());
+    let mut out = Vec::with_capacity(len);
+
+    let words = xs[..common]
+        .chunks_exact(WORD)
+        .zip(ys[..common].chunks_exact(WORD));
+    for (x, y) in words {
+        out.extend_from_slice(&f(word(x), word(y)).to_ne_bytes());
+    }
+
+    let done = out.len();
+    out.extend((done..len).map(|i| {
+        let (x, y) = (xs.get(i).copied(), ys.get(i).copied());
+        f(x.unwrap_or(0) as u64, y.unwrap_or(0) as u64) as u8
+    }));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn count_ones_with_(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+        let byte = |i: usize, bytes: &[u8]| bytes.get(i).copied().unwrap_or(0);
+        let expected = (0..xs.len().max(ys.len()))
+            .map(|i| (byte(i, &xs) ^ byte(i, &ys)).count_ones() as usize)
+            .sum::<usize>();
+
+        count_ones_with(&xs, &ys, |x, y| x ^ y) == expected
+            && count_ones_with(&xs, &[], |x, _| x)
+                == xs.iter().map(|x| x.count_ones() as usize).sum::<usize>()
+    }
+
+    #[quickcheck]
+    fn combine_with_(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+        let byte = |i: usize, bytes: &[u8]| bytes.get(i).copied().unwrap_or(0);
+        let expected: Vec<u8> = (0..xs.len().max(ys.len()))
+            .map(|i| byte(i, &xs) | byte(i, &ys))
+            .collect();
+
+        combine_with(&xs, &ys, |x, y| x | y) == expected
+    }
+
+    #[test]
+    fn long_() {
+        // several unrolled blocks, with tails of words and of bytes
+        let xs: Vec<u8> = (0..1_000).map(|i| (i * 7 % 256) as u8).collect();
+        let ys: Vec<u8> = (0..997).map(|i| (i * 13 % 256) as u8).collect();
+
+        let and = combine_with(&xs, &ys, |x, y| x & y);
+        assert_eq!(and.len(), 1_000);
+        assert_eq!(
+            count_ones_with(&xs, &ys, |x, y| x & y),
+            and.iter().map(|b| b.count_ones() as usize).sum::<usize>()
+        );
+    }
+}
@@ -0,0 +1,303 @@
+use super::BVec;
+use std::{
+    cmp::Ordering,
+    mem::size_of,
+    ops::{BitAnd, BitOr},
+};
+
+/// A set of bit positions, stored as the sorted positions of the bits set to one.
+///
+/// Unlike [`BVec`], the set has no length and its memory grows with the number of ones
+/// instead of the largest position, which suits very sparse data, such as a few features
+/// out of millions. Membership is a binary search, and the set operations merge the sorted
+/// positions in linear time. For sparse data which is also large, [`CompressedBVec`] keeps
+/// dense regions as bitmaps.
+///
+/// [`CompressedBVec`]: super::CompressedBVec
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{BVec, SparseBitSet};
+///
+/// let xs: SparseBitSet = [3, 1_000_000, 7].into_iter().collect();
+/// let ys: SparseBitSet = [7, 42].into_iter().collect();
+///
+/// assert!(xs.contains(1_000_000));
+/// assert_eq!((&xs | &ys).iter().collect::<Vec<_>>(), [3, 7, 42, 1_000_000]);
+/// assert_eq!((&xs & &ys).iter().collect::<Vec<_>>(), [7]);
+///
+/// let bvec = ys.to_bvec(64);
+/// assert_eq!(SparseBitSet::from(&bvec), ys);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct SparseBitSet {
+    ones: Vec<usize>,
+}
+
+impl SparseBitSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of positions in the set.
+    pub fn len(&self) -> usize {
+        self.ones.len()
+    }
+
+    /// Returns true if the set has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.ones.is_empty()
+    }
+
+    /// Returns true if the set contains the position.
+    pub fn contains(&self, bit: usize) -> bool {
+        self.ones.binary_search(&bit).is_ok()
+    }
+
+    /// Adds a position to the set. Returns true if the position was not already in the set.
+    pub fn insert(&mut self, bit: usize) -> bool {
+        match self.ones.binary_search(&bit) {
+            Ok(_) => false,
+            Err(idx) => {
+                self.ones.insert(idx, bit);
+                true
+            }
+        }
+    }
+
+    /// Removes a position from the set. Returns true if the position was in the set.
+    pub fn remove(&mut self, bit: usize) -> bool {
+        match self.ones.binary_search(&bit) {
+            Ok(idx) => {
+                self.ones.remove(idx);
+                true
+            }
+            Err(_) => false,
+        }
+    }
+
+    /// Returns the largest position of the set, or `None` if the set is empty.
+    pub fn max(&self) -> Option<usize> {
+        self.ones.last().copied()
+    }
+
+    /// Returns an iterator over the positions of the set, in increasing order.
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = usize> + ExactSizeIterator + '_ {
+        self.ones.iter().copied()
+    }
+
+    /// Returns the sorted positions of the set.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.ones
+    }
+
+    /// Returns the set of the positions which are in either set.
+    pub fn union(&self, other: &SparseBitSet) -> SparseBitSet {
+        let (xs, ys) = (&self.ones, &other.ones);
+        let mut ones = Vec::with_capacity(xs.len() + ys.len());
+        let (mut i, mut j) = (0, 0);
+
+        while i < xs.len() && j < ys.len() {
+            match xs[i].cmp(&ys[j]) {
+                Ordering::Less => {
+                    ones.push(xs[i]);
+                    i += 1;
+                }
+                Ordering::Greater => {
+                    ones.push(ys[j]);
+                    j += 1;
+                }
+                Ordering::Equal => {
+                    ones.push(xs[i]);
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+        ones.extend_from_slice(&xs[i..]);
+        ones.extend_from_slice(&ys[j..]);
+
+        Self { ones }
+    }
+
+    /// Returns the set of the positions which are in both sets.
+    pub fn intersection(&self, other: &SparseBitSet) -> SparseBitSet {
+        let (xs, ys) = (&self.ones, &other.ones);
+        let mut ones = Vec::with_capacity(xs.len().min(ys.len()));
+        let (mut i, mut j) = (0, 0);
+
+        while i < xs.len() && j < ys.len() {
+            match xs[i].cmp(&ys[j]) {
+                Ordering::Less => i += 1,
+                Ordering::Greater => j += 1,
+                Ordering::Equal => {
+                    ones.push(xs[i]);
+                    i += 1;
+                    j += 1;
+                }
+            }
+        }
+
+        Self { ones }
+    }
+
+    /// Returns the approximate number of heap bytes used by the set.
+    pub fn size_in_bytes(&self) -> usize {
+        self.ones.capacity() * size_of::<usize>()
+    }
+
+    /// Returns a vector of `len` bits with the bits of the set positions set to one.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a position of the set is not smaller than `len`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::SparseBitSet;
+    ///
+    /// let set: SparseBitSet = [0, 9].into_iter().collect();
+    /// assert_eq!(set.to_bvec(10).ones().collect::<Vec<_>>(), [0, 9]);
+    /// ```
+    pub fn to_bvec(&self, len: usize) -> BVec {
+        let mut bvec = BVec::with_length(len);
+        self.ones.iter().for_each(|bit| bvec.set_bit(*bit));
+        bvec
+    }
+}
+
+impl FromIterator<usize> for SparseBitSet {
+    /// Creates a set from positions in any order, ignoring the duplicates.
+    fn from_iter<I: IntoIterator<Item = usize>>(iter: I) -> Self {
+        let mut ones: Vec<usize> = iter.into_iter().collect();
+        ones.sort_unstable();
+        ones.dedup();
+        Self { ones }
+    }
+}
+
+impl Extend<usize> for SparseBitSet {
+    fn extend<I: IntoIterator<Item = usize>>(&mut self, iter: I) {
+        self.ones.extend(iter);
+        self.ones.sort_unstable();
+        self.ones.dedup();
+    }
+}
+
+impl From<&BVec> for SparseBitSet {
+    /// Creates the set of the positions of the bits set to one.
+    fn from(bvec: &BVec) -> Self {
+        Self {
+            ones: bvec.ones().collect(),
+        }
+    }
+}
+
+impl From<BVec> for SparseBitSet {
+    #[inline]
+    fn from(bvec: BVec) -> Self {
+        Self::from(&bvec)
+    }
+}
+
+impl From<&SparseBitSet> for BVec {
+    /// Creates the shortest vector which holds all the positions of the set.
+    #[inline]
+    fn from(set: &SparseBitSet) -> Self {
+        set.to_bvec(set.max().map_or(0, |max| max + 1))
+    }
+}
+
+impl From<SparseBitSet> for BVec {
+    #[inline]
+    fn from(set: SparseBitSet) -> Self {
+        BVec::from(&set)
+    }
+}
+
+impl BitAnd<&SparseBitSet> for &SparseBitSet {
+    type Output = SparseBitSet;
+
+    #[inline]
+    fn bitand(self, rhs: &SparseBitSet) -> Self::Output {
+        self.intersection(rhs)
+    }
+}
+
+impl BitOr<&SparseBitSet> for &SparseBitSet {
+    type Output = SparseBitSet;
+
+    #[inline]
+    fn bitor(self, rhs: &SparseBitSet) -> Self::Output {
+        self.union(rhs)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use std::collections::BTreeSet;
+
+    #[test]
+    fn insert_remove_() {
+        let mut set = SparseBitSet::new();
+        assert!(set.is_empty());
+        assert!(set.insert(10));
+        assert!(set.insert(2));
+        assert!(!set.insert(10));
+        assert_eq!(set.len(), 2);
+        assert_eq!(set.max(), Some(10));
+
+        assert!(set.remove(10));
+        assert!(!set.remove(10));
+        assert!(!set.contains(10));
+        assert_eq!(set.as_slice(), [2]);
+
+        set.extend([5, 1, 5]);
+        assert_eq!(set.as_slice(), [1, 2, 5]);
+    }
+
+    #[test]
+    fn bvec_() {
+        assert!(BVec::from(SparseBitSet::new()).is_empty());
+
+        let set: SparseBitSet = [1, 12].into_iter().collect();
+        let bvec = BVec::from(&set);
+        assert_eq!(bvec.len(), 13);
+        assert_eq!(SparseBitSet::from(bvec), set);
+    }
+
+    #[test]
+    #[should_panic]
+    fn to_bvec_too_short_() {
+        let set: SparseBitSet = [1, 12].into_iter().collect();
+        set.to_bvec(12);
+    }
+
+    #[quickcheck]
+    fn set_ops_(xs: Vec<u16>, ys: Vec<u16>) -> bool {
+        let to_set = |v: &[u16]| v.iter().map(|x| *x as usize).collect::<SparseBitSet>();
+        let to_tree = |v: &[u16]| v.iter().map(|x| *x as usize).collect::<BTreeSet<_>>();
+        let (sx, sy) = (to_set(&xs), to_set(&ys));
+        let (tx, ty) = (to_tree(&xs), to_tree(&ys));
+
+        (&sx | &sy).iter().eq(tx.union(&ty).copied())
+            && (&sx & &sy).iter().eq(tx.intersection(&ty).copied())
+            && xs.iter().all(|x| sx.contains(*x as usize))
+    }
+
+    #[quickcheck]
+    fn dense_agrees_(xs: Vec<u16>, ys: Vec<u16>) -> bool {
+        let (sx, sy): (SparseBitSet, SparseBitSet) = (
+            xs.iter().map(|x| *x as usize).collect(),
+            ys.iter().map(|y| *y as usize).collect(),
+        );
+        let (bx, by) = (sx.to_bvec(1 << 16), sy.to_bvec(1 << 16));
+
+        SparseBitSet::from(&bx | &by) == &sx | &sy && SparseBitSet::from(&bx & &by) == &sx & &sy
+    }
+}
@@ -0,0 +1,360 @@
+use super::{BVec, Bit};
+use std::mem::size_of;
+
+const WORD_BITS: usize = u64::BITS as usize;
+/// The number of bits covered by an entry of the block directory.
+const BLOCK_BITS: usize = 512;
+/// The number of bits covered by an entry of the superblock directory.
+const SUPER_BITS: usize = 4096;
+const BLOCKS_PER_SUPER: usize = SUPER_BITS / BLOCK_BITS;
+/// The number of ones (or zeros) between two select samples.
+const SAMPLE: usize = 4096;
+
+/// An immutable vector of bits with constant time rank and fast select queries.
+///
+/// The bits are stored in 64 bit words, next to two directories of cumulative counts:
+/// an absolute count of ones every 4096 bits and a count relative to it every 512 bits.
+/// A rank query adds the two counts and the popcounts of at most eight words. The directories,
+/// together with the sampled positions of every 4096th one and zero which narrow the search of
+/// a select query, take about 5% on top of the bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{BVec, SuccinctBVec};
+///
+/// let mut bvec = BVec::with_length(10_000);
+/// (0..10_000).step_by(3).for_each(|i| bvec.set_bit(i));
+///
+/// let bits = SuccinctBVec::from(&bvec);
+/// assert_eq!(bits.rank1(9_000), 3_000);
+/// assert_eq!(bits.select1(1_000), Some(3_000));
+/// assert_eq!(bits.select0(1), Some(2));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SuccinctBVec {
+    len: usize,
+    ones: usize,
+    // the bits, MSB-first in each word, with zero padding
+    words: Vec<u64>,
+    // the number of ones before each superblock
+    supers: Vec<u64>,
+    // the number of ones before each block, from the start of its superblock
+    blocks: Vec<u16>,
+    // the superblocks holding the ones and zeros with the ranks which are multiples of SAMPLE
+    samples1: Vec<u32>,
+    samples0: Vec<u32>,
+}
+
+impl SuccinctBVec {
+    /// Returns the length of the vector.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the vector has no bits.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the number of bits set to one.
+    pub fn count_ones(&self) -> usize {
+        self.ones
+    }
+
+    /// Returns the number of bits set to zero.
+    pub fn count_zeros(&self) -> usize {
+        self.len - self.ones
+    }
+
+    /// Returns the approximate number of heap bytes used by the vector.
+    pub fn size_in_bytes(&self) -> usize {
+        self.words.len() * size_of::<u64>()
+            + self.supers.len() * size_of::<u64>()
+            + self.blocks.len() * size_of::<u16>()
+            + (self.samples1.len() + self.samples0.len()) * size_of::<u32>()
+    }
+
+    /// Returns the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the length of the vector.
+    pub fn get_bit(&self, bit: usize) -> Bit {
+        assert!(
+            bit < self.len,
+            "bit index out of bounds: the len is {} but the index is {}",
+            self.len,
+            bit
+        );
+
+        Bit::from((self.words[bit / WORD_BITS] >> (WORD_BITS - 1 - bit % WORD_BITS)) & 1 == 1)
+    }
+
+    /// Returns the number of bits set to one in the range `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is larger than the length of the vector.
+    pub fn rank1(&self, i: usize) -> usize {
+        assert!(
+            i <= self.len,
+            "rank index out of bounds: the len is {} but the index is {}",
+            self.len,
+            i
+        );
+
+        if i == self.len {
+            return self.ones;
+        }
+
+        let block = i / BLOCK_BITS;
+        let word = i / WORD_BITS;
+        let full: usize = self.words[block * BLOCK_BITS / WORD_BITS..word]
+            .iter()
+            .map(|w| w.count_ones() as usize)
+            .sum();
+        let partial = match i % WORD_BITS {
+            0 => 0,
+            bits => (self.words[word] >> (WORD_BITS - bits)).count_ones() as usize,
+        };
+
+        self.supers[i / SUPER_BITS] as usize + self.blocks[block] as usize + full + partial
+    }
+
+    /// Returns the number of bits set to zero in the range `[0, i)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `i` is larger than the length of the vector.
+    pub fn rank0(&self, i: usize) -> usize {
+        i - self.rank1(i)
+    }
+
+    /// Returns the position of the `k`-th (zero-based) bit set to one,
+    /// or `None` if there are not enough bits set to one.
+    pub fn select1(&self, k: usize) -> Option<usize> {
+        (k < self.ones).then(|| self.select(k, false))
+    }
+
+    /// Returns the position of the `k`-th (zero-based) bit set to zero,
+    /// or `None` if there are not enough bits set to zero.
+    pub fn select0(&self, k: usize) -> Option<usize> {
+        (k < self.count_zeros()).then(|| self.select(k, true))
+    }
+
+    /// Returns the number of ones, or zeros, before a superblock.
+    #[inline]
+    fn before_super(&self, s: usize, zeros: bool) -> usize {
+        let ones = self.supers[s] as usize;
+        if zeros {
+            (s * SUPER_BITS).min(self.len) - ones
+        } else {
+            ones
+        }
+    }
+
+    /// Returns the number of ones, or zeros, before a block, from the start of its superblock.
+    #[inline]
+    fn before_block(&self, b: usize, zeros: bool) -> usize {
+        let ones = self.blocks[b] as usize;
+        if zeros {
+            (b % BLOCKS_PER_SUPER) * BLOCK_BITS - ones
+        } else {
+            ones
+        }
+    }
+
+    /// Finds the position of the `k`-th one, or zero, knowing that it exists.
+    fn select(&self, k: usize, zeros: bool) -> usize {
+        let samples = if zeros {
+            &self.samples0
+        } else {
+            &self.samples1
+        };
+        let lo = samples[k / SAMPLE] as usize;
+        let hi = samples
+            .get(k / SAMPLE + 1)
+            .map_or(self.supers.len() - 1, |s| *s as usize + 1);
+
+        // binary search of the last superblock of the sampled range which starts before the k-th bit
+        let (mut s, mut hi) = (lo, hi);
+        while hi - s > 1 {
+            let mid = (s + hi) / 2;
+            if self.before_super(mid, zeros) <= k {
+                s = mid;
+            } else {
+                hi = mid;
+            }
+        }
+        let k = k - self.before_super(s, zeros);
+
+        let blocks = s * BLOCKS_PER_SUPER..((s + 1) * BLOCKS_PER_SUPER).min(self.blocks.len());
+        let block = blocks
+            .rev()
+            .find(|b| self.before_block(*b, zeros) <= k)
+            .unwrap();
+        let mut k = k - self.before_block(block, zeros);
+
+        let mut idx = block * BLOCK_BITS / WORD_BITS;
+        loop {
+            let word = if zeros {
+                !self.words[idx]
+            } else {
+                self.words[idx]
+            };
+            let count = word.count_ones() as usize;
+
+            if k < count {
+                return idx * WORD_BITS + select_in_word(word, k);
+            }
+
+            k -= count;
+            idx += 1;
+        }
+    }
+
+    /// Returns the superblocks holding the ones, or zeros, with the ranks which are multiples of `SAMPLE`.
+    fn samples(&self, zeros: bool) -> Vec<u32> {
+        let total = if zeros { self.count_zeros() } else { self.ones };
+        let mut samples = Vec::with_capacity(total.div_ceil(SAMPLE));
+
+        for s in 0..self.supers.len() - 1 {
+            let end = self.before_super(s + 1, zeros);
+            while samples.len() * SAMPLE < end {
+                samples.push(s as u32);
+            }
+        }
+
+        samples
+    }
+}
+
+/// Returns the position, from the most significant bit, of the `k`-th bit set in a word.
+fn select_in_word(mut word: u64, k: usize) -> usize {
+    for _ in 0..k {
+        word &= !(1 << (WORD_BITS - 1 - word.leading_zeros() as usize));
+    }
+    word.leading_zeros() as usize
+}
+
+impl From<&BVec> for SuccinctBVec {
+    fn from(bvec: &BVec) -> Self {
+        let len = bvec.len();
+        let words: Vec<u64> = bvec
+            .as_bytes()
+            .chunks(size_of::<u64>())
+            .map(|chunk| {
+                let mut bytes = [0; size_of::<u64>()];
+                bytes[..chunk.len()].copy_from_slice(chunk);
+                u64::from_be_bytes(bytes)
+            })
+            .collect();
+
+        let num_blocks = len.div_ceil(BLOCK_BITS);
+        let mut supers = Vec::with_capacity(len.div_ceil(SUPER_BITS) + 1);
+        let mut blocks = Vec::with_capacity(num_blocks);
+        let mut ones = 0_usize;
+        let mut relative = 0_usize;
+
+        for (b, chunk) in words.chunks(BLOCK_BITS / WORD_BITS).enumerate() {
+            if b % BLOCKS_PER_SUPER == 0 {
+                supers.push(ones as u64);
+                relative = 0;
+            }
+            blocks.push(relative as u16);
+
+            let count: usize = chunk.iter().map(|w| w.count_ones() as usize).sum();
+            ones += count;
+            relative += count;
+        }
+        supers.push(ones as u64);
+
+        let mut bits = Self {
+            len,
+            ones,
+            words,
+            supers,
+            blocks,
+            samples1: Vec::new(),
+            samples0: Vec::new(),
+        };
+        bits.samples1 = bits.samples(false);
+        bits.samples0 = bits.samples(true);
+        bits
+    }
+}
+
+impl From<BVec> for SuccinctBVec {
+    #[inline]
+    fn from(bvec: BVec) -> Self {
+        Self::from(&bvec)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn empty_() {
+        let bits = SuccinctBVec::from(BVec::with_length(0));
+        assert!(bits.is_empty());
+        assert_eq!(bits.rank1(0), 0);
+        assert_eq!(bits.select1(0), None);
+        assert_eq!(bits.select0(0), None);
+    }
+
+    #[test]
+    fn dense_and_sparse_() {
+        let mut rng = StdRng::seed_from_u64(17);
+
+        for density in [0.001, 0.5, 0.999] {
+            let len = 50_000 + rng.gen_range(0..100);
+            let bvec: BVec = (0..len).map(|_| rng.gen_bool(density)).collect();
+            let bits = SuccinctBVec::from(&bvec);
+
+            assert_eq!(bits.len(), len);
+            assert_eq!(bits.count_ones(), bvec.count_ones());
+
+            for i in (0..=len).step_by(97).chain([len]) {
+                assert_eq!(bits.rank1(i), bvec.rank1(i), "{density} {i}");
+            }
+            for (k, pos) in bvec.ones().enumerate().step_by(7) {
+                assert_eq!(bits.select1(k), Some(pos));
+                assert_eq!(bits.get_bit(pos), Bit::One);
+            }
+            for (k, pos) in bvec.zeros().enumerate().step_by(7) {
+                assert_eq!(bits.select0(k), Some(pos));
+            }
+            assert_eq!(bits.select1(bits.count_ones()), None);
+            assert_eq!(bits.select0(bits.count_zeros()), None);
+        }
+    }
+
+    #[test]
+    fn overhead_() {
+        let bits = SuccinctBVec::from(BVec::with_length(1 << 20));
+        let raw = (1 << 20) / 8;
+        assert!(bits.size_in_bytes() < raw + raw * 6 / 100);
+    }
+
+    #[quickcheck]
+    fn rank_select_(xs: Vec<bool>) -> bool {
+        let bvec: BVec = xs.into_iter().collect();
+        let bits = SuccinctBVec::from(&bvec);
+
+        (0..=bvec.len()).all(|i| bits.rank1(i) == bvec.rank1(i))
+            && (0..=bvec.len()).all(|k| bits.select1(k) == bvec.select1(k))
+            && (0..=bvec.len()).all(|k| bits.select0(k) == bvec.select0(k))
+    }
+
+    #[test]
+    #[should_panic]
+    fn rank_out_of_bounds_() {
+        let bits = SuccinctBVec::from(BVec::with_length(10));
+        bits.rank1(11);
+    }
+}
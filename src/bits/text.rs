@@ -0,0 +1,226 @@
+use super::{BVec, BitsError};
+
+#[cfg(feature = "base64")]
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+impl BVec {
+    /// Returns the bits of the vector as lowercase hexadecimal digits, each digit covering
+    /// four bits, as formatted by `{:x}`. A last incomplete digit is padded with zero bits.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from(0xBEEF_u16);
+    /// assert_eq!(bvec.to_hex(), "beef");
+    /// assert_eq!(BVec::from_hex("beef"), Ok(bvec));
+    /// ```
+    pub fn to_hex(&self) -> String {
+        format!("{self:x}")
+    }
+
+    /// Parses hexadecimal digits, in either case, into a vector of four bits per digit.
+    /// A vector whose length is not a multiple of four comes back from [`to_hex`](BVec::to_hex)
+    /// with its padding bits, and is restored with [`truncate`](BVec::truncate).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BitsError, BVec};
+    ///
+    /// let bvec = BVec::from_hex("A5c").unwrap();
+    /// assert_eq!(bvec.len(), 12);
+    /// assert_eq!(bvec.as_bytes(), [0xA5, 0xC0]);
+    /// assert_eq!(BVec::from_hex("a5g"), Err(BitsError::InvalidChar { index: 2 }));
+    /// ```
+    pub fn from_hex(hex: &str) -> Result<BVec, BitsError> {
+        let mut bytes = vec![0_u8; hex.len().div_ceil(2)];
+        for (index, c) in hex.bytes().enumerate() {
+            let digit = (c as char)
+                .to_digit(16)
+                .ok_or(BitsError::InvalidChar { index })?;
+            let shift = if index % 2 == 0 { 4 } else { 0 };
+            bytes[index / 2] |= (digit as u8) << shift;
+        }
+
+        Ok(BVec::from_bytes(&bytes, hex.len() * 4))
+    }
+
+    /// Returns the bytes of the vector encoded as standard, padded
+    /// [base64](https://datatracker.ietf.org/doc/html/rfc4648#section-4).
+    /// The vector is padded with zero bits to whole bytes.
+    ///
+    /// The method is available only when the `base64` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from_bytes(b"bits", 32);
+    /// assert_eq!(bvec.to_base64(), "Yml0cw==");
+    /// assert_eq!(BVec::from_base64("Yml0cw=="), Ok(bvec));
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn to_base64(&self) -> String {
+        let bytes = self.as_bytes();
+        let mut text = String::with_capacity(bytes.len().div_ceil(3) * 4);
+
+        for chunk in bytes.chunks(3) {
+            let group = chunk
+                .iter()
+                .enumerate()
+                .fold(0_u32, |acc, (i, byte)| acc | (*byte as u32) << (16 - 8 * i));
+            for i in 0..4 {
+                if i <= chunk.len() {
+                    let sextet = (group >> (18 - 6 * i)) & 0x3F;
+                    text.push(BASE64_ALPHABET[sextet as usize] as char);
+                } else {
+                    text.push('=');
+                }
+            }
+        }
+
+        text
+    }
+
+    /// Parses standard, padded base64 into a vector of eight bits per decoded byte.
+    /// The text must be canonical: the unused bits of the last group are zero.
+    ///
+    /// The method is available only when the `base64` feature is enabled.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{BitsError, BVec};
+    ///
+    /// assert_eq!(BVec::from_base64("3q2+7w==").unwrap().to_hex(), "deadbeef");
+    /// assert_eq!(BVec::from_base64("3q2"), Err(BitsError::InvalidLength { len: 3 }));
+    /// assert_eq!(BVec::from_base64("3q2*"), Err(BitsError::InvalidChar { index: 3 }));
+    /// ```
+    #[cfg(feature = "base64")]
+    pub fn from_base64(text: &str) -> Result<BVec, BitsError> {
+        let text = text.as_bytes();
+        if !text.len().is_multiple_of(4) {
+            return Err(BitsError::InvalidLength { len: text.len() });
+        }
+
+        let padding = text
+            .iter()
+            .rev()
+            .take(2)
+            .take_while(|c| **c == b'=')
+            .count();
+        let mut bytes = Vec::with_capacity(text.len() / 4 * 3);
+
+        for (g, group) in text.chunks(4).enumerate() {
+            let is_last = (g + 1) * 4 == text.len();
+            let digits = if is_last { 4 - padding } else { 4 };
+
+            let mut value = 0_u32;
+            for (i, c) in group[..digits].iter().enumerate() {
+                let sextet = base64_value(*c).ok_or(BitsError::InvalidChar { index: g * 4 + i })?;
+                value |= (sextet as u32) << (18 - 6 * i);
+            }
+
+            let decoded = value.to_be_bytes();
+            let len = digits * 6 / 8;
+            if value & (0x00FF_FFFF >> (8 * len)) != 0 {
+                // the unused bits of the last group are set
+                return Err(BitsError::InvalidChar {
+                    index: g * 4 + digits - 1,
+                });
+            }
+            bytes.extend_from_slice(&decoded[1..1 + len]);
+        }
+
+        Ok(BVec::from_bytes(&bytes, bytes.len() * 8))
+    }
+}
+
+/// Returns the value of a base64 digit, or `None` for other characters, including the padding.
+#[cfg(feature = "base64")]
+fn base64_value(c: u8) -> Option<u8> {
+    match c {
+        b'A'..=b'Z' => Some(c - b'A'),
+        b'a'..=b'z' => Some(c - b'a' + 26),
+        b'0'..=b'9' => Some(c - b'0' + 52),
+        b'+' => Some(62),
+        b'/' => Some(63),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn hex_() {
+        assert_eq!(BVec::with_length(0).to_hex(), "");
+        assert_eq!(BVec::from_hex(""), Ok(BVec::with_length(0)));
+
+        // the padding bits of an incomplete digit
+        let bvec = BVec::from_bytes(&[0b1010_1110], 6);
+        let mut parsed = BVec::from_hex(&bvec.to_hex()).unwrap();
+        assert_eq!(parsed.len(), 8);
+        parsed.truncate(6);
+        assert_eq!(parsed, bvec);
+
+        // not an ASCII digit
+        assert_eq!(
+            BVec::from_hex("é"),
+            Err(BitsError::InvalidChar { index: 0 })
+        );
+    }
+
+    #[quickcheck]
+    fn hex_roundtrip_(bytes: Vec<u8>) -> bool {
+        let bvec = BVec::from_bytes(&bytes, bytes.len() * 8);
+        BVec::from_hex(&bvec.to_hex()) == Ok(bvec.clone())
+            && BVec::from_hex(&bvec.to_hex().to_uppercase()) == Ok(bvec)
+    }
+
+    #[cfg(feature = "base64")]
+    #[test]
+    fn base64_() {
+        // the test vectors of RFC 4648
+        for (text, encoded) in [
+            ("", ""),
+            ("f", "Zg=="),
+            ("fo", "Zm8="),
+            ("foo", "Zm9v"),
+            ("foob", "Zm9vYg=="),
+            ("fooba", "Zm9vYmE="),
+            ("foobar", "Zm9vYmFy"),
+        ] {
+            let bvec = BVec::from_bytes(text.as_bytes(), text.len() * 8);
+            assert_eq!(bvec.to_base64(), encoded);
+            assert_eq!(BVec::from_base64(encoded), Ok(bvec));
+        }
+
+        for (invalid, index) in [
+            ("Zg=a", 2),
+            ("Z===", 1),
+            ("Zh==", 1),
+            ("=g==", 0),
+            ("Zm9=Zm9v", 3),
+        ] {
+            assert_eq!(
+                BVec::from_base64(invalid),
+                Err(BitsError::InvalidChar { index }),
+                "{invalid}"
+            );
+        }
+    }
+
+    #[cfg(feature = "base64")]
+    #[quickcheck]
+    fn base64_roundtrip_(bytes: Vec<u8>) -> bool {
+        let bvec = BVec::from_bytes(&bytes, bytes.len() * 8);
+        BVec::from_base64(&bvec.to_base64()) == Ok(bvec)
+    }
+}
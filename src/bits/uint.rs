@@ -0,0 +1,99 @@
+use super::{BVec, Bit, WordInt};
+
+macro_rules! impl_from_uint {
+    ($($t:ty),*) => {
+        $(
+            impl From<$t> for BVec {
+                /// Creates a vector with the bits of an integer, the most significant one at position 0.
+                #[inline]
+                fn from(value: $t) -> Self {
+                    BVec::from_bytes(&value.to_be_bytes(), <$t>::BITS as usize)
+                }
+            }
+        )*
+    };
+}
+
+impl_from_uint!(u8, u16, u32, u64, u128);
+
+impl From<&[u64]> for BVec {
+    /// Creates a vector with the bits of a sequence of words, such as the output of a hash
+    /// function, each word with its most significant bit first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from([1_u64, 1 << 63].as_slice());
+    /// assert_eq!(bvec.len(), 128);
+    /// assert_eq!(bvec.ones().collect::<Vec<_>>(), [63, 64]);
+    /// ```
+    fn from(words: &[u64]) -> Self {
+        let bytes: Vec<u8> = words.iter().flat_map(|word| word.to_be_bytes()).collect();
+        BVec::from_bytes(&bytes, bytes.len() * super::U8SIZE)
+    }
+}
+
+impl BVec {
+    /// Returns the integer whose binary representation is the vector, with the bit at position 0
+    /// as the most significant one, or `None` if the vector has more bits than the integer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::BVec;
+    ///
+    /// let bvec = BVec::from(0xBEEF_u16);
+    /// assert_eq!(bvec.len(), 16);
+    /// assert_eq!(bvec.to_uint::<u16>(), Some(0xBEEF));
+    /// assert_eq!(bvec.to_uint::<u64>(), Some(0xBEEF));
+    /// assert_eq!(bvec.to_uint::<u8>(), None);
+    ///
+    /// let bvec = BVec::from_bytes(&[0b1010_0000], 3);
+    /// assert_eq!(bvec.to_uint::<u8>(), Some(0b101));
+    /// ```
+    pub fn to_uint<T: WordInt>(&self) -> Option<T> {
+        if self.len() as u64 > T::BITS as u64 {
+            return None;
+        }
+
+        Some(self.iter().fold(T::ZERO, |acc, bit| match bit {
+            Bit::One => (acc << 1) | T::ONE,
+            Bit::Zero => acc << 1,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn empty_() {
+        assert_eq!(BVec::with_length(0).to_uint::<u8>(), Some(0));
+        assert!(BVec::from([].as_slice()).is_empty());
+    }
+
+    #[quickcheck]
+    fn roundtrip_(a: u8, b: u32, c: u64, d: u128) -> bool {
+        BVec::from(a).to_uint::<u8>() == Some(a)
+            && BVec::from(b).to_uint::<u32>() == Some(b)
+            && BVec::from(c).to_uint::<u128>() == Some(c as u128)
+            && BVec::from(d).to_uint::<u128>() == Some(d)
+            && BVec::from(d).to_uint::<u64>().is_none()
+    }
+
+    #[quickcheck]
+    fn words_(words: Vec<u64>) -> bool {
+        let bvec = BVec::from(words.as_slice());
+        let mut bits = bvec.iter();
+
+        bvec.len() == words.len() * 64
+            && words.iter().all(|word| {
+                let chunk: BVec = bits.by_ref().take(64).collect();
+                chunk.to_uint::<u64>() == Some(*word)
+            })
+    }
+}
@@ -0,0 +1,396 @@
+use super::{Bit, Byte};
+use std::fmt::{Binary, Debug, Display};
+use std::iter::FusedIterator;
+use std::ops::{BitAnd, BitOr, BitXor, Not, Shl, Shr};
+
+/// Unsigned integers which can back a [`Word`].
+pub trait WordInt:
+    Copy
+    + Eq
+    + Ord
+    + Debug
+    + Display
+    + Binary
+    + BitAnd<Output = Self>
+    + BitOr<Output = Self>
+    + BitXor<Output = Self>
+    + Not<Output = Self>
+    + Shl<u32, Output = Self>
+    + Shr<u32, Output = Self>
+{
+    /// The number of bits of the integer.
+    const BITS: u32;
+    /// The zero value.
+    const ZERO: Self;
+    /// The value with only the least significant bit set.
+    const ONE: Self;
+
+    /// Returns the number of bits set to one.
+    fn count_ones(self) -> u32;
+}
+
+macro_rules! impl_word_int {
+    ($($t:ty),*) => {
+        $(
+            impl WordInt for $t {
+                const BITS: u32 = <$t>::BITS;
+                const ZERO: Self = 0;
+                const ONE: Self = 1;
+
+                #[inline]
+                fn count_ones(self) -> u32 {
+                    <$t>::count_ones(self)
+                }
+            }
+        )*
+    };
+}
+
+impl_word_int!(u8, u16, u32, u64, u128);
+
+/// A machine word whose bits can be accessed individually, with the same API as [`Byte`].
+/// Bit `0` is the most significant bit of the word.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::bits::{Bit, Word64};
+///
+/// let word = Word64::from(1 << 63).set_bit(63);
+/// assert_eq!(word.get_bit(0), Bit::One);
+/// assert_eq!(word.get_bit(1), Bit::Zero);
+/// assert_eq!(word.count_ones(), 2);
+/// assert_eq!(word.iter().filter(|b| *b == Bit::One).count(), 2);
+/// ```
+#[derive(PartialEq, Eq, Clone, Copy, PartialOrd, Ord, Hash, Default)]
+pub struct Word<T>(T);
+
+/// A word of 16 bits.
+pub type Word16 = Word<u16>;
+/// A word of 32 bits.
+pub type Word32 = Word<u32>;
+/// A word of 64 bits.
+pub type Word64 = Word<u64>;
+/// A word of 128 bits.
+pub type Word128 = Word<u128>;
+
+impl<T: WordInt> Word<T> {
+    /// The number of bits of the word.
+    pub const BITS: u32 = T::BITS;
+
+    #[inline]
+    fn mask(bit: u8) -> T {
+        assert!(
+            (bit as u32) < T::BITS,
+            "bit index out of bounds: the word has {} bits but the index is {}",
+            T::BITS,
+            bit
+        );
+        T::ONE << (T::BITS - 1 - bit as u32)
+    }
+
+    #[inline]
+    pub fn is_zero(&self) -> bool {
+        self.0 == T::ZERO
+    }
+
+    /// Returns the number of bits set to one.
+    #[inline]
+    pub fn count_ones(&self) -> u32 {
+        self.0.count_ones()
+    }
+
+    /// Returns the bit value from a given position.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the position is not smaller than the number of bits of the word.
+    #[inline]
+    pub fn get_bit(&self, bit: u8) -> Bit {
+        Bit::from(self.0 & Self::mask(bit) != T::ZERO)
+    }
+
+    /// Returns the word with the bit from a given position set.
+    #[inline]
+    pub fn set_bit(self, bit: u8) -> Self {
+        Self(self.0 | Self::mask(bit))
+    }
+
+    /// Returns the word with the bit from a given position reset.
+    #[inline]
+    pub fn reset_bit(self, bit: u8) -> Self {
+        Self(self.0 & !Self::mask(bit))
+    }
+
+    /// Returns the word with the bit from a given position toggled.
+    #[inline]
+    pub fn toggle_bit(self, bit: u8) -> Self {
+        Self(self.0 ^ Self::mask(bit))
+    }
+
+    #[inline]
+    pub fn iter(&self) -> WordIter<T> {
+        self.iter_msb()
+    }
+
+    /// Returns an iterator over the bits, starting with the most significant one.
+    #[inline]
+    pub fn iter_msb(&self) -> WordIter<T> {
+        WordIter {
+            word: *self,
+            crnt: 0,
+            end: T::BITS,
+            lsb_first: false,
+        }
+    }
+
+    /// Returns an iterator over the bits, starting with the least significant one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Word16};
+    ///
+    /// let mut iter = Word16::from(1).iter_lsb();
+    /// assert_eq!(iter.next(), Some(Bit::One));
+    /// assert_eq!(iter.last(), Some(Bit::Zero));
+    /// ```
+    #[inline]
+    pub fn iter_lsb(&self) -> WordIter<T> {
+        WordIter {
+            word: *self,
+            crnt: 0,
+            end: T::BITS,
+            lsb_first: true,
+        }
+    }
+
+    /// Creates a word from at most `BITS` bits, starting with the most significant one.
+    /// This is the same as collecting the bits with [`FromIterator`].
+    #[inline]
+    pub fn from_bits_msb<I: IntoIterator<Item = Bit>>(bits: I) -> Self {
+        Word::from_iter(bits)
+    }
+
+    /// Creates a word from at most `BITS` bits, starting with the least significant one.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::{Bit, Word16};
+    ///
+    /// let word = Word16::from_bits_lsb([Bit::One, Bit::Zero, Bit::One]);
+    /// assert_eq!(u16::from(word), 0b101);
+    /// ```
+    pub fn from_bits_lsb<I: IntoIterator<Item = Bit>>(bits: I) -> Self {
+        bits.into_iter().take(T::BITS as usize).enumerate().fold(
+            Word(T::ZERO),
+            |acc, (bit, item)| {
+                if item == Bit::One {
+                    acc.set_bit((T::BITS as usize - 1 - bit) as u8)
+                } else {
+                    acc
+                }
+            },
+        )
+    }
+}
+
+impl<T: WordInt> Display for Word<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.0)
+    }
+}
+
+impl<T: WordInt> Debug for Word<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "({}:{:0width$b})",
+            self.0,
+            self.0,
+            width = T::BITS as usize
+        )
+    }
+}
+
+impl<T: WordInt> Binary for Word<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:0width$b}", self.0, width = T::BITS as usize)
+    }
+}
+
+impl<T: WordInt> From<T> for Word<T> {
+    #[inline]
+    fn from(value: T) -> Self {
+        Word(value)
+    }
+}
+
+impl From<Byte> for Word<u8> {
+    #[inline]
+    fn from(byte: Byte) -> Self {
+        Word(u8::from(byte))
+    }
+}
+
+macro_rules! impl_from_word {
+    ($($t:ty),*) => {
+        $(
+            impl From<Word<$t>> for $t {
+                #[inline]
+                fn from(word: Word<$t>) -> Self {
+                    word.0
+                }
+            }
+        )*
+    };
+}
+
+impl_from_word!(u8, u16, u32, u64, u128);
+
+impl<T: WordInt> FromIterator<Bit> for Word<T> {
+    /// Creates a word from at most `BITS` bits, starting with the most significant one.
+    fn from_iter<I: IntoIterator<Item = Bit>>(iter: I) -> Self {
+        iter.into_iter().take(T::BITS as usize).enumerate().fold(
+            Word(T::ZERO),
+            |acc, (bit, item)| {
+                if item == Bit::One {
+                    acc.set_bit(bit as u8)
+                } else {
+                    acc
+                }
+            },
+        )
+    }
+}
+
+impl<T: WordInt> IntoIterator for Word<T> {
+    type Item = Bit;
+
+    type IntoIter = WordIter<T>;
+
+    #[inline]
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter_msb()
+    }
+}
+
+/// Represents an iterator over a word.
+/// The elements of the iteration are [`Bit`] instances.
+pub struct WordIter<T> {
+    word: Word<T>,
+    // the range of the remaining steps of the iteration
+    crnt: u32,
+    end: u32,
+    lsb_first: bool,
+}
+
+impl<T: WordInt> WordIter<T> {
+    /// Returns the bit visited at a given step of the iteration.
+    #[inline]
+    fn bit_at(&self, step: u32) -> Bit {
+        let bit = if self.lsb_first {
+            T::BITS - 1 - step
+        } else {
+            step
+        };
+        self.word.get_bit(bit as u8)
+    }
+}
+
+impl<T: WordInt> Iterator for WordIter<T> {
+    type Item = Bit;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.crnt >= self.end {
+            None
+        } else {
+            let bit = self.bit_at(self.crnt);
+            self.crnt += 1;
+            Some(bit)
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = (self.end - self.crnt) as usize;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<T: WordInt> DoubleEndedIterator for WordIter<T> {
+    fn next_back(&mut self) -> Option<Self::Item> {
+        if self.crnt >= self.end {
+            None
+        } else {
+            self.end -= 1;
+            Some(self.bit_at(self.end))
+        }
+    }
+}
+
+impl<T: WordInt> ExactSizeIterator for WordIter<T> {}
+
+impl<T: WordInt> FusedIterator for WordIter<T> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn set_reset_toggle_() {
+        let word = Word32::default().set_bit(0).set_bit(31).toggle_bit(5);
+        assert_eq!(u32::from(word), (1 << 31) | 1 | (1 << 26));
+
+        let word = word.reset_bit(0).toggle_bit(5);
+        assert_eq!(u32::from(word), 1);
+        assert!(!word.is_zero());
+        assert_eq!(Word32::BITS, 32);
+    }
+
+    #[test]
+    fn formatting_() {
+        let word = Word16::from(5);
+        assert_eq!(format!("{word}"), "5");
+        assert_eq!(format!("{word:b}"), "0000000000000101");
+        assert_eq!(format!("{word:?}"), "(5:0000000000000101)");
+    }
+
+    #[test]
+    #[should_panic]
+    fn out_of_bounds_() {
+        Word16::default().set_bit(16);
+    }
+
+    #[quickcheck]
+    fn byte_compatible_(x: u8, bit: u8) -> bool {
+        let bit = bit % 8;
+        let (byte, word) = (Byte::from(x), Word::from(Byte::from(x)));
+
+        byte.get_bit(bit) == word.get_bit(bit)
+            && u8::from(byte.set_bit(bit)) == u8::from(word.set_bit(bit))
+            && u8::from(byte.toggle_bit(bit)) == u8::from(word.toggle_bit(bit))
+            && byte.iter().eq(word.iter())
+            && byte.iter_lsb().eq(word.iter_lsb())
+    }
+
+    #[quickcheck]
+    fn collect_roundtrip_(x: u128) -> bool {
+        let word = Word128::from(x);
+        word.iter().collect::<Word128>() == word && word.count_ones() == x.count_ones()
+    }
+
+    #[quickcheck]
+    fn double_ended_(x: u64) -> bool {
+        let word = Word64::from(x);
+        let mut iter = word.iter();
+        let (first, last) = (iter.next(), iter.next_back());
+
+        iter.len() == 62
+            && first == Some(word.get_bit(0))
+            && last == Some(word.get_bit(63))
+            && word.iter_lsb().rev().eq(word.iter_msb())
+    }
+}
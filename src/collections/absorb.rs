@@ -0,0 +1,92 @@
+//! Feeding of streams of items into aggregates.
+
+/// Aggregates which consume a stream of items one at a time, such as counters and sketches,
+/// so a pipeline can feed them as the items arrive instead of collecting the items first.
+///
+/// A mutable reference to an aggregate is itself an aggregate, and a pair of aggregates
+/// absorbs each item into both, which feeds several of them from a single pass.
+/// With the `async` feature enabled, `absorb_stream` and `tap_stream` feed aggregates
+/// from asynchronous streams.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{Absorb, CountedBag};
+/// use aabel_rs::sketches::HyperLogLog;
+///
+/// let mut counts = CountedBag::<u32>::default();
+/// let mut distinct = HyperLogLog::new(10);
+///
+/// (&mut counts, &mut distinct).absorb_all([1, 2, 2, 3, 3, 3]);
+/// assert_eq!(counts.get(&3), Some(&3));
+/// assert_eq!(distinct.estimate().round(), 3.0);
+/// ```
+pub trait Absorb<T> {
+    /// Adds an item to the aggregate.
+    fn absorb(&mut self, item: T);
+
+    /// Adds all the items of an iterator to the aggregate, in order.
+    fn absorb_all<I>(&mut self, items: I)
+    where
+        I: IntoIterator<Item = T>,
+        Self: Sized,
+    {
+        for item in items {
+            self.absorb(item);
+        }
+    }
+}
+
+impl<T, A> Absorb<T> for &mut A
+where
+    A: Absorb<T> + ?Sized,
+{
+    fn absorb(&mut self, item: T) {
+        (**self).absorb(item);
+    }
+}
+
+impl<T, A, B> Absorb<T> for (A, B)
+where
+    T: Clone,
+    A: Absorb<T>,
+    B: Absorb<T>,
+{
+    fn absorb(&mut self, item: T) {
+        self.0.absorb(item.clone());
+        self.1.absorb(item);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::CountedBag;
+    use quickcheck_macros::quickcheck;
+
+    #[quickcheck]
+    fn absorb_all_(items: Vec<u8>) -> bool {
+        let mut bag = CountedBag::<u8>::default();
+        bag.absorb_all(items.iter().copied());
+
+        let count = |item: &u8| items.iter().filter(|x| *x == item).count() as u32;
+        bag.total() as usize == items.len()
+            && items.iter().all(|item| bag.get(item) == Some(&count(item)))
+    }
+
+    #[test]
+    fn fan_out_() {
+        let (mut xs, mut ys) = (CountedBag::<char>::default(), CountedBag::<char>::default());
+        let mut both = (&mut xs, &mut ys);
+        both.absorb('a');
+        both.absorb_all("abc".chars());
+
+        assert_eq!(xs.get(&'a'), Some(&2));
+        assert_eq!(ys.total(), 4);
+
+        // through a trait object
+        let sink: &mut dyn Absorb<char> = &mut xs;
+        sink.absorb('z');
+        assert_eq!(xs.get(&'z'), Some(&1));
+    }
+}
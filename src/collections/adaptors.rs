@@ -0,0 +1,274 @@
+//! Extra iterator adaptors for cleaning up and counting streams of items.
+//!
+//! To extend [`Iterator`] with methods in this module, import the [`Adaptors`] trait:
+//!
+//! ```
+//! use aabel_rs::collections::Adaptors;
+//!
+//! let bag = ['a', 'b', 'a', 'c', 'a'].into_iter().counts();
+//! assert_eq!(bag.get(&'a'), Some(&3));
+//! ```
+
+use super::CountedBag;
+use crate::sketches::HyperLogLog;
+use std::{
+    collections::{hash_map::RandomState, HashSet},
+    hash::{BuildHasher, Hash},
+};
+
+#[cfg(feature = "rayon")]
+use std::num::NonZeroUsize;
+
+/// Extension methods for building one-pass cleanup and counting pipelines.
+pub trait Adaptors: Iterator {
+    /// Returns an iterator which drops the items that were already seen.
+    ///
+    /// Only the hash of each item is remembered, so two distinct items
+    /// whose 64 bit hashes collide are treated as duplicates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Adaptors;
+    ///
+    /// let xs: Vec<_> = [1, 2, 1, 3, 2].into_iter().dedup_by_hash().collect();
+    /// assert_eq!(xs, [1, 2, 3]);
+    /// ```
+    fn dedup_by_hash(self) -> DedupByHash<Self>
+    where
+        Self: Sized,
+        Self::Item: Hash,
+    {
+        DedupByHash {
+            iter: self,
+            seen: HashSet::new(),
+            state: RandomState::new(),
+        }
+    }
+
+    /// Consumes the iterator and counts the occurences of each item.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Adaptors;
+    ///
+    /// let bag = "abracadabra".chars().counts();
+    /// assert_eq!(bag.get(&'a'), Some(&5));
+    /// assert_eq!(bag.total(), 11);
+    /// ```
+    fn counts(self) -> CountedBag<Self::Item>
+    where
+        Self: Sized,
+        Self::Item: Eq + Hash,
+    {
+        CountedBag::from_keys(self)
+    }
+
+    /// Consumes the iterator and returns the estimated number of distinct items.
+    ///
+    /// The items are fed into a [`HyperLogLog`] sketch with the given precision,
+    /// so the memory used is bounded regardless of the length of the stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the precision is not in the `4..=16` range.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Adaptors;
+    ///
+    /// let estimate = (0..10_000).map(|i| i % 500).estimate_distinct(12);
+    /// assert!((estimate - 500.).abs() < 25.);
+    /// ```
+    fn estimate_distinct(self, precision: u8) -> f64
+    where
+        Self: Sized,
+        Self::Item: Hash,
+    {
+        let mut hll = HyperLogLog::new(precision);
+        for item in self {
+            hll.insert(&item);
+        }
+        hll.estimate()
+    }
+
+    /// Returns an iterator which pulls batches of `batch_size` items from the
+    /// underlying iterator, maps each batch in parallel and yields the results
+    /// in the original order.
+    ///
+    /// The adaptor is available only when the `rayon` feature is enabled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `batch_size` is zero.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Adaptors;
+    ///
+    /// let xs: Vec<_> = (1..=5).par_map_batches(2, |x| x * x).collect();
+    /// assert_eq!(xs, [1, 4, 9, 16, 25]);
+    /// ```
+    #[cfg(feature = "rayon")]
+    fn par_map_batches<B, F>(self, batch_size: usize, f: F) -> ParMapBatches<Self, B, F>
+    where
+        Self: Sized,
+        Self::Item: Send,
+        B: Send,
+        F: Fn(Self::Item) -> B + Sync + Send,
+    {
+        ParMapBatches {
+            iter: self,
+            size: NonZeroUsize::new(batch_size).expect("batch size is zero"),
+            f,
+            batch: Vec::new().into_iter(),
+        }
+    }
+}
+
+impl<T: ?Sized> Adaptors for T where T: Iterator {}
+
+/// An iterator which yields only the items that were not seen before.
+///
+/// The `struct` is created by the [`dedup_by_hash`] method on [`Adaptors`]. See its documentation for more.
+///
+/// [`dedup_by_hash`]: Adaptors::dedup_by_hash
+pub struct DedupByHash<I> {
+    iter: I,
+    seen: HashSet<u64>,
+    state: RandomState,
+}
+
+impl<I> Iterator for DedupByHash<I>
+where
+    I: Iterator,
+    I::Item: Hash,
+{
+    type Item = I::Item;
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let item = self.iter.next()?;
+            if self.seen.insert(self.state.hash_one(&item)) {
+                return Some(item);
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+}
+
+/// An iterator which maps batches of items in parallel.
+///
+/// The `struct` is created by the [`par_map_batches`] method on [`Adaptors`]. See its documentation for more.
+///
+/// [`par_map_batches`]: Adaptors::par_map_batches
+#[cfg(feature = "rayon")]
+pub struct ParMapBatches<I, B, F> {
+    iter: I,
+    size: NonZeroUsize,
+    f: F,
+    // the already mapped items of the current batch
+    batch: std::vec::IntoIter<B>,
+}
+
+#[cfg(feature = "rayon")]
+impl<I, B, F> Iterator for ParMapBatches<I, B, F>
+where
+    I: Iterator,
+    I::Item: Send,
+    B: Send,
+    F: Fn(I::Item) -> B + Sync + Send,
+{
+    type Item = B;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        use rayon::prelude::*;
+
+        if let Some(item) = self.batch.next() {
+            return Some(item);
+        }
+
+        let items: Vec<_> = self.iter.by_ref().take(self.size.get()).collect();
+        if items.is_empty() {
+            return None;
+        }
+
+        let f = &self.f;
+        let mapped: Vec<B> = items.into_par_iter().map(f).collect();
+        self.batch = mapped.into_iter();
+        self.batch.next()
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let buffered = self.batch.len();
+        let (lower, upper) = self.iter.size_hint();
+        (
+            lower.saturating_add(buffered),
+            upper.and_then(|u| u.checked_add(buffered)),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn dedup_by_hash_() {
+        let xs: Vec<_> = [1, 2, 1, 3, 2, 4].into_iter().dedup_by_hash().collect();
+        assert_eq!(xs, [1, 2, 3, 4]);
+    }
+
+    #[test]
+    fn dedup_by_hash_empty_() {
+        let xs: Vec<i32> = Vec::new();
+        assert_eq!(xs.into_iter().dedup_by_hash().count(), 0);
+    }
+
+    #[test]
+    fn counts_() {
+        let bag = ["a", "b", "a", "c", "a", "b"].into_iter().counts();
+        assert_eq!(bag.get("a"), Some(&3));
+        assert_eq!(bag.get("b"), Some(&2));
+        assert_eq!(bag.get("c"), Some(&1));
+        assert_eq!(bag.total(), 6);
+    }
+
+    #[test]
+    fn estimate_distinct_() {
+        let estimate = ["a", "b", "a", "c"].into_iter().estimate_distinct(8);
+        assert_eq!(estimate.round(), 3.);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_map_batches_() {
+        let xs: Vec<_> = (0..100).par_map_batches(7, |x| x * 2).collect();
+        let ys: Vec<_> = (0..100).map(|x| x * 2).collect();
+        assert_eq!(xs, ys);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    #[should_panic]
+    fn par_map_batches_zero_() {
+        let _ = (0..10).par_map_batches(0, |x| x);
+    }
+
+    #[test]
+    fn dedup_counts_() {
+        let bag = [1, 2, 1, 3].into_iter().dedup_by_hash().counts();
+        assert_eq!(bag.len(), 3);
+        assert_eq!(bag.total(), 3);
+    }
+}
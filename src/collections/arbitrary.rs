@@ -0,0 +1,31 @@
+use super::CountedBag;
+use arbitrary::{Arbitrary, Result, Unstructured};
+use std::hash::{BuildHasher, Hash};
+
+impl<'a, K, S> Arbitrary<'a> for CountedBag<K, S>
+where
+    K: Arbitrary<'a> + Eq + Hash,
+    S: BuildHasher + Default,
+{
+    /// Creates a bag of arbitrary keys, each counted between 1 and 256 times.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        u.arbitrary_iter::<(K, u8)>()?
+            .map(|pair| pair.map(|(key, count)| (key, count as u32 + 1)))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn arbitrary_() {
+        let data: Vec<u8> = (0..=255).rev().collect();
+        let bag = CountedBag::<u8>::arbitrary(&mut Unstructured::new(&data)).unwrap();
+
+        assert!(!bag.is_empty());
+        assert_eq!(bag.total(), bag.iter().map(|(_, c)| *c).sum::<u32>());
+        assert!(bag.iter().all(|(_, c)| (1..=256).contains(c)));
+    }
+}
@@ -1,6 +1,7 @@
 //! A store based on the [`HashMap`] where we can store counted bags.
 //!
 
+use super::{Absorb, Mergeable};
 use std::{
     borrow::Borrow,
     collections::{
@@ -9,6 +10,7 @@ use std::{
     },
     fmt::Debug,
     hash::{BuildHasher, Hash},
+    io::{self, BufRead, BufReader, Read},
 };
 
 /// Stores the total number of occurences for each elements as well
@@ -26,6 +28,7 @@ use std::{
 ///    println!("{key}");
 /// }
 /// ```
+#[derive(Clone, Debug)]
 pub struct CountedBag<K, S = RandomState> {
     hmap: HashMap<K, u32, S>,
     total: u32,
@@ -134,6 +137,29 @@ impl<K, S> CountedBag<K, S> {
     pub fn total(&self) -> u32 {
         self.total
     }
+
+    /// Returns the [Shannon entropy](https://en.wikipedia.org/wiki/Entropy_(information_theory)),
+    /// in bits, of the distribution of the elements. An empty bag has zero entropy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let bag = CountedBag::<char>::from_iter([('a', 2), ('b', 1), ('c', 1)]);
+    /// assert_eq!(bag.entropy(), 1.5);
+    /// ```
+    pub fn entropy(&self) -> f64 {
+        let total = self.total as f64;
+        self.hmap
+            .values()
+            .filter(|count| **count > 0)
+            .map(|count| {
+                let p = *count as f64 / total;
+                -p * p.log2()
+            })
+            .sum()
+    }
 }
 
 impl<K, S> CountedBag<K, S>
@@ -154,10 +180,10 @@ where
     /// cs.insert('a');
     /// assert_eq!(cs.get(&'a'), Some(&1));
     /// ```
-    pub fn get<Q: ?Sized>(&self, k: &Q) -> Option<&u32>
+    pub fn get<Q>(&self, k: &Q) -> Option<&u32>
     where
         K: Borrow<Q>,
-        Q: Hash + Eq,
+        Q: Hash + Eq + ?Sized,
     {
         self.hmap.get(k)
     }
@@ -187,6 +213,70 @@ where
         self.hmap.insert(k, count).map_or(1, |x| x + 1)
     }
 
+    /// Removes a key with all its occurences.
+    /// The function returns the number of occurences the key had, or `None` if it was missing.
+    ///
+    /// The key may be any borrowed form of the map's key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let mut cs = CountedBag::<char>::from_iter([('a', 3), ('b', 1)]);
+    /// assert_eq!(cs.remove(&'a'), Some(3));
+    /// assert_eq!(cs.remove(&'a'), None);
+    /// assert_eq!(cs.total(), 1);
+    /// ```
+    pub fn remove<Q>(&mut self, k: &Q) -> Option<u32>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let count = self.hmap.remove(k)?;
+        self.total -= count;
+        Some(count)
+    }
+
+    /// Removes one occurence of the key, and the key itself with its last occurence.
+    /// The function returns the number of occurences left, which is zero for a missing key.
+    ///
+    /// The key may be any borrowed form of the map's key type.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let mut cs = CountedBag::<char>::from_iter([('a', 2)]);
+    /// assert_eq!(cs.decrement(&'a'), 1);
+    /// assert_eq!(cs.decrement(&'a'), 0);
+    /// assert_eq!(cs.get(&'a'), None);
+    /// assert_eq!(cs.decrement(&'a'), 0);
+    /// assert!(cs.is_empty());
+    /// ```
+    pub fn decrement<Q>(&mut self, k: &Q) -> u32
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let Some(count) = self.hmap.get_mut(k) else {
+            return 0;
+        };
+
+        // saturating, for a key stored with no occurence
+        if *count > 0 {
+            *count -= 1;
+            self.total -= 1;
+        }
+
+        let count = *count;
+        if count == 0 {
+            self.hmap.remove(k);
+        }
+        count
+    }
+
     /// create a counted bag from a collection of keys.
     pub fn from_keys<J>(xs: J) -> Self
     where
@@ -203,6 +293,73 @@ where
     }
 }
 
+impl<S> CountedBag<String, S>
+where
+    S: BuildHasher + Default,
+{
+    /// Creates a counted bag from the values of one column of a delimited (CSV, TSV) source.
+    ///
+    /// The source is streamed line by line, so the rows are never materialized.
+    /// The columns are zero-indexed and empty lines are skipped. When `has_header` is true,
+    /// the first line holds the names of the columns and is not counted.
+    /// An error is returned if the source cannot be read or if a line has fewer columns than expected.
+    ///
+    /// Quoting is not supported: a delimiter inside a quoted field splits it, and the quotes
+    /// are kept in the values.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let tsv = "name\tcity\nalice\tparis\nbob\tlondon\ncarol\tparis\n";
+    /// let cs = CountedBag::<String>::from_delimited(tsv.as_bytes(), 1, '\t', true).unwrap();
+    /// assert_eq!(cs.get("paris"), Some(&2));
+    /// assert_eq!(cs.get("london"), Some(&1));
+    /// assert_eq!(cs.get("city"), None);
+    /// ```
+    pub fn from_delimited<R: Read>(
+        reader: R,
+        column: usize,
+        delimiter: char,
+        has_header: bool,
+    ) -> io::Result<Self> {
+        let mut reader = BufReader::new(reader);
+        let mut line = String::new();
+        let mut line_no = 0;
+        let mut cs = Self::default();
+
+        loop {
+            line.clear();
+            if reader.read_line(&mut line)? == 0 {
+                break;
+            }
+            line_no += 1;
+
+            let row = line.trim_end_matches(['\n', '\r']);
+            if row.is_empty() || (has_header && line_no == 1) {
+                continue;
+            }
+
+            match row.split(delimiter).nth(column) {
+                Some(value) => {
+                    let count = cs.hmap.entry(value.to_string()).or_insert(0);
+                    *count += 1;
+                    cs.total += 1;
+                }
+                None => {
+                    return Err(io::Error::new(
+                        io::ErrorKind::InvalidData,
+                        format!("line {line_no} has no column {column}"),
+                    ))
+                }
+            }
+        }
+
+        Ok(cs)
+    }
+}
+
 impl<K, S> CountedBag<K, S> {
     /// An iterator visiting all distinct items and their count in an arbitrary order.
     /// The iterator element type is (&'a K, &'a V)
@@ -360,6 +517,49 @@ where
     }
 }
 
+impl<K, S> Absorb<K> for CountedBag<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Adds one to the count of the key.
+    fn absorb(&mut self, key: K) {
+        self.insert(key);
+    }
+}
+
+impl<K, S> Mergeable for CountedBag<K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    /// Adds the counts of the other bag to the counts of this bag.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::{CountedBag, Mergeable};
+    ///
+    /// let mut xs = CountedBag::<char>::from_iter([('a', 1), ('b', 2)]);
+    /// let ys = CountedBag::<char>::from_iter([('b', 1), ('c', 5)]);
+    ///
+    /// xs.merge_from(ys);
+    /// assert_eq!(xs.get(&'b'), Some(&3));
+    /// assert_eq!(xs.total(), 9);
+    /// ```
+    fn merge_from(&mut self, mut other: Self) {
+        // iterate over the smaller bag
+        if other.len() > self.len() {
+            std::mem::swap(self, &mut other);
+        }
+
+        for (key, count) in other.hmap {
+            *self.hmap.entry(key).or_insert(0) += count;
+        }
+        self.total += other.total;
+    }
+}
+
 //
 // Intersection
 //
@@ -446,6 +646,343 @@ impl<K, S> CountedBag<K, S> {
     }
 }
 
+//
+// Union and sum
+//
+
+/// A lazy iterator producing the keys of either of two [`CountedBag`]s with the larger
+/// of their counts, the union of the multisets.
+///
+/// The `struct` is created by the [`union`] method on [`CountedBag`]. See the documentation for more.
+///
+/// [`union`]: CountedBag::union
+pub struct Union<'a, K: 'a, S: 'a> {
+    iter: Iter<'a, K>,
+    rest: Iter<'a, K>,
+    first: &'a CountedBag<K, S>,
+    second: &'a CountedBag<K, S>,
+}
+
+/// A lazy iterator producing the keys of either of two [`CountedBag`]s with the sum
+/// of their counts, the counts of the merged bags.
+///
+/// The `struct` is created by the [`sum`] method on [`CountedBag`]. See the documentation for more.
+///
+/// [`sum`]: CountedBag::sum
+pub struct Sum<'a, K: 'a, S: 'a> {
+    iter: Iter<'a, K>,
+    rest: Iter<'a, K>,
+    first: &'a CountedBag<K, S>,
+    second: &'a CountedBag<K, S>,
+}
+
+impl<K, S> Clone for Union<'_, K, S> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            rest: self.rest.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<K, S> Clone for Sum<'_, K, S> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            rest: self.rest.clone(),
+            ..*self
+        }
+    }
+}
+
+/// Returns the next key of the first bag, with its count combined with the count in the
+/// second bag, and then the next key which is only in the second bag, with its count.
+#[inline]
+fn next_combined<'a, K, S, F>(
+    iter: &mut Iter<'a, K>,
+    rest: &mut Iter<'a, K>,
+    first: &CountedBag<K, S>,
+    second: &CountedBag<K, S>,
+    combine: F,
+) -> Option<(&'a K, u32)>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+    F: Fn(u32, u32) -> u32,
+{
+    if let Some((key, val)) = iter.next() {
+        let val1 = second.get(key).copied().unwrap_or(0);
+        return Some((key, combine(*val, val1)));
+    }
+
+    rest.find(|(key, _)| first.get(*key).is_none())
+        .map(|(key, val)| (key, *val))
+}
+
+impl<'a, K, S> Iterator for Union<'a, K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = (&'a K, u32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        next_combined(
+            &mut self.iter,
+            &mut self.rest,
+            self.first,
+            self.second,
+            u32::max,
+        )
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, rest) = (self.iter.size_hint().0, self.rest.size_hint().0);
+        // the keys of the second bag may all be in the first one
+        (len, Some(len + rest))
+    }
+}
+
+impl<'a, K, S> Iterator for Sum<'a, K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = (&'a K, u32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        next_combined(
+            &mut self.iter,
+            &mut self.rest,
+            self.first,
+            self.second,
+            |x, y| x + y,
+        )
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, rest) = (self.iter.size_hint().0, self.rest.size_hint().0);
+        // the keys of the second bag may all be in the first one
+        (len, Some(len + rest))
+    }
+}
+
+impl<K, S> CountedBag<K, S> {
+    /// Returns a lazy iterator over the keys of either bag, each with the larger of its
+    /// two counts, a missing key counting zero times.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let xs = CountedBag::<char>::from_iter([('a', 3), ('b', 1)]);
+    /// let ys = CountedBag::<char>::from_iter([('a', 1), ('c', 2)]);
+    ///
+    /// let mut union: Vec<_> = xs.union(&ys).collect();
+    /// union.sort();
+    /// assert_eq!(union, [(&'a', 3), (&'b', 1), (&'c', 2)]);
+    ///
+    /// // the weighted Jaccard similarity of the multisets
+    /// let min: u32 = xs.intersection(&ys).map(|(_, c)| c).sum();
+    /// let max: u32 = xs.union(&ys).map(|(_, c)| c).sum();
+    /// assert_eq!(min as f32 / max as f32, 1. / 6.);
+    /// ```
+    pub fn union<'a>(&'a self, other: &'a CountedBag<K, S>) -> Union<'a, K, S> {
+        Union {
+            iter: self.iter(),
+            rest: other.iter(),
+            first: self,
+            second: other,
+        }
+    }
+
+    /// Returns a lazy iterator over the keys of either bag, each with the sum of its
+    /// two counts, as in the bag merged by [`Mergeable::merge_from`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let xs = CountedBag::<char>::from_iter([('a', 3), ('b', 1)]);
+    /// let ys = CountedBag::<char>::from_iter([('a', 1), ('c', 2)]);
+    ///
+    /// let mut sum: Vec<_> = xs.sum(&ys).collect();
+    /// sum.sort();
+    /// assert_eq!(sum, [(&'a', 4), (&'b', 1), (&'c', 2)]);
+    /// ```
+    pub fn sum<'a>(&'a self, other: &'a CountedBag<K, S>) -> Sum<'a, K, S> {
+        Sum {
+            iter: self.iter(),
+            rest: other.iter(),
+            first: self,
+            second: other,
+        }
+    }
+}
+
+//
+// Difference and symmetric difference
+//
+
+/// A lazy iterator producing the keys of a [`CountedBag`] which are more frequent than
+/// in another one, with the difference of their counts.
+///
+/// The `struct` is created by the [`difference`] method on [`CountedBag`]. See the documentation for more.
+///
+/// [`difference`]: CountedBag::difference
+pub struct Difference<'a, K: 'a, S: 'a> {
+    // iterator of the first set
+    iter: Iter<'a, K>,
+    // the second set
+    other: &'a CountedBag<K, S>,
+}
+
+/// A lazy iterator producing the keys whose counts differ between two [`CountedBag`]s,
+/// with the absolute difference of their counts.
+///
+/// The `struct` is created by the [`symmetric_difference`] method on [`CountedBag`].
+/// See the documentation for more.
+///
+/// [`symmetric_difference`]: CountedBag::symmetric_difference
+pub struct SymmetricDifference<'a, K: 'a, S: 'a> {
+    iter: Iter<'a, K>,
+    rest: Iter<'a, K>,
+    first: &'a CountedBag<K, S>,
+    second: &'a CountedBag<K, S>,
+}
+
+impl<K, S> Clone for Difference<'_, K, S> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<K, S> Clone for SymmetricDifference<'_, K, S> {
+    fn clone(&self) -> Self {
+        Self {
+            iter: self.iter.clone(),
+            rest: self.rest.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<'a, K, S> Iterator for Difference<'a, K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = (&'a K, u32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, val) = self.iter.next()?;
+            let val1 = self.other.get(key).copied().unwrap_or(0);
+            if *val > val1 {
+                return Some((key, *val - val1));
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+}
+
+impl<'a, K, S> Iterator for SymmetricDifference<'a, K, S>
+where
+    K: Eq + Hash,
+    S: BuildHasher,
+{
+    type Item = (&'a K, u32);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            let (key, val) = next_combined(
+                &mut self.iter,
+                &mut self.rest,
+                self.first,
+                self.second,
+                u32::abs_diff,
+            )?;
+            if val > 0 {
+                return Some((key, val));
+            }
+        }
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (len, rest) = (self.iter.size_hint().0, self.rest.size_hint().0);
+        (0, Some(len + rest))
+    }
+}
+
+impl<K, S> CountedBag<K, S> {
+    /// Returns a lazy iterator over the keys which are more frequent in this bag than in
+    /// the other one, each with the difference of its counts, the multiset difference.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let xs = CountedBag::<char>::from_iter([('a', 3), ('b', 1), ('c', 1)]);
+    /// let ys = CountedBag::<char>::from_iter([('a', 1), ('b', 2)]);
+    ///
+    /// let mut difference: Vec<_> = xs.difference(&ys).collect();
+    /// difference.sort();
+    /// assert_eq!(difference, [(&'a', 2), (&'c', 1)]);
+    /// ```
+    pub fn difference<'a>(&'a self, other: &'a CountedBag<K, S>) -> Difference<'a, K, S> {
+        Difference {
+            iter: self.iter(),
+            other,
+        }
+    }
+
+    /// Returns a lazy iterator over the keys whose counts differ between the bags, each
+    /// with the absolute difference of its counts, the union of the two differences.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::CountedBag;
+    ///
+    /// let xs = CountedBag::<char>::from_iter([('a', 3), ('b', 1), ('c', 1)]);
+    /// let ys = CountedBag::<char>::from_iter([('a', 1), ('b', 2), ('c', 1)]);
+    ///
+    /// let mut difference: Vec<_> = xs.symmetric_difference(&ys).collect();
+    /// difference.sort();
+    /// assert_eq!(difference, [(&'a', 2), (&'b', 1)]);
+    /// ```
+    pub fn symmetric_difference<'a>(
+        &'a self,
+        other: &'a CountedBag<K, S>,
+    ) -> SymmetricDifference<'a, K, S> {
+        SymmetricDifference {
+            iter: self.iter(),
+            rest: other.iter(),
+            first: self,
+            second: other,
+        }
+    }
+}
+
 //
 // Tests
 //
@@ -453,6 +990,7 @@ impl<K, S> CountedBag<K, S> {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use quickcheck_macros::quickcheck;
 
     #[test]
     fn len_() {
@@ -511,6 +1049,48 @@ mod tests {
         assert_eq!(x, Some(&1));
     }
 
+    #[test]
+    fn remove_decrement_() {
+        let mut cs = CountedBag::<String>::new();
+        cs.insert("a".to_string());
+        cs.insert("a".to_string());
+        cs.insert("b".to_string());
+
+        assert_eq!(cs.decrement("a"), 1);
+        assert_eq!(cs.total(), 2);
+        assert_eq!(cs.remove("a"), Some(1));
+        assert_eq!(cs.decrement("a"), 0);
+        assert_eq!(cs.remove("z"), None);
+        assert_eq!(cs.total(), 1);
+        assert_eq!(cs.len(), 1);
+
+        let mut cs = CountedBag::<char>::from_iter([('z', 0)]);
+        assert_eq!(cs.decrement(&'z'), 0);
+        assert!(cs.is_empty());
+        assert_eq!(cs.total(), 0);
+    }
+
+    #[quickcheck]
+    fn sliding_window_(keys: Vec<u8>, window: u8) -> bool {
+        // the bag of the last `window` keys, against counting the window directly
+        let window = window as usize % 8 + 1;
+        let mut cs = CountedBag::<u8>::new();
+
+        keys.iter().enumerate().all(|(i, key)| {
+            cs.insert(*key);
+            if i >= window {
+                cs.decrement(&keys[i - window]);
+            }
+
+            let live = &keys[(i + 1).saturating_sub(window)..=i];
+            cs.total() as usize == live.len()
+                && cs.iter().map(|(_, c)| *c as usize).sum::<usize>() == live.len()
+                && live.iter().all(|k| {
+                    cs.get(k).copied() == Some(live.iter().filter(|x| *x == k).count() as u32)
+                })
+        })
+    }
+
     #[test]
     fn insert_() {
         let mut cs = CountedBag::<char>::new();
@@ -593,6 +1173,43 @@ mod tests {
         assert_eq!(v, Some(&3));
     }
 
+    #[test]
+    fn from_delimited_() {
+        let csv = "id,city\r\n1,paris\r\n2,london\r\n\r\n3,paris\r\n";
+        let cs = CountedBag::<String>::from_delimited(csv.as_bytes(), 1, ',', true).unwrap();
+        assert_eq!(cs.get("paris"), Some(&2));
+        assert_eq!(cs.get("london"), Some(&1));
+        assert_eq!(cs.get("city"), None);
+        assert_eq!(cs.total(), 3);
+
+        // without a header, the first line is a row
+        let cs = CountedBag::<String>::from_delimited(csv.as_bytes(), 1, ',', false).unwrap();
+        assert_eq!(cs.get("city"), Some(&1));
+        assert_eq!(cs.total(), 4);
+    }
+
+    #[test]
+    fn from_delimited_missing_column_() {
+        let tsv = "a\tb\nc\n";
+        let res = CountedBag::<String>::from_delimited(tsv.as_bytes(), 1, '\t', false);
+        assert_eq!(
+            res.err().map(|e| e.kind()),
+            Some(io::ErrorKind::InvalidData)
+        );
+    }
+
+    #[test]
+    fn merge_from_() {
+        let mut xs = CountedBag::<char>::from_iter([('a', 2)]);
+        let ys = CountedBag::<char>::from_iter([('a', 1), ('b', 1), ('c', 3)]);
+
+        xs.merge_from(ys);
+        assert_eq!(xs.len(), 3);
+        assert_eq!(xs.get(&'a'), Some(&3));
+        assert_eq!(xs.get(&'c'), Some(&3));
+        assert_eq!(xs.total(), 7);
+    }
+
     #[test]
     fn intersection_() {
         let xs = [('a', 2), ('b', 1), ('x', 10)];
@@ -657,4 +1274,96 @@ mod tests {
         let intersection = CountedBag::<&char>::from_iter(intersection);
         assert_eq!(intersection.total(), 2);
     }
+
+    #[test]
+    fn union_sum_() {
+        let xs = CountedBag::<char>::from_iter([('a', 2), ('b', 1), ('x', 10)]);
+        let ys = CountedBag::<char>::from_iter([('a', 3), ('b', 1), ('c', 20)]);
+
+        let union = CountedBag::<&char>::from_iter(xs.union(&ys).clone());
+        assert_eq!(union.len(), 4);
+        assert_eq!(union.get(&&'a'), Some(&3));
+        assert_eq!(union.total(), 34);
+
+        let sum = CountedBag::<&char>::from_iter(xs.sum(&ys));
+        assert_eq!(sum.get(&&'b'), Some(&2));
+        assert_eq!(sum.total(), xs.total() + ys.total());
+
+        let empty = CountedBag::<char>::new();
+        assert_eq!(empty.union(&xs).count(), 3);
+        assert_eq!(xs.sum(&empty).count(), 3);
+    }
+
+    #[quickcheck]
+    fn union_sum_merge_(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+        let bx = CountedBag::<u8>::from_keys(xs.into_iter());
+        let by = CountedBag::<u8>::from_keys(ys.into_iter());
+        let mut merged = bx.clone();
+        merged.merge_from(by.clone());
+
+        let sum: Vec<_> = bx.sum(&by).collect();
+        let union: Vec<_> = bx.union(&by).collect();
+        let min: u32 = bx.intersection(&by).map(|(_, c)| c).sum();
+        let max: u32 = union.iter().map(|(_, c)| c).sum();
+        let hints = [bx.sum(&by).size_hint(), bx.union(&by).size_hint()];
+
+        hints
+            .iter()
+            .all(|(lower, upper)| *lower <= merged.len() && Some(merged.len()) <= *upper)
+            && sum.len() == merged.len()
+            && sum.iter().all(|(k, c)| merged.get(*k) == Some(c))
+            && union.len() == merged.len()
+            && union
+                .iter()
+                .all(|(k, c)| *c == *bx.get(*k).unwrap_or(&0).max(by.get(*k).unwrap_or(&0)))
+            && min + max == bx.total() + by.total()
+    }
+
+    #[test]
+    fn difference_() {
+        let xs = CountedBag::<char>::from_iter([('a', 2), ('b', 1), ('x', 10)]);
+        let ys = CountedBag::<char>::from_iter([('a', 3), ('b', 1), ('c', 20)]);
+
+        let difference = CountedBag::<&char>::from_iter(xs.difference(&ys).clone());
+        assert_eq!(difference.len(), 1);
+        assert_eq!(difference.get(&&'x'), Some(&10));
+
+        let difference = CountedBag::<&char>::from_iter(xs.symmetric_difference(&ys).clone());
+        assert_eq!(difference.len(), 3);
+        assert_eq!(difference.get(&&'a'), Some(&1));
+        assert_eq!(difference.get(&&'b'), None);
+        assert_eq!(difference.total(), 31);
+
+        assert_eq!(xs.symmetric_difference(&xs).count(), 0);
+    }
+
+    #[quickcheck]
+    fn difference_union_(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+        let bx = CountedBag::<u8>::from_keys(xs.into_iter());
+        let by = CountedBag::<u8>::from_keys(ys.into_iter());
+        let total = |it: &mut dyn Iterator<Item = (&u8, u32)>| it.map(|(_, c)| c).sum::<u32>();
+
+        let xy = total(&mut bx.difference(&by));
+        let yx = total(&mut by.difference(&bx));
+        let min = total(&mut bx.intersection(&by));
+        let max = total(&mut bx.union(&by));
+
+        xy + min == bx.total()
+            && yx + min == by.total()
+            && total(&mut bx.symmetric_difference(&by)) == xy + yx
+            && max - min == xy + yx
+            && bx.difference(&by).all(|(_, c)| c > 0)
+    }
+
+    #[test]
+    fn entropy_() {
+        let bag = CountedBag::<char>::new();
+        assert_eq!(bag.entropy(), 0.);
+
+        let bag = CountedBag::<char>::from_keys("aaaa".chars());
+        assert_eq!(bag.entropy(), 0.);
+
+        let bag = CountedBag::<u32>::from_keys(0..8);
+        assert_eq!(bag.entropy(), 3.);
+    }
 }
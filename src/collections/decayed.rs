@@ -0,0 +1,237 @@
+use super::Absorb;
+use std::{borrow::Borrow, collections::HashMap, hash::Hash};
+
+/// A counter whose counts decay exponentially with time, so recent items weigh more than old ones.
+///
+/// The time is driven explicitly with [`advance`](DecayedCounter::advance) or
+/// [`advance_to`](DecayedCounter::advance_to), in any unit. A count halves every `half_life`
+/// units of time. The counts are decayed lazily, when they are updated or read.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::DecayedCounter;
+///
+/// let mut trending = DecayedCounter::new(1.);
+/// trending.insert("old");
+/// trending.insert("old");
+/// trending.advance(2.);
+/// trending.insert("new");
+///
+/// assert_eq!(trending.get("old"), Some(0.5));
+/// assert_eq!(trending.get("new"), Some(1.));
+/// assert_eq!(trending.most_common(1), [(&"new", 1.)]);
+/// ```
+#[derive(Clone, Debug)]
+pub struct DecayedCounter<K> {
+    half_life: f64,
+    now: f64,
+    // the count of each key and the time it was last decayed at
+    counts: HashMap<K, (f64, f64)>,
+}
+
+impl<K> DecayedCounter<K> {
+    /// Creates an empty counter at time zero.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `half_life` is not a positive finite number.
+    pub fn new(half_life: f64) -> Self {
+        assert!(
+            half_life > 0. && half_life.is_finite(),
+            "the half life is not a positive finite number"
+        );
+
+        Self {
+            half_life,
+            now: 0.,
+            counts: HashMap::new(),
+        }
+    }
+
+    /// Returns the current time of the counter.
+    pub fn now(&self) -> f64 {
+        self.now
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.counts.len()
+    }
+
+    /// Returns true if the counter has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.counts.is_empty()
+    }
+
+    /// Moves the time forward by `dt` units.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `dt` is negative.
+    pub fn advance(&mut self, dt: f64) {
+        self.advance_to(self.now + dt);
+    }
+
+    /// Moves the time forward to `time`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` is before the current time.
+    pub fn advance_to(&mut self, time: f64) {
+        assert!(
+            time >= self.now,
+            "the time cannot go backwards from {} to {}",
+            self.now,
+            time
+        );
+        self.now = time;
+    }
+
+    /// Returns a count decayed from the time it was recorded at to the current time.
+    #[inline]
+    fn decayed(&self, (count, time): (f64, f64)) -> f64 {
+        decay(count, self.now - time, self.half_life)
+    }
+
+    /// Returns the `k` keys with the largest decayed counts, sorted by descending count.
+    pub fn most_common(&self, k: usize) -> Vec<(&K, f64)> {
+        let mut counts: Vec<(&K, f64)> = self
+            .counts
+            .iter()
+            .map(|(key, entry)| (key, self.decayed(*entry)))
+            .collect();
+
+        counts.sort_by(|(_, c1), (_, c2)| c2.total_cmp(c1));
+        counts.truncate(k);
+        counts
+    }
+}
+
+impl<K> DecayedCounter<K>
+where
+    K: Eq + Hash,
+{
+    /// Adds one to the count of a key at the current time.
+    pub fn insert(&mut self, key: K) {
+        self.add(key, 1.);
+    }
+
+    /// Adds a weight to the count of a key at the current time.
+    pub fn add(&mut self, key: K, weight: f64) {
+        let now = self.now;
+        let half_life = self.half_life;
+
+        let (count, time) = self.counts.entry(key).or_insert((0., now));
+        *count = decay(*count, now - *time, half_life) + weight;
+        *time = now;
+    }
+
+    /// Returns the decayed count of a key at the current time, or `None` if the key is missing.
+    pub fn get<Q>(&self, key: &Q) -> Option<f64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.counts.get(key).map(|entry| self.decayed(*entry))
+    }
+
+    /// Removes the keys whose decayed count dropped below `min_count`, bounding the memory
+    /// used by a long running counter.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::DecayedCounter;
+    ///
+    /// let mut counter = DecayedCounter::new(1.);
+    /// counter.insert('a');
+    /// counter.advance(10.);
+    /// counter.insert('b');
+    ///
+    /// counter.prune(0.01);
+    /// assert_eq!(counter.len(), 1);
+    /// assert_eq!(counter.get(&'a'), None);
+    /// ```
+    pub fn prune(&mut self, min_count: f64) {
+        let now = self.now;
+        let half_life = self.half_life;
+        self.counts
+            .retain(|_, (count, time)| decay(*count, now - *time, half_life) >= min_count);
+    }
+}
+
+impl<K> Absorb<K> for DecayedCounter<K>
+where
+    K: Eq + Hash,
+{
+    /// Adds one to the count of the key at the current time.
+    fn absorb(&mut self, key: K) {
+        self.insert(key);
+    }
+}
+
+/// Returns a count decayed over `elapsed` units of time.
+#[inline]
+fn decay(count: f64, elapsed: f64, half_life: f64) -> f64 {
+    count * 0.5_f64.powf(elapsed / half_life)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+
+    #[test]
+    fn decay_() {
+        let mut counter = DecayedCounter::new(2.);
+        counter.insert('a');
+        counter.advance(2.);
+        assert_eq!(counter.get(&'a'), Some(0.5));
+
+        counter.insert('a');
+        assert_eq!(counter.get(&'a'), Some(1.5));
+
+        counter.advance_to(6.);
+        assert_close(counter.get(&'a').unwrap(), 0.375, DEFAULT_EPSILON);
+        assert_eq!(counter.get(&'b'), None);
+        assert_eq!(counter.now(), 6.);
+    }
+
+    #[test]
+    fn most_common_() {
+        let mut counter = DecayedCounter::new(1.);
+        for _ in 0..8 {
+            counter.insert("old");
+        }
+        counter.advance(2.);
+        for _ in 0..3 {
+            counter.insert("new");
+        }
+        counter.add("weighted", 2.5);
+
+        let top = counter.most_common(2);
+        assert_eq!(top, [(&"new", 3.), (&"weighted", 2.5)]);
+        assert_eq!(counter.most_common(10).len(), 3);
+    }
+
+    #[test]
+    fn empty_() {
+        let counter = DecayedCounter::<u32>::new(1.);
+        assert!(counter.is_empty());
+        assert!(counter.most_common(3).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn backwards_() {
+        let mut counter = DecayedCounter::<u32>::new(1.);
+        counter.advance(-1.);
+    }
+
+    #[test]
+    #[should_panic]
+    fn half_life_() {
+        let _ = DecayedCounter::<u32>::new(0.);
+    }
+}
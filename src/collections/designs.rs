@@ -0,0 +1,246 @@
+//! Generators of combinatorial designs for experiments and benchmarks.
+
+use super::Permutation;
+use itertools::Itertools;
+use rand::{seq::SliceRandom, Rng};
+
+/// Returns a random [Latin square](https://en.wikipedia.org/wiki/Latin_square) of order `n`,
+/// an `n x n` grid of the symbols `0..n` where each symbol occurs once in every row and column.
+///
+/// The square is the cyclic square with its rows, columns and symbols randomly permuted,
+/// which balances the order of the treatments of an experiment, but it is not drawn
+/// uniformly from all the Latin squares of order `n`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{is_latin_square, latin_square};
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(3);
+/// let square = latin_square(4, &mut rng);
+/// assert_eq!(square.len(), 4);
+/// assert!(is_latin_square(&square));
+/// ```
+pub fn latin_square<R: Rng + ?Sized>(n: usize, rng: &mut R) -> Vec<Vec<usize>> {
+    let rows = Permutation::random(n, rng);
+    let columns = Permutation::random(n, rng);
+    let symbols = Permutation::random(n, rng);
+
+    rows.as_slice()
+        .iter()
+        .map(|r| {
+            columns
+                .as_slice()
+                .iter()
+                .map(|c| symbols.as_slice()[(r + c) % n])
+                .collect()
+        })
+        .collect()
+}
+
+/// Returns true if a grid is a Latin square: it is square and each of the symbols `0..n`
+/// occurs once in every row and column.
+pub fn is_latin_square(square: &[Vec<usize>]) -> bool {
+    let n = square.len();
+    let is_permutation = |xs: Vec<usize>| Permutation::from_indices(xs).is_some();
+
+    square.iter().all(|row| row.len() == n)
+        && square.iter().all(|row| is_permutation(row.clone()))
+        && (0..n).all(|c| is_permutation(square.iter().map(|row| row[c]).collect()))
+}
+
+/// A [block design](https://en.wikipedia.org/wiki/Block_design): a family of blocks
+/// of `k` points out of the points `0..v`.
+///
+/// The design is a balanced incomplete block design (BIBD) when every pair of points
+/// occurs together in the same number of blocks, `lambda`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::BlockDesign;
+///
+/// // the Fano plane
+/// let design = BlockDesign::projective_plane(2);
+/// assert_eq!(design.points(), 7);
+/// assert_eq!(design.block_size(), 3);
+/// assert_eq!(design.blocks().len(), 7);
+/// assert_eq!(design.lambda(), Some(1));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BlockDesign {
+    v: usize,
+    k: usize,
+    blocks: Vec<Vec<usize>>,
+}
+
+impl BlockDesign {
+    /// Creates the design of all the blocks of `k` out of `v` points,
+    /// which is balanced with `lambda = C(v - 2, k - 2)`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `k` is smaller than 2 or larger than `v`.
+    pub fn complete(v: usize, k: usize) -> Self {
+        assert!(
+            2 <= k && k <= v,
+            "the block size must be between 2 and the number of points"
+        );
+
+        Self {
+            v,
+            k,
+            blocks: (0..v).combinations(k).collect(),
+        }
+    }
+
+    /// Creates the design of the lines of the projective plane of prime order `q`:
+    /// `q^2 + q + 1` points and as many blocks of `q + 1` points, with `lambda = 1`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `q` is not a prime.
+    pub fn projective_plane(q: usize) -> Self {
+        assert!(
+            q >= 2
+                && (2..q)
+                    .take_while(|d| d * d <= q)
+                    .all(|d| !q.is_multiple_of(d)),
+            "the order of the plane must be a prime"
+        );
+
+        // the points and lines are the triples over GF(q) whose first non-zero coordinate is 1
+        let triples: Vec<[usize; 3]> = (0..q)
+            .flat_map(|a| (0..q).map(move |b| [1, a, b]))
+            .chain((0..q).map(|b| [0, 1, b]))
+            .chain([[0, 0, 1]])
+            .collect();
+
+        let blocks = triples
+            .iter()
+            .map(|line| {
+                (0..triples.len())
+                    .filter(|p| {
+                        let point = triples[*p];
+                        (0..3)
+                            .map(|i| point[i] * line[i])
+                            .sum::<usize>()
+                            .is_multiple_of(q)
+                    })
+                    .collect()
+            })
+            .collect();
+
+        Self {
+            v: triples.len(),
+            k: q + 1,
+            blocks,
+        }
+    }
+
+    /// Returns the number of points of the design.
+    pub fn points(&self) -> usize {
+        self.v
+    }
+
+    /// Returns the number of points of each block.
+    pub fn block_size(&self) -> usize {
+        self.k
+    }
+
+    /// Returns the blocks of the design, each with its points in increasing order.
+    pub fn blocks(&self) -> &[Vec<usize>] {
+        &self.blocks
+    }
+
+    /// Returns the number of blocks which contain each pair of points,
+    /// or `None` if the design is not balanced.
+    pub fn lambda(&self) -> Option<usize> {
+        let mut pairs = vec![0; self.v * self.v];
+        for block in self.blocks.iter() {
+            for (a, b) in block.iter().tuple_combinations() {
+                pairs[a * self.v + b] += 1;
+            }
+        }
+
+        let mut counts = (0..self.v)
+            .tuple_combinations()
+            .map(|(a, b)| pairs[a * self.v + b]);
+        let lambda = counts.next()?;
+        counts.all(|count| count == lambda).then_some(lambda)
+    }
+
+    /// Returns the design with its points relabelled and its blocks reordered at random,
+    /// which keeps the design balanced.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::BlockDesign;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(5);
+    /// let design = BlockDesign::complete(5, 3).shuffled(&mut rng);
+    /// assert_eq!(design.blocks().len(), 10);
+    /// assert_eq!(design.lambda(), Some(3));
+    /// ```
+    pub fn shuffled<R: Rng + ?Sized>(mut self, rng: &mut R) -> Self {
+        let labels = Permutation::random(self.v, rng);
+        for block in self.blocks.iter_mut() {
+            for point in block.iter_mut() {
+                *point = labels.as_slice()[*point];
+            }
+            block.sort_unstable();
+        }
+        self.blocks.shuffle(rng);
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn latin_square_() {
+        let mut rng = StdRng::seed_from_u64(17);
+        for n in 0..8 {
+            assert!(is_latin_square(&latin_square(n, &mut rng)));
+        }
+
+        assert!(!is_latin_square(&[vec![0, 1], vec![0, 1]]));
+        assert!(!is_latin_square(&[vec![0, 1], vec![1]]));
+    }
+
+    #[test]
+    fn projective_planes_() {
+        for q in [2, 3, 5, 7] {
+            let design = BlockDesign::projective_plane(q);
+            assert_eq!(design.points(), q * q + q + 1);
+            assert_eq!(design.blocks().len(), q * q + q + 1);
+            assert!(design.blocks().iter().all(|b| b.len() == q + 1));
+            assert_eq!(design.lambda(), Some(1));
+        }
+    }
+
+    #[test]
+    fn lambda_() {
+        assert_eq!(BlockDesign::complete(6, 2).lambda(), Some(1));
+        assert_eq!(BlockDesign::complete(6, 4).lambda(), Some(6));
+
+        let unbalanced = BlockDesign {
+            v: 3,
+            k: 2,
+            blocks: vec![vec![0, 1], vec![1, 2]],
+        };
+        assert_eq!(unbalanced.lambda(), None);
+    }
+
+    #[test]
+    #[should_panic]
+    fn not_prime_() {
+        BlockDesign::projective_plane(4);
+    }
+}
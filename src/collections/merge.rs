@@ -0,0 +1,120 @@
+//! Merging of per-shard aggregates.
+
+/// Structures which can absorb the content of another instance of the same type,
+/// such as counters built independently over different shards of a data set.
+pub trait Mergeable {
+    /// Merges the content of `other` into `self`.
+    fn merge_from(&mut self, other: Self);
+}
+
+/// Merges all the given structures into a single one, using pairwise (tree) merging.
+///
+/// Returns `None` when there is nothing to merge.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{merge_all, CountedBag};
+///
+/// let shards = vec![
+///     CountedBag::<char>::from_iter([('a', 1), ('b', 2)]),
+///     CountedBag::<char>::from_iter([('b', 1), ('c', 5)]),
+///     CountedBag::<char>::from_iter([('a', 3)]),
+/// ];
+///
+/// let merged = merge_all(shards).unwrap();
+/// assert_eq!(merged.get(&'a'), Some(&4));
+/// assert_eq!(merged.get(&'b'), Some(&3));
+/// assert_eq!(merged.total(), 12);
+/// ```
+pub fn merge_all<T, I>(items: I) -> Option<T>
+where
+    T: Mergeable,
+    I: IntoIterator<Item = T>,
+{
+    let mut items: Vec<T> = items.into_iter().collect();
+
+    while items.len() > 1 {
+        let mut merged = Vec::with_capacity(items.len().div_ceil(2));
+        let mut iter = items.into_iter();
+
+        while let Some(mut first) = iter.next() {
+            if let Some(second) = iter.next() {
+                first.merge_from(second);
+            }
+            merged.push(first);
+        }
+
+        items = merged;
+    }
+
+    items.pop()
+}
+
+/// Merges all the given structures into a single one, merging them in parallel.
+///
+/// Returns `None` when there is nothing to merge.
+/// The function is available only when the `rayon` feature is enabled.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{par_merge_all, CountedBag};
+///
+/// let shards: Vec<_> = (0..10)
+///     .map(|i| CountedBag::<u32>::from_iter([(i % 3, 1)]))
+///     .collect();
+///
+/// let merged = par_merge_all(shards).unwrap();
+/// assert_eq!(merged.get(&0), Some(&4));
+/// assert_eq!(merged.total(), 10);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_merge_all<T>(items: Vec<T>) -> Option<T>
+where
+    T: Mergeable + Send,
+{
+    use rayon::prelude::*;
+
+    items.into_par_iter().reduce_with(|mut first, second| {
+        first.merge_from(second);
+        first
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum(u32);
+
+    impl Mergeable for Sum {
+        fn merge_from(&mut self, other: Self) {
+            self.0 += other.0;
+        }
+    }
+
+    #[test]
+    fn merge_all_() {
+        let merged = merge_all((1..=7).map(Sum)).unwrap();
+        assert_eq!(merged.0, 28);
+    }
+
+    #[test]
+    fn merge_all_one_() {
+        let merged = merge_all([Sum(5)]).unwrap();
+        assert_eq!(merged.0, 5);
+    }
+
+    #[test]
+    fn merge_all_empty_() {
+        assert!(merge_all(Vec::<Sum>::new()).is_none());
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_merge_all_() {
+        let merged = par_merge_all((1..=100).map(Sum).collect()).unwrap();
+        assert_eq!(merged.0, 5050);
+    }
+}
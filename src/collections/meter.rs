@@ -0,0 +1,177 @@
+use super::{Absorb, DecayedCounter};
+use std::f64::consts::LN_2;
+
+/// The windows of the rolling rates, in seconds.
+const WINDOWS: [f64; 3] = [60., 300., 900.];
+
+/// A throughput meter which records timestamped events and reports their rolling rates
+/// over the last 1, 5 and 15 minutes, in events per second.
+///
+/// Like the load averages of Unix, each rate is an exponentially weighted moving average
+/// whose weights decay with a time constant of the length of its window, kept by a
+/// [`DecayedCounter`]: a decayed count of `c` events over a time constant of `w` seconds
+/// is a rate of `c / w` events per second. The rates start from zero, so they underestimate
+/// a steady throughput during the first minutes.
+///
+/// The timestamps are in seconds from time zero, such as the elapsed time of a pipeline,
+/// and they cannot go backwards.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::Meter;
+///
+/// let mut meter = Meter::new();
+/// // 10 events per second during two hours
+/// for i in 0..72_000 {
+///     meter.mark(i as f64 / 10.);
+/// }
+///
+/// assert_eq!(meter.count(), 72_000);
+/// assert!((meter.rate_1m() - 10.).abs() < 0.1);
+/// assert!((meter.rate_15m() - 10.).abs() < 0.1);
+///
+/// // the short window forgets faster than the long one
+/// meter.advance_to(7_800.);
+/// assert!(meter.rate_1m() < 0.01);
+/// assert!(meter.rate_15m() > 4.);
+/// ```
+#[derive(Clone, Debug)]
+pub struct Meter {
+    count: u64,
+    windows: [DecayedCounter<()>; 3],
+}
+
+impl Default for Meter {
+    fn default() -> Self {
+        Self {
+            count: 0,
+            windows: WINDOWS.map(|window| DecayedCounter::new(window * LN_2)),
+        }
+    }
+}
+
+impl Meter {
+    /// Creates a meter without events at time zero.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the time of the last event or of the last call to [`advance_to`](Meter::advance_to).
+    pub fn now(&self) -> f64 {
+        self.windows[0].now()
+    }
+
+    /// Returns the number of recorded events.
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    /// Records an event at `time`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` is before the current time of the meter.
+    pub fn mark(&mut self, time: f64) {
+        self.mark_n(time, 1);
+    }
+
+    /// Records `n` events at `time`, such as a batch of items.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` is before the current time of the meter.
+    pub fn mark_n(&mut self, time: f64, n: u64) {
+        self.advance_to(time);
+        self.count += n;
+        self.windows
+            .iter_mut()
+            .for_each(|window| window.add((), n as f64));
+    }
+
+    /// Moves the time forward to `time` without recording events, so the rates decay.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `time` is before the current time of the meter.
+    pub fn advance_to(&mut self, time: f64) {
+        self.windows
+            .iter_mut()
+            .for_each(|window| window.advance_to(time));
+    }
+
+    /// Returns the rate of the events over the last minute, in events per second.
+    pub fn rate_1m(&self) -> f64 {
+        self.rate(0)
+    }
+
+    /// Returns the rate of the events over the last 5 minutes, in events per second.
+    pub fn rate_5m(&self) -> f64 {
+        self.rate(1)
+    }
+
+    /// Returns the rate of the events over the last 15 minutes, in events per second.
+    pub fn rate_15m(&self) -> f64 {
+        self.rate(2)
+    }
+
+    fn rate(&self, idx: usize) -> f64 {
+        self.windows[idx].get(&()).unwrap_or(0.) / WINDOWS[idx]
+    }
+}
+
+impl Absorb<f64> for Meter {
+    /// Records an event at the given time.
+    fn absorb(&mut self, time: f64) {
+        self.mark(time);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+
+    #[test]
+    fn empty_() {
+        let mut meter = Meter::new();
+        assert_eq!(meter.count(), 0);
+        assert_eq!(meter.rate_1m(), 0.);
+
+        meter.advance_to(100.);
+        assert_eq!(meter.now(), 100.);
+        assert_eq!(meter.rate_15m(), 0.);
+    }
+
+    #[test]
+    fn decay_() {
+        let mut meter = Meter::new();
+        meter.mark_n(0., 600);
+        assert_eq!(meter.count(), 600);
+        assert_close(meter.rate_1m(), 10., DEFAULT_EPSILON);
+        assert_close(meter.rate_5m(), 2., DEFAULT_EPSILON);
+
+        // a time constant later the rate dropped to 1/e
+        meter.advance_to(60.);
+        assert_close(meter.rate_1m(), 10. / std::f64::consts::E, DEFAULT_EPSILON);
+    }
+
+    #[test]
+    fn steady_rate_() {
+        let mut meter = Meter::new();
+        meter.absorb_all((0..20_000).map(|i| i as f64 / 2.));
+
+        // after 10,000 seconds even the 15 minutes window reached 1 - e^-11 of the rate
+        for rate in [meter.rate_1m(), meter.rate_5m(), meter.rate_15m()] {
+            assert!((rate - 2.).abs() < 0.02, "{rate}");
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn backwards_() {
+        let mut meter = Meter::new();
+        meter.mark(10.);
+        meter.mark(5.);
+    }
+}
@@ -1,9 +1,33 @@
 //! Different structures for managing data.
 
+mod absorb;
+mod adaptors;
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
 mod counted_bag;
+mod decayed;
+mod designs;
+mod merge;
+mod meter;
+mod permutation;
 mod permutations;
+#[cfg(any(test, feature = "test-util"))]
+mod quickcheck;
+mod report;
 mod shingles;
+#[cfg(feature = "async")]
+mod stream;
 
+pub use absorb::*;
+pub use adaptors::*;
 pub use counted_bag::*;
+pub use decayed::*;
+pub use designs::*;
+pub use merge::*;
+pub use meter::*;
+pub use permutation::*;
 pub use permutations::*;
+pub use report::*;
 pub use shingles::*;
+#[cfg(feature = "async")]
+pub use stream::*;
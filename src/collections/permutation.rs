@@ -0,0 +1,159 @@
+use rand::{seq::SliceRandom, Rng};
+use std::io::{self, BufWriter, Write};
+
+/// A permutation of the positions `0..len`, which lists for every position
+/// the index of the source element placed there.
+///
+/// Unlike [`Permutations`](crate::collections::Permutations), which enumerates all the
+/// orderings of a slice, a permutation is a single ordering which can be applied to data
+/// too large to be permuted in memory, such as the lines of a file with
+/// [`permute_file_lines`](crate::io::permute_file_lines).
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::Permutation;
+///
+/// let permutation = Permutation::from_indices(vec![2, 0, 1]).unwrap();
+/// assert_eq!(permutation.apply(&['a', 'b', 'c']), ['c', 'a', 'b']);
+/// assert_eq!(permutation.inverse().as_slice(), [1, 2, 0]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Permutation {
+    indices: Vec<usize>,
+}
+
+impl Permutation {
+    /// Creates the permutation which leaves every position in place.
+    pub fn identity(len: usize) -> Self {
+        Self {
+            indices: (0..len).collect(),
+        }
+    }
+
+    /// Creates a uniformly random permutation of `len` positions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Permutation;
+    /// use rand::{rngs::StdRng, SeedableRng};
+    ///
+    /// let mut rng = StdRng::seed_from_u64(7);
+    /// let permutation = Permutation::random(10, &mut rng);
+    /// assert_eq!(permutation.len(), 10);
+    /// ```
+    pub fn random<R: Rng + ?Sized>(len: usize, rng: &mut R) -> Self {
+        let mut permutation = Self::identity(len);
+        permutation.indices.shuffle(rng);
+        permutation
+    }
+
+    /// Creates a permutation from the source index of each position,
+    /// or returns `None` if the indices are not a permutation of `0..len`.
+    pub fn from_indices(indices: Vec<usize>) -> Option<Self> {
+        let mut seen = vec![false; indices.len()];
+        for i in indices.iter() {
+            if *i >= seen.len() || seen[*i] {
+                return None;
+            }
+            seen[*i] = true;
+        }
+
+        Some(Self { indices })
+    }
+
+    /// Returns the number of positions of the permutation.
+    pub fn len(&self) -> usize {
+        self.indices.len()
+    }
+
+    /// Returns true if the permutation has no positions.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty()
+    }
+
+    /// Returns the source index of each position.
+    pub fn as_slice(&self) -> &[usize] {
+        &self.indices
+    }
+
+    /// Returns the permutation which undoes this one.
+    pub fn inverse(&self) -> Self {
+        let mut indices = vec![0; self.indices.len()];
+        for (position, source) in self.indices.iter().enumerate() {
+            indices[*source] = position;
+        }
+        Self { indices }
+    }
+
+    /// Returns the elements of a slice in the order of the permutation.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the slice and the permutation have different lengths.
+    pub fn apply<T: Clone>(&self, xs: &[T]) -> Vec<T> {
+        assert_eq!(
+            xs.len(),
+            self.len(),
+            "the slice and the permutation have different lengths"
+        );
+        self.indices.iter().map(|i| xs[*i].clone()).collect()
+    }
+
+    /// Streams the source index of each position to a writer, one per line, so a shuffle
+    /// of a dataset bigger than memory can be planned once and replayed by other tools.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Permutation;
+    ///
+    /// let permutation = Permutation::from_indices(vec![1, 2, 0]).unwrap();
+    /// let mut plan = Vec::new();
+    /// permutation.apply_to_indices(&mut plan).unwrap();
+    /// assert_eq!(plan, b"1\n2\n0\n");
+    /// ```
+    pub fn apply_to_indices<W: Write>(&self, writer: W) -> io::Result<()> {
+        let mut writer = BufWriter::new(writer);
+        for i in self.indices.iter() {
+            writeln!(writer, "{i}")?;
+        }
+        writer.flush()
+    }
+}
+
+impl From<Permutation> for Vec<usize> {
+    fn from(permutation: Permutation) -> Self {
+        permutation.indices
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn from_indices_() {
+        assert!(Permutation::from_indices(vec![]).is_some());
+        assert!(Permutation::from_indices(vec![0, 0]).is_none());
+        assert!(Permutation::from_indices(vec![0, 2]).is_none());
+        assert_eq!(
+            Permutation::from_indices(vec![0, 1, 2]),
+            Some(Permutation::identity(3))
+        );
+    }
+
+    #[quickcheck]
+    fn inverse_(len: u8, seed: u64) -> bool {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let permutation = Permutation::random(len as usize, &mut rng);
+        let xs: Vec<usize> = (0..len as usize).map(|x| x * 3).collect();
+
+        let shuffled = permutation.apply(&xs);
+        Permutation::from_indices(permutation.as_slice().to_vec()).as_ref() == Some(&permutation)
+            && permutation.inverse().apply(&shuffled) == xs
+    }
+}
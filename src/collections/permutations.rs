@@ -1,7 +1,9 @@
-use std::ptr;
-
 /// Builder that can generate the permutations
-/// for a slice set of a given size.
+/// of the elements of a slice.
+///
+/// The permutations are produced with [Heap's algorithm](https://en.wikipedia.org/wiki/Heap%27s_algorithm),
+/// which swaps the elements of the slice in place, so each permutation differs from the previous
+/// one by a single swap.
 ///
 /// # Examples
 ///
@@ -9,13 +11,11 @@ use std::ptr;
 /// use aabel_rs::collections::Permutations;
 ///
 /// let source = &mut [1, 2, 3, 4];
-/// let mut permutations = Permutations::new(4, source);
+/// let mut permutations = Permutations::new(source);
 /// let results = permutations.generate();
 /// assert_eq!(24, results.len());
 /// ```
 pub struct Permutations<'a, T> {
-    /// The length of the slice.
-    len: usize,
     /// The slice of data
     arr: &'a mut [T],
 }
@@ -29,29 +29,73 @@ impl<'a, T> Permutations<'a, T> {
     /// use aabel_rs::collections::Permutations;
     ///
     /// let source = &mut [1, 2, 3, 4];
-    /// let mut permutations = Permutations::new(4, source);
+    /// let permutations = Permutations::new(source);
+    /// assert_eq!(4, permutations.len());
     /// ```
-    pub fn new(len: usize, arr: &'a mut [T]) -> Self {
-        Self { arr, len }
+    pub fn new(arr: &'a mut [T]) -> Self {
+        Self { arr }
     }
 
-    fn swap(&mut self, a: usize, b: usize) {
-        unsafe {
-            let pa: *mut T = &mut self.arr[a];
-            let pb: *mut T = &mut self.arr[b];
-            ptr::swap(pa, pb);
-        }
+    /// Returns the number of elements which are permuted.
+    pub fn len(&self) -> usize {
+        self.arr.len()
+    }
+
+    /// Returns true if there are no elements to permute.
+    pub fn is_empty(&self) -> bool {
+        self.arr.is_empty()
+    }
+
+    /// Generates all the orderings of the positions `0..len`, in the same order as [`generate`],
+    /// without cloning the data. Each ordering lists, for every position of a permutation,
+    /// the index of the element of the slice placed there.
+    ///
+    /// [`generate`]: Permutations::generate
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Permutations;
+    ///
+    /// // `String` values are not copied, only their indices are permuted.
+    /// let source = &mut [String::from("a"), String::from("b"), String::from("c")];
+    /// let orderings = Permutations::new(source).generate_indices();
+    /// assert_eq!(6, orderings.len());
+    /// assert_eq!(orderings[0], [0, 1, 2]);
+    /// assert_eq!(orderings[1], [1, 0, 2]);
+    /// ```
+    pub fn generate_indices(&self) -> Vec<Vec<usize>> {
+        let mut indices: Vec<usize> = (0..self.len()).collect();
+        Permutations::new(&mut indices).generate()
     }
 }
 
 impl<'a, T> Permutations<'a, T>
 where
-    T: Clone + Default,
+    T: Clone,
 {
-    fn to_result(&self) -> Vec<T> {
-        let mut res: Vec<T> = vec![Default::default(); self.len];
-        res.clone_from_slice(self.arr);
-        res
+    /// Returns an iterator over all the permutations, which permutes the slice in place
+    /// as it advances.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Permutations;
+    ///
+    /// let source = &mut [1, 2, 3];
+    /// let mut permutations = Permutations::new(source);
+    /// let mut iter = permutations.iter();
+    /// assert_eq!(iter.next(), Some(vec![1, 2, 3]));
+    /// assert_eq!(iter.next(), Some(vec![2, 1, 3]));
+    /// assert_eq!(iter.count(), 4);
+    /// ```
+    pub fn iter(&mut self) -> PermutationsIter<'_, T> {
+        PermutationsIter {
+            stack: vec![0; self.arr.len()],
+            arr: self.arr,
+            i: 1,
+            started: false,
+        }
     }
 
     /// Gnerates all permutations.
@@ -62,34 +106,56 @@ where
     /// use aabel_rs::collections::Permutations;
     ///
     /// let source = &mut [1, 2, 3, 4];
-    /// let mut permutations = Permutations::new(4, source);
+    /// let mut permutations = Permutations::new(source);
     /// let results = permutations.generate();
     /// assert_eq!(24, results.len());
     /// ```
     pub fn generate(&mut self) -> Vec<Vec<T>> {
-        let mut results: Vec<Vec<T>> = vec![];
-        results.push(self.to_result());
+        self.iter().collect()
+    }
+}
 
-        let mut stack = vec![0; self.len];
-        let mut i = 1;
+/// An iterator over the permutations of a slice, created by [`Permutations::iter`].
+pub struct PermutationsIter<'b, T> {
+    arr: &'b mut [T],
+    /// The state of Heap's algorithm, one counter for each position.
+    stack: Vec<usize>,
+    i: usize,
+    started: bool,
+}
+
+impl<'b, T: Clone> Iterator for PermutationsIter<'b, T> {
+    type Item = Vec<T>;
 
-        while i < self.len {
-            if stack[i] < i {
-                let (a, b) = if i % 2 == 0 { (0, i) } else { (stack[i], i) };
+    fn next(&mut self) -> Option<Self::Item> {
+        debug_assert_eq!(self.stack.len(), self.arr.len());
 
-                self.swap(a, b);
+        if !self.started {
+            self.started = true;
+            return Some(self.arr.to_vec());
+        }
 
-                results.push(self.to_result());
+        while self.i < self.arr.len() {
+            let i = self.i;
+            if self.stack[i] < i {
+                let a = if i.is_multiple_of(2) {
+                    0
+                } else {
+                    self.stack[i]
+                };
+                debug_assert!(a < i);
+                self.arr.swap(a, i);
 
-                stack[i] += 1;
-                i = 1;
+                self.stack[i] += 1;
+                self.i = 1;
+                return Some(self.arr.to_vec());
             } else {
-                stack[i] = 0;
-                i += 1;
+                self.stack[i] = 0;
+                self.i += 1;
             }
         }
 
-        results
+        None
     }
 }
 
@@ -102,7 +168,7 @@ mod tests {
     #[test]
     fn permutations_() {
         let xs = &mut [1, 2, 3, 4];
-        let mut permutations = Permutations::new(4, xs);
+        let mut permutations = Permutations::new(xs);
 
         let results = permutations.generate();
         assert_eq!(24, results.len());
@@ -111,4 +177,43 @@ mod tests {
         let res = results.choose(&mut rng).unwrap();
         println!("ARR: {res:?}");
     }
+
+    #[test]
+    fn indices_() {
+        // a type which is neither `Clone` nor `Default`
+        struct Opaque(u8);
+
+        let xs = &mut [Opaque(1), Opaque(2), Opaque(3), Opaque(4)];
+        let orderings = Permutations::new(xs).generate_indices();
+        assert_eq!(24, orderings.len());
+
+        let mut sorted = orderings.clone();
+        sorted.sort();
+        sorted.dedup();
+        assert_eq!(24, sorted.len());
+
+        let values: Vec<Vec<u8>> = orderings
+            .iter()
+            .map(|o| o.iter().map(|i| xs[*i].0).collect())
+            .collect();
+        let ys = &mut [1, 2, 3, 4];
+        assert_eq!(values, Permutations::new(ys).generate());
+    }
+
+    #[test]
+    fn iter_() {
+        let xs = &mut ['a', 'b', 'c'];
+        let mut permutations = Permutations::new(xs);
+        assert_eq!(permutations.len(), 3);
+
+        let mut iter = permutations.iter();
+        assert_eq!(iter.next(), Some(vec!['a', 'b', 'c']));
+        assert_eq!(iter.next(), Some(vec!['b', 'a', 'c']));
+        assert_eq!(iter.count(), 4);
+
+        let empty: &mut [u8] = &mut [];
+        let mut permutations = Permutations::new(empty);
+        assert!(permutations.is_empty());
+        assert_eq!(permutations.generate(), [Vec::<u8>::new()]);
+    }
 }
@@ -0,0 +1,68 @@
+use super::CountedBag;
+use quickcheck::{Arbitrary, Gen};
+use std::{
+    collections::HashMap,
+    hash::{BuildHasher, Hash},
+};
+
+impl<K, S> Arbitrary for CountedBag<K, S>
+where
+    K: Arbitrary + Eq + Hash,
+    S: BuildHasher + Default + Clone + 'static,
+{
+    /// Creates a bag of up to `g.size()` distinct keys, each counted between 1 and 256 times.
+    fn arbitrary(g: &mut Gen) -> Self {
+        Vec::<(K, u8)>::arbitrary(g)
+            .into_iter()
+            .map(|(key, count)| (key, count as u32 + 1))
+            .collect()
+    }
+
+    /// Shrinks towards smaller frequency tables: fewer keys, smaller keys and lower counts.
+    /// A key whose count shrinks to zero is removed, and keys which shrink to the same
+    /// key are merged, adding their counts.
+    fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+        let pairs: Vec<(K, u32)> = self.iter().map(|(k, c)| (k.clone(), *c)).collect();
+        Box::new(pairs.shrink().map(|pairs| {
+            let mut counts = HashMap::<K, u32, S>::default();
+            for (key, count) in pairs.into_iter().filter(|(_, c)| *c > 0) {
+                let total = counts.entry(key).or_insert(0);
+                *total = total.saturating_add(count);
+            }
+            counts.into_iter().collect()
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck::{QuickCheck, TestResult};
+
+    #[test]
+    fn arbitrary_() {
+        let mut g = Gen::new(20);
+        for _ in 0..100 {
+            let bag = CountedBag::<u8>::arbitrary(&mut g);
+            assert!(bag.len() <= 20);
+            assert_eq!(bag.total(), bag.iter().map(|(_, c)| *c).sum::<u32>());
+            assert!(bag.iter().all(|(_, c)| (1..=256).contains(c)));
+        }
+    }
+
+    #[test]
+    fn shrink_minimal_() {
+        // a property failing for any bag with a total of at least 3 shrinks to a single key
+        fn prop(bag: CountedBag<u8>) -> TestResult {
+            TestResult::from_bool(bag.total() < 3)
+        }
+
+        let result = std::panic::catch_unwind(|| {
+            QuickCheck::new()
+                .tests(1_000)
+                .quickcheck(prop as fn(CountedBag<u8>) -> TestResult)
+        });
+        let message = *result.unwrap_err().downcast::<String>().unwrap();
+        assert!(message.contains("{0: 3}"), "{message}");
+    }
+}
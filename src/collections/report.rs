@@ -0,0 +1,229 @@
+//! Frequency reports for counted bags.
+
+use super::CountedBag;
+use std::{
+    fmt::{Display, Write as _},
+    io::{self, Write},
+};
+
+/// The format of a frequency report written by [`CountedBag::write_report`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReportFormat {
+    /// Tab separated values, one key per line, preceded by a header line.
+    /// Tabs, line breaks and backslashes in the keys are escaped as `\t`, `\n`, `\r` and `\\`.
+    Tsv {
+        /// Adds the cumulative percentage column.
+        cumulative: bool,
+    },
+    /// An array of JSON objects, one object per key.
+    Json {
+        /// Adds the cumulative percentage field.
+        cumulative: bool,
+    },
+}
+
+/// A line of the report.
+struct Row {
+    key: String,
+    count: u32,
+    percent: f64,
+    cumulative: f64,
+}
+
+impl<K, S> CountedBag<K, S>
+where
+    K: Display,
+{
+    /// Writes a frequency report with the keys sorted by descending count.
+    ///
+    /// Keys with the same count are sorted by their textual representation,
+    /// so the report is deterministic. Each key is reported with its count and
+    /// its percentage of the total, optionally followed by the cumulative percentage.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::{CountedBag, ReportFormat};
+    ///
+    /// let cs = CountedBag::<char>::from_iter([('a', 1), ('b', 3)]);
+    ///
+    /// let mut out = Vec::new();
+    /// cs.write_report(&mut out, ReportFormat::Tsv { cumulative: true }).unwrap();
+    ///
+    /// let out = String::from_utf8(out).unwrap();
+    /// assert_eq!(out, "key\tcount\tpercent\tcumulative\nb\t3\t75.00\t75.00\na\t1\t25.00\t100.00\n");
+    /// ```
+    pub fn write_report<W: Write>(&self, mut writer: W, format: ReportFormat) -> io::Result<()> {
+        let rows = self.report_rows();
+
+        match format {
+            ReportFormat::Tsv { cumulative } => {
+                write!(writer, "key\tcount\tpercent")?;
+                if cumulative {
+                    write!(writer, "\tcumulative")?;
+                }
+                writeln!(writer)?;
+
+                for row in rows {
+                    write!(
+                        writer,
+                        "{}\t{}\t{:.2}",
+                        tsv_escape(&row.key),
+                        row.count,
+                        row.percent
+                    )?;
+                    if cumulative {
+                        write!(writer, "\t{:.2}", row.cumulative)?;
+                    }
+                    writeln!(writer)?;
+                }
+            }
+            ReportFormat::Json { cumulative } => {
+                write!(writer, "[")?;
+                for (i, row) in rows.iter().enumerate() {
+                    if i > 0 {
+                        write!(writer, ",")?;
+                    }
+                    write!(
+                        writer,
+                        "{{\"key\":\"{}\",\"count\":{},\"percent\":{:.2}",
+                        json_escape(&row.key),
+                        row.count,
+                        row.percent
+                    )?;
+                    if cumulative {
+                        write!(writer, ",\"cumulative\":{:.2}", row.cumulative)?;
+                    }
+                    write!(writer, "}}")?;
+                }
+                writeln!(writer, "]")?;
+            }
+        }
+
+        writer.flush()
+    }
+
+    fn report_rows(&self) -> Vec<Row> {
+        let mut entries: Vec<(String, u32)> = self
+            .iter()
+            .map(|(key, count)| (key.to_string(), *count))
+            .collect();
+        entries.sort_by(|(k1, c1), (k2, c2)| c2.cmp(c1).then_with(|| k1.cmp(k2)));
+
+        let total = self.total() as f64;
+        let percent = |count: u64| {
+            if total == 0. {
+                0.
+            } else {
+                count as f64 * 100. / total
+            }
+        };
+
+        let mut running = 0_u64;
+        entries
+            .into_iter()
+            .map(|(key, count)| {
+                running += count as u64;
+                Row {
+                    key,
+                    count,
+                    percent: percent(count as u64),
+                    cumulative: percent(running),
+                }
+            })
+            .collect()
+    }
+}
+
+fn tsv_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+fn json_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            '\r' => escaped.push_str("\\r"),
+            '\t' => escaped.push_str("\\t"),
+            c if (c as u32) < 0x20 => {
+                let _ = write!(escaped, "\\u{:04x}", c as u32);
+            }
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report<K: Display>(cs: &CountedBag<K>, format: ReportFormat) -> String {
+        let mut out = Vec::new();
+        cs.write_report(&mut out, format).unwrap();
+        String::from_utf8(out).unwrap()
+    }
+
+    #[test]
+    fn tsv_() {
+        let cs = CountedBag::<&str>::from_iter([("x", 2), ("y", 6), ("z", 2)]);
+        let out = report(&cs, ReportFormat::Tsv { cumulative: false });
+        assert_eq!(
+            out,
+            "key\tcount\tpercent\ny\t6\t60.00\nx\t2\t20.00\nz\t2\t20.00\n"
+        );
+    }
+
+    #[test]
+    fn tsv_escape_() {
+        let cs = CountedBag::<&str>::from_iter([("a\tb", 2), ("c\nd\\", 1)]);
+        let out = report(&cs, ReportFormat::Tsv { cumulative: false });
+        assert_eq!(
+            out,
+            "key\tcount\tpercent\na\\tb\t2\t66.67\nc\\nd\\\\\t1\t33.33\n"
+        );
+        assert_eq!(tsv_escape("a\rb\"c"), "a\\rb\"c");
+    }
+
+    #[test]
+    fn json_() {
+        let cs = CountedBag::<&str>::from_iter([("a\"b", 1), ("c", 3)]);
+        let out = report(&cs, ReportFormat::Json { cumulative: true });
+        assert_eq!(
+            out,
+            "[{\"key\":\"c\",\"count\":3,\"percent\":75.00,\"cumulative\":75.00},\
+             {\"key\":\"a\\\"b\",\"count\":1,\"percent\":25.00,\"cumulative\":100.00}]\n"
+        );
+    }
+
+    #[test]
+    fn empty_() {
+        let cs = CountedBag::<char>::new();
+        assert_eq!(
+            report(&cs, ReportFormat::Json { cumulative: false }),
+            "[]\n"
+        );
+        assert_eq!(
+            report(&cs, ReportFormat::Tsv { cumulative: false }),
+            "key\tcount\tpercent\n"
+        );
+    }
+
+    #[test]
+    fn json_escape_() {
+        assert_eq!(json_escape("a\tb\\c\u{1}"), "a\\tb\\\\c\\u0001");
+    }
+}
@@ -13,19 +13,99 @@
 //! assert_eq!(None, ss.next());
 //! ```
 
-use std::num::NonZeroUsize;
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    num::NonZeroUsize,
+};
 
+/// An iterator over the shingles of a slice.
+///
+/// A shingle is a window of `size` consecutive elements which starts with an element
+/// accepted by the `is_start` predicate. When the slice is shorter than `size`,
+/// the iterator yields nothing.
+///
+/// The `struct` is created by the [`shingles`] and [`try_shingles`] functions. See their documentation for more.
 pub struct Shingles<'a, T, P> {
     slice: &'a [T],
     size: NonZeroUsize,
     is_start: P,
 }
 
-pub fn shingles<'a, T, P>(slice: &'a [T], size: usize, is_start: P) -> Shingles<'a, T, P> {
-    Shingles {
+/// The error returned when the shingles cannot be created.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ShinglesError {
+    /// The size of the shingles is zero.
+    ZeroSize,
+}
+
+impl Display for ShinglesError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::ZeroSize => write!(f, "the shingle size is zero"),
+        }
+    }
+}
+
+impl Error for ShinglesError {}
+
+/// Returns an iterator over the shingles of a given size.
+///
+/// # Panics
+///
+/// Panics if `size` is zero. See [`try_shingles`] for a non-panicking version.
+pub fn shingles<T, P>(slice: &[T], size: usize, is_start: P) -> Shingles<'_, T, P> {
+    try_shingles(slice, size, is_start).expect("size is zero")
+}
+
+/// Returns an iterator over the shingles of a given size,
+/// or an error if the size is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{try_shingles, ShinglesError};
+///
+/// let source = [1, 2, 3];
+/// let pred = |_: &i32| true;
+///
+/// let ss = try_shingles(source.as_slice(), 2, pred).unwrap();
+/// assert_eq!(ss.count(), 2);
+///
+/// let ss = try_shingles(source.as_slice(), 0, pred);
+/// assert_eq!(ss.err(), Some(ShinglesError::ZeroSize));
+/// ```
+pub fn try_shingles<T, P>(
+    slice: &[T],
+    size: usize,
+    is_start: P,
+) -> Result<Shingles<'_, T, P>, ShinglesError> {
+    let size = NonZeroUsize::new(size).ok_or(ShinglesError::ZeroSize)?;
+    Ok(Shingles {
         slice,
-        size: NonZeroUsize::new(size).expect("size is zero"),
+        size,
         is_start,
+    })
+}
+
+impl<T, P> Shingles<'_, T, P> {
+    /// Returns the number of windows which are still to be checked,
+    /// an upper bound of the number of shingles left.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::shingles;
+    ///
+    /// let source = [1, 2, 3, 4];
+    /// let mut ss = shingles(source.as_slice(), 2, |_: &i32| true);
+    /// assert_eq!(ss.remaining(), 3);
+    ///
+    /// ss.next();
+    /// assert_eq!(ss.remaining(), 2);
+    /// ```
+    pub fn remaining(&self) -> usize {
+        (self.slice.len() + 1).saturating_sub(self.size.get())
     }
 }
 
@@ -37,18 +117,21 @@ where
 
     #[inline]
     fn next(&mut self) -> Option<&'a [T]> {
-        if self.size.get() > self.slice.len() {
-            None
-        } else {
-            if (self.is_start)(&self.slice[0]) {
-                let ret = Some(&self.slice[..self.size.get()]);
-                self.slice = &self.slice[1..];
-                ret
-            } else {
-                self.slice = &self.slice[1..];
-                self.next()
+        while self.size.get() <= self.slice.len() {
+            let window = &self.slice[..self.size.get()];
+            self.slice = &self.slice[1..];
+
+            if (self.is_start)(&window[0]) {
+                return Some(window);
             }
         }
+
+        None
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.remaining()))
     }
 }
 
@@ -68,6 +151,64 @@ mod tests {
         assert_eq!(None, ss.next());
     }
 
+    #[test]
+    fn try_shingles_zero_() {
+        let source = vec![1, 2, 3];
+        let res = try_shingles(source.as_slice(), 0, |_: &i32| true);
+        assert_eq!(res.err(), Some(ShinglesError::ZeroSize));
+    }
+
+    #[test]
+    #[should_panic]
+    fn shingles_zero_() {
+        let source = vec![1, 2, 3];
+        let _ = shingles(source.as_slice(), 0, |_: &i32| true);
+    }
+
+    #[test]
+    fn shingles_too_short_() {
+        let source = vec![1, 2, 3];
+        let mut ss = shingles(source.as_slice(), 4, |_: &i32| true);
+        assert_eq!(ss.remaining(), 0);
+        assert_eq!(None, ss.next());
+
+        let empty: Vec<i32> = vec![];
+        let mut ss = shingles(empty.as_slice(), 1, |_: &i32| true);
+        assert_eq!(None, ss.next());
+    }
+
+    #[test]
+    fn shingles_whole_slice_() {
+        let source = vec![1, 2, 3];
+        let mut ss = shingles(source.as_slice(), 3, |_: &i32| true);
+        assert_eq!(Some([1, 2, 3].as_slice()), ss.next());
+        assert_eq!(None, ss.next());
+    }
+
+    #[test]
+    fn shingles_no_start_() {
+        // a predicate which never matches must not exhaust the stack
+        let source = vec![0; 1_000_000];
+        let mut ss = shingles(source.as_slice(), 2, |_: &i32| false);
+        assert_eq!(None, ss.next());
+        assert_eq!(ss.remaining(), 0);
+    }
+
+    #[test]
+    fn remaining_() {
+        let source = vec![1, 2, 3, 4, 5];
+        let mut ss = shingles(source.as_slice(), 2, |x: &i32| x % 2 == 0);
+        assert_eq!(ss.remaining(), 4);
+        assert_eq!(ss.size_hint(), (0, Some(4)));
+
+        assert_eq!(Some([2, 3].as_slice()), ss.next());
+        assert_eq!(ss.remaining(), 2);
+
+        assert_eq!(Some([4, 5].as_slice()), ss.next());
+        assert_eq!(ss.remaining(), 0);
+        assert_eq!(None, ss.next());
+    }
+
     // gets the word-shingles from a given text. The shingles can start only from a stop-word and they have a length of 3.
     #[test]
     fn singles_words_() {
@@ -0,0 +1,227 @@
+//! Feeding of asynchronous streams of items into aggregates.
+
+use super::Absorb;
+use futures_core::Stream;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
+
+/// Returns a future which absorbs all the items of a stream into an aggregate, as they arrive,
+/// and resolves to the aggregate when the stream ends.
+///
+/// The stream must be [`Unpin`]; a stream which is not can be pinned with [`Box::pin`].
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{absorb_stream, CountedBag};
+/// use futures::{executor::block_on, stream};
+///
+/// let counts = block_on(absorb_stream(
+///     stream::iter(["a", "b", "a"]),
+///     CountedBag::<&str>::default(),
+/// ));
+/// assert_eq!(counts.get(&"a"), Some(&2));
+/// ```
+pub fn absorb_stream<S, A>(stream: S, aggregate: A) -> AbsorbStream<S, A>
+where
+    S: Stream + Unpin,
+    A: Absorb<S::Item>,
+{
+    AbsorbStream {
+        stream,
+        aggregate: Some(aggregate),
+    }
+}
+
+/// A future which absorbs the items of a stream into an aggregate.
+///
+/// The `struct` is created by the [`absorb_stream`] function. See its documentation for more.
+#[derive(Debug)]
+#[must_use = "futures do nothing unless polled"]
+pub struct AbsorbStream<S, A> {
+    stream: S,
+    // taken when the future resolves
+    aggregate: Option<A>,
+}
+
+// the aggregate is never pinned, so the future can move whenever the stream can
+impl<S: Unpin, A> Unpin for AbsorbStream<S, A> {}
+
+impl<S, A> Future for AbsorbStream<S, A>
+where
+    S: Stream + Unpin,
+    A: Absorb<S::Item>,
+{
+    type Output = A;
+
+    /// # Panics
+    ///
+    /// Panics if the future is polled after it resolved.
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<A> {
+        let this = self.get_mut();
+        loop {
+            match Pin::new(&mut this.stream).poll_next(cx) {
+                Poll::Ready(Some(item)) => this
+                    .aggregate
+                    .as_mut()
+                    .expect("the future is polled after it resolved")
+                    .absorb(item),
+                Poll::Ready(None) => {
+                    let aggregate = this.aggregate.take();
+                    return Poll::Ready(aggregate.expect("the future is polled after it resolved"));
+                }
+                Poll::Pending => return Poll::Pending,
+            }
+        }
+    }
+}
+
+/// Returns a stream which absorbs a copy of each item of a stream into an aggregate,
+/// and then yields the item, so an aggregate can watch an asynchronous pipeline
+/// without ending it.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::{tap_stream, CountedBag};
+/// use futures::{executor::block_on_stream, stream};
+///
+/// let mut counts = CountedBag::<u32>::default();
+/// let doubled: Vec<u32> = block_on_stream(tap_stream(stream::iter([1, 2, 1]), &mut counts))
+///     .map(|x| x * 2)
+///     .collect();
+///
+/// assert_eq!(doubled, [2, 4, 2]);
+/// assert_eq!(counts.get(&1), Some(&2));
+/// ```
+pub fn tap_stream<S, A>(stream: S, aggregate: A) -> TapStream<S, A>
+where
+    S: Stream + Unpin,
+    S::Item: Clone,
+    A: Absorb<S::Item>,
+{
+    TapStream { stream, aggregate }
+}
+
+/// A stream which absorbs its items into an aggregate as it yields them.
+///
+/// The `struct` is created by the [`tap_stream`] function. See its documentation for more.
+#[derive(Debug)]
+#[must_use = "streams do nothing unless polled"]
+pub struct TapStream<S, A> {
+    stream: S,
+    aggregate: A,
+}
+
+// the aggregate is never pinned, so the stream can move whenever the inner one can
+impl<S: Unpin, A> Unpin for TapStream<S, A> {}
+
+impl<S, A> TapStream<S, A> {
+    /// Returns the aggregate, with the items yielded so far.
+    pub fn into_inner(self) -> A {
+        self.aggregate
+    }
+}
+
+impl<S, A> Stream for TapStream<S, A>
+where
+    S: Stream + Unpin,
+    S::Item: Clone,
+    A: Absorb<S::Item>,
+{
+    type Item = S::Item;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<S::Item>> {
+        let this = self.get_mut();
+        let item = Pin::new(&mut this.stream).poll_next(cx);
+        if let Poll::Ready(Some(item)) = &item {
+            this.aggregate.absorb(item.clone());
+        }
+        item
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.stream.size_hint()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::CountedBag;
+    use crate::sketches::HyperLogLog;
+    use futures::{
+        executor::{block_on, block_on_stream},
+        stream,
+    };
+    use quickcheck_macros::quickcheck;
+    use std::task::Waker;
+
+    /// A stream which is not ready on every other poll.
+    struct Hesitant<I> {
+        items: I,
+        ready: bool,
+    }
+
+    impl<I: Iterator + Unpin> Stream for Hesitant<I> {
+        type Item = I::Item;
+
+        fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<I::Item>> {
+            self.ready = !self.ready;
+            if self.ready {
+                Poll::Ready(self.items.next())
+            } else {
+                cx.waker().wake_by_ref();
+                Poll::Pending
+            }
+        }
+    }
+
+    #[quickcheck]
+    fn absorb_stream_(items: Vec<u8>) -> bool {
+        let counts = block_on(absorb_stream(
+            stream::iter(items.clone()),
+            CountedBag::<u8>::default(),
+        ));
+
+        let count = |item: &u8| items.iter().filter(|x| *x == item).count() as u32;
+        counts.total() as usize == items.len()
+            && items
+                .iter()
+                .all(|item| counts.get(item) == Some(&count(item)))
+    }
+
+    #[test]
+    fn pending_() {
+        let hesitant = Hesitant {
+            items: 0..3,
+            ready: false,
+        };
+        let mut future = absorb_stream(hesitant, CountedBag::<u32>::default());
+        let mut cx = Context::from_waker(Waker::noop());
+
+        // each item and the end of the stream come after a pending poll
+        for _ in 0..3 {
+            assert!(Pin::new(&mut future).poll(&mut cx).is_pending());
+        }
+        match Pin::new(&mut future).poll(&mut cx) {
+            Poll::Ready(counts) => assert_eq!(counts.total(), 3),
+            Poll::Pending => panic!("the stream has ended"),
+        }
+    }
+
+    #[test]
+    fn tap_stream_() {
+        let tap = tap_stream(stream::iter(0..100), HyperLogLog::new(8));
+        let mut items = block_on_stream(tap);
+        assert_eq!(items.by_ref().take(10).count(), 10);
+        let rest: u32 = items.by_ref().sum();
+
+        assert_eq!(rest, (10..100).sum::<u32>());
+        let hll = items.into_inner().into_inner();
+        assert!((hll.estimate() - 100.).abs() < 10.);
+    }
+}
@@ -0,0 +1,209 @@
+use crate::bits::{BVec, Bit, SuccinctBVec};
+
+/// The [Elias-Fano](https://www.antoniomallia.it/sorted-integers-compression-with-elias-fano-encoding.html)
+/// encoding of a sorted sequence, with constant time access to any value.
+///
+/// Each value is split into its `l` low bits, stored verbatim, and its high bits, stored as
+/// gaps in unary in a [`SuccinctBVec`]. With `l = floor(log2(u / n))` for `n` values up to `u`,
+/// the sequence takes less than `2 + l` bits per value.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::compress::EliasFano;
+///
+/// let ef = EliasFano::new(&[3, 4, 7, 13, 14, 15, 21, 43]);
+/// assert_eq!(ef.len(), 8);
+/// assert_eq!(ef.get(3), Some(13));
+/// assert_eq!(ef.next_geq(16), Some((6, 21)));
+/// assert_eq!(ef.next_geq(44), None);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct EliasFano {
+    len: usize,
+    low_bits: usize,
+    // the low bits of the values, MSB-first, `low_bits` per value
+    lows: BVec,
+    // the value `i` with high part `h` sets the bit `h + i`
+    highs: SuccinctBVec,
+}
+
+impl EliasFano {
+    /// Encodes a sorted sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the values are not sorted in non-decreasing order.
+    pub fn new(values: &[u64]) -> Self {
+        assert!(
+            values.windows(2).all(|w| w[0] <= w[1]),
+            "the values must be sorted in non-decreasing order"
+        );
+
+        let len = values.len();
+        let universe = values.last().map_or(0, |last| *last as u128 + 1);
+        let low_bits = match len {
+            0 => 0,
+            n if universe <= n as u128 => 0,
+            // at most 63, so the values can still be shifted by `low_bits`
+            n => ((universe / n as u128).ilog2() as usize).min(63),
+        };
+
+        let mut lows = BVec::with_capacity(len * low_bits);
+        let high_len = values
+            .last()
+            .map_or(0, |last| (last >> low_bits) as usize + len);
+        let mut highs = BVec::with_length(high_len);
+
+        for (i, value) in values.iter().enumerate() {
+            for b in (0..low_bits).rev() {
+                lows.push(Bit::from((value >> b) & 1 == 1));
+            }
+            highs.set_bit((value >> low_bits) as usize + i);
+        }
+
+        Self {
+            len,
+            low_bits,
+            lows,
+            highs: SuccinctBVec::from(highs),
+        }
+    }
+
+    /// Returns the number of values of the sequence.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the sequence has no values.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the approximate number of heap bytes used by the sequence.
+    pub fn size_in_bytes(&self) -> usize {
+        self.lows.as_bytes().len() + self.highs.size_in_bytes()
+    }
+
+    /// Returns the value at a given index, or `None` if the index is out of bounds.
+    pub fn get(&self, i: usize) -> Option<u64> {
+        let high = self.highs.select1(i)? - i;
+        Some(((high as u64) << self.low_bits) | self.low(i))
+    }
+
+    /// Returns the first value which is greater than or equal to `x`, with its index,
+    /// or `None` if all the values are smaller.
+    ///
+    /// The values with the same high bits as `x` are found with a select on the high bits,
+    /// and only these are scanned.
+    pub fn next_geq(&self, x: u64) -> Option<(usize, u64)> {
+        let high = (x >> self.low_bits) as usize;
+
+        // the number of values with smaller high bits
+        let mut i = match high {
+            0 => 0,
+            h => self.highs.select0(h - 1)? + 1 - h,
+        };
+
+        while i < self.len {
+            let value = self.get(i)?;
+            if value >= x {
+                return Some((i, value));
+            }
+            i += 1;
+        }
+
+        None
+    }
+
+    /// Returns an iterator over the values of the sequence.
+    pub fn iter(&self) -> impl Iterator<Item = u64> + '_ {
+        (0..self.len).filter_map(|i| self.get(i))
+    }
+
+    fn low(&self, i: usize) -> u64 {
+        (i * self.low_bits..(i + 1) * self.low_bits).fold(0, |value, bit| {
+            (value << 1) | (self.lows.get_bit(bit) == Bit::One) as u64
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    #[test]
+    fn empty_() {
+        let ef = EliasFano::new(&[]);
+        assert!(ef.is_empty());
+        assert_eq!(ef.get(0), None);
+        assert_eq!(ef.next_geq(0), None);
+    }
+
+    #[test]
+    fn duplicates_and_extremes_() {
+        let values = [0, 0, 5, 5, 5, u64::MAX - 1, u64::MAX];
+        let ef = EliasFano::new(&values);
+        assert!(ef.iter().eq(values));
+        assert_eq!(ef.next_geq(1), Some((2, 5)));
+        assert_eq!(ef.next_geq(6), Some((5, u64::MAX - 1)));
+        assert_eq!(ef.next_geq(u64::MAX), Some((6, u64::MAX)));
+
+        let ef = EliasFano::new(&[u64::MAX]);
+        assert_eq!(ef.get(0), Some(u64::MAX));
+        assert_eq!(ef.next_geq(0), Some((0, u64::MAX)));
+    }
+
+    #[test]
+    fn compression_() {
+        let mut rng = StdRng::seed_from_u64(23);
+        let mut values: Vec<u64> = (0..10_000).map(|_| rng.gen_range(0..1_000_000)).collect();
+        values.sort_unstable();
+
+        let ef = EliasFano::new(&values);
+        assert!(ef.iter().eq(values.iter().copied()));
+        // l = 6 bits, so less than 8 bits per value plus the directories
+        assert!(
+            ef.size_in_bytes() < 10_000 * 9 / 8,
+            "{}",
+            ef.size_in_bytes()
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn unsorted_() {
+        EliasFano::new(&[2, 1]);
+    }
+
+    #[quickcheck]
+    fn next_geq_(mut values: Vec<u64>, queries: Vec<u64>) -> bool {
+        values.sort_unstable();
+        let ef = EliasFano::new(&values);
+
+        let accessed = values
+            .iter()
+            .enumerate()
+            .all(|(i, v)| ef.get(i) == Some(*v));
+        accessed
+            && queries.iter().all(|q| {
+                let expected = values.iter().position(|v| v >= q).map(|i| (i, values[i]));
+                ef.next_geq(*q) == expected
+            })
+    }
+
+    #[quickcheck]
+    fn next_geq_small_(values: Vec<u8>, q: u8) -> bool {
+        let mut values: Vec<u64> = values.into_iter().map(u64::from).collect();
+        values.sort_unstable();
+        let ef = EliasFano::new(&values);
+
+        let expected = values
+            .iter()
+            .position(|v| *v >= q as u64)
+            .map(|i| (i, values[i]));
+        ef.next_geq(q as u64) == expected
+    }
+}
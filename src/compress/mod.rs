@@ -0,0 +1,22 @@
+//! Compact encodings of integer sequences.
+//!
+//! Sorted lists of identifiers, such as the posting lists of an inverted index or
+//! the candidate sets of a similarity join, are stored as the varint encoded
+//! differences between consecutive values:
+//!
+//! ```
+//! use aabel_rs::compress::{compress_sorted_u64, decompress_sorted_u64};
+//!
+//! let ids = [3, 7, 1_000, 1_001];
+//! let bytes = compress_sorted_u64(&ids);
+//! assert_eq!(bytes.len(), 5);
+//! assert_eq!(decompress_sorted_u64(&bytes).unwrap(), ids);
+//! ```
+
+mod elias_fano;
+mod sorted;
+pub(crate) mod varint;
+
+pub use elias_fano::*;
+pub use sorted::*;
+pub use varint::VarintError;
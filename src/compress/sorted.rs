@@ -0,0 +1,233 @@
+use super::varint::{decode_u64, encode_u64, VarintError};
+use crate::collections::Absorb;
+
+/// Returns the compressed encoding of a sorted sequence: the first value followed by
+/// the differences between consecutive values, each encoded as a varint.
+///
+/// # Panics
+///
+/// Panics if the values are not sorted in non-decreasing order.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::compress::compress_sorted_u64;
+///
+/// assert_eq!(compress_sorted_u64(&[1, 2, 130]), [1, 1, 0x80, 0x01]);
+/// ```
+pub fn compress_sorted_u64(values: &[u64]) -> Vec<u8> {
+    let mut encoder = SortedU64Encoder::new();
+    for value in values {
+        encoder.push(*value);
+    }
+    encoder.into_bytes()
+}
+
+/// Decodes all the values of a sequence encoded by [`compress_sorted_u64`].
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::compress::{decompress_sorted_u64, VarintError};
+///
+/// assert_eq!(decompress_sorted_u64(&[1, 1, 0x80, 0x01]), Ok(vec![1, 2, 130]));
+/// assert_eq!(decompress_sorted_u64(&[1, 0x80]), Err(VarintError::Truncated));
+/// ```
+pub fn decompress_sorted_u64(bytes: &[u8]) -> Result<Vec<u64>, VarintError> {
+    SortedU64Decoder::new(bytes).collect()
+}
+
+/// An incremental encoder of a sorted sequence, which appends the values one at a time.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::compress::{SortedU64Decoder, SortedU64Encoder};
+///
+/// let mut encoder = SortedU64Encoder::new();
+/// encoder.push(10);
+/// encoder.push(12);
+/// assert_eq!(encoder.len(), 2);
+///
+/// let values: Result<Vec<u64>, _> = SortedU64Decoder::new(encoder.as_bytes()).collect();
+/// assert_eq!(values, Ok(vec![10, 12]));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SortedU64Encoder {
+    bytes: Vec<u8>,
+    last: u64,
+    len: usize,
+}
+
+impl SortedU64Encoder {
+    /// Creates an encoder for an empty sequence.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of encoded values.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if no value was encoded.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the last encoded value, or `None` if no value was encoded.
+    pub fn last(&self) -> Option<u64> {
+        if self.len == 0 {
+            None
+        } else {
+            Some(self.last)
+        }
+    }
+
+    /// Appends a value to the sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is smaller than the last encoded one.
+    pub fn push(&mut self, value: u64) {
+        assert!(
+            value >= self.last,
+            "the values are not sorted: {} follows {}",
+            value,
+            self.last
+        );
+
+        encode_u64(value - self.last, &mut self.bytes);
+        self.last = value;
+        self.len += 1;
+    }
+
+    /// Returns the encoded bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+
+    /// Consumes the encoder and returns the encoded bytes.
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+impl Absorb<u64> for SortedU64Encoder {
+    /// Appends a value to the sequence.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the value is smaller than the last encoded one.
+    fn absorb(&mut self, value: u64) {
+        self.push(value);
+    }
+}
+
+/// A streaming decoder of a sorted sequence, which yields the values one at a time.
+///
+/// A corrupt input yields an error, after which the iterator ends.
+pub struct SortedU64Decoder<'a> {
+    bytes: &'a [u8],
+    last: u64,
+    failed: bool,
+}
+
+impl<'a> SortedU64Decoder<'a> {
+    /// Creates a decoder over the bytes of an encoded sequence.
+    pub fn new(bytes: &'a [u8]) -> Self {
+        Self {
+            bytes,
+            last: 0,
+            failed: false,
+        }
+    }
+}
+
+impl Iterator for SortedU64Decoder<'_> {
+    type Item = Result<u64, VarintError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.failed || self.bytes.is_empty() {
+            return None;
+        }
+
+        let res = decode_u64(self.bytes).and_then(|(delta, n)| {
+            self.bytes = &self.bytes[n..];
+            self.last.checked_add(delta).ok_or(VarintError::Overflow)
+        });
+
+        match res {
+            Ok(value) => {
+                self.last = value;
+                Some(Ok(value))
+            }
+            Err(e) => {
+                self.failed = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn compress_() {
+        assert!(compress_sorted_u64(&[]).is_empty());
+        assert_eq!(decompress_sorted_u64(&[]), Ok(vec![]));
+
+        let values = [0, 0, 5, u64::MAX];
+        let bytes = compress_sorted_u64(&values);
+        assert_eq!(decompress_sorted_u64(&bytes), Ok(values.to_vec()));
+    }
+
+    #[test]
+    #[should_panic]
+    fn compress_unsorted_() {
+        let _ = compress_sorted_u64(&[3, 2]);
+    }
+
+    #[test]
+    fn decoder_errors_() {
+        let mut decoder = SortedU64Decoder::new(&[1, 0xFF]);
+        assert_eq!(decoder.next(), Some(Ok(1)));
+        assert_eq!(decoder.next(), Some(Err(VarintError::Truncated)));
+        assert_eq!(decoder.next(), None);
+
+        // the sum of the deltas overflows
+        let mut bytes = compress_sorted_u64(&[u64::MAX]);
+        bytes.push(1);
+        assert_eq!(decompress_sorted_u64(&bytes), Err(VarintError::Overflow));
+    }
+
+    #[test]
+    fn encoder_() {
+        let mut encoder = SortedU64Encoder::new();
+        assert!(encoder.is_empty());
+        assert_eq!(encoder.last(), None);
+
+        encoder.push(0);
+        encoder.push(1_000);
+        assert_eq!(encoder.last(), Some(1_000));
+        assert_eq!(encoder.as_bytes(), [0, 0xE8, 0x07]);
+    }
+
+    #[quickcheck]
+    fn roundtrip_(mut values: Vec<u64>) -> bool {
+        values.sort_unstable();
+        decompress_sorted_u64(&compress_sorted_u64(&values)) == Ok(values)
+    }
+
+    #[quickcheck]
+    fn garbage_(bytes: Vec<u8>) -> bool {
+        // arbitrary input is rejected, or decodes to a sorted sequence
+        match decompress_sorted_u64(&bytes) {
+            Ok(values) => values.windows(2).all(|w| w[0] <= w[1]),
+            Err(_) => true,
+        }
+    }
+}
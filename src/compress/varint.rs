@@ -0,0 +1,98 @@
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+};
+
+/// The error returned when a varint cannot be decoded.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VarintError {
+    /// The input ends in the middle of a value.
+    Truncated,
+    /// The value does not fit in 64 bits.
+    Overflow,
+}
+
+impl Display for VarintError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "the varint is truncated"),
+            Self::Overflow => write!(f, "the varint does not fit in 64 bits"),
+        }
+    }
+}
+
+impl Error for VarintError {}
+
+/// Appends the [LEB128](https://en.wikipedia.org/wiki/LEB128) encoding of a value:
+/// seven bits per byte, least significant group first, with the high bit set on all but the last byte.
+pub(crate) fn encode_u64(mut value: u64, out: &mut Vec<u8>) {
+    while value >= 0x80 {
+        out.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    out.push(value as u8);
+}
+
+/// Decodes a LEB128 value from the start of a slice and returns it with the number of bytes read.
+pub(crate) fn decode_u64(bytes: &[u8]) -> Result<(u64, usize), VarintError> {
+    let mut value = 0u64;
+
+    for (i, byte) in bytes.iter().enumerate() {
+        let group = (byte & 0x7F) as u64;
+        let shift = 7 * i as u32;
+
+        if shift >= 64 || (shift == 63 && group > 1) {
+            return Err(VarintError::Overflow);
+        }
+
+        value |= group << shift;
+        if byte & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+
+    Err(VarintError::Truncated)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn encode_() {
+        let mut out = Vec::new();
+        encode_u64(0, &mut out);
+        encode_u64(127, &mut out);
+        encode_u64(300, &mut out);
+        assert_eq!(out, [0x00, 0x7F, 0xAC, 0x02]);
+
+        let mut out = Vec::new();
+        encode_u64(u64::MAX, &mut out);
+        assert_eq!(out.len(), 10);
+        assert_eq!(decode_u64(&out), Ok((u64::MAX, 10)));
+    }
+
+    #[test]
+    fn decode_errors_() {
+        assert_eq!(decode_u64(&[]), Err(VarintError::Truncated));
+        assert_eq!(decode_u64(&[0x80, 0x80]), Err(VarintError::Truncated));
+        assert_eq!(decode_u64(&[0xFF; 10]), Err(VarintError::Overflow));
+        assert_eq!(decode_u64(&[0x80; 11]), Err(VarintError::Overflow));
+    }
+
+    #[quickcheck]
+    fn roundtrip_(value: u64) -> bool {
+        let mut out = Vec::new();
+        encode_u64(value, &mut out);
+        decode_u64(&out) == Ok((value, out.len()))
+    }
+
+    #[quickcheck]
+    fn garbage_(bytes: Vec<u8>) -> bool {
+        match decode_u64(&bytes) {
+            Ok((_, read)) => 0 < read && read <= bytes.len().min(10),
+            Err(_) => true,
+        }
+    }
+}
@@ -0,0 +1,136 @@
+use super::{Corpus, DocId};
+use crate::{
+    approx::{close_to, DEFAULT_EPSILON},
+    lsh::{candidate_pairs, choose_bands, MinHasher},
+};
+use std::hash::Hash;
+
+/// The number of MinHash values of the signatures used for clustering.
+const NUM_HASHES: usize = 128;
+
+/// The seed of the MinHash functions used for clustering.
+const SEED: u64 = 0x5eed;
+
+impl<T> Corpus<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Groups the documents whose Jaccard similarity is at least `threshold`,
+    /// up to the [`DEFAULT_EPSILON`] tolerance.
+    ///
+    /// The documents are summarized by MinHash signatures, the candidate pairs are found by
+    /// banding the signatures, and each candidate pair is verified with the exact similarity.
+    /// The verified pairs are joined transitively, so two documents of the same cluster can
+    /// be less similar than the threshold. Each document, including the isolated ones,
+    /// belongs to exactly one cluster. The documents of a cluster are sorted and the
+    /// clusters are sorted by their first document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::corpus::Corpus;
+    ///
+    /// let mut corpus = Corpus::new();
+    /// corpus.add(0..100);
+    /// corpus.add(500..600);
+    /// corpus.add(5..100);
+    ///
+    /// let clusters = corpus.cluster(0.8);
+    /// assert_eq!(clusters, [vec![0, 2], vec![1]]);
+    /// ```
+    pub fn cluster(&self, threshold: f32) -> Vec<Vec<DocId>> {
+        let hasher = MinHasher::new(NUM_HASHES, SEED);
+        let signatures: Vec<Vec<u64>> = (0..self.len())
+            .map(|doc| hasher.signature(self.tokens(doc)))
+            .collect();
+
+        let (bands, rows) = choose_bands(NUM_HASHES, threshold);
+        let mut sets = DisjointSets::new(self.len());
+
+        for (i, j) in candidate_pairs(&signatures, bands, rows) {
+            let similarity = self.jaccard(i, j);
+            if similarity >= threshold || close_to(similarity, threshold, DEFAULT_EPSILON) {
+                sets.union(i, j);
+            }
+        }
+
+        let mut clusters: Vec<Vec<DocId>> = vec![Vec::new(); self.len()];
+        for doc in 0..self.len() {
+            let root = sets.find(doc);
+            clusters[root].push(doc);
+        }
+
+        clusters.retain(|c| !c.is_empty());
+        clusters.sort_by_key(|c| c[0]);
+        clusters
+    }
+}
+
+/// A union-find structure over the indices `0..n`.
+struct DisjointSets {
+    parents: Vec<usize>,
+}
+
+impl DisjointSets {
+    fn new(n: usize) -> Self {
+        Self {
+            parents: (0..n).collect(),
+        }
+    }
+
+    fn find(&mut self, mut x: usize) -> usize {
+        while self.parents[x] != x {
+            // path halving
+            self.parents[x] = self.parents[self.parents[x]];
+            x = self.parents[x];
+        }
+        x
+    }
+
+    fn union(&mut self, x: usize, y: usize) {
+        let (x, y) = (self.find(x), self.find(y));
+        if x != y {
+            self.parents[x.max(y)] = x.min(y);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn cluster_() {
+        let mut corpus = Corpus::new();
+        corpus.add(0..100);
+        corpus.add(1000..1100);
+        corpus.add(2..100);
+        corpus.add(1000..1095);
+        corpus.add(5000..5010);
+        corpus.add(4..102);
+
+        let clusters = corpus.cluster(0.8);
+        assert_eq!(clusters, [vec![0, 2, 5], vec![1, 3], vec![4]]);
+    }
+
+    #[test]
+    fn cluster_empty_() {
+        let corpus = Corpus::<u32>::new();
+        assert!(corpus.cluster(0.5).is_empty());
+
+        let mut corpus = Corpus::<u32>::new();
+        corpus.add([]);
+        corpus.add([]);
+        assert_eq!(corpus.cluster(0.5), [vec![0], vec![1]]);
+    }
+
+    #[test]
+    fn disjoint_sets_() {
+        let mut sets = DisjointSets::new(5);
+        sets.union(3, 4);
+        sets.union(1, 4);
+        assert_eq!(sets.find(3), 1);
+        assert_eq!(sets.find(4), 1);
+        assert_eq!(sets.find(0), 0);
+    }
+}
@@ -0,0 +1,21 @@
+//! Similarity search over a collection of documents.
+//!
+//! A document is a set of tokens (words, shingles, hashes of shingles, ...).
+//! The [`Corpus`] keeps an inverted index from tokens to documents,
+//! so only the documents sharing at least one token with a query are scored.
+//!
+//! ```
+//! use aabel_rs::corpus::Corpus;
+//!
+//! let mut corpus = Corpus::new();
+//! corpus.add("the quick brown fox".split_whitespace());
+//! corpus.add("the lazy dog".split_whitespace());
+//!
+//! let similar = corpus.most_similar("a quick fox".split_whitespace(), 1);
+//! assert_eq!(similar[0].0, 0);
+//! ```
+
+mod cluster;
+mod store;
+
+pub use store::*;
@@ -0,0 +1,418 @@
+use crate::compress::{EliasFano, SortedU64Decoder, SortedU64Encoder};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+    sync::OnceLock,
+};
+
+/// The identifier of a document in a [`Corpus`], given by its insertion order.
+pub type DocId = usize;
+
+/// The encoding of the posting lists of a [`Corpus`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub enum PostingsBackend {
+    /// Delta and varint encoded lists, which are compact and cheap to extend,
+    /// but are decoded by every query.
+    #[default]
+    Varint,
+    /// [`EliasFano`] lists, which queries skip through without decoding them.
+    /// The documents are appended to varint lists, and a list is encoded again
+    /// at the first query after documents were added to it.
+    EliasFano,
+}
+
+/// The documents which contain a token, in increasing order.
+#[derive(Clone, Debug)]
+enum PostingList {
+    Varint(SortedU64Encoder),
+    // the sequence is built from the varint list when it is first queried,
+    // so adding documents one at a time does not encode it again each time
+    EliasFano(SortedU64Encoder, OnceLock<EliasFano>),
+}
+
+impl PostingList {
+    fn new(backend: PostingsBackend, docs: &[u64]) -> Self {
+        let mut postings = SortedU64Encoder::default();
+        docs.iter().for_each(|doc| postings.push(*doc));
+
+        match backend {
+            PostingsBackend::Varint => Self::Varint(postings),
+            PostingsBackend::EliasFano => {
+                Self::EliasFano(postings, OnceLock::from(EliasFano::new(docs)))
+            }
+        }
+    }
+
+    fn push(&mut self, doc: u64) {
+        match self {
+            Self::Varint(postings) => postings.push(doc),
+            Self::EliasFano(postings, encoded) => {
+                postings.push(doc);
+                encoded.take();
+            }
+        }
+    }
+
+    fn docs(&self) -> impl Iterator<Item = u64> + '_ {
+        let postings = match self {
+            Self::Varint(postings) | Self::EliasFano(postings, _) => postings,
+        };
+        SortedU64Decoder::new(postings.as_bytes()).filter_map(Result::ok)
+    }
+}
+
+/// A collection of documents indexed by their tokens.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::corpus::Corpus;
+///
+/// let mut corpus = Corpus::new();
+/// let a = corpus.add(['a', 'b', 'c']);
+/// let b = corpus.add(['b', 'c', 'd']);
+///
+/// assert_eq!(corpus.len(), 2);
+/// assert_eq!(corpus.jaccard(a, b), 0.5);
+/// ```
+pub struct Corpus<T> {
+    docs: Vec<HashSet<T>>,
+    backend: PostingsBackend,
+    // the posting list of each token
+    index: HashMap<T, PostingList>,
+}
+
+impl<T> Default for Corpus<T> {
+    fn default() -> Self {
+        Self::with_backend(PostingsBackend::default())
+    }
+}
+
+impl<T> Corpus<T> {
+    /// Creates an empty corpus, with varint encoded posting lists.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an empty corpus whose posting lists use the given encoding.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::corpus::{Corpus, PostingsBackend};
+    ///
+    /// let mut corpus = Corpus::with_backend(PostingsBackend::EliasFano);
+    /// corpus.add(["a", "b"]);
+    /// corpus.add(["b", "c"]);
+    ///
+    /// assert_eq!(corpus.backend(), PostingsBackend::EliasFano);
+    /// assert_eq!(corpus.documents_with_all(["b"]), [0, 1]);
+    /// ```
+    pub fn with_backend(backend: PostingsBackend) -> Self {
+        Self {
+            docs: Vec::new(),
+            backend,
+            index: HashMap::new(),
+        }
+    }
+
+    /// Returns the encoding of the posting lists.
+    pub fn backend(&self) -> PostingsBackend {
+        self.backend
+    }
+
+    /// Encodes all the posting lists with another backend, for example to query
+    /// with Elias-Fano lists a corpus which was built with the cheaper varint lists.
+    pub fn set_backend(&mut self, backend: PostingsBackend) {
+        if backend == self.backend {
+            return;
+        }
+
+        for postings in self.index.values_mut() {
+            let docs: Vec<u64> = postings.docs().collect();
+            *postings = PostingList::new(backend, &docs);
+        }
+        self.backend = backend;
+    }
+
+    /// Returns the number of documents.
+    pub fn len(&self) -> usize {
+        self.docs.len()
+    }
+
+    /// Returns true if the corpus has no documents.
+    pub fn is_empty(&self) -> bool {
+        self.docs.is_empty()
+    }
+
+    /// Returns the distinct tokens of a document.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the document does not exist.
+    pub fn tokens(&self, doc: DocId) -> &HashSet<T> {
+        &self.docs[doc]
+    }
+}
+
+impl<T> Corpus<T>
+where
+    T: Eq + Hash + Clone,
+{
+    /// Adds a document given by its tokens and returns its identifier.
+    /// Repeated tokens are counted once.
+    pub fn add<I>(&mut self, tokens: I) -> DocId
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let id = self.docs.len();
+        let tokens: HashSet<T> = tokens.into_iter().collect();
+
+        for token in tokens.iter() {
+            let backend = self.backend;
+            self.index
+                .entry(token.clone())
+                .or_insert_with(|| PostingList::new(backend, &[]))
+                .push(id as u64);
+        }
+
+        self.docs.push(tokens);
+        id
+    }
+
+    /// Returns the documents which contain a token as an [`EliasFano`] sequence,
+    /// or `None` if no document contains it.
+    ///
+    /// With the [`PostingsBackend::EliasFano`] backend the stored list is borrowed, and is
+    /// only encoded again after documents were added to it; with the varint backend the list
+    /// is decoded and encoded on every call.
+    pub fn posting_list(&self, token: &T) -> Option<Cow<'_, EliasFano>> {
+        let postings = self.index.get(token)?;
+        let docs = || postings.docs().collect::<Vec<_>>();
+        match postings {
+            PostingList::EliasFano(_, encoded) => Some(Cow::Borrowed(
+                encoded.get_or_init(|| EliasFano::new(&docs())),
+            )),
+            PostingList::Varint(_) => Some(Cow::Owned(EliasFano::new(&docs()))),
+        }
+    }
+
+    /// Returns the documents which contain all the given tokens, in increasing order.
+    /// The posting lists are intersected by skipping through them with `next_geq`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::corpus::Corpus;
+    ///
+    /// let mut corpus = Corpus::new();
+    /// corpus.add(["a", "b", "c"]);
+    /// corpus.add(["a", "c"]);
+    /// corpus.add(["b", "c"]);
+    ///
+    /// assert_eq!(corpus.documents_with_all(["a", "c"]), [0, 1]);
+    /// assert_eq!(corpus.documents_with_all(["a", "d"]), []);
+    /// ```
+    pub fn documents_with_all<I>(&self, tokens: I) -> Vec<DocId>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let lists: Option<Vec<Cow<'_, EliasFano>>> = tokens
+            .into_iter()
+            .map(|token| self.posting_list(&token))
+            .collect();
+        let mut lists = match lists {
+            Some(lists) if !lists.is_empty() => lists,
+            _ => return Vec::new(),
+        };
+        // drive the intersection with the shortest list
+        lists.sort_by_key(|list| list.len());
+
+        let mut docs = Vec::new();
+        let mut candidate = 0;
+        'outer: while let Some((_, doc)) = lists[0].next_geq(candidate) {
+            candidate = doc;
+            for list in &lists[1..] {
+                match list.next_geq(candidate) {
+                    Some((_, next)) if next == candidate => {}
+                    Some((_, next)) => {
+                        candidate = next;
+                        continue 'outer;
+                    }
+                    None => break 'outer,
+                }
+            }
+
+            docs.push(candidate as DocId);
+            candidate += 1;
+        }
+
+        docs
+    }
+
+    /// Returns the [Jaccard](https://en.wikipedia.org/wiki/Jaccard_index) similarity
+    /// between the token sets of two documents.
+    ///
+    /// # Panics
+    ///
+    /// Panics if one of the documents does not exist.
+    pub fn jaccard(&self, first: DocId, second: DocId) -> f32 {
+        let (xs, ys) = (&self.docs[first], &self.docs[second]);
+        let intersection = xs.intersection(ys).count();
+        set_jaccard(intersection, xs.len(), ys.len())
+    }
+
+    /// Returns the `k` documents most similar to the query, as `(doc_id, score)` pairs
+    /// sorted by descending Jaccard similarity and then by identifier.
+    ///
+    /// The candidates are the documents which share at least one token with the query,
+    /// found through the inverted index, and their scores are exact.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::corpus::Corpus;
+    ///
+    /// let mut corpus = Corpus::new();
+    /// corpus.add([1, 2, 3, 4]);
+    /// corpus.add([3, 4, 5]);
+    /// corpus.add([7, 8]);
+    ///
+    /// let similar = corpus.most_similar([3, 4, 5, 6], 5);
+    /// assert_eq!(similar, [(1, 0.75), (0, 1. / 3.)]);
+    /// ```
+    pub fn most_similar<I>(&self, query: I, k: usize) -> Vec<(DocId, f32)>
+    where
+        I: IntoIterator<Item = T>,
+    {
+        let query: HashSet<T> = query.into_iter().collect();
+
+        // the number of tokens each candidate shares with the query
+        let mut shared: HashMap<DocId, usize> = HashMap::new();
+        for token in query.iter() {
+            if let Some(postings) = self.index.get(token) {
+                for doc in postings.docs() {
+                    *shared.entry(doc as DocId).or_insert(0) += 1;
+                }
+            }
+        }
+
+        let mut scored: Vec<(DocId, f32)> = shared
+            .into_iter()
+            .map(|(doc, n)| (doc, set_jaccard(n, query.len(), self.docs[doc].len())))
+            .collect();
+
+        scored.sort_by(|(d1, s1), (d2, s2)| s2.total_cmp(s1).then(d1.cmp(d2)));
+        scored.truncate(k);
+        scored
+    }
+}
+
+/// Returns the Jaccard similarity of two sets from the size of their intersection and their sizes.
+pub(crate) fn set_jaccard(intersection: usize, first: usize, second: usize) -> f32 {
+    let union = first + second - intersection;
+    if union == 0 {
+        0.
+    } else {
+        intersection as f32 / union as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn corpus() -> Corpus<&'static str> {
+        let mut corpus = Corpus::new();
+        corpus.add("a b c d".split_whitespace());
+        corpus.add("c d e".split_whitespace());
+        corpus.add("x y".split_whitespace());
+        corpus.add("a b c d".split_whitespace());
+        corpus
+    }
+
+    #[test]
+    fn add_() {
+        let mut corpus = Corpus::new();
+        assert!(corpus.is_empty());
+
+        assert_eq!(corpus.add(['a', 'a', 'b']), 0);
+        assert_eq!(corpus.add(['c']), 1);
+        assert_eq!(corpus.len(), 2);
+        assert_eq!(corpus.tokens(0).len(), 2);
+    }
+
+    #[test]
+    fn jaccard_() {
+        let corpus = corpus();
+        assert_eq!(corpus.jaccard(0, 3), 1.);
+        assert_eq!(corpus.jaccard(0, 1), 0.4);
+        assert_eq!(corpus.jaccard(0, 2), 0.);
+    }
+
+    #[test]
+    fn most_similar_() {
+        let corpus = corpus();
+        let similar = corpus.most_similar("a b c d".split_whitespace(), 3);
+        assert_eq!(similar, [(0, 1.), (3, 1.), (1, 0.4)]);
+
+        let similar = corpus.most_similar("y".split_whitespace(), 3);
+        assert_eq!(similar, [(2, 0.5)]);
+    }
+
+    #[test]
+    fn most_similar_none_() {
+        let corpus = corpus();
+        assert!(corpus.most_similar("q".split_whitespace(), 3).is_empty());
+        assert!(corpus.most_similar("a".split_whitespace(), 0).is_empty());
+        assert!(corpus.most_similar(std::iter::empty(), 3).is_empty());
+    }
+
+    #[test]
+    fn posting_list_() {
+        let corpus = corpus();
+        let postings = corpus.posting_list(&"c").unwrap();
+        assert!(postings.iter().eq([0, 1, 3]));
+        assert!(corpus.posting_list(&"q").is_none());
+    }
+
+    #[test]
+    fn documents_with_all_() {
+        let mut corpus = corpus();
+        for backend in [PostingsBackend::EliasFano, PostingsBackend::Varint] {
+            corpus.set_backend(backend);
+            assert_eq!(corpus.documents_with_all(["c", "d"]), [0, 1, 3]);
+            assert_eq!(corpus.documents_with_all(["a", "d", "b"]), [0, 3]);
+            assert!(corpus.documents_with_all(["e", "x"]).is_empty());
+            assert!(corpus.documents_with_all([]).is_empty());
+        }
+    }
+
+    #[test]
+    fn elias_fano_backend_() {
+        let mut corpus = Corpus::with_backend(PostingsBackend::EliasFano);
+        corpus.add("a b c d".split_whitespace());
+        corpus.add("c d e".split_whitespace());
+        corpus.add("x y".split_whitespace());
+        corpus.add("a b c d".split_whitespace());
+
+        // the stored lists are lent out, not built again
+        assert!(matches!(corpus.posting_list(&"c"), Some(Cow::Borrowed(_))));
+        assert!(matches!(
+            self::corpus().posting_list(&"c"),
+            Some(Cow::Owned(_))
+        ));
+
+        assert!(corpus.posting_list(&"c").unwrap().iter().eq([0, 1, 3]));
+        assert_eq!(
+            corpus.most_similar("a b c d".split_whitespace(), 3),
+            self::corpus().most_similar("a b c d".split_whitespace(), 3)
+        );
+
+        // a document added after a query is in the next one
+        corpus.add(["c"]);
+        assert!(corpus.posting_list(&"c").unwrap().iter().eq([0, 1, 3, 4]));
+        assert_eq!(corpus.documents_with_all(["c", "e"]), [1]);
+    }
+}
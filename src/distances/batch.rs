@@ -0,0 +1,192 @@
+/// The metrics supported by the batch distance kernels.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Metric {
+    /// The [Euclidean](https://en.wikipedia.org/wiki/Euclidean_distance) distance.
+    Euclid,
+    /// The square of the Euclidean distance, which ranks the rows the same way and avoids the square root.
+    SquaredEuclid,
+    /// The [Manhattan](https://en.wikipedia.org/wiki/Taxicab_geometry) distance.
+    Manhattan,
+    /// The cosine distance, one minus the [cosine](https://en.wikipedia.org/wiki/Cosine_similarity)
+    /// similarity. It is one when any of the two vectors is zero.
+    Cosine,
+}
+
+/// Returns the distances between a query and every row of a data set, in the order of the rows.
+///
+/// The rows are scanned in a single pass and the per-query values, such as the
+/// norm of the query for the cosine distance, are computed only once.
+///
+/// # Panics
+///
+/// Panics if a row does not have the length of the query.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{one_to_many, Metric};
+///
+/// let rows = vec![vec![0., 0.], vec![3., 4.], vec![1., 0.]];
+/// let ds = one_to_many(&[0., 0.], &rows, Metric::Euclid);
+/// assert_eq!(ds, [0., 5., 1.]);
+/// ```
+pub fn one_to_many<R>(query: &[f32], rows: &[R], metric: Metric) -> Vec<f32>
+where
+    R: AsRef<[f32]>,
+{
+    let kernel = Kernel::new(query, metric);
+    rows.iter()
+        .map(|row| kernel.distance(row.as_ref()))
+        .collect()
+}
+
+/// Returns the distances between a query and every row of a data set, scanning the rows in parallel.
+///
+/// The function is available only when the `rayon` feature is enabled.
+/// See [`one_to_many`] for more.
+///
+/// # Panics
+///
+/// Panics if a row does not have the length of the query.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{par_one_to_many, Metric};
+///
+/// let rows = vec![vec![0., 0.], vec![3., 4.], vec![1., 0.]];
+/// let ds = par_one_to_many(&[0., 0.], &rows, Metric::Manhattan);
+/// assert_eq!(ds, [0., 7., 1.]);
+/// ```
+#[cfg(feature = "rayon")]
+pub fn par_one_to_many<R>(query: &[f32], rows: &[R], metric: Metric) -> Vec<f32>
+where
+    R: AsRef<[f32]> + Sync,
+{
+    use rayon::prelude::*;
+
+    let kernel = Kernel::new(query, metric);
+    rows.par_iter()
+        .map(|row| kernel.distance(row.as_ref()))
+        .collect()
+}
+
+/// The distance to a fixed query, with the values depending only on the query precomputed.
+struct Kernel<'a> {
+    query: &'a [f32],
+    metric: Metric,
+    query_norm: f32,
+}
+
+impl<'a> Kernel<'a> {
+    fn new(query: &'a [f32], metric: Metric) -> Self {
+        let query_norm = match metric {
+            Metric::Cosine => query.iter().map(|x| x * x).sum::<f32>().sqrt(),
+            _ => 0.,
+        };
+
+        Self {
+            query,
+            metric,
+            query_norm,
+        }
+    }
+
+    fn distance(&self, row: &[f32]) -> f32 {
+        assert_eq!(
+            self.query.len(),
+            row.len(),
+            "the row does not have the length of the query"
+        );
+
+        let pairs = self.query.iter().zip(row);
+
+        match self.metric {
+            Metric::Euclid => pairs.map(|(x, y)| (x - y) * (x - y)).sum::<f32>().sqrt(),
+            Metric::SquaredEuclid => pairs.map(|(x, y)| (x - y) * (x - y)).sum(),
+            Metric::Manhattan => pairs.map(|(x, y)| (x - y).abs()).sum(),
+            Metric::Cosine => {
+                let (prod, row_square) = pairs.fold((0., 0.), |(prod, square), (x, y)| {
+                    (prod + x * y, square + y * y)
+                });
+
+                let denom = self.query_norm * f32::sqrt(row_square);
+                if denom == 0. {
+                    1.
+                } else {
+                    1. - prod / denom
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+    use crate::distances::{cosine, euclid, manhattan};
+
+    fn rows() -> Vec<Vec<f32>> {
+        vec![
+            vec![1., 2., -1.],
+            vec![0., 0., 0.],
+            vec![-3., 0.5, 2.],
+            vec![2., 1., 1.],
+        ]
+    }
+
+    #[test]
+    fn one_to_many_() {
+        let query = [2., 1., 1.];
+        let rows = rows();
+
+        let pairs = |row: &Vec<f32>| query.into_iter().zip(row.clone());
+
+        let ds = one_to_many(&query, &rows, Metric::Euclid);
+        for (d, row) in ds.iter().zip(&rows) {
+            assert_close(*d, euclid(pairs(row)), DEFAULT_EPSILON);
+        }
+
+        let ds = one_to_many(&query, &rows, Metric::SquaredEuclid);
+        for (d, row) in ds.iter().zip(&rows) {
+            assert_close(*d, euclid(pairs(row)).powi(2), DEFAULT_EPSILON);
+        }
+
+        let ds = one_to_many(&query, &rows, Metric::Manhattan);
+        for (d, row) in ds.iter().zip(&rows) {
+            assert_close(*d, manhattan(pairs(row)), DEFAULT_EPSILON);
+        }
+
+        let ds = one_to_many(&query, &rows, Metric::Cosine);
+        assert_close(ds[0], 1. - cosine(pairs(&rows[0])), DEFAULT_EPSILON);
+        assert_eq!(ds[1], 1.);
+        assert_close(ds[3], 0., DEFAULT_EPSILON);
+    }
+
+    #[test]
+    fn one_to_many_empty_() {
+        let rows: Vec<Vec<f32>> = Vec::new();
+        assert!(one_to_many(&[1., 2.], &rows, Metric::Euclid).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn one_to_many_lengths_() {
+        let _ = one_to_many(&[1., 2.], &[[1., 2., 3.]], Metric::Euclid);
+    }
+
+    #[cfg(feature = "rayon")]
+    #[test]
+    fn par_one_to_many_() {
+        let query = [2., 1., 1.];
+        let rows = rows();
+
+        for metric in [Metric::Euclid, Metric::Manhattan, Metric::Cosine] {
+            assert_eq!(
+                par_one_to_many(&query, &rows, metric),
+                one_to_many(&query, &rows, metric)
+            );
+        }
+    }
+}
@@ -42,6 +42,7 @@ where
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approx::assert_close;
 
     #[test]
     fn cosine_() {
@@ -51,6 +52,6 @@ mod tests {
 
         let xys = [(1., 2.), (2., 1.), (-1., 1.)];
         let it = cosine(xys.into_iter());
-        assert!((it - 0.5).abs() <= 0.01);
+        assert_close(it, 0.5, 0.01);
     }
 }
@@ -26,6 +26,52 @@ pub trait Distance: Iterator {
         super::euclid(xys)
     }
 
+    /// Returns the [Euclidean](https://en.wikipedia.org/wiki/Euclidean_distance) distance between two
+    /// collections of references, so slices can be compared without copying their items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::distances::Distance;
+    ///
+    /// let xs: &[f32] = &[3., 4.];
+    /// let ys: &[f32] = &[0., 0.];
+    /// let it = xs.iter().euclid_by_ref(ys);
+    /// assert_eq!(5., it)
+    /// ```
+    fn euclid_by_ref<'a, T, J>(self, ys: J) -> f32
+    where
+        J: IntoIterator<Item = &'a T>,
+        Self: Iterator<Item = &'a T> + Sized,
+        T: Into<f32> + Copy + 'a,
+    {
+        let xys = self.zip_eq(ys).map(|(x, y)| (*x, *y));
+        super::euclid(xys)
+    }
+
+    /// Returns the [cosine](https://en.wikipedia.org/wiki/Cosine_similarity) similarity between two
+    /// collections of references, so slices can be compared without copying their items.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::distances::Distance;
+    ///
+    /// let xs: &[f32] = &[1., 0.];
+    /// let ys: &[f32] = &[2., 0.];
+    /// let it = xs.iter().cosine_by_ref(ys);
+    /// assert_eq!(1., it)
+    /// ```
+    fn cosine_by_ref<'a, T, J>(self, ys: J) -> f32
+    where
+        J: IntoIterator<Item = &'a T>,
+        Self: Iterator<Item = &'a T> + Sized,
+        T: Into<f32> + Copy + 'a,
+    {
+        let xys = self.zip_eq(ys).map(|(x, y)| (*x, *y));
+        super::cosine(xys)
+    }
+
     /// Returns the [Manhattan](https://en.wikipedia.org/wiki/Taxicab_geometry) distance between two collections.
     ///
     /// # Examples
@@ -119,6 +165,7 @@ impl<T: ?Sized> Distance for T where T: Iterator {}
 #[cfg(test)]
 mod tests {
     use super::Distance;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
 
     #[test]
     fn euclid_() {
@@ -126,6 +173,34 @@ mod tests {
         assert_eq!(5., it)
     }
 
+    #[test]
+    fn euclid_by_ref_() {
+        let xs = [3_f32, 1.];
+        let ys = [0_f32, 1.];
+        assert_eq!(3., xs.iter().euclid_by_ref(&ys));
+
+        let xs: &[u8] = &[3, 4];
+        let ys: &[u8] = &[0, 0];
+        assert_eq!(5., xs.iter().euclid_by_ref(ys));
+    }
+
+    #[test]
+    fn cosine_by_ref_() {
+        let xs: &[f32] = &[1., 2., -1.];
+        let ys: &[f32] = &[2., 1., 1.];
+        assert_close(xs.iter().cosine_by_ref(ys), 0.5, DEFAULT_EPSILON);
+
+        let xs: &[f32] = &[0., 0.];
+        assert_eq!(0., xs.iter().cosine_by_ref(ys.iter().take(2)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn euclid_by_ref_lengths_() {
+        let xs: &[f32] = &[1., 2.];
+        let _ = xs.iter().euclid_by_ref(&[1_f32]);
+    }
+
     #[test]
     fn manhattan_() {
         let it = [3., 4.].into_iter().manhattan([0., 0.]);
@@ -155,10 +230,7 @@ mod tests {
             .hamming(['k', 'a', 't', 'h', 'r', 'i', 'n']);
         assert_eq!(3, it);
 
-        let it = "karolin"
-            .as_bytes()
-            .into_iter()
-            .hamming("kathrin".as_bytes());
+        let it = "karolin".as_bytes().iter().hamming("kathrin".as_bytes());
         assert_eq!(3, it);
     }
 }
@@ -0,0 +1,130 @@
+/// Returns the [Levenshtein](https://en.wikipedia.org/wiki/Levenshtein_distance) distance
+/// between two sequences: the smallest number of insertions, deletions and substitutions
+/// of single elements which turn one sequence into the other.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::levenshtein;
+///
+/// assert_eq!(levenshtein(b"kitten", b"sitting"), 3);
+/// assert_eq!(levenshtein(b"", b"abc"), 3);
+/// ```
+pub fn levenshtein<T: PartialEq>(xs: &[T], ys: &[T]) -> usize {
+    let mut previous: Vec<usize> = (0..=ys.len()).collect();
+    let mut current = vec![0; ys.len() + 1];
+
+    for (i, x) in xs.iter().enumerate() {
+        current[0] = i + 1;
+        for (j, y) in ys.iter().enumerate() {
+            let substitution = previous[j] + usize::from(x != y);
+            current[j + 1] = substitution.min(previous[j + 1] + 1).min(current[j] + 1);
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[ys.len()]
+}
+
+/// Returns the weighted edit distance between two sequences: the smallest total cost of the
+/// insertions, deletions and substitutions which turn `xs` into `ys`, with the cost of each
+/// edit given by a function of the edited elements, such as the distance between two keys on
+/// a keyboard for typos. Keeping an element is free, so `substitute` is called only for
+/// different elements.
+///
+/// The result is a distance when the costs are positive, the substitutions symmetric and
+/// cheaper than a deletion followed by an insertion; with unit costs it is the [`levenshtein`]
+/// distance.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::weighted_edit_distance;
+///
+/// // neighbour keys are likely typos
+/// let row = b"qwertyuiop";
+/// let key = |c: &u8| row.iter().position(|k| k == c);
+/// let substitute = |a: &u8, b: &u8| match (key(a), key(b)) {
+///     (Some(i), Some(j)) if i.abs_diff(j) == 1 => 0.25,
+///     _ => 1.,
+/// };
+///
+/// let typo = weighted_edit_distance(b"type", b"tyoe", |_| 1., |_| 1., substitute);
+/// let other = weighted_edit_distance(b"type", b"tyre", |_| 1., |_| 1., substitute);
+/// assert_eq!(typo, 0.25);
+/// assert_eq!(other, 1.);
+/// ```
+pub fn weighted_edit_distance<T, I, D, S>(
+    xs: &[T],
+    ys: &[T],
+    insert: I,
+    delete: D,
+    substitute: S,
+) -> f64
+where
+    T: PartialEq,
+    I: Fn(&T) -> f64,
+    D: Fn(&T) -> f64,
+    S: Fn(&T, &T) -> f64,
+{
+    // previous[j] is the distance from the current prefix of `xs` to `ys[..j]`
+    let mut previous = Vec::with_capacity(ys.len() + 1);
+    previous.push(0.);
+    for y in ys {
+        previous.push(previous[previous.len() - 1] + insert(y));
+    }
+    let mut current = vec![0.; ys.len() + 1];
+
+    for x in xs {
+        current[0] = previous[0] + delete(x);
+        for (j, y) in ys.iter().enumerate() {
+            let substitution = if x == y {
+                previous[j]
+            } else {
+                previous[j] + substitute(x, y)
+            };
+            current[j + 1] = substitution
+                .min(previous[j + 1] + delete(x))
+                .min(current[j] + insert(y));
+        }
+        std::mem::swap(&mut previous, &mut current);
+    }
+
+    previous[ys.len()]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn levenshtein_() {
+        assert_eq!(levenshtein::<u8>(&[], &[]), 0);
+        assert_eq!(levenshtein(b"flaw", b"lawn"), 2);
+        assert_eq!(levenshtein(&['a', 'b'], &['b', 'a']), 2);
+    }
+
+    #[test]
+    fn costs_() {
+        // deleting is cheap, inserting is expensive
+        let distance =
+            |xs: &[u8], ys: &[u8]| weighted_edit_distance(xs, ys, |_| 3., |_| 0.5, |_, _| 10.);
+        assert_eq!(distance(b"abc", b""), 1.5);
+        assert_eq!(distance(b"", b"abc"), 9.);
+        // a substitution costs more than a deletion and an insertion
+        assert_eq!(distance(b"a", b"b"), 3.5);
+    }
+
+    #[quickcheck]
+    fn unit_costs_(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+        let xs: Vec<u8> = xs.into_iter().map(|x| x % 4).collect();
+        let ys: Vec<u8> = ys.into_iter().map(|y| y % 4).collect();
+        let distance = levenshtein(&xs, &ys);
+
+        weighted_edit_distance(&xs, &ys, |_| 1., |_| 1., |_, _| 1.) == distance as f64
+            && levenshtein(&ys, &xs) == distance
+            && distance >= xs.len().abs_diff(ys.len())
+            && distance <= xs.len().max(ys.len())
+    }
+}
@@ -1,5 +1,10 @@
 use crate::collections::CountedBag;
-use std::hash::{BuildHasher, Hash};
+use crate::seeded::stable_hash;
+use rand::Rng;
+use std::{
+    collections::BTreeSet,
+    hash::{BuildHasher, Hash},
+};
 
 /// Represents the Jaccard similarity value.
 pub struct JaccardSim {
@@ -30,7 +35,7 @@ impl JaccardSim {
 /// let j = jaccard(&xs, &ys);
 /// assert_eq!(j.value(), 0.25);
 /// ```
-pub fn jaccard<'a, K, S>(first: &CountedBag<K, S>, second: &CountedBag<K, S>) -> JaccardSim
+pub fn jaccard<K, S>(first: &CountedBag<K, S>, second: &CountedBag<K, S>) -> JaccardSim
 where
     K: Eq + Hash,
     S: BuildHasher + Default,
@@ -43,9 +48,102 @@ where
     }
 }
 
+/// Represents an estimate of the Jaccard similarity together with its standard error.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct JaccardEstimate {
+    /// The estimated similarity.
+    pub value: f32,
+    /// The standard error of the estimate.
+    pub std_error: f32,
+}
+
+/// Returns an estimate of the [Jaccard](https://en.wikipedia.org/wiki/Jaccard_index) similarity
+/// between the sets of distinct items of two collections.
+///
+/// Each collection is summarized on the fly by a bottom-k MinHash sketch which keeps
+/// the `sample_size` smallest hashes of its items, so the memory is bounded regardless of
+/// the size of the inputs. The hash function is salted with a value drawn from `rng`.
+/// When both collections have fewer than `sample_size` distinct items the result is exact.
+///
+/// # Panics
+///
+/// Panics if `sample_size` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::jaccard_sampled;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let j = jaccard_sampled(0..10_000, 5_000..15_000, 1_024, &mut rng);
+/// assert!((j.value - 1. / 3.).abs() <= 4. * j.std_error);
+/// ```
+pub fn jaccard_sampled<I, J, T, R>(xs: I, ys: J, sample_size: usize, rng: &mut R) -> JaccardEstimate
+where
+    I: IntoIterator<Item = T>,
+    J: IntoIterator<Item = T>,
+    T: Hash,
+    R: Rng + ?Sized,
+{
+    assert!(sample_size > 0, "sample size is zero");
+
+    let salt: u64 = rng.gen();
+    let xs = bottom_k(xs, sample_size, salt);
+    let ys = bottom_k(ys, sample_size, salt);
+
+    // the smallest hashes of the union, and how many of them are in both sets
+    let union: Vec<u64> = xs.union(&ys).take(sample_size).copied().collect();
+    if union.is_empty() {
+        return JaccardEstimate {
+            value: 0.,
+            std_error: 0.,
+        };
+    }
+
+    let common = union
+        .iter()
+        .filter(|h| xs.contains(h) && ys.contains(h))
+        .count();
+
+    let k = union.len() as f32;
+    let value = common as f32 / k;
+    let exact = union.len() < sample_size;
+    let std_error = if exact {
+        0.
+    } else {
+        (value * (1. - value) / k).sqrt()
+    };
+
+    JaccardEstimate { value, std_error }
+}
+
+/// Returns the `k` smallest distinct salted hashes of the items.
+fn bottom_k<I, T>(xs: I, k: usize, salt: u64) -> BTreeSet<u64>
+where
+    I: IntoIterator<Item = T>,
+    T: Hash,
+{
+    let mut sketch = BTreeSet::new();
+
+    for x in xs {
+        let h = stable_hash(&x, salt);
+
+        if sketch.len() < k {
+            sketch.insert(h);
+        } else if sketch.last().is_some_and(|max| h < *max) && sketch.insert(h) {
+            sketch.pop_last();
+        }
+    }
+
+    sketch
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+    use rand::{rngs::StdRng, SeedableRng};
 
     #[test]
     fn jaccard_ratio_() {
@@ -61,6 +159,36 @@ mod tests {
         assert_eq!(j.value(), 0.25);
     }
 
+    #[test]
+    fn jaccard_sampled_exact_() {
+        let mut rng = StdRng::seed_from_u64(1);
+        let j = jaccard_sampled(['a', 'b', 'c', 'c'], ['b', 'c', 'd'], 16, &mut rng);
+        assert_eq!(j.value, 0.5);
+        assert_eq!(j.std_error, 0.);
+    }
+
+    #[test]
+    fn jaccard_sampled_empty_() {
+        let mut rng = StdRng::seed_from_u64(2);
+        let j = jaccard_sampled(Vec::<u32>::new(), Vec::new(), 16, &mut rng);
+        assert_eq!(j.value, 0.);
+    }
+
+    #[test]
+    fn jaccard_sampled_estimate_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let j = jaccard_sampled(0..20_000, 10_000..30_000, 2_048, &mut rng);
+        assert!(j.std_error > 0.);
+        assert!((j.value - 1. / 3.).abs() <= 5. * j.std_error);
+    }
+
+    #[test]
+    #[should_panic]
+    fn jaccard_sampled_zero_() {
+        let mut rng = StdRng::seed_from_u64(4);
+        let _ = jaccard_sampled(0..10, 0..10, 0, &mut rng);
+    }
+
     #[test]
     fn jaccard_() {
         let xs = [("a", 3), ("b", 1)];
@@ -72,6 +200,6 @@ mod tests {
         let j = jaccard(&xs, &ys);
         assert_eq!(j.numer, 3);
         assert_eq!(j.denom, 9);
-        assert_eq!(j.value(), 1. / 3.);
+        assert_close(j.value(), 1. / 3., DEFAULT_EPSILON);
     }
 }
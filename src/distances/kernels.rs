@@ -0,0 +1,282 @@
+use crate::collections::{shingles, CountedBag};
+use std::hash::Hash;
+
+/// Returns the counts of the k-mers, the windows of `k` consecutive elements, of a sequence.
+///
+/// # Panics
+///
+/// Panics if `k` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::spectrum;
+///
+/// let kmers = spectrum(b"abab", 2);
+/// assert_eq!(kmers.get(b"ab".as_slice()), Some(&2));
+/// assert_eq!(kmers.get(b"ba".as_slice()), Some(&1));
+/// ```
+pub fn spectrum<T: Eq + Hash>(xs: &[T], k: usize) -> CountedBag<&[T]> {
+    CountedBag::from_keys(shingles(xs, k, |_: &T| true))
+}
+
+/// Returns the [spectrum kernel](https://doi.org/10.1142/9789812799623_0053) of two sequences:
+/// the dot product of their k-mer counts, as given by [`spectrum`].
+///
+/// # Panics
+///
+/// Panics if `k` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::spectrum_kernel;
+///
+/// // "ab" twice by once, "ba" once by once
+/// assert_eq!(spectrum_kernel(b"abab", b"aba", 2), 3.);
+/// assert_eq!(spectrum_kernel(b"abab", b"xyz", 2), 0.);
+/// ```
+pub fn spectrum_kernel<T: Eq + Hash>(xs: &[T], ys: &[T], k: usize) -> f64 {
+    dot(&spectrum(xs, k), &spectrum(ys, k))
+}
+
+/// Returns the spectrum kernel of two sequences normalized by their own kernels,
+/// `K(x, y) / sqrt(K(x, x) K(y, y))`, the cosine of their k-mer counts, in `[0, 1]`.
+/// Returns zero when a sequence is shorter than `k`.
+///
+/// # Panics
+///
+/// Panics if `k` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::normalized_spectrum_kernel;
+///
+/// assert_eq!(normalized_spectrum_kernel(b"abab", b"abab", 2), 1.);
+/// assert!(normalized_spectrum_kernel(b"abab", b"aba", 2) < 1.);
+/// ```
+pub fn normalized_spectrum_kernel<T: Eq + Hash>(xs: &[T], ys: &[T], k: usize) -> f64 {
+    let (xs, ys) = (spectrum(xs, k), spectrum(ys, k));
+    normalize(dot(&xs, &ys), dot(&xs, &xs), dot(&ys, &ys))
+}
+
+/// Returns the gap-weighted [subsequence kernel](https://www.jmlr.org/papers/v2/lodhi02a.html)
+/// of two sequences: the common subsequences of length `k`, contiguous or not, weighted by
+/// `lambda` to the power of the lengths they span in both sequences, so gaps are penalized.
+///
+/// The kernel is computed by dynamic programming in `O(k |xs| |ys|)` time.
+///
+/// # Panics
+///
+/// Panics if `k` is zero or `lambda` is not in the `(0, 1]` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::subsequence_kernel;
+///
+/// // "ca" spans two characters in both words
+/// let l: f64 = 0.5;
+/// let kernel = subsequence_kernel(b"cat", b"car", 2, l);
+/// assert!((kernel - l.powi(4)).abs() < 1e-12);
+/// ```
+pub fn subsequence_kernel<T: Eq>(xs: &[T], ys: &[T], k: usize, lambda: f64) -> f64 {
+    assert!(k > 0, "the subsequence length is zero");
+    assert!(
+        lambda > 0. && lambda <= 1.,
+        "the decay is not in the (0, 1] range"
+    );
+
+    let (m, n) = (xs.len(), ys.len());
+    if m < k || n < k {
+        return 0.;
+    }
+
+    // partial[p][q] is K'_i of the prefixes of lengths p and q, the weight of the common
+    // subsequences of length i, measured up to the ends of the prefixes
+    let mut partial = vec![vec![1.; n + 1]; m + 1];
+    for i in 1..k {
+        let mut next = vec![vec![0.; n + 1]; m + 1];
+        for p in i..=m {
+            // K''_i, for the subsequences which end exactly at the last element of `ys[..q]`
+            let mut ending = 0.;
+            for q in i..=n {
+                ending *= lambda;
+                if xs[p - 1] == ys[q - 1] {
+                    ending += lambda * lambda * partial[p - 1][q - 1];
+                }
+                next[p][q] = lambda * next[p - 1][q] + ending;
+            }
+        }
+        partial = next;
+    }
+
+    let mut kernel = 0.;
+    for p in k..=m {
+        for q in k..=n {
+            if xs[p - 1] == ys[q - 1] {
+                kernel += lambda * lambda * partial[p - 1][q - 1];
+            }
+        }
+    }
+    kernel
+}
+
+/// Returns the subsequence kernel of two sequences normalized by their own kernels,
+/// `K(x, y) / sqrt(K(x, x) K(y, y))`, in `[0, 1]`. Returns zero when a sequence is shorter than `k`.
+///
+/// # Panics
+///
+/// Panics if `k` is zero or `lambda` is not in the `(0, 1]` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::normalized_subsequence_kernel;
+///
+/// let same = normalized_subsequence_kernel(b"kernel", b"kernel", 3, 0.5);
+/// assert!((same - 1.).abs() < 1e-12);
+///
+/// let close = normalized_subsequence_kernel(b"kernel", b"kennel", 3, 0.5);
+/// let far = normalized_subsequence_kernel(b"kernel", b"pasta", 3, 0.5);
+/// assert!(close > far);
+/// ```
+pub fn normalized_subsequence_kernel<T: Eq>(xs: &[T], ys: &[T], k: usize, lambda: f64) -> f64 {
+    normalize(
+        subsequence_kernel(xs, ys, k, lambda),
+        subsequence_kernel(xs, xs, k, lambda),
+        subsequence_kernel(ys, ys, k, lambda),
+    )
+}
+
+/// Returns the kernel (Gram) matrix of a collection, whose entry `(i, j)` is the kernel
+/// of the items `i` and `j`. The kernel is assumed symmetric, so it runs once per pair.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{gram_matrix, normalized_spectrum_kernel};
+///
+/// let docs = [b"abab".as_slice(), b"abba", b"bbbb"];
+/// let gram = gram_matrix(&docs, |x, y| normalized_spectrum_kernel(x, y, 2));
+/// assert_eq!(gram[0][0], 1.);
+/// assert_eq!(gram[0][2], gram[2][0]);
+/// assert_eq!(gram[0][2], 0.);
+/// ```
+pub fn gram_matrix<T, F>(items: &[T], kernel: F) -> Vec<Vec<f64>>
+where
+    F: Fn(&T, &T) -> f64,
+{
+    let mut gram = vec![vec![0.; items.len()]; items.len()];
+    for i in 0..items.len() {
+        for j in i..items.len() {
+            let value = kernel(&items[i], &items[j]);
+            gram[i][j] = value;
+            gram[j][i] = value;
+        }
+    }
+    gram
+}
+
+/// Returns the dot product of two count vectors.
+pub(super) fn dot<K: Eq + Hash>(xs: &CountedBag<K>, ys: &CountedBag<K>) -> f64 {
+    let (xs, ys) = if xs.len() <= ys.len() {
+        (xs, ys)
+    } else {
+        (ys, xs)
+    };
+    xs.iter()
+        .filter_map(|(key, x)| ys.get(key).map(|y| *x as f64 * *y as f64))
+        .sum()
+}
+
+/// Normalizes a kernel by the kernels of its arguments with themselves.
+pub(super) fn normalize(xy: f64, xx: f64, yy: f64) -> f64 {
+    let denom = (xx * yy).sqrt();
+    if denom == 0. {
+        0.
+    } else {
+        (xy / denom).min(1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+    use quickcheck_macros::quickcheck;
+
+    /// Sums the weights of all the pairs of index tuples of length `k` with equal elements.
+    fn brute_force(xs: &[u8], ys: &[u8], k: usize, lambda: f64) -> f64 {
+        fn tuples(len: usize, k: usize) -> Vec<Vec<usize>> {
+            if k == 0 {
+                return vec![vec![]];
+            }
+            tuples(len, k - 1)
+                .into_iter()
+                .flat_map(|t| {
+                    let start = t.last().map_or(0, |last| last + 1);
+                    (start..len).map(move |i| {
+                        let mut t = t.clone();
+                        t.push(i);
+                        t
+                    })
+                })
+                .collect()
+        }
+
+        let span = |t: &[usize]| (t[t.len() - 1] - t[0] + 1) as i32;
+        let mut kernel = 0.;
+        for ti in tuples(xs.len(), k) {
+            for tj in tuples(ys.len(), k) {
+                if ti.iter().zip(&tj).all(|(i, j)| xs[*i] == ys[*j]) {
+                    kernel += lambda.powi(span(&ti) + span(&tj));
+                }
+            }
+        }
+        kernel
+    }
+
+    #[test]
+    fn subsequence_() {
+        let l: f64 = 0.5;
+        // "ca", "ct" and "at"
+        let kernel = subsequence_kernel(b"cat", b"cat", 2, l);
+        assert_close(kernel, 2. * l.powi(4) + l.powi(6), DEFAULT_EPSILON);
+
+        // with no decay, the number of pairs of equal subsequences
+        assert_eq!(subsequence_kernel(b"aa", b"aaa", 1, 1.), 6.);
+        assert_eq!(subsequence_kernel(b"ab", b"abc", 3, 0.5), 0.);
+        assert_eq!(normalized_subsequence_kernel(b"", b"ab", 1, 0.5), 0.);
+    }
+
+    #[quickcheck]
+    fn subsequence_brute_force_(xs: Vec<u8>, ys: Vec<u8>, k: u8) -> bool {
+        let xs: Vec<u8> = xs.into_iter().take(7).map(|x| x % 3).collect();
+        let ys: Vec<u8> = ys.into_iter().take(7).map(|y| y % 3).collect();
+        let k = k as usize % 3 + 1;
+
+        let kernel = subsequence_kernel(&xs, &ys, k, 0.7);
+        let expected = brute_force(&xs, &ys, k, 0.7);
+        (kernel - expected).abs() <= 1e-9 * expected.max(1.)
+            && (kernel - subsequence_kernel(&ys, &xs, k, 0.7)).abs() <= 1e-9 * kernel.max(1.)
+    }
+
+    #[quickcheck]
+    fn spectrum_(xs: Vec<u8>, ys: Vec<u8>, k: u8) -> bool {
+        let k = k as usize % 4 + 1;
+        let kernel = spectrum_kernel(&xs, &ys, k);
+        let normalized = normalized_spectrum_kernel(&xs, &ys, k);
+
+        kernel == spectrum_kernel(&ys, &xs, k)
+            && (0. ..=1.).contains(&normalized)
+            && (xs.len() < k || (normalized_spectrum_kernel(&xs, &xs, k) - 1.).abs() < 1e-12)
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_length_() {
+        let _ = subsequence_kernel(b"ab", b"ab", 0, 0.5);
+    }
+}
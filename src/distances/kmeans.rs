@@ -0,0 +1,106 @@
+use rand::{seq::index, Rng};
+
+/// Returns the square of the Euclidean distance between two vectors of the same length.
+#[inline]
+pub(crate) fn squared_euclid(xs: &[f32], ys: &[f32]) -> f32 {
+    xs.iter().zip(ys).map(|(x, y)| (x - y) * (x - y)).sum()
+}
+
+/// Returns the index of the centroid closest to a point.
+pub(crate) fn nearest(centroids: &[Vec<f32>], point: &[f32]) -> usize {
+    centroids
+        .iter()
+        .map(|c| squared_euclid(c, point))
+        .enumerate()
+        .min_by(|(_, d1), (_, d2)| d1.total_cmp(d2))
+        .map(|(i, _)| i)
+        .unwrap_or(0)
+}
+
+/// Clusters the points with Lloyd's algorithm and returns the centroids.
+///
+/// The centroids are initialized with `k` distinct points sampled with `rng`, or with all
+/// the points when there are fewer than `k`. A centroid left without points keeps its position.
+pub(crate) fn kmeans<R: Rng + ?Sized>(
+    points: &[&[f32]],
+    k: usize,
+    iterations: usize,
+    rng: &mut R,
+) -> Vec<Vec<f32>> {
+    let dim = points.first().map_or(0, |p| p.len());
+
+    let mut centroids: Vec<Vec<f32>> = index::sample(rng, points.len(), k.min(points.len()))
+        .into_iter()
+        .map(|i| points[i].to_vec())
+        .collect();
+
+    let mut assignments = vec![usize::MAX; points.len()];
+
+    for _ in 0..iterations {
+        let mut changed = false;
+        for (point, assignment) in points.iter().zip(assignments.iter_mut()) {
+            let closest = nearest(&centroids, point);
+            if closest != *assignment {
+                *assignment = closest;
+                changed = true;
+            }
+        }
+
+        if !changed {
+            break;
+        }
+
+        let mut sums = vec![vec![0_f32; dim]; centroids.len()];
+        let mut counts = vec![0_usize; centroids.len()];
+        for (point, assignment) in points.iter().zip(&assignments) {
+            counts[*assignment] += 1;
+            for (s, x) in sums[*assignment].iter_mut().zip(point.iter()) {
+                *s += x;
+            }
+        }
+
+        for ((centroid, sum), count) in centroids.iter_mut().zip(sums).zip(counts) {
+            if count > 0 {
+                *centroid = sum.into_iter().map(|s| s / count as f32).collect();
+            }
+        }
+    }
+
+    centroids
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn kmeans_() {
+        let points: Vec<[f32; 2]> = vec![
+            [0., 0.],
+            [0.1, 0.],
+            [0., 0.1],
+            [10., 10.],
+            [10.1, 10.],
+            [10., 10.1],
+        ];
+        let points: Vec<&[f32]> = points.iter().map(|p| p.as_slice()).collect();
+
+        let mut rng = StdRng::seed_from_u64(1);
+        let centroids = kmeans(&points, 2, 10, &mut rng);
+        assert_eq!(centroids.len(), 2);
+
+        let a = nearest(&centroids, &[0., 0.]);
+        let b = nearest(&centroids, &[10., 10.]);
+        assert_ne!(a, b);
+        assert!(squared_euclid(&centroids[a], &[0.1 / 3., 0.1 / 3.]) < 1e-6);
+    }
+
+    #[test]
+    fn kmeans_few_points_() {
+        let points: Vec<&[f32]> = vec![&[1., 2.]];
+        let mut rng = StdRng::seed_from_u64(1);
+        let centroids = kmeans(&points, 4, 10, &mut rng);
+        assert_eq!(centroids, [vec![1., 2.]]);
+    }
+}
@@ -0,0 +1,194 @@
+use super::kernels::{dot, normalize};
+use crate::{bits::dna, collections::CountedBag};
+use std::hash::Hash;
+
+/// Returns the counts of the k-mers of a DNA sequence, each k-mer encoded with two bits
+/// per nucleotide into a `u64` by [`dna::kmers`], the first nucleotide in the most
+/// significant bits.
+///
+/// The code of `ACG` is `0b00_01_10`, and [`dna::decode_kmer`] gives back the nucleotides.
+/// The windows which contain a base other than `A`, `C`, `G` or `T`, such as an `N`, are
+/// skipped. Comparing profiles instead of aligning the sequences gives the alignment-free
+/// distances [`d2_distance`] and [`cosine_distance`].
+///
+/// # Panics
+///
+/// Panics if `k` is not in the `1..=32` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::kmer_profile;
+///
+/// let profile = kmer_profile(b"ACGNACGT", 3);
+/// assert_eq!(profile.get(&0b00_01_10), Some(&2));
+/// assert_eq!(profile.get(&0b01_10_11), Some(&1));
+/// assert_eq!(profile.total(), 3);
+/// ```
+pub fn kmer_profile(seq: &[u8], k: usize) -> CountedBag<u64> {
+    CountedBag::from_keys(dna::kmers(seq, k))
+}
+
+/// Returns the counts of the [canonical](dna::canonical) k-mers of a DNA sequence, which
+/// count a k-mer together with its reverse complement, so the profile does not depend on
+/// the strand the sequence was read from.
+///
+/// # Panics
+///
+/// Panics if `k` is not in the `1..=32` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{canonical_kmer_profile, cosine_distance};
+///
+/// // the two strands of the same fragment
+/// let xs = canonical_kmer_profile(b"GATTACA", 3);
+/// let ys = canonical_kmer_profile(b"TGTAATC", 3);
+/// assert_eq!(cosine_distance(&xs, &ys), 0.);
+/// ```
+pub fn canonical_kmer_profile(seq: &[u8], k: usize) -> CountedBag<u64> {
+    CountedBag::from_keys(dna::canonical_kmers(seq, k))
+}
+
+/// Returns the D2 statistic of two k-mer profiles, the number of pairs of equal k-mers
+/// between the two sequences, which is the dot product of their counts.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{d2, kmer_profile};
+///
+/// // "AC" twice by once, "CA" once by once
+/// assert_eq!(d2(&kmer_profile(b"ACAC", 2), &kmer_profile(b"ACA", 2)), 3.);
+/// ```
+pub fn d2<K: Eq + Hash>(xs: &CountedBag<K>, ys: &CountedBag<K>) -> f64 {
+    dot(xs, ys)
+}
+
+/// Returns the [d2 dissimilarity](https://doi.org/10.1089/cmb.2009.0198) of two k-mer
+/// profiles, `(1 - D2 / (|x| |y|)) / 2`, in `[0, 1/2]` for counts. It is zero for
+/// proportional profiles and one half for profiles without common k-mers.
+/// Returns one half when a profile is empty.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{d2_distance, kmer_profile};
+///
+/// let xs = kmer_profile(b"ACGTACGT", 3);
+/// assert_eq!(d2_distance(&xs, &xs), 0.);
+/// assert_eq!(d2_distance(&xs, &kmer_profile(b"AAAA", 3)), 0.5);
+/// ```
+pub fn d2_distance<K: Eq + Hash>(xs: &CountedBag<K>, ys: &CountedBag<K>) -> f64 {
+    cosine_distance(xs, ys) / 2.
+}
+
+/// Returns the cosine distance of two k-mer profiles, one minus the cosine of their counts,
+/// in `[0, 1]` for counts. Returns one when a profile is empty.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{cosine_distance, kmer_profile};
+///
+/// let xs = kmer_profile(b"ACGTTGCA", 2);
+/// let ys = kmer_profile(b"ACGTTGCC", 2);
+/// assert!(cosine_distance(&xs, &ys) < cosine_distance(&xs, &kmer_profile(b"GGGGCCCC", 2)));
+/// ```
+pub fn cosine_distance<K: Eq + Hash>(xs: &CountedBag<K>, ys: &CountedBag<K>) -> f64 {
+    1. - normalize(dot(xs, ys), dot(xs, xs), dot(ys, ys))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+    use crate::bits::pack;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn profile_() {
+        let profile = kmer_profile(b"acgtACGT", 4);
+        assert_eq!(profile.get(&0b00_01_10_11), Some(&2));
+        assert_eq!(profile.len(), 4);
+
+        assert!(kmer_profile(b"ACG", 4).is_empty());
+        assert!(kmer_profile(b"NNNNN", 1).is_empty());
+
+        let long = [b'T'; 40];
+        assert_eq!(kmer_profile(&long, 32).get(&u64::MAX), Some(&9));
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_k_() {
+        kmer_profile(b"ACGT", 0);
+    }
+
+    #[test]
+    #[should_panic]
+    fn large_k_() {
+        kmer_profile(b"ACGT", 33);
+    }
+
+    #[test]
+    fn distances_() {
+        let xs = kmer_profile(b"ACGTACGT", 2);
+        let empty = kmer_profile(b"", 2);
+
+        assert_eq!(cosine_distance(&xs, &empty), 1.);
+        assert_eq!(d2_distance(&empty, &empty), 0.5);
+        assert_eq!(d2(&xs, &empty), 0.);
+
+        // doubling the sequence doubles the counts but keeps the direction
+        let doubled = kmer_profile(b"ACGTACGTNACGTACGT", 2);
+        assert_close(cosine_distance(&xs, &doubled), 0., DEFAULT_EPSILON);
+    }
+
+    #[quickcheck]
+    fn packed_codes_(seq: Vec<u8>, k: u8) -> bool {
+        let seq: Vec<u8> = seq.iter().map(|x| b"ACGT"[*x as usize % 4]).collect();
+        let k = k as usize % dna::MAX_K + 1;
+
+        let expected = CountedBag::<u64>::from_keys(seq.windows(k).map(|kmer| {
+            let bases = kmer.iter().map(|base| dna::encode(*base).unwrap());
+            pack(bases, 2).to_uint::<u64>().unwrap()
+        }));
+        let profile = kmer_profile(&seq, k);
+
+        profile.len() == expected.len()
+            && profile
+                .iter()
+                .all(|(kmer, c)| expected.get(kmer) == Some(c))
+    }
+
+    #[quickcheck]
+    fn canonical_strands_(seq: Vec<u8>) -> bool {
+        let seq: Vec<u8> = seq.iter().map(|x| b"ACGT"[*x as usize % 4]).collect();
+        let rc: Vec<u8> = seq
+            .iter()
+            .rev()
+            .map(|base| dna::decode(dna::encode(*base).unwrap() ^ 3))
+            .collect();
+        let (xs, ys) = (
+            canonical_kmer_profile(&seq, 5),
+            canonical_kmer_profile(&rc, 5),
+        );
+
+        xs.len() == ys.len() && xs.iter().all(|(kmer, c)| ys.get(kmer) == Some(c))
+    }
+
+    #[quickcheck]
+    fn symmetric_(xs: Vec<u8>, ys: Vec<u8>) -> bool {
+        let dna = |v: &[u8]| {
+            v.iter()
+                .map(|x| b"ACGT"[*x as usize % 4])
+                .collect::<Vec<_>>()
+        };
+        let (px, py) = (kmer_profile(&dna(&xs), 3), kmer_profile(&dna(&ys), 3));
+        let dist = cosine_distance(&px, &py);
+
+        dist == cosine_distance(&py, &px) && (0. ..=1.).contains(&dist)
+    }
+}
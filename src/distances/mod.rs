@@ -22,16 +22,29 @@
 //!
 //! This version of itertools requires Rust 1.32 or later.
 
+mod batch;
 pub(crate) mod cosine;
 mod distance;
+mod edit;
 pub(crate) mod euclid;
 pub(crate) mod hamming;
 pub(crate) mod jaccard;
+mod kernels;
+mod kmeans;
+mod kmers;
 pub(crate) mod manhattan;
+mod pq;
+mod quantized;
 
+pub use batch::*;
 pub use cosine::cosine;
 pub use distance::*;
+pub use edit::*;
 pub use euclid::euclid;
 pub use hamming::*;
-pub use jaccard::jaccard;
+pub use jaccard::{jaccard, jaccard_sampled, JaccardEstimate};
+pub use kernels::*;
+pub use kmers::*;
 pub use manhattan::manhattan;
+pub use pq::*;
+pub use quantized::*;
@@ -0,0 +1,272 @@
+use super::kmeans::{kmeans, nearest, squared_euclid};
+use rand::Rng;
+
+/// A [product quantizer](https://en.wikipedia.org/wiki/Vector_quantization) which compresses
+/// vectors into codes of one byte per subspace.
+///
+/// The vectors are split into equal subvectors, and each subspace has its own codebook of
+/// centroids trained with k-means. A vector is encoded by the indices of the closest centroids
+/// of its subvectors. The distances between a query and the encoded vectors are computed
+/// asymmetrically, from a [`DistanceTable`] of the query, without decoding the vectors.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::ProductQuantizer;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let rows: Vec<Vec<f32>> = (0..100)
+///     .map(|i| vec![(i % 10) as f32, (i / 10) as f32, 1., 0.])
+///     .collect();
+///
+/// let mut rng = StdRng::seed_from_u64(7);
+/// let pq = ProductQuantizer::train(&rows, 2, 16, 20, &mut rng);
+///
+/// let codes: Vec<Vec<u8>> = rows.iter().map(|row| pq.encode(row)).collect();
+/// assert_eq!(codes[0].len(), 2);
+///
+/// let table = pq.distance_table(&[3., 4., 1., 0.]);
+/// let closest = (0..rows.len())
+///     .min_by(|a, b| table.distance(&codes[*a]).total_cmp(&table.distance(&codes[*b])))
+///     .unwrap();
+/// assert!((rows[closest][0] - 3.).abs() <= 1. && (rows[closest][1] - 4.).abs() <= 1.);
+/// ```
+#[derive(Clone, Debug)]
+pub struct ProductQuantizer {
+    dim: usize,
+    // the centroids of each subspace
+    codebooks: Vec<Vec<Vec<f32>>>,
+}
+
+impl ProductQuantizer {
+    /// Trains the codebooks of the quantizer on a set of rows.
+    ///
+    /// The rows are split into `subspaces` subvectors, and the subvectors of each subspace
+    /// are clustered into at most `centroids` clusters, with `iterations` rounds of k-means
+    /// initialized from rows sampled with `rng`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if there are no rows, if the rows are empty or have different lengths, if the
+    /// length is not a multiple of `subspaces`, or if `centroids` is not in the `1..=256` range.
+    pub fn train<T, R>(
+        rows: &[T],
+        subspaces: usize,
+        centroids: usize,
+        iterations: usize,
+        rng: &mut R,
+    ) -> Self
+    where
+        T: AsRef<[f32]>,
+        R: Rng + ?Sized,
+    {
+        assert!(!rows.is_empty(), "there are no rows to train on");
+        assert!(
+            (1..=256).contains(&centroids),
+            "the number of centroids is not in 1..=256"
+        );
+
+        let dim = rows[0].as_ref().len();
+        assert!(dim > 0, "the rows are empty");
+        assert!(
+            rows.iter().all(|row| row.as_ref().len() == dim),
+            "rows have different lengths"
+        );
+        assert!(
+            subspaces > 0 && dim.is_multiple_of(subspaces),
+            "the length {} is not a multiple of the number of subspaces {}",
+            dim,
+            subspaces
+        );
+
+        let sub = dim / subspaces;
+        let codebooks = (0..subspaces)
+            .map(|s| {
+                let points: Vec<&[f32]> = rows
+                    .iter()
+                    .map(|row| &row.as_ref()[s * sub..(s + 1) * sub])
+                    .collect();
+                kmeans(&points, centroids, iterations, rng)
+            })
+            .collect();
+
+        Self { dim, codebooks }
+    }
+
+    /// Returns the length of the vectors.
+    pub fn dim(&self) -> usize {
+        self.dim
+    }
+
+    /// Returns the number of subspaces, which is also the length of the codes.
+    pub fn subspaces(&self) -> usize {
+        self.codebooks.len()
+    }
+
+    /// Returns the length of the subvectors.
+    fn sub_dim(&self) -> usize {
+        self.dim / self.codebooks.len()
+    }
+
+    /// Encodes a vector into the indices of the closest centroids of its subvectors.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the vector does not have the length of the training rows.
+    pub fn encode(&self, xs: &[f32]) -> Vec<u8> {
+        assert_eq!(xs.len(), self.dim, "the vector has a different length");
+
+        xs.chunks_exact(self.sub_dim())
+            .zip(&self.codebooks)
+            .map(|(x, codebook)| nearest(codebook, x) as u8)
+            .collect()
+    }
+
+    /// Decodes a code into the vector made of the centroids it refers to.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code does not have one value per subspace or refers to a missing centroid.
+    pub fn decode(&self, code: &[u8]) -> Vec<f32> {
+        assert_eq!(
+            code.len(),
+            self.subspaces(),
+            "the code has a different length"
+        );
+
+        code.iter()
+            .zip(&self.codebooks)
+            .flat_map(|(c, codebook)| codebook[*c as usize].iter().copied())
+            .collect()
+    }
+
+    /// Returns the table of the squared distances between the subvectors of a query
+    /// and the centroids of each subspace.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the query does not have the length of the training rows.
+    pub fn distance_table(&self, query: &[f32]) -> DistanceTable {
+        assert_eq!(query.len(), self.dim, "the query has a different length");
+
+        let table = query
+            .chunks_exact(self.sub_dim())
+            .zip(&self.codebooks)
+            .map(|(q, codebook)| codebook.iter().map(|c| squared_euclid(q, c)).collect())
+            .collect();
+
+        DistanceTable { table }
+    }
+}
+
+/// The squared distances between the subvectors of a query and the centroids of a [`ProductQuantizer`].
+///
+/// The `struct` is created by the [`distance_table`] method on [`ProductQuantizer`]. See its documentation for more.
+///
+/// [`distance_table`]: ProductQuantizer::distance_table
+#[derive(Clone, Debug)]
+pub struct DistanceTable {
+    table: Vec<Vec<f32>>,
+}
+
+impl DistanceTable {
+    /// Returns the approximate squared Euclidean distance between the query and an encoded vector.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the code does not have one value per subspace or refers to a missing centroid.
+    pub fn distance(&self, code: &[u8]) -> f32 {
+        assert_eq!(
+            code.len(),
+            self.table.len(),
+            "the code has a different length"
+        );
+
+        code.iter()
+            .zip(&self.table)
+            .map(|(c, distances)| distances[*c as usize])
+            .sum()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn rows() -> Vec<Vec<f32>> {
+        (0..64)
+            .map(|i| {
+                let x = i as f32;
+                vec![
+                    x.sin(),
+                    x.cos(),
+                    (x * 0.5).sin(),
+                    (x * 0.5).cos(),
+                    x / 64.,
+                    1.,
+                ]
+            })
+            .collect()
+    }
+
+    #[test]
+    fn train_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let pq = ProductQuantizer::train(&rows(), 3, 8, 10, &mut rng);
+        assert_eq!(pq.dim(), 6);
+        assert_eq!(pq.subspaces(), 3);
+    }
+
+    #[test]
+    fn encode_decode_() {
+        let rows = rows();
+        let mut rng = StdRng::seed_from_u64(3);
+
+        // as many centroids as rows reproduces the rows
+        let pq = ProductQuantizer::train(&rows, 2, 64, 10, &mut rng);
+        for row in &rows {
+            let code = pq.encode(row);
+            assert_eq!(code.len(), 2);
+            assert_eq!(&pq.decode(&code), row);
+        }
+    }
+
+    #[test]
+    fn distance_table_() {
+        let rows = rows();
+        let mut rng = StdRng::seed_from_u64(5);
+        let pq = ProductQuantizer::train(&rows, 3, 16, 20, &mut rng);
+
+        let query = [0.5, 0.5, 0., 1., 0.5, 1.];
+        let table = pq.distance_table(&query);
+
+        for row in &rows {
+            let code = pq.encode(row);
+            let expected = squared_euclid(&query, &pq.decode(&code));
+            assert_close(table.distance(&code), expected, DEFAULT_EPSILON);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn train_subspaces_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let _ = ProductQuantizer::train(&rows(), 4, 8, 10, &mut rng);
+    }
+
+    #[test]
+    #[should_panic]
+    fn train_dim_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let _ = ProductQuantizer::train(&[[0_f32; 0]; 4], 1, 8, 10, &mut rng);
+    }
+
+    #[test]
+    #[should_panic]
+    fn train_centroids_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let _ = ProductQuantizer::train(&rows(), 2, 257, 10, &mut rng);
+    }
+}
@@ -0,0 +1,186 @@
+/// Quantizes a vector to one byte per value, using its own range.
+///
+/// Returns the codes together with the `scale` and the `bias` which map them back,
+/// each value being approximated by `code * scale + bias`. The error of each value
+/// is at most `scale / 2`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{dequantize, quantize};
+///
+/// let xs = [-1., 0., 0.5, 1.];
+/// let (codes, scale, bias) = quantize(&xs);
+/// assert_eq!(codes, [0, 127, 191, 255]);
+///
+/// let ys = dequantize(&codes, scale, bias);
+/// assert!(xs.iter().zip(&ys).all(|(x, y)| (x - y).abs() <= scale / 2.));
+/// ```
+pub fn quantize(xs: &[f32]) -> (Vec<u8>, f32, f32) {
+    let (min, max) = xs
+        .iter()
+        .fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), x| {
+            (min.min(*x), max.max(*x))
+        });
+
+    if xs.is_empty() {
+        return (Vec::new(), 0., 0.);
+    }
+
+    let scale = (max - min) / u8::MAX as f32;
+    (quantize_with(xs, scale, min), scale, min)
+}
+
+/// Quantizes a vector to one byte per value with a given `scale` and `bias`.
+/// The values outside of the range `bias..=bias + 255 * scale` are clamped.
+///
+/// The vectors of a data set should be quantized with the same parameters,
+/// so their codes can be compared directly by the `*_u8` kernels.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::quantize_with;
+///
+/// let codes = quantize_with(&[0., 1., 2., 10.], 0.01, 0.);
+/// assert_eq!(codes, [0, 100, 200, 255]);
+/// ```
+pub fn quantize_with(xs: &[f32], scale: f32, bias: f32) -> Vec<u8> {
+    xs.iter()
+        .map(|x| {
+            if scale > 0. {
+                ((x - bias) / scale).round().clamp(0., u8::MAX as f32) as u8
+            } else {
+                0
+            }
+        })
+        .collect()
+}
+
+/// Returns the values approximated by quantized codes.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::dequantize;
+///
+/// assert_eq!(dequantize(&[0, 2, 4], 0.5, -1.), [-1., 0., 1.]);
+/// ```
+pub fn dequantize(codes: &[u8], scale: f32, bias: f32) -> Vec<f32> {
+    codes.iter().map(|c| *c as f32 * scale + bias).collect()
+}
+
+/// Returns the square of the Euclidean distance between two vectors of codes, in code units.
+///
+/// # Panics
+///
+/// Panics if the vectors have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::squared_euclid_u8;
+///
+/// assert_eq!(squared_euclid_u8(&[0, 3, 255], &[4, 0, 255]), 25);
+/// ```
+pub fn squared_euclid_u8(xs: &[u8], ys: &[u8]) -> u64 {
+    assert_eq!(xs.len(), ys.len(), "vectors have different lengths");
+
+    xs.iter()
+        .zip(ys)
+        .map(|(x, y)| {
+            let d = x.abs_diff(*y) as u32;
+            d * d
+        })
+        .fold(0u64, |acc, d| acc + d as u64)
+}
+
+/// Returns the Euclidean distance between two vectors quantized with the same `scale`.
+/// The bias cancels out, so it is not needed.
+///
+/// # Panics
+///
+/// Panics if the vectors have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::{euclid_u8, quantize_with};
+///
+/// let xs = quantize_with(&[0., 0.], 0.1, 0.);
+/// let ys = quantize_with(&[3., 4.], 0.1, 0.);
+/// assert!((euclid_u8(&xs, &ys, 0.1) - 5.).abs() < 1e-5);
+/// ```
+pub fn euclid_u8(xs: &[u8], ys: &[u8], scale: f32) -> f32 {
+    (squared_euclid_u8(xs, ys) as f32).sqrt() * scale
+}
+
+/// Returns the Manhattan distance between two vectors quantized with the same `scale`.
+/// The bias cancels out, so it is not needed.
+///
+/// # Panics
+///
+/// Panics if the vectors have different lengths.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::distances::manhattan_u8;
+///
+/// assert_eq!(manhattan_u8(&[0, 3, 255], &[4, 0, 255], 0.5), 3.5);
+/// ```
+pub fn manhattan_u8(xs: &[u8], ys: &[u8], scale: f32) -> f32 {
+    assert_eq!(xs.len(), ys.len(), "vectors have different lengths");
+
+    let sum = xs
+        .iter()
+        .zip(ys)
+        .fold(0u64, |acc, (x, y)| acc + x.abs_diff(*y) as u64);
+    sum as f32 * scale
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::distances::{euclid, manhattan};
+
+    #[test]
+    fn quantize_() {
+        let xs = [2., 2., 2.];
+        let (codes, scale, bias) = quantize(&xs);
+        assert_eq!(codes, [0, 0, 0]);
+        assert_eq!(dequantize(&codes, scale, bias), xs);
+
+        assert_eq!(quantize(&[]), (Vec::new(), 0., 0.));
+    }
+
+    #[test]
+    fn quantize_with_clamp_() {
+        let codes = quantize_with(&[-5., 0.5, 100.], 0.5, 0.);
+        assert_eq!(codes, [0, 1, 200]);
+    }
+
+    #[test]
+    fn kernels_() {
+        let xs: Vec<f32> = (0..64).map(|i| (i as f32 * 0.37).sin()).collect();
+        let ys: Vec<f32> = (0..64).map(|i| (i as f32 * 0.11).cos()).collect();
+
+        let (scale, bias) = (2. / 255., -1.);
+        let cxs = quantize_with(&xs, scale, bias);
+        let cys = quantize_with(&ys, scale, bias);
+
+        let pairs = || xs.iter().copied().zip(ys.iter().copied());
+
+        let expected = euclid(pairs());
+        assert!((euclid_u8(&cxs, &cys, scale) - expected).abs() < 0.05);
+
+        let expected = manhattan(pairs());
+        assert!((manhattan_u8(&cxs, &cys, scale) - expected).abs() < 64. * scale);
+    }
+
+    #[test]
+    #[should_panic]
+    fn kernels_lengths_() {
+        let _ = squared_euclid_u8(&[1, 2], &[1]);
+    }
+}
@@ -0,0 +1,300 @@
+use crate::collections::{Absorb, CountedBag, Mergeable};
+use std::hash::Hash;
+
+/// The counts of the (predicted, actual) label pairs of a classifier,
+/// with the usual metrics derived from them.
+///
+/// The metrics of a class are zero when they are undefined, such as the precision
+/// of a class which was never predicted.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::eval::ConfusionMatrix;
+///
+/// let mut matrix = ConfusionMatrix::new();
+/// matrix.insert("spam", "spam");
+/// matrix.insert("spam", "ham");
+/// matrix.insert("ham", "ham");
+/// matrix.insert("ham", "ham");
+///
+/// assert_eq!(matrix.count(&"spam", &"ham"), 1);
+/// assert_eq!(matrix.precision(&"spam"), 0.5);
+/// assert_eq!(matrix.recall(&"spam"), 1.0);
+/// assert_eq!(matrix.accuracy(), 0.75);
+/// ```
+pub struct ConfusionMatrix<L> {
+    pairs: CountedBag<(L, L)>,
+    predicted: CountedBag<L>,
+    actual: CountedBag<L>,
+}
+
+impl<L> Default for ConfusionMatrix<L> {
+    fn default() -> Self {
+        Self {
+            pairs: CountedBag::default(),
+            predicted: CountedBag::default(),
+            actual: CountedBag::default(),
+        }
+    }
+}
+
+impl<L> ConfusionMatrix<L> {
+    /// Creates an empty confusion matrix.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the number of recorded predictions.
+    pub fn total(&self) -> u32 {
+        self.pairs.total()
+    }
+
+    /// Returns true if no prediction was recorded.
+    pub fn is_empty(&self) -> bool {
+        self.total() == 0
+    }
+}
+
+impl<L> ConfusionMatrix<L>
+where
+    L: Eq + Hash + Clone,
+{
+    /// Records a prediction.
+    pub fn insert(&mut self, predicted: L, actual: L) {
+        self.predicted.insert(predicted.clone());
+        self.actual.insert(actual.clone());
+        self.pairs.insert((predicted, actual));
+    }
+
+    /// Returns the number of predictions of the class `predicted` for items of the class `actual`.
+    pub fn count(&self, predicted: &L, actual: &L) -> u32 {
+        self.pairs
+            .get(&(predicted.clone(), actual.clone()))
+            .copied()
+            .unwrap_or(0)
+    }
+
+    /// Returns the classes which were predicted or were actual, in no particular order.
+    pub fn classes(&self) -> Vec<&L> {
+        let mut classes: Vec<&L> = self.actual.keys().collect();
+        classes.extend(
+            self.predicted
+                .keys()
+                .filter(|class| self.actual.get(*class).is_none()),
+        );
+        classes
+    }
+
+    /// Returns the fraction of the predictions which are correct.
+    pub fn accuracy(&self) -> f64 {
+        let correct: u32 = self.actual.keys().map(|c| self.true_positives(c)).sum();
+        ratio(correct.into(), self.total().into())
+    }
+
+    /// Returns the fraction of the predictions of a class which are correct.
+    pub fn precision(&self, class: &L) -> f64 {
+        ratio(
+            self.true_positives(class).into(),
+            count(&self.predicted, class).into(),
+        )
+    }
+
+    /// Returns the fraction of the items of a class which are predicted correctly.
+    pub fn recall(&self, class: &L) -> f64 {
+        ratio(
+            self.true_positives(class).into(),
+            count(&self.actual, class).into(),
+        )
+    }
+
+    /// Returns the harmonic mean of the precision and the recall of a class.
+    pub fn f1(&self, class: &L) -> f64 {
+        // 2 tp / (2 tp + fp + fn), in 64 bits so large counts do not overflow
+        let tp = u64::from(self.true_positives(class));
+        ratio(
+            2 * tp,
+            u64::from(count(&self.predicted, class)) + u64::from(count(&self.actual, class)),
+        )
+    }
+
+    /// Returns the unweighted mean of the precisions of the classes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::eval::ConfusionMatrix;
+    ///
+    /// let matrix: ConfusionMatrix<u8> = [(0, 0), (0, 1), (1, 1), (1, 1)].into_iter().collect();
+    /// assert_eq!(matrix.macro_precision(), 0.75);
+    /// assert!((matrix.macro_recall() - 5.0 / 6.0).abs() < 1e-9);
+    /// assert_eq!(matrix.micro_f1(), matrix.accuracy());
+    /// ```
+    pub fn macro_precision(&self) -> f64 {
+        self.macro_average(|c| self.precision(c))
+    }
+
+    /// Returns the unweighted mean of the recalls of the classes.
+    pub fn macro_recall(&self) -> f64 {
+        self.macro_average(|c| self.recall(c))
+    }
+
+    /// Returns the unweighted mean of the F1 scores of the classes.
+    pub fn macro_f1(&self) -> f64 {
+        self.macro_average(|c| self.f1(c))
+    }
+
+    /// Returns the precision over all the predictions. With one label per item,
+    /// every false positive of a class is a false negative of another one,
+    /// so the micro averaged precision, recall and F1 score all equal the accuracy.
+    pub fn micro_precision(&self) -> f64 {
+        self.accuracy()
+    }
+
+    /// Returns the recall over all the predictions, which equals the accuracy.
+    pub fn micro_recall(&self) -> f64 {
+        self.accuracy()
+    }
+
+    /// Returns the F1 score over all the predictions, which equals the accuracy.
+    pub fn micro_f1(&self) -> f64 {
+        self.accuracy()
+    }
+
+    fn true_positives(&self, class: &L) -> u32 {
+        self.count(class, class)
+    }
+
+    fn macro_average<F: Fn(&L) -> f64>(&self, metric: F) -> f64 {
+        let classes = self.classes();
+        if classes.is_empty() {
+            0.0
+        } else {
+            classes.iter().map(|c| metric(c)).sum::<f64>() / classes.len() as f64
+        }
+    }
+}
+
+impl<L> FromIterator<(L, L)> for ConfusionMatrix<L>
+where
+    L: Eq + Hash + Clone,
+{
+    /// Creates a confusion matrix from (predicted, actual) pairs.
+    fn from_iter<I: IntoIterator<Item = (L, L)>>(iter: I) -> Self {
+        let mut matrix = Self::new();
+        for (predicted, actual) in iter {
+            matrix.insert(predicted, actual);
+        }
+        matrix
+    }
+}
+
+impl<L> Absorb<(L, L)> for ConfusionMatrix<L>
+where
+    L: Eq + Hash + Clone,
+{
+    /// Records a prediction, given as a `(predicted, actual)` pair.
+    fn absorb(&mut self, (predicted, actual): (L, L)) {
+        self.insert(predicted, actual);
+    }
+}
+
+impl<L> Mergeable for ConfusionMatrix<L>
+where
+    L: Eq + Hash,
+{
+    /// Adds the counts of a confusion matrix built over another shard of the predictions.
+    fn merge_from(&mut self, other: Self) {
+        self.pairs.merge_from(other.pairs);
+        self.predicted.merge_from(other.predicted);
+        self.actual.merge_from(other.actual);
+    }
+}
+
+fn count<L: Eq + Hash>(bag: &CountedBag<L>, class: &L) -> u32 {
+    bag.get(class).copied().unwrap_or(0)
+}
+
+fn ratio(numerator: u64, denominator: u64) -> f64 {
+    if denominator == 0 {
+        0.0
+    } else {
+        numerator as f64 / denominator as f64
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::approx::{assert_close, DEFAULT_EPSILON};
+    use crate::collections::merge_all;
+
+    #[test]
+    fn metrics_() {
+        // predicted, actual
+        let pairs = [
+            ('a', 'a'),
+            ('a', 'a'),
+            ('b', 'a'),
+            ('b', 'b'),
+            ('c', 'b'),
+            ('c', 'c'),
+            ('c', 'c'),
+            ('a', 'c'),
+        ];
+        let matrix: ConfusionMatrix<char> = pairs.into_iter().collect();
+
+        assert_eq!(matrix.total(), 8);
+        assert_eq!(matrix.classes().len(), 3);
+        assert_close(matrix.accuracy(), 5.0 / 8.0, DEFAULT_EPSILON);
+        assert_close(matrix.precision(&'a'), 2.0 / 3.0, DEFAULT_EPSILON);
+        assert_close(matrix.recall(&'a'), 2.0 / 3.0, DEFAULT_EPSILON);
+        assert_close(matrix.precision(&'b'), 0.5, DEFAULT_EPSILON);
+        assert_close(matrix.recall(&'b'), 0.5, DEFAULT_EPSILON);
+        assert_close(matrix.precision(&'c'), 2.0 / 3.0, DEFAULT_EPSILON);
+        assert_close(matrix.f1(&'c'), 2.0 / 3.0, DEFAULT_EPSILON);
+        assert_close(
+            matrix.macro_f1(),
+            (2.0 / 3.0 + 0.5 + 2.0 / 3.0) / 3.0,
+            DEFAULT_EPSILON,
+        );
+
+        // a class which is never predicted
+        assert_eq!(matrix.precision(&'z'), 0.0);
+        assert_eq!(matrix.f1(&'z'), 0.0);
+    }
+
+    #[test]
+    fn f1_large_counts_() {
+        // the predicted and actual counts of the class add up to more than u32::MAX
+        let count = 3_000_000_000;
+        let matrix = ConfusionMatrix {
+            pairs: CountedBag::from_iter([(('a', 'a'), count)]),
+            predicted: CountedBag::from_iter([('a', count)]),
+            actual: CountedBag::from_iter([('a', count)]),
+        };
+        assert_eq!(matrix.f1(&'a'), 1.0);
+    }
+
+    #[test]
+    fn empty_() {
+        let matrix = ConfusionMatrix::<u8>::new();
+        assert!(matrix.is_empty());
+        assert_eq!(matrix.accuracy(), 0.0);
+        assert_eq!(matrix.macro_precision(), 0.0);
+    }
+
+    #[test]
+    fn merge_() {
+        let pairs = [(1, 1), (1, 2), (2, 2), (3, 2), (3, 3)];
+        let whole: ConfusionMatrix<u8> = pairs.into_iter().collect();
+        let shards = pairs
+            .chunks(2)
+            .map(|chunk| chunk.iter().copied().collect::<ConfusionMatrix<u8>>());
+        let merged = merge_all(shards).unwrap();
+
+        assert_eq!(merged.total(), whole.total());
+        assert_eq!(merged.macro_f1(), whole.macro_f1());
+        assert_eq!(merged.count(&3, &2), 1);
+    }
+}
@@ -0,0 +1,19 @@
+//! Evaluation metrics for classifiers and rankers.
+//!
+//! The metrics are computed from counters, so the predictions can be streamed
+//! and the counts of independent shards merged:
+//!
+//! ```
+//! use aabel_rs::eval::ConfusionMatrix;
+//!
+//! let matrix: ConfusionMatrix<&str> = [("cat", "cat"), ("dog", "cat"), ("dog", "dog")]
+//!     .into_iter()
+//!     .collect();
+//! assert!((matrix.accuracy() - 2.0 / 3.0).abs() < 1e-9);
+//! ```
+
+mod confusion;
+mod retrieval;
+
+pub use confusion::*;
+pub use retrieval::*;
@@ -0,0 +1,253 @@
+use std::{
+    collections::HashSet,
+    hash::{BuildHasher, Hash},
+};
+
+/// Returns the fraction of the top `k` results which are relevant.
+/// Missing results, when fewer than `k` are ranked, count as not relevant, and so do
+/// the repetitions of a result ranked before. Returns zero when `k` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::eval::precision_at_k;
+/// use std::collections::HashSet;
+///
+/// let relevant: HashSet<u32> = [1, 2, 3].into();
+/// assert_eq!(precision_at_k(&[1, 7, 2, 9], &relevant, 2), 0.5);
+/// assert_eq!(precision_at_k(&[1, 7, 2, 9], &relevant, 4), 0.5);
+/// ```
+pub fn precision_at_k<T, S>(ranked: &[T], relevant: &HashSet<T, S>, k: usize) -> f64
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    if k == 0 {
+        0.0
+    } else {
+        hits(ranked, relevant, k) as f64 / k as f64
+    }
+}
+
+/// Returns the fraction of the relevant items found in the top `k` results,
+/// where a repeated result is found once. Returns zero when there are no relevant items.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::eval::recall_at_k;
+/// use std::collections::HashSet;
+///
+/// let relevant: HashSet<u32> = [1, 2, 3, 4].into();
+/// assert_eq!(recall_at_k(&[1, 7, 2, 9], &relevant, 2), 0.25);
+/// assert_eq!(recall_at_k(&[1, 7, 2, 9], &relevant, 10), 0.5);
+/// ```
+pub fn recall_at_k<T, S>(ranked: &[T], relevant: &HashSet<T, S>, k: usize) -> f64
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    if relevant.is_empty() {
+        0.0
+    } else {
+        hits(ranked, relevant, k) as f64 / relevant.len() as f64
+    }
+}
+
+/// Returns the average of the precisions at the ranks of the relevant results,
+/// over all the relevant items, so relevant items which are not ranked count as zero.
+/// A repeated result is relevant only at its first rank. Returns zero when there are
+/// no relevant items.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::eval::average_precision;
+/// use std::collections::HashSet;
+///
+/// let relevant: HashSet<char> = ['a', 'b'].into();
+/// // (1/1 + 2/3) / 2
+/// let ap = average_precision(&['a', 'x', 'b'], &relevant);
+/// assert!((ap - 5.0 / 6.0).abs() < 1e-9);
+/// ```
+pub fn average_precision<T, S>(ranked: &[T], relevant: &HashSet<T, S>) -> f64
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    if relevant.is_empty() {
+        return 0.0;
+    }
+
+    let (_, sum) = relevant_ranks(ranked, relevant).fold((0, 0.0), |(hits, sum), rank| {
+        (hits + 1, sum + (hits + 1) as f64 / (rank + 1) as f64)
+    });
+    sum / relevant.len() as f64
+}
+
+/// Returns the mean of the average precisions of several queries,
+/// each given by its ranked results and its relevant items.
+/// Returns zero when there are no queries.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::eval::mean_average_precision;
+/// use std::collections::HashSet;
+///
+/// let queries = [
+///     (vec![1, 2], HashSet::from([1])),
+///     (vec![1, 2], HashSet::from([2])),
+/// ];
+/// let map = mean_average_precision(queries.iter().map(|(r, rel)| (r.as_slice(), rel)));
+/// assert_eq!(map, 0.75);
+/// ```
+pub fn mean_average_precision<'a, T, S, I>(queries: I) -> f64
+where
+    T: Eq + Hash + 'a,
+    S: BuildHasher + 'a,
+    I: IntoIterator<Item = (&'a [T], &'a HashSet<T, S>)>,
+{
+    let (count, sum) = queries
+        .into_iter()
+        .fold((0, 0.0), |(count, sum), (ranked, relevant)| {
+            (count + 1, sum + average_precision(ranked, relevant))
+        });
+
+    if count == 0 {
+        0.0
+    } else {
+        sum / count as f64
+    }
+}
+
+/// Returns the normalized discounted cumulative gain of the top `k` results with binary relevance:
+/// the sum of `1 / log2(rank + 1)` over the relevant results, divided by the same sum for
+/// an ideal ranking which lists the relevant items first. A repeated result is relevant
+/// only at its first rank. Returns zero when there are no relevant items or `k` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::eval::ndcg;
+/// use std::collections::HashSet;
+///
+/// let relevant: HashSet<u32> = [1, 2].into();
+/// assert_eq!(ndcg(&[1, 2, 3], &relevant, 3), 1.0);
+///
+/// // 1 / log2(3) against 1 + 1 / log2(3)
+/// let expected = (1.0 / 3f64.log2()) / (1.0 + 1.0 / 3f64.log2());
+/// assert!((ndcg(&[3, 1], &relevant, 2) - expected).abs() < 1e-9);
+/// ```
+pub fn ndcg<T, S>(ranked: &[T], relevant: &HashSet<T, S>, k: usize) -> f64
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    let discount = |rank: usize| 1.0 / (rank as f64 + 2.0).log2();
+
+    let dcg: f64 = relevant_ranks(ranked, relevant)
+        .take_while(|rank| *rank < k)
+        .map(discount)
+        .sum();
+    let ideal: f64 = (0..k.min(relevant.len())).map(discount).sum();
+
+    if ideal == 0.0 {
+        0.0
+    } else {
+        dcg / ideal
+    }
+}
+
+/// Returns the number of relevant items in the top `k` results.
+fn hits<T, S>(ranked: &[T], relevant: &HashSet<T, S>, k: usize) -> usize
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    relevant_ranks(ranked, relevant)
+        .take_while(|rank| *rank < k)
+        .count()
+}
+
+/// Returns the ranks of the relevant results, in increasing order. A repeated result
+/// is relevant only at its first rank, so each relevant item is counted once.
+fn relevant_ranks<'a, T, S>(
+    ranked: &'a [T],
+    relevant: &'a HashSet<T, S>,
+) -> impl Iterator<Item = usize> + 'a
+where
+    T: Eq + Hash,
+    S: BuildHasher,
+{
+    let mut seen = HashSet::new();
+    ranked
+        .iter()
+        .enumerate()
+        .filter(move |(_, item)| relevant.contains(*item) && seen.insert(*item))
+        .map(|(rank, _)| rank)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn empty_() {
+        let none: HashSet<u8> = HashSet::new();
+        let some: HashSet<u8> = [1].into();
+
+        assert_eq!(precision_at_k(&[1], &some, 0), 0.0);
+        assert_eq!(recall_at_k(&[1], &none, 1), 0.0);
+        assert_eq!(average_precision(&[], &some), 0.0);
+        assert_eq!(ndcg(&[1], &none, 5), 0.0);
+        assert_eq!(ndcg(&[1], &some, 0), 0.0);
+        assert_eq!(
+            mean_average_precision(std::iter::empty::<(&[u8], &HashSet<u8>)>()),
+            0.0
+        );
+    }
+
+    #[test]
+    fn perfect_ranking_() {
+        let relevant: HashSet<u8> = (0..5).collect();
+        let ranked: Vec<u8> = (0..10).collect();
+
+        assert_eq!(precision_at_k(&ranked, &relevant, 5), 1.0);
+        assert_eq!(recall_at_k(&ranked, &relevant, 5), 1.0);
+        assert_eq!(average_precision(&ranked, &relevant), 1.0);
+        assert!((ndcg(&ranked, &relevant, 10) - 1.0).abs() < 1e-12);
+
+        let reversed: Vec<u8> = ranked.iter().rev().copied().collect();
+        assert!(average_precision(&reversed, &relevant) < 0.5);
+        assert!(ndcg(&reversed, &relevant, 10) < 1.0);
+    }
+
+    #[test]
+    fn repeated_() {
+        let relevant: HashSet<u8> = [1, 2].into();
+
+        // the repetitions of a relevant result are not relevant
+        assert_eq!(precision_at_k(&[1, 1, 1, 1], &relevant, 4), 0.25);
+        assert_eq!(recall_at_k(&[1, 1, 2], &relevant, 2), 0.5);
+        assert_eq!(average_precision(&[1, 1, 1], &relevant), 0.5);
+        assert_eq!(
+            average_precision(&[1, 1, 2], &relevant),
+            (1.0 + 2.0 / 3.0) / 2.0
+        );
+        assert_eq!(ndcg(&[1, 1], &relevant, 2), ndcg(&[1, 3], &relevant, 2));
+    }
+
+    #[quickcheck]
+    fn bounds_(ranked: Vec<u8>, relevant: Vec<u8>, k: u8) -> bool {
+        let relevant: HashSet<u8> = relevant.into_iter().collect();
+        let k = k as usize;
+
+        let in_unit = |x: f64| (0.0..=1.0 + 1e-12).contains(&x);
+        in_unit(precision_at_k(&ranked, &relevant, k))
+            && in_unit(recall_at_k(&ranked, &relevant, k))
+            && in_unit(average_precision(&ranked, &relevant))
+            && in_unit(ndcg(&ranked, &relevant, k))
+    }
+}
@@ -0,0 +1,147 @@
+use std::{
+    io::{self, BufReader, Read},
+    num::NonZeroUsize,
+};
+
+/// Returns an iterator which reads the content of a reader in chunks of `size` bytes.
+///
+/// All the chunks have exactly `size` bytes, except the last one which can be shorter.
+/// A read error is returned as an item, after which the iteration ends. The bytes read
+/// before the error are not lost: they are yielded as a shorter chunk, and the error follows.
+///
+/// # Panics
+///
+/// Panics if `size` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::io::byte_chunks;
+///
+/// let source = [1u8, 2, 3, 4, 5];
+/// let mut chunks = byte_chunks(source.as_slice(), 2);
+/// assert_eq!(chunks.next().unwrap().unwrap(), [1, 2]);
+/// assert_eq!(chunks.next().unwrap().unwrap(), [3, 4]);
+/// assert_eq!(chunks.next().unwrap().unwrap(), [5]);
+/// assert!(chunks.next().is_none());
+/// ```
+pub fn byte_chunks<R: Read>(reader: R, size: usize) -> ByteChunks<R> {
+    ByteChunks {
+        reader: BufReader::new(reader),
+        size: NonZeroUsize::new(size).expect("chunk size is zero"),
+        error: None,
+        done: false,
+    }
+}
+
+/// An iterator over the chunks of bytes of a reader.
+///
+/// The `struct` is created by the [`byte_chunks`] function. See its documentation for more.
+pub struct ByteChunks<R> {
+    reader: BufReader<R>,
+    size: NonZeroUsize,
+    // an error which interrupted a partial chunk, returned after it
+    error: Option<io::Error>,
+    done: bool,
+}
+
+impl<R: Read> Iterator for ByteChunks<R> {
+    type Item = io::Result<Vec<u8>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.error.take() {
+            return Some(Err(err));
+        }
+        if self.done {
+            return None;
+        }
+
+        let mut chunk = vec![0; self.size.get()];
+        let mut filled = 0;
+
+        while filled < chunk.len() {
+            match self.reader.read(&mut chunk[filled..]) {
+                Ok(0) => {
+                    self.done = true;
+                    break;
+                }
+                Ok(n) => filled += n,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => continue,
+                Err(err) => {
+                    self.done = true;
+                    if filled == 0 {
+                        return Some(Err(err));
+                    }
+                    self.error = Some(err);
+                    break;
+                }
+            }
+        }
+
+        if filled == 0 {
+            None
+        } else {
+            chunk.truncate(filled);
+            Some(Ok(chunk))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Failing;
+
+    impl Read for Failing {
+        fn read(&mut self, _buf: &mut [u8]) -> io::Result<usize> {
+            Err(io::Error::other("failing reader"))
+        }
+    }
+
+    #[test]
+    fn byte_chunks_() {
+        let source: Vec<u8> = (0..10).collect();
+        let chunks: Vec<Vec<u8>> = byte_chunks(source.as_slice(), 4)
+            .map(Result::unwrap)
+            .collect();
+
+        assert_eq!(chunks, [vec![0, 1, 2, 3], vec![4, 5, 6, 7], vec![8, 9]]);
+    }
+
+    #[test]
+    fn byte_chunks_exact_() {
+        let source = [1u8, 2, 3, 4];
+        let chunks = byte_chunks(source.as_slice(), 2);
+        assert_eq!(chunks.count(), 2);
+    }
+
+    #[test]
+    fn byte_chunks_empty_() {
+        let source: [u8; 0] = [];
+        assert!(byte_chunks(source.as_slice(), 2).next().is_none());
+    }
+
+    #[test]
+    fn byte_chunks_error_() {
+        let mut chunks = byte_chunks(Failing, 2);
+        assert!(chunks.next().unwrap().is_err());
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    fn byte_chunks_partial_error_() {
+        let mut chunks = byte_chunks([1u8, 2, 3].as_slice().chain(Failing), 2);
+        assert_eq!(chunks.next().unwrap().unwrap(), [1, 2]);
+        assert_eq!(chunks.next().unwrap().unwrap(), [3]);
+        assert!(chunks.next().unwrap().is_err());
+        assert!(chunks.next().is_none());
+    }
+
+    #[test]
+    #[should_panic]
+    fn byte_chunks_zero_() {
+        let source = [1u8];
+        let _ = byte_chunks(source.as_slice(), 0);
+    }
+}
@@ -0,0 +1,81 @@
+use std::{
+    fs::File,
+    io::{self, BufRead, BufReader, Lines},
+    path::Path,
+};
+
+/// Returns an iterator over the lines of the file at the given path.
+///
+/// The file is read through a buffered reader. If the file cannot be opened,
+/// the error is returned as the first and only item of the iteration.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::io::lines_of;
+///
+/// let mut lines = lines_of("missing-file.txt");
+/// assert!(lines.next().unwrap().is_err());
+/// assert!(lines.next().is_none());
+/// ```
+pub fn lines_of<P: AsRef<Path>>(path: P) -> LinesOf {
+    match File::open(path) {
+        Ok(file) => LinesOf {
+            lines: Some(BufReader::new(file).lines()),
+            error: None,
+        },
+        Err(err) => LinesOf {
+            lines: None,
+            error: Some(err),
+        },
+    }
+}
+
+/// An iterator over the lines of a file.
+///
+/// The `struct` is created by the [`lines_of`] function. See its documentation for more.
+pub struct LinesOf {
+    lines: Option<Lines<BufReader<File>>>,
+    // the error raised when opening the file
+    error: Option<io::Error>,
+}
+
+impl Iterator for LinesOf {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(err) = self.error.take() {
+            return Some(Err(err));
+        }
+
+        self.lines.as_mut()?.next()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[test]
+    fn lines_of_() {
+        let path = std::env::temp_dir().join("aabel_rs_lines_of_.txt");
+        let mut file = File::create(&path).unwrap();
+        writeln!(file, "first line").unwrap();
+        writeln!(file, "second line").unwrap();
+        drop(file);
+
+        let lines: Vec<String> = lines_of(&path).map(Result::unwrap).collect();
+        assert_eq!(lines, ["first line", "second line"]);
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn lines_of_missing_() {
+        let mut lines = lines_of("this/file/does/not/exist.txt");
+        let err = lines.next().unwrap().unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::NotFound);
+        assert!(lines.next().is_none());
+    }
+}
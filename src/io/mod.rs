@@ -0,0 +1,21 @@
+//! Helpers for streaming data from files and readers.
+//!
+//! The iterators in this module report I/O failures as `Err` items instead of
+//! panicking, so they can be plugged at the start of a pipeline
+//! (tokenize → shingle → sketch) and the errors handled at the end of it.
+//!
+//! ```no_run
+//! use aabel_rs::io::lines_of;
+//!
+//! let words = lines_of("corpus.txt")
+//!     .map(|line| line.map(|l| l.split_whitespace().count()))
+//!     .sum::<std::io::Result<usize>>();
+//! ```
+
+mod chunks;
+mod lines;
+mod permute;
+
+pub use chunks::*;
+pub use lines::*;
+pub use permute::*;
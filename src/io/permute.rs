@@ -0,0 +1,172 @@
+use crate::collections::Permutation;
+use std::{
+    collections::VecDeque,
+    fs::File,
+    io::{self, BufRead, BufReader, Read, Seek, SeekFrom},
+    path::Path,
+};
+
+/// The number of lines read from the file at a time.
+const CHUNK: usize = 4096;
+
+/// Returns an iterator over the lines of a file in the order of a permutation,
+/// so the `i`-th item is the line `permutation.as_slice()[i]` of the file.
+///
+/// Only the offsets of the lines are kept in memory. The lines are read in chunks, and the
+/// lines of a chunk are read in the order of their offsets, so files bigger than memory can
+/// be shuffled. The line terminators are not part of the items.
+///
+/// An error is returned if the file cannot be read or if it does not have as many lines as the
+/// permutation has positions. A read error during the iteration is returned as an item, after
+/// which the iteration ends.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::collections::Permutation;
+/// use aabel_rs::io::permute_file_lines;
+///
+/// let path = std::env::temp_dir().join("aabel_rs_permute_doc.txt");
+/// std::fs::write(&path, "a\nb\nc\n").unwrap();
+///
+/// let permutation = Permutation::from_indices(vec![2, 0, 1]).unwrap();
+/// let lines: Vec<String> = permute_file_lines(&path, &permutation)
+///     .unwrap()
+///     .collect::<std::io::Result<_>>()
+///     .unwrap();
+/// assert_eq!(lines, ["c", "a", "b"]);
+/// # std::fs::remove_file(path).unwrap();
+/// ```
+pub fn permute_file_lines<P: AsRef<Path>>(
+    path: P,
+    permutation: &Permutation,
+) -> io::Result<PermutedLines> {
+    let mut reader = BufReader::new(File::open(path)?);
+
+    // the offset of every line, and the end of the file
+    let mut offsets = vec![0];
+    let mut line = Vec::new();
+    loop {
+        line.clear();
+        match reader.read_until(b'\n', &mut line)? {
+            0 => break,
+            n => offsets.push(offsets[offsets.len() - 1] + n as u64),
+        }
+    }
+
+    if offsets.len() - 1 != permutation.len() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!(
+                "the file has {} lines but the permutation has {} positions",
+                offsets.len() - 1,
+                permutation.len()
+            ),
+        ));
+    }
+
+    Ok(PermutedLines {
+        reader,
+        offsets,
+        indices: permutation.as_slice().to_vec(),
+        next: 0,
+        chunk: VecDeque::new(),
+        done: false,
+    })
+}
+
+/// An iterator over the lines of a file in the order of a permutation.
+///
+/// The `struct` is created by the [`permute_file_lines`] function. See its documentation for more.
+pub struct PermutedLines {
+    reader: BufReader<File>,
+    offsets: Vec<u64>,
+    indices: Vec<usize>,
+    // the next position whose line is not in the chunk
+    next: usize,
+    chunk: VecDeque<String>,
+    done: bool,
+}
+
+impl PermutedLines {
+    /// Reads the lines of the next chunk of positions.
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let end = (self.next + CHUNK).min(self.indices.len());
+        let sources = &self.indices[self.next..end];
+
+        let mut order: Vec<usize> = (0..sources.len()).collect();
+        order.sort_unstable_by_key(|k| sources[*k]);
+
+        let mut lines = vec![String::new(); sources.len()];
+        for k in order {
+            let (start, stop) = (self.offsets[sources[k]], self.offsets[sources[k] + 1]);
+            let mut bytes = vec![0; (stop - start) as usize];
+            self.reader.seek(SeekFrom::Start(start))?;
+            self.reader.read_exact(&mut bytes)?;
+
+            if bytes.last() == Some(&b'\n') {
+                bytes.pop();
+                if bytes.last() == Some(&b'\r') {
+                    bytes.pop();
+                }
+            }
+            lines[k] = String::from_utf8(bytes)
+                .map_err(|err| io::Error::new(io::ErrorKind::InvalidData, err))?;
+        }
+
+        self.chunk.extend(lines);
+        self.next = end;
+        Ok(())
+    }
+}
+
+impl Iterator for PermutedLines {
+    type Item = io::Result<String>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        if self.chunk.is_empty() && self.next < self.indices.len() {
+            if let Err(err) = self.read_chunk() {
+                self.done = true;
+                return Some(Err(err));
+            }
+        }
+
+        self.chunk.pop_front().map(Ok)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn permute_file_lines_() {
+        let path = std::env::temp_dir().join("aabel_rs_permute_file_lines_.txt");
+        let lines: Vec<String> = (0..10_000).map(|i| format!("line {i}")).collect();
+        std::fs::write(&path, lines.join("\r\n")).unwrap();
+
+        let mut rng = StdRng::seed_from_u64(11);
+        let permutation = Permutation::random(lines.len(), &mut rng);
+        let permuted: Vec<String> = permute_file_lines(&path, &permutation)
+            .unwrap()
+            .map(Result::unwrap)
+            .collect();
+        assert_eq!(permuted, permutation.apply(&lines));
+
+        let err = permute_file_lines(&path, &Permutation::identity(3)).err();
+        assert_eq!(err.map(|e| e.kind()), Some(io::ErrorKind::InvalidInput));
+
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_() {
+        let err = permute_file_lines("this/file/does/not/exist.txt", &Permutation::identity(0));
+        assert_eq!(err.err().map(|e| e.kind()), Some(io::ErrorKind::NotFound));
+    }
+}
@@ -0,0 +1,17 @@
+//! Building blocks for joining data sets by key.
+//!
+//! A hash join builds a table from the smaller side and probes it with the rows of the
+//! larger one. When most probing rows have no match, [`bloom_prefilter`] drops them
+//! early with a compact [`BloomFilter`], before they reach the table or the network.
+//!
+//! A similarity join pairs the sets, such as the shingles of documents, whose Jaccard
+//! similarity is above a threshold. [`similarity_join`] finds exactly these pairs, where
+//! the [`lsh`](crate::lsh) module trades some of them for speed on larger collections.
+//!
+//! [`BloomFilter`]: crate::sketches::BloomFilter
+
+mod prefilter;
+mod similarity;
+
+pub use prefilter::*;
+pub use similarity::*;
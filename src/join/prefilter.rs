@@ -0,0 +1,135 @@
+use crate::sketches::BloomFilter;
+use std::{borrow::Borrow, hash::Hash};
+
+/// The false positive rate of the filter built by [`bloom_prefilter`].
+pub const PREFILTER_FP_RATE: f64 = 0.01;
+
+/// Builds a [`BloomFilter`] over the keys of the smaller, build side of a join and returns
+/// an iterator over the `(key, row)` pairs of the other side which drops the rows that
+/// cannot match. The kept rows are a superset of the matching ones: about
+/// [`PREFILTER_FP_RATE`] of the rows without a match are kept too.
+///
+/// The keys of the build side are borrowed as the keys of the other side, for example
+/// `&K` as `K`, so both sides hash their keys the same way.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::join::bloom_prefilter;
+/// use std::collections::HashMap;
+///
+/// let users: HashMap<u32, &str> = [(1, "ann"), (2, "bob")].into();
+/// let orders = (0..1_000_u32).map(|i| (i % 100, format!("order {i}")));
+///
+/// let candidates: Vec<_> = bloom_prefilter(users.keys(), orders)
+///     .filter_map(|(user, order)| users.get(&user).map(|name| (*name, order)))
+///     .collect();
+/// assert_eq!(candidates.len(), 20);
+/// ```
+pub fn bloom_prefilter<L, R, K, V>(left_keys: L, right: R) -> BloomPrefilter<R::IntoIter>
+where
+    L: IntoIterator,
+    L::IntoIter: ExactSizeIterator,
+    L::Item: Borrow<K>,
+    R: IntoIterator<Item = (K, V)>,
+    K: Hash,
+{
+    bloom_prefilter_with_rate(left_keys, right, PREFILTER_FP_RATE)
+}
+
+/// Like [`bloom_prefilter`], with a filter sized for a false positive rate of about `fp_rate`,
+/// which trades the memory of the filter for the number of rows kept without a match.
+///
+/// # Panics
+///
+/// Panics if `fp_rate` is not in the `(0, 1)` range.
+pub fn bloom_prefilter_with_rate<L, R, K, V>(
+    left_keys: L,
+    right: R,
+    fp_rate: f64,
+) -> BloomPrefilter<R::IntoIter>
+where
+    L: IntoIterator,
+    L::IntoIter: ExactSizeIterator,
+    L::Item: Borrow<K>,
+    R: IntoIterator<Item = (K, V)>,
+    K: Hash,
+{
+    let keys = left_keys.into_iter();
+    let mut filter = BloomFilter::with_rate(keys.len(), fp_rate);
+    keys.for_each(|key| filter.insert(key.borrow()));
+
+    BloomPrefilter {
+        iter: right.into_iter(),
+        filter,
+    }
+}
+
+/// An iterator which drops the rows whose key is not in a [`BloomFilter`].
+///
+/// The `struct` is created by the [`bloom_prefilter`] function. See its documentation for more.
+pub struct BloomPrefilter<I> {
+    iter: I,
+    filter: BloomFilter,
+}
+
+impl<I> BloomPrefilter<I> {
+    /// Returns the filter built over the keys of the build side.
+    pub fn bloom(&self) -> &BloomFilter {
+        &self.filter
+    }
+}
+
+impl<I, K, V> Iterator for BloomPrefilter<I>
+where
+    I: Iterator<Item = (K, V)>,
+    K: Hash,
+{
+    type Item = (K, V);
+
+    #[inline]
+    fn next(&mut self) -> Option<Self::Item> {
+        let filter = &self.filter;
+        self.iter.find(|(key, _)| filter.contains(key))
+    }
+
+    #[inline]
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let (_, upper) = self.iter.size_hint();
+        (0, upper)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashSet;
+
+    #[quickcheck]
+    fn no_false_negatives_(left: HashSet<u16>, right: Vec<(u16, u8)>) -> bool {
+        let kept: Vec<_> = bloom_prefilter(&left, right.iter().copied()).collect();
+        let matching: Vec<_> = right
+            .iter()
+            .copied()
+            .filter(|(key, _)| left.contains(key))
+            .collect();
+
+        // the kept rows are in order and include all the matching ones
+        let mut kept_iter = kept.iter();
+        matching.iter().all(|row| kept_iter.any(|kept| kept == row))
+    }
+
+    #[test]
+    fn drops_non_matching_() {
+        let left: Vec<u32> = (0..1_000).collect();
+        let right = (1_000..101_000_u32).map(|key| (key, ()));
+
+        let kept = bloom_prefilter_with_rate(&left, right, 0.01).count();
+        assert!(kept < 2_000, "{kept}");
+
+        let empty = bloom_prefilter(Vec::<u32>::new(), (0..100_u32).map(|key| (key, ())));
+        assert!(empty.bloom().is_empty());
+        assert_eq!(empty.count(), 0);
+    }
+}
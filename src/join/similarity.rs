@@ -0,0 +1,243 @@
+use crate::collections::CountedBag;
+use std::{
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    hash::Hash,
+};
+
+/// Returns the pairs `(i, j, similarity)`, with `i < j`, of sets whose
+/// [Jaccard](https://en.wikipedia.org/wiki/Jaccard_index) similarity is at least `threshold`,
+/// sorted by `(i, j)`. Duplicated tokens of a set are counted once, and empty sets match nothing.
+///
+/// Unlike the candidate pairs of [`candidate_pairs`], the result is exact. It uses the prefix
+/// filtering of the [AllPairs](https://doi.org/10.1145/1242572.1242591) algorithm: the tokens of
+/// every set are ordered from the rarest to the most frequent, counted over all the sets in
+/// a [`CountedBag`], and two sets are compared only when they share a token among the few
+/// rarest ones of each, which similar sets must do. Sets whose sizes are too far apart for
+/// the threshold are not compared either.
+///
+/// [`candidate_pairs`]: crate::lsh::candidate_pairs
+///
+/// # Panics
+///
+/// Panics if `threshold` is not in the `(0, 1]` range.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::join::similarity_join;
+///
+/// let docs = [
+///     vec!["the", "quick", "brown", "fox"],
+///     vec!["the", "quick", "brown", "dog"],
+///     vec!["a", "lazy", "dog"],
+///     vec!["the", "quick", "brown", "fox", "jumps"],
+/// ];
+///
+/// let pairs = similarity_join(&docs, 0.6);
+/// assert_eq!(pairs, [(0, 1, 0.6), (0, 3, 0.8)]);
+/// ```
+pub fn similarity_join<S, T>(sets: &[S], threshold: f32) -> Vec<(usize, usize, f32)>
+where
+    S: AsRef<[T]>,
+    T: Eq + Hash,
+{
+    assert!(
+        threshold > 0. && threshold <= 1.,
+        "the threshold is not in (0, 1]"
+    );
+    // the filters use a slightly lower threshold, so they never drop a pair whose
+    // similarity rounds to the threshold
+    let t = threshold as f64 - TOLERANCE;
+
+    let records = ranked_records(sets);
+
+    // the sets from the smallest to the largest, so each set is probed against the smaller ones
+    let mut order: Vec<usize> = (0..records.len()).collect();
+    order.sort_by_key(|i| records[*i].len());
+
+    let num_tokens = records
+        .iter()
+        .flatten()
+        .max()
+        .map_or(0, |max| *max as usize + 1);
+    let mut index: Vec<Vec<usize>> = vec![Vec::new(); num_tokens];
+    let mut overlaps = vec![0_u32; records.len()];
+    let mut pairs = Vec::new();
+
+    for x in order {
+        let record = &records[x];
+        if record.is_empty() {
+            continue;
+        }
+
+        let len = record.len() as f64;
+        let prefix = record.len() - min_overlap(t * len) + 1;
+
+        let mut candidates = Vec::new();
+        for token in &record[..prefix] {
+            for y in &index[*token as usize] {
+                // a smaller set needs at least t |x| tokens
+                if (records[*y].len() as f64) < t * len {
+                    continue;
+                }
+                if overlaps[*y] == 0 {
+                    candidates.push(*y);
+                }
+                overlaps[*y] += 1;
+            }
+        }
+
+        for y in candidates {
+            overlaps[y] = 0;
+
+            let overlap = intersection_len(record, &records[y]);
+            let union = record.len() + records[y].len() - overlap;
+            let similarity = (overlap as f64 / union as f64) as f32;
+            if similarity >= threshold {
+                pairs.push((x.min(y), x.max(y), similarity));
+            }
+        }
+
+        for token in &record[..prefix] {
+            index[*token as usize].push(x);
+        }
+    }
+
+    pairs.sort_unstable_by_key(|(i, j, _)| (*i, *j));
+    pairs
+}
+
+/// The tolerance of the filters, which only lets more pairs through to the exact check.
+const TOLERANCE: f64 = 1e-6;
+
+/// Returns the smallest number of tokens at least `overlap`.
+#[inline]
+fn min_overlap(overlap: f64) -> usize {
+    overlap.ceil().max(1.) as usize
+}
+
+/// Replaces the tokens of the sets with their ranks in the increasing order of the frequencies,
+/// with the ties broken by the first appearance, and sorts the ranks of each set.
+fn ranked_records<S, T>(sets: &[S]) -> Vec<Vec<u32>>
+where
+    S: AsRef<[T]>,
+    T: Eq + Hash,
+{
+    let distinct: Vec<Vec<&T>> = sets
+        .iter()
+        .map(|set| {
+            let mut seen = HashSet::new();
+            set.as_ref().iter().filter(|t| seen.insert(*t)).collect()
+        })
+        .collect();
+
+    let frequencies = CountedBag::<&T>::from_keys(distinct.iter().flatten().copied());
+
+    let mut first = HashMap::new();
+    for token in distinct.iter().flatten() {
+        let next = first.len();
+        first.entry(*token).or_insert(next);
+    }
+
+    let mut tokens: Vec<(&T, usize)> = first.into_iter().collect();
+    tokens.sort_by(|(t1, f1), (t2, f2)| {
+        let (c1, c2) = (frequencies.get(t1), frequencies.get(t2));
+        c1.cmp(&c2).then(f1.cmp(f2))
+    });
+    let ranks: HashMap<&T, u32> = tokens
+        .into_iter()
+        .enumerate()
+        .map(|(rank, (token, _))| (token, rank as u32))
+        .collect();
+
+    distinct
+        .into_iter()
+        .map(|set| {
+            let mut record: Vec<u32> = set.into_iter().map(|token| ranks[token]).collect();
+            record.sort_unstable();
+            record
+        })
+        .collect()
+}
+
+/// Returns the number of common values of two sorted slices.
+fn intersection_len(xs: &[u32], ys: &[u32]) -> usize {
+    let (mut i, mut j, mut count) = (0, 0, 0);
+    while i < xs.len() && j < ys.len() {
+        match xs[i].cmp(&ys[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                count += 1;
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    count
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    fn brute_force(sets: &[Vec<u8>], threshold: f32) -> Vec<(usize, usize, f32)> {
+        let sets: Vec<HashSet<u8>> = sets.iter().map(|s| s.iter().copied().collect()).collect();
+        let mut pairs = Vec::new();
+        for i in 0..sets.len() {
+            for j in i + 1..sets.len() {
+                let inter = sets[i].intersection(&sets[j]).count();
+                let union = sets[i].union(&sets[j]).count();
+                let similarity = (inter as f64 / union as f64) as f32;
+                if union > 0 && similarity >= threshold {
+                    pairs.push((i, j, similarity));
+                }
+            }
+        }
+        pairs
+    }
+
+    #[test]
+    fn empty_() {
+        assert!(similarity_join::<Vec<u8>, u8>(&[], 0.5).is_empty());
+        assert!(similarity_join(&[vec![], vec![], vec![1_u8]], 0.5).is_empty());
+        assert_eq!(similarity_join(&[vec![1, 1], vec![1]], 1.), [(0, 1, 1.)]);
+    }
+
+    #[test]
+    fn thresholds_() {
+        // exact fractions at the boundaries of the filters
+        let sets: Vec<Vec<u8>> = vec![
+            (0..5).collect(),
+            (0..4).collect(),
+            (1..6).collect(),
+            (0..10).collect(),
+        ];
+        for threshold in [0.1, 0.4, 0.5, 2. / 3., 0.8, 1.] {
+            assert_eq!(
+                similarity_join(&sets, threshold),
+                brute_force(&sets, threshold),
+                "{threshold}"
+            );
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn threshold_zero_() {
+        let _ = similarity_join(&[vec![1_u8]], 0.);
+    }
+
+    #[quickcheck]
+    fn brute_force_(sets: Vec<Vec<u8>>, threshold: u8) -> bool {
+        // a small alphabet, so the sets overlap
+        let sets: Vec<Vec<u8>> = sets
+            .into_iter()
+            .map(|set| set.into_iter().map(|t| t % 12).collect())
+            .collect();
+        let threshold = (threshold % 10 + 1) as f32 / 10.;
+        similarity_join(&sets, threshold) == brute_force(&sets, threshold)
+    }
+}
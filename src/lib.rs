@@ -1,3 +1,13 @@
+pub mod approx;
 pub mod bits;
 pub mod collections;
+pub mod compress;
+pub mod corpus;
 pub mod distances;
+pub mod eval;
+pub mod io;
+pub mod join;
+pub mod lsh;
+pub mod sampling;
+pub mod seeded;
+pub mod sketches;
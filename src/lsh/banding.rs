@@ -0,0 +1,115 @@
+use crate::seeded::stable_hash;
+use std::collections::{HashMap, HashSet};
+
+/// Returns the number of bands and of rows per band, with `bands * rows == num_hashes`,
+/// whose similarity threshold `(1 / bands) ^ (1 / rows)` is the closest to the given one.
+/// Returns `(1, 1)` when `num_hashes` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::lsh::choose_bands;
+///
+/// let (bands, rows) = choose_bands(128, 0.5);
+/// assert_eq!(bands * rows, 128);
+/// assert_eq!((bands, rows), (32, 4));
+/// ```
+pub fn choose_bands(num_hashes: usize, threshold: f32) -> (usize, usize) {
+    (1..=num_hashes)
+        .filter(|rows| num_hashes.is_multiple_of(*rows))
+        .map(|rows| (num_hashes / rows, rows))
+        .min_by(|(b1, r1), (b2, r2)| {
+            let d1 = (band_threshold(*b1, *r1) - threshold).abs();
+            let d2 = (band_threshold(*b2, *r2) - threshold).abs();
+            d1.total_cmp(&d2)
+        })
+        .unwrap_or((1, 1))
+}
+
+/// Returns the similarity at which the probability of becoming a candidate pair rises steeply.
+fn band_threshold(bands: usize, rows: usize) -> f32 {
+    (1. / bands as f32).powf(1. / rows as f32)
+}
+
+/// Returns the pairs `(i, j)`, with `i < j`, of signatures which are identical on at least one band.
+///
+/// # Panics
+///
+/// Panics if `rows` is zero.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::lsh::candidate_pairs;
+///
+/// let signatures = vec![vec![1, 2, 3, 4], vec![1, 2, 9, 9], vec![5, 6, 7, 8]];
+/// let pairs = candidate_pairs(&signatures, 2, 2);
+/// assert_eq!(pairs, [(0, 1)]);
+/// ```
+pub fn candidate_pairs(signatures: &[Vec<u64>], bands: usize, rows: usize) -> Vec<(usize, usize)> {
+    assert!(rows > 0, "rows is zero");
+
+    let mut pairs = HashSet::new();
+
+    for band in 0..bands {
+        let mut buckets: HashMap<u64, Vec<usize>> = HashMap::new();
+
+        for (i, signature) in signatures.iter().enumerate() {
+            if let Some(values) = signature.get(band * rows..(band + 1) * rows) {
+                buckets
+                    .entry(stable_hash(values, band as u64))
+                    .or_default()
+                    .push(i);
+            }
+        }
+
+        for bucket in buckets.values() {
+            for (k, i) in bucket.iter().enumerate() {
+                for j in &bucket[k + 1..] {
+                    pairs.insert((*i, *j));
+                }
+            }
+        }
+    }
+
+    let mut pairs: Vec<(usize, usize)> = pairs.into_iter().collect();
+    pairs.sort_unstable();
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn choose_bands_() {
+        let (bands, rows) = choose_bands(100, 0.8);
+        assert_eq!(bands * rows, 100);
+        assert!((band_threshold(bands, rows) - 0.8).abs() < 0.1);
+
+        assert_eq!(choose_bands(1, 0.5), (1, 1));
+        assert_eq!(choose_bands(0, 0.5), (1, 1));
+    }
+
+    #[test]
+    fn candidate_pairs_() {
+        let signatures = vec![
+            vec![1, 2, 3, 4, 5, 6],
+            vec![0, 0, 3, 4, 0, 0],
+            vec![1, 2, 0, 0, 5, 6],
+            vec![9, 9, 9, 9, 9, 9],
+        ];
+
+        let pairs = candidate_pairs(&signatures, 3, 2);
+        assert_eq!(pairs, [(0, 1), (0, 2)]);
+
+        let pairs = candidate_pairs(&signatures, 1, 6);
+        assert!(pairs.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn candidate_pairs_rows_() {
+        let _ = candidate_pairs(&[], 1, 0);
+    }
+}
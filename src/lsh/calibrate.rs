@@ -0,0 +1,185 @@
+/// The statistics of the errors of similarity estimates against the exact similarities,
+/// created by [`calibrate`].
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Calibration {
+    samples: usize,
+    bias: f32,
+    variance: f32,
+    slope: f32,
+    intercept: f32,
+}
+
+/// Compares estimated similarities, such as the ones of [`MinHasher::similarity`], with the
+/// exact similarities of a sample of pairs, given as `(estimate, exact)` pairs.
+///
+/// The mean error of the estimates is their bias, and the variance of the errors shrinks with
+/// the length of the signatures, as `J (1 - J) / num_hashes` for MinHash, so calibrating several
+/// lengths on the same pairs shows the shortest which is accurate enough. The least squares fit
+/// of the exact similarities on the estimates gives a [corrector](Calibration::corrector) for
+/// systematic errors.
+///
+/// [`MinHasher::similarity`]: crate::lsh::MinHasher::similarity
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::lsh::{calibrate, MinHasher};
+///
+/// let hasher = MinHasher::new(64, 7);
+/// // the pairs have no items in common, so their errors are independent
+/// let samples = (0..50).map(|i| {
+///     let (xs, ys) = (1000 * i..1000 * i + 100, 1000 * i + i..1000 * i + 100 + i);
+///     let exact = (100 - i) as f32 / (100 + i) as f32;
+///     (MinHasher::similarity(&hasher.signature(xs), &hasher.signature(ys)), exact)
+/// });
+///
+/// let calibration = calibrate(samples);
+/// assert_eq!(calibration.samples(), 50);
+/// assert!(calibration.bias().abs() < 0.05);
+/// assert!(calibration.rmse() < 0.1);
+///
+/// let correct = calibration.corrector();
+/// assert!((0.0..=1.0).contains(&correct(0.5)));
+/// ```
+pub fn calibrate<I>(samples: I) -> Calibration
+where
+    I: IntoIterator<Item = (f32, f32)>,
+{
+    let (mut n, mut sum_x, mut sum_y, mut sum_xx, mut sum_xy, mut sum_e, mut sum_ee) =
+        (0usize, 0f64, 0f64, 0f64, 0f64, 0f64, 0f64);
+
+    for (estimate, exact) in samples {
+        let (x, y) = (estimate as f64, exact as f64);
+        n += 1;
+        sum_x += x;
+        sum_y += y;
+        sum_xx += x * x;
+        sum_xy += x * y;
+        sum_e += x - y;
+        sum_ee += (x - y) * (x - y);
+    }
+
+    if n == 0 {
+        return Calibration {
+            samples: 0,
+            bias: 0.,
+            variance: 0.,
+            slope: 1.,
+            intercept: 0.,
+        };
+    }
+
+    let count = n as f64;
+    let bias = sum_e / count;
+    let variance = (sum_ee / count - bias * bias).max(0.);
+
+    // the least squares line of the exact similarities on the estimates,
+    // or a shift by the bias when all the estimates are the same
+    let spread = sum_xx - sum_x * sum_x / count;
+    let (slope, intercept) = if spread > f64::EPSILON {
+        let slope = (sum_xy - sum_x * sum_y / count) / spread;
+        (slope, (sum_y - slope * sum_x) / count)
+    } else {
+        (1., -bias)
+    };
+
+    Calibration {
+        samples: n,
+        bias: bias as f32,
+        variance: variance as f32,
+        slope: slope as f32,
+        intercept: intercept as f32,
+    }
+}
+
+impl Calibration {
+    /// Returns the number of compared pairs.
+    pub fn samples(&self) -> usize {
+        self.samples
+    }
+
+    /// Returns the mean of the errors, `estimate - exact`.
+    pub fn bias(&self) -> f32 {
+        self.bias
+    }
+
+    /// Returns the variance of the errors.
+    pub fn variance(&self) -> f32 {
+        self.variance
+    }
+
+    /// Returns the root mean squared error of the estimates.
+    pub fn rmse(&self) -> f32 {
+        (self.variance + self.bias * self.bias).sqrt()
+    }
+
+    /// Returns the slope and the intercept of the least squares line
+    /// which predicts the exact similarity from the estimate.
+    pub fn line(&self) -> (f32, f32) {
+        (self.slope, self.intercept)
+    }
+
+    /// Returns a function which corrects an estimate with the least squares line,
+    /// clamping the result to the `[0, 1]` range of the similarities.
+    pub fn corrector(&self) -> impl Fn(f32) -> f32 {
+        let (slope, intercept) = self.line();
+        move |estimate| (slope * estimate + intercept).clamp(0., 1.)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn systematic_error_() {
+        // estimates which overshoot by 10% plus 0.05
+        let samples: Vec<(f32, f32)> = (0..=10)
+            .map(|i| i as f32 / 20.)
+            .map(|exact| (exact * 1.1 + 0.05, exact))
+            .collect();
+
+        let calibration = calibrate(samples.iter().copied());
+        assert!(calibration.bias() > 0.05);
+        assert!(calibration.variance() > 0.);
+
+        let correct = calibration.corrector();
+        for (estimate, exact) in samples {
+            assert!((correct(estimate) - exact).abs() < 1e-5);
+        }
+    }
+
+    #[test]
+    fn degenerate_() {
+        let empty = calibrate(std::iter::empty());
+        assert_eq!(empty.samples(), 0);
+        assert_eq!(empty.rmse(), 0.);
+        assert_eq!(empty.corrector()(0.3), 0.3);
+
+        let constant = calibrate([(0.5, 0.4), (0.5, 0.4)]);
+        assert!((constant.bias() - 0.1).abs() < 1e-6);
+        assert!(constant.variance() < 1e-6);
+        assert!((constant.corrector()(0.5) - 0.4).abs() < 1e-6);
+    }
+
+    #[test]
+    fn signature_length_() {
+        use crate::lsh::MinHasher;
+
+        // longer signatures have a smaller variance
+        let rmse = |num_hashes| {
+            let hasher = MinHasher::new(num_hashes, 11);
+            calibrate((0..40).map(|i| {
+                let (xs, ys) = (
+                    hasher.signature(0..200),
+                    hasher.signature(i * 5..200 + i * 5),
+                );
+                let exact = (200 - i * 5) as f32 / (200 + i * 5) as f32;
+                (MinHasher::similarity(&xs, &ys), exact)
+            }))
+            .rmse()
+        };
+
+        assert!(rmse(512) < rmse(16));
+    }
+}
@@ -0,0 +1,164 @@
+use crate::seeded::{mix, stable_hash, Fingerprint, Fingerprinted};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::hash::Hash;
+
+/// Computes [MinHash](https://en.wikipedia.org/wiki/MinHash) signatures of sets.
+///
+/// Each position of the signature is the minimum, over the items of the set,
+/// of an independently salted hash function.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::lsh::MinHasher;
+///
+/// let hasher = MinHasher::new(128, 42);
+/// let xs = hasher.signature(0..100);
+/// let ys = hasher.signature(50..150);
+///
+/// let estimate = MinHasher::similarity(&xs, &ys);
+/// assert!((estimate - 1. / 3.).abs() < 0.15);
+/// ```
+#[derive(Clone, Debug)]
+pub struct MinHasher {
+    salts: Vec<u64>,
+    seed: u64,
+}
+
+impl MinHasher {
+    /// Creates a hasher producing signatures of `num_hashes` values.
+    /// Hashers created with the same seed produce the same signatures.
+    pub fn new(num_hashes: usize, seed: u64) -> Self {
+        let mut rng = ChaCha8Rng::seed_from_u64(seed);
+        let salts = (0..num_hashes).map(|_| rng.gen()).collect();
+        Self { salts, seed }
+    }
+
+    /// Returns the seed of the hasher.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of values of a signature.
+    pub fn len(&self) -> usize {
+        self.salts.len()
+    }
+
+    /// Returns true if the signatures are empty.
+    pub fn is_empty(&self) -> bool {
+        self.salts.is_empty()
+    }
+
+    /// Returns the signature of a set of items. Repeated items do not change the signature.
+    /// The signature of an empty set has all the values equal to `u64::MAX`.
+    pub fn signature<I, T>(&self, items: I) -> Vec<u64>
+    where
+        I: IntoIterator<Item = T>,
+        T: Hash,
+    {
+        let mut signature = vec![u64::MAX; self.salts.len()];
+
+        for item in items {
+            let h = stable_hash(&item, self.seed);
+
+            for (min, salt) in signature.iter_mut().zip(&self.salts) {
+                let x = mix(h ^ salt);
+                if x < *min {
+                    *min = x;
+                }
+            }
+        }
+
+        signature
+    }
+
+    /// Returns the fraction of positions where two signatures agree,
+    /// an estimate of the Jaccard similarity of the two sets.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the signatures have different lengths.
+    pub fn similarity(xs: &[u64], ys: &[u64]) -> f32 {
+        assert_eq!(xs.len(), ys.len(), "signatures have different lengths");
+
+        if xs.is_empty() {
+            return 0.;
+        }
+
+        let same = xs.iter().zip(ys).filter(|(x, y)| x == y).count();
+        same as f32 / xs.len() as f32
+    }
+}
+
+impl Fingerprinted for MinHasher {
+    /// Describes the number of hashes and the seed, which determine the signatures.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::lsh::MinHasher;
+    /// use aabel_rs::seeded::Fingerprinted;
+    ///
+    /// let hasher = MinHasher::new(64, 7);
+    /// assert_eq!(hasher.fingerprint().to_string(), "MinHasher(num_hashes=64, seed=7)");
+    /// assert!(!hasher.is_compatible(&MinHasher::new(64, 8)));
+    /// ```
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("MinHasher")
+            .with("num_hashes", self.salts.len() as u64)
+            .with("seed", self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn signature_() {
+        let hasher = MinHasher::new(64, 1);
+        assert_eq!(hasher.len(), 64);
+
+        let xs = hasher.signature(['a', 'b', 'c']);
+        let ys = hasher.signature(['c', 'b', 'a', 'a']);
+        assert_eq!(xs, ys);
+        assert_eq!(MinHasher::similarity(&xs, &ys), 1.);
+    }
+
+    #[test]
+    fn seed_() {
+        let xs = MinHasher::new(16, 7).signature(0..10);
+        let ys = MinHasher::new(16, 7).signature(0..10);
+        let zs = MinHasher::new(16, 8).signature(0..10);
+        assert_eq!(xs, ys);
+        assert_ne!(xs, zs);
+    }
+
+    #[test]
+    fn empty_() {
+        let hasher = MinHasher::new(4, 1);
+        assert_eq!(hasher.signature(Vec::<u32>::new()), [u64::MAX; 4]);
+
+        let hasher = MinHasher::new(0, 1);
+        assert!(hasher.is_empty());
+        assert_eq!(MinHasher::similarity(&[], &[]), 0.);
+    }
+
+    #[test]
+    fn similarity_() {
+        let hasher = MinHasher::new(512, 3);
+        let xs = hasher.signature(0..1000);
+        let ys = hasher.signature(500..1500);
+        let zs = hasher.signature(5000..6000);
+
+        assert!((MinHasher::similarity(&xs, &ys) - 1. / 3.).abs() < 0.1);
+        assert!(MinHasher::similarity(&xs, &zs) < 0.05);
+    }
+
+    #[test]
+    #[should_panic]
+    fn similarity_lengths_() {
+        let _ = MinHasher::similarity(&[1, 2], &[1]);
+    }
+}
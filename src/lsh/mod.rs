@@ -0,0 +1,14 @@
+//! Locality sensitive hashing of sets.
+//!
+//! The [`MinHasher`] summarizes a set into a short signature, such that the probability
+//! of two signatures agreeing on a position equals the Jaccard similarity of the sets.
+//! Splitting the signatures into bands and bucketing them finds the candidate pairs
+//! of similar sets without comparing all the pairs.
+
+mod banding;
+mod calibrate;
+mod minhash;
+
+pub use banding::*;
+pub use calibrate::*;
+pub use minhash::*;
@@ -0,0 +1,176 @@
+use crate::collections::Permutation;
+use rand::Rng;
+use std::{collections::HashMap, hash::Hash};
+
+/// A split of the indices of a dataset for one round of cross-validation.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Fold {
+    /// The indices used to fit the model, in increasing order.
+    pub train: Vec<usize>,
+    /// The indices held out to evaluate the model, in increasing order.
+    pub test: Vec<usize>,
+}
+
+/// Splits the indices `0..n` into `k` folds of sizes which differ by at most one,
+/// after shuffling them. Each index is in the test set of exactly one fold
+/// and in the training set of the others.
+///
+/// # Panics
+///
+/// Panics if `k` is zero or larger than `n`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sampling::kfold;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(9);
+/// let folds = kfold(10, 3, &mut rng);
+/// assert_eq!(folds.len(), 3);
+/// assert_eq!(folds.iter().map(|f| f.test.len()).sum::<usize>(), 10);
+/// assert!(folds.iter().all(|f| f.train.len() + f.test.len() == 10));
+/// ```
+pub fn kfold<R: Rng + ?Sized>(n: usize, k: usize, rng: &mut R) -> Vec<Fold> {
+    assert!(0 < k && k <= n, "cannot split {n} indices into {k} folds");
+
+    let mut assignment = vec![0; n];
+    for (position, index) in Permutation::random(n, rng).as_slice().iter().enumerate() {
+        assignment[*index] = position % k;
+    }
+
+    folds(&assignment, k)
+}
+
+/// Splits the indices of a dataset into `k` folds which preserve the proportions
+/// of the labels: the indices of each label are shuffled and dealt to the folds in turn,
+/// so the folds have sizes which differ by at most one and so do the counts of each label.
+///
+/// # Panics
+///
+/// Panics if `k` is zero or larger than the number of labels.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sampling::stratified_kfold;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let labels = ["spam", "ham", "ham", "ham", "spam", "ham"];
+/// let mut rng = StdRng::seed_from_u64(9);
+/// let folds = stratified_kfold(&labels, 2, &mut rng);
+///
+/// for fold in folds.iter() {
+///     let spam = fold.test.iter().filter(|i| labels[**i] == "spam").count();
+///     assert_eq!(spam, 1);
+///     assert_eq!(fold.test.len(), 3);
+/// }
+/// ```
+pub fn stratified_kfold<L, R>(labels: &[L], k: usize, rng: &mut R) -> Vec<Fold>
+where
+    L: Eq + Hash,
+    R: Rng + ?Sized,
+{
+    let n = labels.len();
+    assert!(0 < k && k <= n, "cannot split {n} indices into {k} folds");
+
+    // the indices of each label, with the labels in the order of their first occurrence
+    let mut groups: Vec<Vec<usize>> = vec![];
+    let mut group_of = HashMap::new();
+    for (index, label) in labels.iter().enumerate() {
+        let group = *group_of.entry(label).or_insert_with(|| {
+            groups.push(vec![]);
+            groups.len() - 1
+        });
+        groups[group].push(index);
+    }
+
+    // keep dealing where the previous label stopped, to balance the sizes of the folds
+    let mut assignment = vec![0; n];
+    let mut position = 0;
+    for group in groups {
+        for i in Permutation::random(group.len(), rng).as_slice() {
+            assignment[group[*i]] = position % k;
+            position += 1;
+        }
+    }
+
+    folds(&assignment, k)
+}
+
+/// Builds the folds from the test fold of each index.
+fn folds(assignment: &[usize], k: usize) -> Vec<Fold> {
+    (0..k)
+        .map(|fold| {
+            let (test, train) = (0..assignment.len()).partition(|i| assignment[*i] == fold);
+            Fold { train, test }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    fn is_partition(folds: &[Fold], n: usize) -> bool {
+        let mut tests: Vec<usize> = folds.iter().flat_map(|f| f.test.clone()).collect();
+        tests.sort_unstable();
+
+        tests.into_iter().eq(0..n)
+            && folds.iter().all(|f| {
+                let mut all = [f.train.clone(), f.test.clone()].concat();
+                all.sort_unstable();
+                all.into_iter().eq(0..n)
+            })
+    }
+
+    #[test]
+    fn stratified_() {
+        let labels: Vec<u8> = (0..100).map(|i| if i < 30 { 1 } else { 0 }).collect();
+        let mut rng = StdRng::seed_from_u64(13);
+        let folds = stratified_kfold(&labels, 5, &mut rng);
+
+        assert!(is_partition(&folds, 100));
+        for fold in folds {
+            assert_eq!(fold.test.len(), 20);
+            assert_eq!(fold.test.iter().filter(|i| labels[**i] == 1).count(), 6);
+        }
+    }
+
+    #[test]
+    #[should_panic]
+    fn too_many_folds_() {
+        let mut rng = StdRng::seed_from_u64(13);
+        kfold(3, 4, &mut rng);
+    }
+
+    #[quickcheck]
+    fn kfold_(n: u8, k: u8, seed: u64) -> bool {
+        let n = n as usize + 1;
+        let k = k as usize % n + 1;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let folds = kfold(n, k, &mut rng);
+        let sizes: Vec<usize> = folds.iter().map(|f| f.test.len()).collect();
+        is_partition(&folds, n) && sizes.iter().max().unwrap() - sizes.iter().min().unwrap() <= 1
+    }
+
+    #[quickcheck]
+    fn stratified_kfold_(labels: Vec<bool>, k: u8, seed: u64) -> bool {
+        if labels.is_empty() {
+            return true;
+        }
+        let k = k as usize % labels.len() + 1;
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let folds = stratified_kfold(&labels, k, &mut rng);
+        let ones: Vec<usize> = folds
+            .iter()
+            .map(|f| f.test.iter().filter(|i| labels[**i]).count())
+            .collect();
+        is_partition(&folds, labels.len())
+            && ones.iter().max().unwrap() - ones.iter().min().unwrap() <= 1
+    }
+}
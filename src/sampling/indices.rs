@@ -0,0 +1,175 @@
+use crate::seeded::mix;
+use rand::Rng;
+use std::collections::HashSet;
+
+/// Returns `k` distinct indices out of `0..n`, chosen uniformly at random.
+///
+/// It uses [Floyd's algorithm](https://fermatslibrary.com/s/a-sample-of-brilliance),
+/// which draws `k` random numbers and uses memory proportional to `k`, not to `n`.
+/// Every subset of `k` indices is equally likely, but the order of the indices
+/// in the returned vector is not random.
+///
+/// # Panics
+///
+/// Panics if `k` is larger than `n`.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sampling::sample_indices;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(1);
+/// let mut indices = sample_indices(10, 4, &mut rng);
+/// indices.sort_unstable();
+/// indices.dedup();
+/// assert_eq!(indices.len(), 4);
+/// assert!(indices.iter().all(|i| *i < 10));
+/// ```
+pub fn sample_indices<R: Rng + ?Sized>(n: usize, k: usize, rng: &mut R) -> Vec<usize> {
+    assert!(k <= n, "cannot sample {k} indices out of {n}");
+
+    let mut chosen = HashSet::with_capacity(k);
+    let mut indices = Vec::with_capacity(k);
+    for j in n - k..n {
+        let t = rng.gen_range(0..=j);
+        let index = if chosen.contains(&t) { j } else { t };
+        chosen.insert(index);
+        indices.push(index);
+    }
+
+    indices
+}
+
+/// Returns an iterator over the indices `0..n` in a pseudo-random order.
+///
+/// The order is given by a keyed bijection, a small Feistel network over the next power of
+/// four which is at least `n`, and the outputs beyond `n` are skipped. So the iterator uses
+/// constant memory and each index is produced in constant amortized time. The orders are
+/// not drawn uniformly from all the permutations of `0..n`; use
+/// [`Permutation::random`](crate::collections::Permutation::random) when that matters.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sampling::shuffled_indices;
+/// use rand::{rngs::StdRng, SeedableRng};
+///
+/// let mut rng = StdRng::seed_from_u64(2);
+/// let mut indices: Vec<usize> = shuffled_indices(100, &mut rng).collect();
+/// assert_ne!(indices, (0..100).collect::<Vec<_>>());
+///
+/// indices.sort_unstable();
+/// assert_eq!(indices, (0..100).collect::<Vec<_>>());
+/// ```
+pub fn shuffled_indices<R: Rng + ?Sized>(n: usize, rng: &mut R) -> ShuffledIndices {
+    // the domain has 2 * half bits, enough for all the indices
+    let bits = usize::BITS - n.saturating_sub(1).leading_zeros();
+    let half = bits.div_ceil(2).max(1);
+
+    ShuffledIndices {
+        n: n as u64,
+        half,
+        keys: [rng.gen(), rng.gen(), rng.gen(), rng.gen()],
+        next: 0,
+        domain: 1 << (2 * half),
+    }
+}
+
+/// An iterator over the indices of a range in a pseudo-random order.
+///
+/// The `struct` is created by the [`shuffled_indices`] function. See its documentation for more.
+#[derive(Clone, Debug)]
+pub struct ShuffledIndices {
+    n: u64,
+    half: u32,
+    keys: [u64; 4],
+    next: u128,
+    domain: u128,
+}
+
+impl ShuffledIndices {
+    /// The keyed bijection over the domain.
+    fn permute(&self, x: u64) -> u64 {
+        let mask = u64::MAX >> (u64::BITS - self.half);
+        let (mut left, mut right) = (x >> self.half, x & mask);
+        for key in self.keys {
+            let f = mix(right ^ key) & mask;
+            (left, right) = (right, left ^ f);
+        }
+        (left << self.half) | right
+    }
+}
+
+impl Iterator for ShuffledIndices {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.domain {
+            let index = self.permute(self.next as u64);
+            self.next += 1;
+            if index < self.n {
+                return Some(index as usize);
+            }
+        }
+        None
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rand::{rngs::StdRng, SeedableRng};
+
+    #[test]
+    fn sample_indices_uniform_() {
+        // each index of 0..10 is picked with probability 3/10
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut counts = [0; 10];
+        for _ in 0..10_000 {
+            for i in sample_indices(10, 3, &mut rng) {
+                counts[i] += 1;
+            }
+        }
+        assert!(
+            counts.iter().all(|c| (2_700..3_300).contains(c)),
+            "{counts:?}"
+        );
+
+        assert!(sample_indices(5, 0, &mut rng).is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn sample_too_many_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        sample_indices(3, 4, &mut rng);
+    }
+
+    #[test]
+    fn shuffled_edges_() {
+        let mut rng = StdRng::seed_from_u64(4);
+        assert_eq!(shuffled_indices(0, &mut rng).count(), 0);
+        assert!(shuffled_indices(1, &mut rng).eq([0]));
+    }
+
+    #[quickcheck]
+    fn sample_indices_(n: u16, k: u16, seed: u64) -> bool {
+        let (n, k) = (n as usize, (k % (n.saturating_add(1))) as usize);
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        let mut indices = sample_indices(n, k, &mut rng);
+        indices.sort_unstable();
+        indices.dedup();
+        indices.len() == k && indices.iter().all(|i| *i < n)
+    }
+
+    #[quickcheck]
+    fn shuffled_indices_(n: u16, seed: u64) -> bool {
+        let mut rng = StdRng::seed_from_u64(seed);
+        let mut indices: Vec<usize> = shuffled_indices(n as usize, &mut rng).collect();
+        indices.sort_unstable();
+        indices.into_iter().eq(0..n as usize)
+    }
+}
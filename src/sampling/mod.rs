@@ -0,0 +1,19 @@
+//! Random sampling of indices.
+//!
+//! The samplers pick sets of positions out of `0..n` without materializing the range,
+//! so they can drive train/test splits or random projections over large datasets:
+//!
+//! ```
+//! use aabel_rs::sampling::sample_indices;
+//! use rand::{rngs::StdRng, SeedableRng};
+//!
+//! let mut rng = StdRng::seed_from_u64(42);
+//! let test = sample_indices(1_000_000, 3, &mut rng);
+//! assert_eq!(test.len(), 3);
+//! ```
+
+mod folds;
+mod indices;
+
+pub use folds::*;
+pub use indices::*;
@@ -0,0 +1,344 @@
+//! Reproducible randomness and the parameters of the randomized structures.
+//!
+//! A [`Seeded`] configuration holds the single seed of a pipeline and derives from it an
+//! independent seed for each named component, such as the [`MinHasher`] of a deduplication
+//! step or the random generator of the [`kfold`] splits, so a whole run is reproduced from
+//! one number. The randomized structures describe their parameters, including their seeds,
+//! with a [`Fingerprint`]: structures with different fingerprints hash or sample differently,
+//! so their results are not comparable and merging them is rejected.
+//!
+//! The results are reproduced across releases too: the structures hash their items with
+//! a [`StableHasher`] instead of the hashers of the standard library, whose output may
+//! change with the Rust release, and the generators of [`Seeded`] are ChaCha generators,
+//! whose output does not change with the `rand` release.
+//!
+//! [`MinHasher`]: crate::lsh::MinHasher
+//! [`kfold`]: crate::sampling::kfold
+//!
+//! # Examples
+//!
+//! ```
+//! use aabel_rs::lsh::MinHasher;
+//! use aabel_rs::sampling::kfold;
+//! use aabel_rs::seeded::{Fingerprinted, Seeded};
+//!
+//! let seeded = Seeded::new(42);
+//! let hasher = MinHasher::new(64, seeded.derive("minhash"));
+//! let folds = kfold(10, 5, &mut seeded.rng_for("folds"));
+//!
+//! // the same seed gives the same hasher and the same folds
+//! let again = Seeded::new(42);
+//! assert_eq!(MinHasher::new(64, again.derive("minhash")).fingerprint(), hasher.fingerprint());
+//! assert_eq!(kfold(10, 5, &mut again.rng_for("folds")), folds);
+//! ```
+
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use std::{
+    fmt::Display,
+    hash::{Hash, Hasher},
+};
+
+/// The offset basis of the 64 bit FNV-1a hash.
+const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+/// The prime of the 64 bit FNV-1a hash.
+const FNV_PRIME: u64 = 0x0100_0000_01b3;
+
+/// The seed of a pipeline, from which each randomized component derives its own seed.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Seeded {
+    seed: u64,
+}
+
+impl Seeded {
+    /// Creates a configuration with an explicit seed.
+    pub fn new(seed: u64) -> Self {
+        Self { seed }
+    }
+
+    /// Returns the seed of the configuration.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the seed of the component called `name`. The derived seeds are stable
+    /// across runs and platforms, and different names give unrelated seeds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::seeded::Seeded;
+    ///
+    /// let seeded = Seeded::new(7);
+    /// assert_eq!(seeded.derive("bloom"), Seeded::new(7).derive("bloom"));
+    /// assert_ne!(seeded.derive("bloom"), seeded.derive("minhash"));
+    /// assert_ne!(seeded.derive("bloom"), Seeded::new(8).derive("bloom"));
+    /// ```
+    pub fn derive(&self, name: &str) -> u64 {
+        // FNV-1a, which unlike the std hashers is guaranteed to be stable
+        let hash = name.bytes().fold(FNV_OFFSET, fnv);
+        mix(mix(self.seed) ^ hash)
+    }
+
+    /// Returns a random generator seeded with the seed of the configuration.
+    pub fn rng(&self) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.seed)
+    }
+
+    /// Returns a random generator seeded with the seed of the component called `name`.
+    pub fn rng_for(&self, name: &str) -> ChaCha8Rng {
+        ChaCha8Rng::seed_from_u64(self.derive(name))
+    }
+}
+
+impl From<u64> for Seeded {
+    fn from(seed: u64) -> Self {
+        Self::new(seed)
+    }
+}
+
+#[cfg(feature = "arbitrary")]
+impl<'a> arbitrary::Arbitrary<'a> for Seeded {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u64::arbitrary(u).map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        u64::size_hint(depth)
+    }
+}
+
+/// The description of a randomized structure: its kind and the parameters,
+/// such as the sizes and the seeds, which determine how it hashes or samples.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::seeded::Fingerprint;
+///
+/// let fingerprint = Fingerprint::new("Sketch").with("width", 64).with("seed", 7);
+/// assert_eq!(fingerprint.name(), "Sketch");
+/// assert_eq!(fingerprint.get("seed"), Some(7));
+/// assert_eq!(fingerprint.get("depth"), None);
+/// assert_eq!(fingerprint.to_string(), "Sketch(width=64, seed=7)");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct Fingerprint {
+    name: &'static str,
+    params: Vec<(&'static str, u64)>,
+}
+
+impl Fingerprint {
+    /// Creates the fingerprint of a kind of structure, without parameters.
+    pub fn new(name: &'static str) -> Self {
+        Self {
+            name,
+            params: vec![],
+        }
+    }
+
+    /// Returns the fingerprint with one more parameter.
+    pub fn with(mut self, param: &'static str, value: u64) -> Self {
+        self.params.push((param, value));
+        self
+    }
+
+    /// Returns the kind of the structure.
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// Returns the value of a parameter, or `None` if the structure has no such parameter.
+    pub fn get(&self, param: &str) -> Option<u64> {
+        self.params
+            .iter()
+            .find(|(p, _)| *p == param)
+            .map(|(_, value)| *value)
+    }
+
+    /// An iterator visiting the parameters and their values.
+    pub fn params(&self) -> impl Iterator<Item = (&'static str, u64)> + '_ {
+        self.params.iter().copied()
+    }
+}
+
+impl Display for Fingerprint {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(", self.name)?;
+        for (i, (param, value)) in self.params.iter().enumerate() {
+            if i > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{param}={value}")?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Structures whose behavior is determined by parameters and seeds, described by a [`Fingerprint`].
+pub trait Fingerprinted {
+    /// Returns the description of the parameters of the structure.
+    fn fingerprint(&self) -> Fingerprint;
+
+    /// Returns true if the two structures have the same parameters,
+    /// so their results can be compared or merged.
+    fn is_compatible(&self, other: &Self) -> bool {
+        self.fingerprint() == other.fingerprint()
+    }
+}
+
+/// Panics with both fingerprints when two structures cannot be merged.
+pub(crate) fn assert_compatible<T: Fingerprinted>(this: &T, other: &T) {
+    let (this, other) = (this.fingerprint(), other.fingerprint());
+    assert!(this == other, "cannot merge {this} with {other}");
+}
+
+/// A [`Hasher`] whose hashes depend only on the written bytes and a seed, and not on the
+/// Rust release or the platform, so hashes persisted by a sketch stay valid.
+///
+/// The bytes are hashed with FNV-1a and the hash is finished with the SplitMix64 finalizer,
+/// which mixes all the bits. Integers are written in little-endian order, and `usize` and
+/// `isize` as 64 bit integers, so 32 and 64 bit platforms agree. The hashes are as stable
+/// as the `Hash` implementations of the items, which for integers, strings, slices and
+/// tuples write the same values across releases.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::seeded::{stable_hash, StableHasher};
+/// use std::hash::{Hash, Hasher};
+///
+/// let mut hasher = StableHasher::with_seed(7);
+/// "item".hash(&mut hasher);
+/// assert_eq!(hasher.finish(), stable_hash("item", 7));
+/// assert_ne!(stable_hash("item", 7), stable_hash("item", 8));
+/// ```
+#[derive(Clone, Debug)]
+pub struct StableHasher {
+    state: u64,
+}
+
+impl StableHasher {
+    /// Creates a hasher without a seed.
+    pub fn new() -> Self {
+        Self::with_seed(0)
+    }
+
+    /// Creates a hasher salted with `seed`. Different seeds give unrelated hashes.
+    pub fn with_seed(seed: u64) -> Self {
+        Self {
+            state: FNV_OFFSET ^ mix(seed),
+        }
+    }
+}
+
+impl Default for StableHasher {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hasher for StableHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        mix(self.state)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.state = bytes.iter().fold(self.state, |hash, byte| fnv(hash, *byte));
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.state = fnv(self.state, i);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.write(&i.to_le_bytes());
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.write_u64(i as u64);
+    }
+}
+
+/// Returns the hash of an item computed by a [`StableHasher`] salted with `seed`.
+#[inline]
+pub fn stable_hash<T: Hash + ?Sized>(item: &T, seed: u64) -> u64 {
+    let mut hasher = StableHasher::with_seed(seed);
+    item.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Adds a byte to an FNV-1a hash.
+#[inline]
+fn fnv(hash: u64, byte: u8) -> u64 {
+    (hash ^ byte as u64).wrapping_mul(FNV_PRIME)
+}
+
+/// The finalizer of the SplitMix64 generator, a cheap bijective mixing of the bits.
+#[inline]
+pub(crate) fn mix(mut x: u64) -> u64 {
+    x = (x ^ (x >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x = (x ^ (x >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    x ^ (x >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+    use rand::Rng;
+
+    #[test]
+    fn stable_() {
+        // the derived seeds must not change between releases
+        assert_eq!(Seeded::new(0).derive(""), mix(mix(0) ^ FNV_OFFSET));
+        assert_eq!(Seeded::default(), Seeded::from(0));
+    }
+
+    #[test]
+    fn stable_hash_() {
+        // the hashes must not change between releases or platforms
+        assert_eq!(stable_hash(&0_u8, 0), mix(fnv(FNV_OFFSET, 0)));
+        assert_eq!(stable_hash(&1_usize, 0), stable_hash(&1_u64, 0));
+        assert_eq!(stable_hash("", 0), mix(fnv(FNV_OFFSET, 0xff)));
+        assert_eq!(StableHasher::default().finish(), mix(FNV_OFFSET));
+    }
+
+    #[test]
+    fn fingerprint_() {
+        let empty = Fingerprint::new("Empty");
+        assert_eq!(empty.to_string(), "Empty()");
+        assert_eq!(empty.params().count(), 0);
+
+        let xs = Fingerprint::new("Sketch").with("seed", 1);
+        assert_ne!(xs, Fingerprint::new("Sketch").with("seed", 2));
+        assert_ne!(xs, Fingerprint::new("Other").with("seed", 1));
+    }
+
+    #[quickcheck]
+    fn rng_(seed: u64, name: String) -> bool {
+        let seeded = Seeded::new(seed);
+        seeded.rng_for(&name).gen::<u64>() == seeded.rng_for(&name).gen::<u64>()
+            && seeded.rng().gen::<u64>() == Seeded::new(seed).rng().gen::<u64>()
+    }
+}
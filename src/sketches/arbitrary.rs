@@ -0,0 +1,71 @@
+use super::{BloomFilter, CountMinSketch, HyperLogLog, HLL_MAX_PRECISION, HLL_MIN_PRECISION};
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+/// The largest number of bits of an arbitrary filter, so a few input bytes cannot
+/// ask for a large allocation.
+const MAX_BLOOM_BITS: usize = 1 << 16;
+
+/// The largest number of counters of an arbitrary count-min sketch.
+const MAX_COUNT_MIN_COUNTERS: usize = 1 << 12;
+
+impl<'a> Arbitrary<'a> for HyperLogLog {
+    /// Creates a sketch with an arbitrary precision and seed, and inserts arbitrary hashes into it.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let precision = u.int_in_range(HLL_MIN_PRECISION..=HLL_MAX_PRECISION)?;
+        let mut hll = HyperLogLog::new(precision).with_seed(u.arbitrary()?);
+        for hash in u.arbitrary_iter::<u64>()? {
+            hll.insert_hash(hash?);
+        }
+        Ok(hll)
+    }
+}
+
+impl<'a> Arbitrary<'a> for BloomFilter {
+    /// Creates a filter with arbitrary sizes and seed, and inserts arbitrary items into it.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let num_bits = u.int_in_range(1..=MAX_BLOOM_BITS)?;
+        let num_hashes = u.int_in_range(1..=num_bits.min(16))?;
+        let mut filter = BloomFilter::new(num_bits, num_hashes).with_seed(u.arbitrary()?);
+        for item in u.arbitrary_iter::<u64>()? {
+            filter.insert(&item?);
+        }
+        Ok(filter)
+    }
+}
+
+impl<'a> Arbitrary<'a> for CountMinSketch {
+    /// Creates a sketch with arbitrary sizes and seed, and counts arbitrary items into it.
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let depth = u.int_in_range(1..=8)?;
+        let width = u.int_in_range(1..=MAX_COUNT_MIN_COUNTERS / depth)?;
+        let mut cms = CountMinSketch::new(width, depth).with_seed(u.arbitrary()?);
+        for pair in u.arbitrary_iter::<(u64, u64)>()? {
+            let (item, count) = pair?;
+            cms.insert_count(&item, count);
+        }
+        Ok(cms)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::sketches::Frame;
+
+    #[test]
+    fn arbitrary_() {
+        let data: Vec<u8> = (0..=255).cycle().take(1_000).collect();
+
+        let hll = HyperLogLog::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!((HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&hll.precision()));
+        assert_eq!(HyperLogLog::from_frame(&hll.to_frame()), Ok(hll));
+
+        let filter = BloomFilter::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!(filter.num_bits() <= MAX_BLOOM_BITS);
+        assert_eq!(BloomFilter::from_frame(&filter.to_frame()), Ok(filter));
+
+        let cms = CountMinSketch::arbitrary(&mut Unstructured::new(&data)).unwrap();
+        assert!(cms.width() * cms.depth() <= MAX_COUNT_MIN_COUNTERS);
+        assert_eq!(CountMinSketch::from_frame(&cms.to_frame()), Ok(cms));
+    }
+}
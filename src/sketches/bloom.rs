@@ -0,0 +1,530 @@
+use super::{DecodeError, Frame, FrameReader, FrameWriter};
+use crate::bits::{BVec, Bit};
+use crate::collections::{Absorb, Mergeable};
+use crate::seeded::{assert_compatible, mix, stable_hash, Fingerprint, Fingerprinted};
+use std::hash::Hash;
+
+/// A [Bloom filter](https://en.wikipedia.org/wiki/Bloom_filter) which answers approximate
+/// set membership queries. It has no false negatives, and its false positive rate grows
+/// with the number of inserted items.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::BloomFilter;
+///
+/// let mut filter = BloomFilter::with_rate(1_000, 0.01);
+/// filter.insert("apple");
+/// filter.insert("pear");
+///
+/// assert!(filter.contains("apple"));
+/// assert!(filter.contains_all(["apple", "pear"]));
+/// assert!(!filter.contains_all(["apple", "plum"]));
+/// assert!(filter.contains_any(["plum", "pear"]));
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BloomFilter {
+    bits: BVec,
+    num_hashes: usize,
+    seed: u64,
+}
+
+impl BloomFilter {
+    /// Creates an empty filter with `num_bits` bits and `num_hashes` hash functions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `num_bits` or `num_hashes` is zero, or if there are more hashes than bits.
+    pub fn new(num_bits: usize, num_hashes: usize) -> Self {
+        assert!(num_bits > 0, "the number of bits is zero");
+        assert!(num_hashes > 0, "the number of hashes is zero");
+        assert!(num_hashes <= num_bits, "there are more hashes than bits");
+
+        Self {
+            bits: BVec::with_length(num_bits),
+            num_hashes,
+            seed: 0,
+        }
+    }
+
+    /// Returns an empty filter with the same sizes whose hash functions are salted with `seed`.
+    /// Filters with different seeds set different bits for the same items, so they cannot be merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::sketches::BloomFilter;
+    ///
+    /// let mut filter = BloomFilter::with_rate(100, 0.01).with_seed(7);
+    /// filter.insert("apple");
+    /// assert!(filter.contains("apple"));
+    /// assert_eq!(filter.seed(), 7);
+    /// ```
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            bits: BVec::with_length(self.bits.len()),
+            num_hashes: self.num_hashes,
+            seed,
+        }
+    }
+
+    /// Creates an empty filter sized for `expected_items` items at a false positive rate of about `fp_rate`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `fp_rate` is not in the `(0, 1)` range.
+    pub fn with_rate(expected_items: usize, fp_rate: f64) -> Self {
+        assert!(
+            fp_rate > 0. && fp_rate < 1.,
+            "the false positive rate is not in (0, 1)"
+        );
+
+        let ln2 = std::f64::consts::LN_2;
+        let n = expected_items.max(1) as f64;
+        let num_bits = (-n * fp_rate.ln() / (ln2 * ln2)).ceil() as usize;
+        let num_hashes = ((num_bits as f64 / n) * ln2).round() as usize;
+
+        Self::new(num_bits.max(1), num_hashes.max(1))
+    }
+
+    /// Returns the number of bits of the filter.
+    pub fn num_bits(&self) -> usize {
+        self.bits.len()
+    }
+
+    /// Returns the number of hash functions of the filter.
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+
+    /// Returns the seed of the hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns true if no item was inserted into the filter.
+    pub fn is_empty(&self) -> bool {
+        self.bits.count_ones() == 0
+    }
+
+    /// Inserts an item into the filter.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        let (h1, h2) = hashes(self.seed, item);
+        for i in 0..self.num_hashes {
+            let bit = probe(self.bits.len(), h1, h2, i);
+            self.bits.set_bit(bit);
+        }
+    }
+
+    /// Returns true if the item may have been inserted, false if it certainly was not.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        let (h1, h2) = hashes(self.seed, item);
+        (0..self.num_hashes)
+            .all(|i| self.bits.get_bit(probe(self.bits.len(), h1, h2, i)) == Bit::One)
+    }
+
+    /// Returns true if all the items may have been inserted. It stops at the first missing item.
+    pub fn contains_all<'a, I, T>(&self, items: I) -> bool
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: Hash + ?Sized + 'a,
+    {
+        items.into_iter().all(|item| self.contains(item))
+    }
+
+    /// Returns true if any of the items may have been inserted. It stops at the first present item.
+    pub fn contains_any<'a, I, T>(&self, items: I) -> bool
+    where
+        I: IntoIterator<Item = &'a T>,
+        T: Hash + ?Sized + 'a,
+    {
+        items.into_iter().any(|item| self.contains(item))
+    }
+
+    /// Queries a batch of items and returns a bit vector whose bit `i` is set
+    /// when the `i`-th item may have been inserted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::bits::Bit;
+    /// use aabel_rs::sketches::BloomFilter;
+    ///
+    /// let mut filter = BloomFilter::with_rate(100, 0.001);
+    /// filter.insert(&2);
+    /// filter.insert(&3);
+    ///
+    /// let found = filter.contains_many(&[1, 2, 3]);
+    /// assert_eq!(found.ones().collect::<Vec<_>>(), [1, 2]);
+    /// ```
+    pub fn contains_many<T: Hash>(&self, items: &[T]) -> BVec {
+        let mut found = BVec::with_length(items.len());
+        for (idx, item) in items.iter().enumerate() {
+            if self.contains(item) {
+                found.set_bit(idx);
+            }
+        }
+        found
+    }
+}
+
+impl<T: Hash> Absorb<T> for BloomFilter {
+    /// Inserts the item into the filter.
+    fn absorb(&mut self, item: T) {
+        self.insert(&item);
+    }
+}
+
+impl Fingerprinted for BloomFilter {
+    /// Describes the number of bits, the number of hashes and the seed, which determine the probed bits.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("BloomFilter")
+            .with("num_bits", self.bits.len() as u64)
+            .with("num_hashes", self.num_hashes as u64)
+            .with("seed", self.seed)
+    }
+}
+
+impl Mergeable for BloomFilter {
+    /// Merges another filter into this one, so the result contains the items of both.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two filters have different sizes or seeds.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::collections::Mergeable;
+    /// use aabel_rs::sketches::BloomFilter;
+    ///
+    /// let mut xs = BloomFilter::new(1 << 10, 3);
+    /// let mut ys = BloomFilter::new(1 << 10, 3);
+    /// xs.insert("apple");
+    /// ys.insert("pear");
+    ///
+    /// xs.merge_from(ys);
+    /// assert!(xs.contains_all(["apple", "pear"]));
+    /// ```
+    fn merge_from(&mut self, other: Self) {
+        assert_compatible(self, &other);
+        self.bits = &self.bits | &other.bits;
+    }
+}
+
+const BLOOM_TAG: [u8; 2] = *b"BF";
+
+/// A filter is encoded as its number of bits, its number of hashes and its seed,
+/// followed by the bytes of its bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::{BloomFilter, DecodeError, Frame};
+///
+/// let mut filter = BloomFilter::new(100, 3).with_seed(7);
+/// filter.insert("apple");
+///
+/// let frame = filter.to_frame();
+/// assert_eq!(BloomFilter::from_frame(&frame), Ok(filter));
+/// assert_eq!(BloomFilter::from_frame(&frame[..10]), Err(DecodeError::Truncated));
+/// ```
+impl Frame for BloomFilter {
+    fn to_frame(&self) -> Vec<u8> {
+        let mut writer = FrameWriter::new(BLOOM_TAG);
+        writer.varint(self.bits.len() as u64);
+        writer.varint(self.num_hashes as u64);
+        writer.varint(self.seed);
+        writer.bytes(self.bits.as_bytes());
+        writer.finish()
+    }
+
+    fn from_frame(frame: &[u8]) -> Result<Self, DecodeError> {
+        BloomFilterView::new(frame).map(|view| view.to_owned())
+    }
+
+    fn apply_delta(&mut self, frame: &[u8]) -> Result<(), DecodeError> {
+        let delta = Self::from_frame(frame)?;
+        if !self.is_compatible(&delta) {
+            return Err(DecodeError::Mismatch);
+        }
+
+        self.merge_from(delta);
+        Ok(())
+    }
+}
+
+/// A read-only view of a [`BloomFilter`] over its frame, which answers membership queries
+/// directly from the borrowed bytes, for example from a memory mapped file or a network
+/// buffer, without copying the bits.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::{BloomFilter, BloomFilterView, Frame};
+///
+/// let mut filter = BloomFilter::with_rate(1_000, 0.01).with_seed(7);
+/// filter.insert("apple");
+/// filter.insert("pear");
+///
+/// let frame = filter.to_frame();
+/// let view = BloomFilterView::new(&frame).unwrap();
+/// assert!(view.contains("apple"));
+/// assert!(view.contains_all(["apple", "pear"]));
+/// assert!(!view.contains_any(["plum", "fig"]));
+/// assert_eq!(view.to_owned(), filter);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct BloomFilterView<'a> {
+    bytes: &'a [u8],
+    num_bits: usize,
+    num_hashes: usize,
+    seed: u64,
+}
+
+impl<'a> BloomFilterView<'a> {
+    /// Creates a view over a frame written by [`Frame::to_frame`], after checking it.
+    pub fn new(frame: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut reader = FrameReader::new(BLOOM_TAG, frame)?;
+
+        let num_bits = usize::try_from(reader.varint()?).map_err(|_| DecodeError::Overflow)?;
+        let num_hashes = usize::try_from(reader.varint()?).map_err(|_| DecodeError::Overflow)?;
+        let seed = reader.varint()?;
+        if num_bits == 0 || num_hashes == 0 {
+            return Err(DecodeError::Invalid);
+        }
+        if num_hashes > num_bits {
+            // each query would probe the bits more than once
+            return Err(DecodeError::Overflow);
+        }
+
+        let bytes = reader.bytes(num_bits.div_ceil(8))?;
+        reader.finish()?;
+
+        let padding = (8 - num_bits % 8) % 8;
+        if bytes[bytes.len() - 1] & ((1 << padding) - 1) != 0 {
+            // the padding bits of the last byte are set
+            return Err(DecodeError::Invalid);
+        }
+
+        Ok(Self {
+            bytes,
+            num_bits,
+            num_hashes,
+            seed,
+        })
+    }
+
+    /// Returns the number of bits of the filter.
+    pub fn num_bits(&self) -> usize {
+        self.num_bits
+    }
+
+    /// Returns the number of hash functions of the filter.
+    pub fn num_hashes(&self) -> usize {
+        self.num_hashes
+    }
+
+    /// Returns the seed of the hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns true if the item may have been inserted, false if it certainly was not.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        let (h1, h2) = hashes(self.seed, item);
+        (0..self.num_hashes).all(|i| {
+            let bit = probe(self.num_bits, h1, h2, i);
+            self.bytes[bit / 8] & (0x80 >> (bit % 8)) != 0
+        })
+    }
+
+    /// Returns true if all the items may have been inserted. It stops at the first missing item.
+    pub fn contains_all<'b, I, T>(&self, items: I) -> bool
+    where
+        I: IntoIterator<Item = &'b T>,
+        T: Hash + ?Sized + 'b,
+    {
+        items.into_iter().all(|item| self.contains(item))
+    }
+
+    /// Returns true if any of the items may have been inserted. It stops at the first present item.
+    pub fn contains_any<'b, I, T>(&self, items: I) -> bool
+    where
+        I: IntoIterator<Item = &'b T>,
+        T: Hash + ?Sized + 'b,
+    {
+        items.into_iter().any(|item| self.contains(item))
+    }
+
+    /// Copies the filter into an owned [`BloomFilter`].
+    pub fn to_owned(&self) -> BloomFilter {
+        BloomFilter {
+            bits: BVec::from_bytes(self.bytes, self.num_bits),
+            num_hashes: self.num_hashes,
+            seed: self.seed,
+        }
+    }
+}
+
+/// Returns the bit probed by the `i`-th hash function, using double hashing.
+#[inline]
+fn probe(num_bits: usize, h1: u64, h2: u64, i: usize) -> usize {
+    (h1.wrapping_add((i as u64).wrapping_mul(h2)) % num_bits as u64) as usize
+}
+
+/// Returns the two base hashes of an item, from its stable hash salted with the seed.
+fn hashes<T: Hash + ?Sized>(seed: u64, item: &T) -> (u64, u64) {
+    let h1 = stable_hash(item, seed);
+
+    // the second hash is odd, so the probes do not collapse for even sizes
+    let h2 = mix(h1) | 1;
+    (h1, h2)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new_() {
+        let filter = BloomFilter::new(100, 3);
+        assert_eq!(filter.num_bits(), 100);
+        assert_eq!(filter.num_hashes(), 3);
+        assert!(filter.is_empty());
+        assert!(!filter.contains("a"));
+    }
+
+    #[test]
+    fn with_rate_() {
+        let filter = BloomFilter::with_rate(1_000, 0.01);
+        assert_eq!(filter.num_bits(), 9_586);
+        assert_eq!(filter.num_hashes(), 7);
+    }
+
+    #[test]
+    #[should_panic]
+    fn with_rate_invalid_() {
+        let _ = BloomFilter::with_rate(1_000, 1.);
+    }
+
+    #[test]
+    fn false_positives_() {
+        let mut filter = BloomFilter::with_rate(1_000, 0.01);
+        for i in 0..1_000 {
+            filter.insert(&i);
+        }
+
+        assert!(filter.contains_all(&(0..1_000).collect::<Vec<_>>()));
+
+        let fps = (1_000..11_000).filter(|i| filter.contains(i)).count();
+        assert!(fps < 200, "{fps} false positives");
+    }
+
+    #[test]
+    fn contains_batch_() {
+        let mut filter = BloomFilter::new(1 << 12, 4);
+        for word in ["a", "b", "c"] {
+            filter.insert(word);
+        }
+
+        assert!(filter.contains_all(["a", "b", "c"]));
+        assert!(filter.contains_all(Vec::<&str>::new()));
+        assert!(!filter.contains_any(Vec::<&str>::new()));
+        assert!(!filter.contains_any(["x", "y"]));
+
+        let found = filter.contains_many(&["x", "a", "y", "c"]);
+        assert_eq!(found.len(), 4);
+        assert_eq!(found.ones().collect::<Vec<_>>(), [1, 3]);
+    }
+
+    #[test]
+    fn seed_() {
+        let mut xs = BloomFilter::new(1 << 10, 4).with_seed(1);
+        let mut ys = BloomFilter::new(1 << 10, 4).with_seed(2);
+        xs.insert("a");
+        ys.insert("a");
+
+        assert!(ys.contains("a"));
+        assert_ne!(xs, ys);
+        assert!(!xs.is_compatible(&ys));
+        assert_eq!(xs.fingerprint().get("seed"), Some(1));
+    }
+
+    #[test]
+    fn stable_() {
+        // the probed bits do not depend on the platform nor on the release
+        let mut filter = BloomFilter::new(64, 3);
+        filter.insert("a");
+        assert_eq!(
+            filter.to_frame(),
+            [b'B', b'F', 1, 64, 3, 0, 0, 0, 0, 0, 16, 8, 4, 0]
+        );
+    }
+
+    #[test]
+    fn frame_errors_() {
+        let mut filter = BloomFilter::new(12, 2);
+        filter.insert("a");
+        filter
+            .apply_delta(&BloomFilter::new(12, 2).to_frame())
+            .unwrap();
+        assert_eq!(
+            filter.apply_delta(&BloomFilter::new(12, 2).with_seed(1).to_frame()),
+            Err(DecodeError::Mismatch)
+        );
+
+        // no bits, no hashes and padding bits set
+        for invalid in [
+            &b"BF\x01\x00\x01\x00"[..],
+            b"BF\x01\x0c\x00\x00\x00\x00",
+            b"BF\x01\x0c\x01\x00\x00\x01",
+        ] {
+            assert_eq!(BloomFilter::from_frame(invalid), Err(DecodeError::Invalid));
+        }
+
+        // a huge filter is rejected without allocating it
+        assert_eq!(
+            BloomFilter::from_frame(b"BF\x01\xff\xff\xff\xff\x0f\x01\x00\x00"),
+            Err(DecodeError::Truncated)
+        );
+        // a few bytes cannot make every query loop for long
+        assert_eq!(
+            BloomFilter::from_frame(b"BF\x01\x08\xff\xff\xff\xff\x0f\x00\x00"),
+            Err(DecodeError::Overflow)
+        );
+    }
+
+    #[test]
+    fn view_() {
+        let mut filter = BloomFilter::new(1001, 5).with_seed(3);
+        (0..100).for_each(|i| filter.insert(&i));
+
+        let frame = filter.to_frame();
+        let view = BloomFilterView::new(&frame).unwrap();
+        assert_eq!(view.num_bits(), 1001);
+        assert_eq!(view.num_hashes(), 5);
+        assert_eq!(view.seed(), 3);
+        assert!((0..1000).all(|i| view.contains(&i) == filter.contains(&i)));
+
+        assert_eq!(
+            BloomFilterView::new(&frame[..frame.len() - 1]).err(),
+            Some(DecodeError::Truncated)
+        );
+        assert_eq!(
+            BloomFilterView::new(b"BF\x01\x0c\x01\x00\x00\x01").err(),
+            Some(DecodeError::Invalid)
+        );
+        assert_eq!(
+            BloomFilterView::new(b"BF\x01\x08\xff\xff\xff\xff\x0f\x00\x00").err(),
+            Some(DecodeError::Overflow)
+        );
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_seed_() {
+        let mut xs = BloomFilter::new(1 << 10, 4).with_seed(1);
+        xs.merge_from(BloomFilter::new(1 << 10, 4));
+    }
+}
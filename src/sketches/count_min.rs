@@ -0,0 +1,397 @@
+use super::{DecodeError, Frame, FrameReader, FrameWriter};
+use crate::collections::{Absorb, Mergeable};
+use crate::seeded::{assert_compatible, mix, stable_hash, Fingerprint, Fingerprinted};
+use std::hash::Hash;
+
+/// A [count-min sketch](https://en.wikipedia.org/wiki/Count%E2%80%93min_sketch) which estimates
+/// the number of occurrences of the items of a stream.
+///
+/// Each of the `depth` rows of `width` counters counts every item in one of its counters,
+/// chosen by a hash of the item. The estimate of an item is its smallest counter, which is
+/// never smaller than its true count, and exceeds it by more than `e / width` of the total
+/// with a probability of at most `exp(-depth)`. The counters saturate instead of overflowing.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::CountMinSketch;
+///
+/// let mut cms = CountMinSketch::with_error(0.01, 0.01);
+/// for i in 0..1_000 {
+///     cms.insert(&(i % 10));
+/// }
+///
+/// assert!(cms.estimate(&3) >= 100);
+/// assert!(cms.estimate(&3) <= 100 + 10);
+/// assert_eq!(cms.total(), 1_000);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountMinSketch {
+    width: usize,
+    depth: usize,
+    seed: u64,
+    total: u64,
+    // the rows one after the other
+    counters: Vec<u64>,
+}
+
+impl CountMinSketch {
+    /// Creates an empty sketch with `depth` rows of `width` counters.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `width` or `depth` is zero.
+    pub fn new(width: usize, depth: usize) -> Self {
+        assert!(width > 0, "the width is zero");
+        assert!(depth > 0, "the depth is zero");
+
+        Self {
+            width,
+            depth,
+            seed: 0,
+            total: 0,
+            counters: vec![0; width * depth],
+        }
+    }
+
+    /// Creates an empty sketch whose estimates exceed the true counts by at most `epsilon`
+    /// times the total, with a probability of at least `1 - delta`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `epsilon` or `delta` is not in the `(0, 1)` range.
+    pub fn with_error(epsilon: f64, delta: f64) -> Self {
+        assert!(
+            epsilon > 0. && epsilon < 1.,
+            "the error rate is not in (0, 1)"
+        );
+        assert!(delta > 0. && delta < 1., "the probability is not in (0, 1)");
+
+        let width = (std::f64::consts::E / epsilon).ceil() as usize;
+        let depth = (1. / delta).ln().ceil() as usize;
+        Self::new(width, depth.max(1))
+    }
+
+    /// Returns an empty sketch with the same sizes whose hash functions are salted with `seed`.
+    /// Sketches with different seeds count the same items in different counters,
+    /// so they cannot be merged.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::new(self.width, self.depth)
+        }
+    }
+
+    /// Returns the number of counters of each row.
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    /// Returns the number of rows.
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// Returns the seed of the hash functions.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of occurrences counted by the sketch.
+    pub fn total(&self) -> u64 {
+        self.total
+    }
+
+    /// Returns true if no item was counted.
+    pub fn is_empty(&self) -> bool {
+        self.total == 0
+    }
+
+    /// Counts one occurrence of an item.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        self.insert_count(item, 1);
+    }
+
+    /// Counts `count` occurrences of an item.
+    pub fn insert_count<T: Hash + ?Sized>(&mut self, item: &T, count: u64) {
+        for cell in cells(stable_hash(item, self.seed), self.width, self.depth) {
+            self.counters[cell] = self.counters[cell].saturating_add(count);
+        }
+        self.total = self.total.saturating_add(count);
+    }
+
+    /// Returns the estimated number of occurrences of an item, which is never smaller than
+    /// the true number.
+    pub fn estimate<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        cells(stable_hash(item, self.seed), self.width, self.depth)
+            .map(|cell| self.counters[cell])
+            .min()
+            .unwrap_or(0)
+    }
+}
+
+impl<T: Hash> Absorb<T> for CountMinSketch {
+    /// Counts one occurrence of the item.
+    fn absorb(&mut self, item: T) {
+        self.insert(&item);
+    }
+}
+
+impl Fingerprinted for CountMinSketch {
+    /// Describes the width, the depth and the seed, which determine the counters of the items.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("CountMinSketch")
+            .with("width", self.width as u64)
+            .with("depth", self.depth as u64)
+            .with("seed", self.seed)
+    }
+}
+
+impl Mergeable for CountMinSketch {
+    /// Merges another sketch into this one, so the result counts the items of both streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sketches have different sizes or seeds.
+    fn merge_from(&mut self, other: Self) {
+        assert_compatible(self, &other);
+
+        for (counter, o) in self.counters.iter_mut().zip(other.counters) {
+            *counter = counter.saturating_add(o);
+        }
+        self.total = self.total.saturating_add(other.total);
+    }
+}
+
+/// Returns the counter of an item in each row, using double hashing.
+fn cells(h1: u64, width: usize, depth: usize) -> impl Iterator<Item = usize> {
+    // the second hash is odd, so the rows do not collapse for even widths
+    let h2 = mix(h1) | 1;
+
+    (0..depth).map(move |row| {
+        let column = h1.wrapping_add((row as u64).wrapping_mul(h2)) % width as u64;
+        row * width + column as usize
+    })
+}
+
+const COUNT_MIN_TAG: [u8; 2] = *b"CM";
+
+/// A sketch is encoded as its width, its depth and its seed, followed by its counters,
+/// row after row. Every row counts all the occurrences, so the total is the sum of the first row.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::{CountMinSketch, Frame};
+///
+/// let mut aggregator = CountMinSketch::new(64, 4);
+/// for worker in 0..3 {
+///     let mut delta = CountMinSketch::new(64, 4);
+///     delta.insert_count("page", worker + 1);
+///     aggregator.apply_delta(&delta.to_frame()).unwrap();
+/// }
+///
+/// assert_eq!(aggregator.estimate("page"), 6);
+/// ```
+impl Frame for CountMinSketch {
+    fn to_frame(&self) -> Vec<u8> {
+        let mut writer = FrameWriter::new(COUNT_MIN_TAG);
+        writer.varint(self.width as u64);
+        writer.varint(self.depth as u64);
+        writer.varint(self.seed);
+        for counter in &self.counters {
+            writer.varint(*counter);
+        }
+        writer.finish()
+    }
+
+    fn from_frame(frame: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = FrameReader::new(COUNT_MIN_TAG, frame)?;
+
+        let width = usize::try_from(reader.varint()?).map_err(|_| DecodeError::Overflow)?;
+        let depth = usize::try_from(reader.varint()?).map_err(|_| DecodeError::Overflow)?;
+        let seed = reader.varint()?;
+        if width == 0 || depth == 0 {
+            return Err(DecodeError::Invalid);
+        }
+        let len = width.checked_mul(depth).ok_or(DecodeError::Overflow)?;
+
+        // the counters are read one at a time, so a short frame cannot ask for a large allocation
+        let mut counters = Vec::new();
+        for _ in 0..len {
+            counters.push(reader.varint()?);
+        }
+        reader.finish()?;
+
+        // the counters saturate like the total, so the sum of a row saturates to the total
+        let total = counters[..width]
+            .iter()
+            .fold(0_u64, |total, counter| total.saturating_add(*counter));
+
+        Ok(Self {
+            width,
+            depth,
+            seed,
+            total,
+            counters,
+        })
+    }
+
+    fn apply_delta(&mut self, frame: &[u8]) -> Result<(), DecodeError> {
+        let delta = Self::from_frame(frame)?;
+        if !self.is_compatible(&delta) {
+            return Err(DecodeError::Mismatch);
+        }
+
+        self.merge_from(delta);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::merge_all;
+    use crate::sketches::FRAME_VERSION;
+    use quickcheck_macros::quickcheck;
+    use std::collections::HashMap;
+
+    #[test]
+    fn empty_() {
+        let cms = CountMinSketch::new(16, 3);
+        assert!(cms.is_empty());
+        assert_eq!(cms.estimate("a"), 0);
+        assert_eq!((cms.width(), cms.depth()), (16, 3));
+    }
+
+    #[test]
+    fn with_error_() {
+        let cms = CountMinSketch::with_error(0.001, 0.01);
+        assert_eq!(cms.width(), 2_719);
+        assert_eq!(cms.depth(), 5);
+    }
+
+    #[quickcheck]
+    fn upper_bounds_(items: Vec<u8>) -> bool {
+        let mut cms = CountMinSketch::new(8, 3);
+        let mut counts: HashMap<u8, u64> = HashMap::new();
+        for item in &items {
+            cms.insert(item);
+            *counts.entry(*item).or_default() += 1;
+        }
+
+        cms.total() == items.len() as u64
+            && counts
+                .iter()
+                .all(|(item, count)| cms.estimate(item) >= *count)
+    }
+
+    #[test]
+    fn accuracy_() {
+        let mut cms = CountMinSketch::with_error(0.01, 0.001);
+        for i in 0..10_000u32 {
+            cms.insert(&(i % 1_000));
+        }
+        cms.insert_count("heavy", 5_000);
+
+        // the error is at most 1% of the 15000 occurrences
+        assert!((0..1_000u32).all(|i| cms.estimate(&i) <= 10 + 150));
+        assert!((5_000..=5_150).contains(&cms.estimate("heavy")));
+    }
+
+    #[test]
+    fn merge_() {
+        let shards = (0..4u32).map(|s| {
+            let mut cms = CountMinSketch::new(256, 4);
+            // the shards share half of their items
+            (0..100u32).for_each(|i| cms.insert(&(s * 5 + i % 10)));
+            cms
+        });
+
+        let merged = merge_all(shards).unwrap();
+        assert_eq!(merged.total(), 400);
+        assert_eq!(merged.estimate(&7), 20);
+        assert_eq!(merged.estimate(&0), 10);
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_sizes_() {
+        let mut xs = CountMinSketch::new(16, 2);
+        xs.merge_from(CountMinSketch::new(16, 3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_seed_() {
+        let mut xs = CountMinSketch::new(16, 2);
+        xs.merge_from(CountMinSketch::new(16, 2).with_seed(1));
+    }
+
+    #[test]
+    fn frame_() {
+        let mut cms = CountMinSketch::new(10, 3).with_seed(300);
+        (0..50u32).for_each(|i| cms.insert(&i));
+
+        let frame = cms.to_frame();
+        assert_eq!(&frame[..3], [b'C', b'M', FRAME_VERSION]);
+        assert_eq!(CountMinSketch::from_frame(&frame), Ok(cms));
+
+        let empty = CountMinSketch::new(2, 1);
+        assert_eq!(empty.to_frame(), [b'C', b'M', 1, 2, 1, 0, 0, 0]);
+    }
+
+    #[quickcheck]
+    fn frame_roundtrip_(counts: Vec<(u8, u64)>) -> bool {
+        // large counts saturate the counters and the total
+        let mut cms = CountMinSketch::new(4, 2);
+        for (item, count) in counts {
+            cms.insert_count(&item, count);
+        }
+
+        CountMinSketch::from_frame(&cms.to_frame()) == Ok(cms)
+    }
+
+    #[test]
+    fn frame_errors_() {
+        let mut cms = CountMinSketch::new(2, 2);
+        assert_eq!(
+            cms.apply_delta(&CountMinSketch::new(3, 2).to_frame()),
+            Err(DecodeError::Mismatch)
+        );
+        assert_eq!(
+            cms.apply_delta(&CountMinSketch::new(2, 2).with_seed(1).to_frame()),
+            Err(DecodeError::Mismatch)
+        );
+
+        // no counters
+        assert_eq!(
+            CountMinSketch::from_frame(b"CM\x01\x00\x01\x00"),
+            Err(DecodeError::Invalid)
+        );
+        // a huge sketch described by a few bytes
+        assert_eq!(
+            CountMinSketch::from_frame(b"CM\x01\xff\xff\xff\x0f\xff\xff\xff\x0f\x00\x00"),
+            Err(DecodeError::Truncated)
+        );
+
+        // a saturated delta saturates the sketch
+        cms.insert_count("a", u64::MAX);
+        cms.insert("b");
+        let mut delta = CountMinSketch::new(2, 2);
+        delta.insert("a");
+        assert_eq!(cms.apply_delta(&delta.to_frame()), Ok(()));
+        assert_eq!(cms.total(), u64::MAX);
+        assert_eq!(cms.estimate("a"), u64::MAX);
+    }
+
+    #[quickcheck]
+    fn frame_garbage_(content: Vec<u8>) -> bool {
+        // a valid header, so the content itself is decoded
+        let frame = [&[b'C', b'M', FRAME_VERSION][..], &content].concat();
+        match CountMinSketch::from_frame(&frame) {
+            Ok(cms) => CountMinSketch::from_frame(&cms.to_frame()) == Ok(cms),
+            Err(_) => true,
+        }
+    }
+}
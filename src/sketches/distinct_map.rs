@@ -0,0 +1,240 @@
+use super::{HyperLogLog, HLL_MAX_PRECISION, HLL_MIN_PRECISION};
+use crate::collections::{Absorb, Mergeable};
+use crate::seeded::{assert_compatible, Fingerprint, Fingerprinted};
+use std::{
+    borrow::Borrow,
+    collections::{hash_map::Keys, HashMap},
+    hash::Hash,
+};
+
+/// A map which estimates the number of distinct items seen with each key,
+/// keeping a small [`HyperLogLog`] sketch per key, such as the unique users of each page.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::CountedDistinctMap;
+///
+/// let mut visits = CountedDistinctMap::new(8);
+/// visits.insert("home", "alice");
+/// visits.insert("home", "bob");
+/// visits.insert("home", "alice");
+/// visits.insert("about", "bob");
+///
+/// assert_eq!(visits.estimate("home").map(f64::round), Some(2.));
+/// assert_eq!(visits.estimate("about").map(f64::round), Some(1.));
+/// assert_eq!(visits.estimate("blog"), None);
+/// ```
+#[derive(Clone, Debug)]
+pub struct CountedDistinctMap<K> {
+    precision: u8,
+    seed: u64,
+    sketches: HashMap<K, HyperLogLog>,
+}
+
+impl<K> CountedDistinctMap<K> {
+    /// Creates an empty map whose sketches have `2^precision` registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the precision is not in the `4..=16` range.
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&precision),
+            "precision must be between {HLL_MIN_PRECISION} and {HLL_MAX_PRECISION}"
+        );
+
+        Self {
+            precision,
+            seed: 0,
+            sketches: HashMap::new(),
+        }
+    }
+
+    /// Returns an empty map with the same precision whose sketches are salted with `seed`.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::new(self.precision)
+        }
+    }
+
+    /// Returns the precision of the sketches.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns the seed of the sketches.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of keys.
+    pub fn len(&self) -> usize {
+        self.sketches.len()
+    }
+
+    /// Returns true if the map has no keys.
+    pub fn is_empty(&self) -> bool {
+        self.sketches.is_empty()
+    }
+
+    /// An iterator visiting all the keys in arbitrary order.
+    pub fn keys(&self) -> Keys<'_, K, HyperLogLog> {
+        self.sketches.keys()
+    }
+
+    /// Returns the estimated number of distinct items over all the keys.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::sketches::CountedDistinctMap;
+    ///
+    /// let mut visits = CountedDistinctMap::new(8);
+    /// visits.insert("home", "alice");
+    /// visits.insert("about", "alice");
+    /// visits.insert("about", "bob");
+    ///
+    /// assert_eq!(visits.estimate_all().round(), 2.);
+    /// ```
+    pub fn estimate_all(&self) -> f64 {
+        let mut all = HyperLogLog::new(self.precision).with_seed(self.seed);
+        for sketch in self.sketches.values() {
+            all.merge_from(sketch.clone());
+        }
+        all.estimate()
+    }
+}
+
+impl<K> CountedDistinctMap<K>
+where
+    K: Eq + Hash,
+{
+    /// Records an item seen with a key.
+    pub fn insert<T: Hash + ?Sized>(&mut self, key: K, item: &T) {
+        let (precision, seed) = (self.precision, self.seed);
+        self.sketches
+            .entry(key)
+            .or_insert_with(|| HyperLogLog::new(precision).with_seed(seed))
+            .insert(item);
+    }
+
+    /// Returns the estimated number of distinct items seen with a key, or `None` if the key is missing.
+    pub fn estimate<Q>(&self, key: &Q) -> Option<f64>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.sketches.get(key).map(HyperLogLog::estimate)
+    }
+}
+
+impl<K, T> Absorb<(K, T)> for CountedDistinctMap<K>
+where
+    K: Eq + Hash,
+    T: Hash,
+{
+    /// Records an item seen with a key, given as a `(key, item)` pair.
+    fn absorb(&mut self, (key, item): (K, T)) {
+        self.insert(key, &item);
+    }
+}
+
+impl<K> Fingerprinted for CountedDistinctMap<K> {
+    /// Describes the precision and the seed of the sketches.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("CountedDistinctMap")
+            .with("precision", self.precision as u64)
+            .with("seed", self.seed)
+    }
+}
+
+impl<K> Mergeable for CountedDistinctMap<K>
+where
+    K: Eq + Hash,
+{
+    /// Merges another map into this one, so the estimate of each key covers the items of both maps.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two maps have different precisions or seeds.
+    fn merge_from(&mut self, other: Self) {
+        assert_compatible(self, &other);
+
+        for (key, sketch) in other.sketches {
+            match self.sketches.get_mut(&key) {
+                Some(existing) => existing.merge_from(sketch),
+                None => {
+                    self.sketches.insert(key, sketch);
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::merge_all;
+
+    #[test]
+    fn insert_estimate_() {
+        let mut map = CountedDistinctMap::new(12);
+        for i in 0..1_000 {
+            map.insert(i % 3, &(i / 2));
+        }
+
+        assert_eq!(map.len(), 3);
+        assert_eq!(map.precision(), 12);
+        for key in 0..3 {
+            let estimate = map.estimate(&key).unwrap();
+            assert!((estimate - 333.).abs() < 20., "{estimate}");
+        }
+        assert!((map.estimate_all() - 500.).abs() < 25.);
+    }
+
+    #[test]
+    fn empty_() {
+        let map = CountedDistinctMap::<String>::new(4);
+        assert!(map.is_empty());
+        assert_eq!(map.estimate("a"), None);
+        assert_eq!(map.estimate_all(), 0.);
+    }
+
+    #[test]
+    fn merge_() {
+        let shards = (0..4).map(|s| {
+            let mut map = CountedDistinctMap::new(10);
+            for user in 0..100 {
+                map.insert("home", &(s * 50 + user));
+                map.insert(if s % 2 == 0 { "even" } else { "odd" }, &user);
+            }
+            map
+        });
+
+        let map = merge_all(shards).unwrap();
+        assert_eq!(map.len(), 3);
+        assert!((map.estimate("home").unwrap() - 250.).abs() < 10.);
+        assert!((map.estimate("even").unwrap() - 100.).abs() < 5.);
+        assert!((map.estimate("odd").unwrap() - 100.).abs() < 5.);
+    }
+
+    #[test]
+    fn seed_() {
+        let mut map = CountedDistinctMap::new(8).with_seed(5);
+        map.insert("home", "alice");
+        map.insert("about", "alice");
+
+        assert_eq!(map.seed(), 5);
+        assert_eq!(map.estimate_all().round(), 1.);
+        assert!(!map.is_compatible(&CountedDistinctMap::new(8)));
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_precisions_() {
+        let mut xs = CountedDistinctMap::<u32>::new(8);
+        xs.merge_from(CountedDistinctMap::new(9));
+    }
+}
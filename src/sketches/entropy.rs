@@ -0,0 +1,189 @@
+use crate::collections::Absorb;
+use crate::seeded::{stable_hash, Fingerprint, Fingerprinted};
+use rand::{Rng, SeedableRng};
+use rand_chacha::ChaCha8Rng;
+use std::hash::Hash;
+
+/// A sketch which estimates the [Shannon entropy](https://en.wikipedia.org/wiki/Entropy_(information_theory))
+/// of a stream with bounded memory, regardless of the number of distinct items.
+///
+/// The sketch uses the sampling estimator of Chakrabarti, Cormode and McGregor. Each sampler
+/// picks a uniformly random position of the stream, by reservoir sampling, and counts
+/// the occurrences `r` of the item at that position from there on. The value
+/// `r log r - (r - 1) log (r - 1)` is an unbiased estimate of `Σ f log f / m`, where `f` are
+/// the frequencies of the items and `m` is the length of the stream. More samplers reduce the
+/// variance of the estimate, each insertion costs time proportional to their number.
+/// A stream of a single distinct item, whose estimate would only approach zero, is recognized
+/// and has an entropy of exactly zero.
+///
+/// See [`CountedBag::entropy`](crate::collections::CountedBag::entropy) for the exact entropy.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::EntropyEstimator;
+///
+/// let mut estimator = EntropyEstimator::new(256, 42);
+/// for i in 0..10_000 {
+///     estimator.insert(&(i % 16));
+/// }
+///
+/// assert!((estimator.estimate() - 4.).abs() < 0.3);
+/// ```
+#[derive(Clone, Debug)]
+pub struct EntropyEstimator {
+    // the hash of the sampled item and its number of occurrences since it was sampled
+    samplers: Vec<(u64, u64)>,
+    len: u64,
+    // the hash of all the items, while they are the same
+    single: Option<u64>,
+    rng: ChaCha8Rng,
+    seed: u64,
+}
+
+impl EntropyEstimator {
+    /// Creates an empty estimator with a given number of samplers.
+    /// Estimators created with the same seed sample the same positions.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `samples` is zero.
+    pub fn new(samples: usize, seed: u64) -> Self {
+        assert!(samples > 0, "the number of samples is zero");
+
+        Self {
+            samplers: vec![(0, 0); samples],
+            len: 0,
+            single: None,
+            rng: ChaCha8Rng::seed_from_u64(seed),
+            seed,
+        }
+    }
+
+    /// Returns the number of items inserted into the estimator.
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Returns true if no item was inserted into the estimator.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Inserts an item into the estimator.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        self.insert_hash(stable_hash(item, self.seed));
+    }
+
+    /// Inserts an item given by its hash.
+    pub fn insert_hash(&mut self, hash: u64) {
+        self.len += 1;
+        if self.len == 1 {
+            self.single = Some(hash);
+        } else if self.single != Some(hash) {
+            self.single = None;
+        }
+
+        for (sampled, count) in self.samplers.iter_mut() {
+            if self.rng.gen_range(0..self.len) == 0 {
+                *sampled = hash;
+                *count = 1;
+            } else if *sampled == hash {
+                *count += 1;
+            }
+        }
+    }
+
+    /// Returns the estimated entropy, in bits, of the inserted items.
+    pub fn estimate(&self) -> f64 {
+        if self.len == 0 || self.single.is_some() {
+            return 0.;
+        }
+
+        fn f(r: u64) -> f64 {
+            if r <= 1 {
+                0.
+            } else {
+                let r = r as f64;
+                r * r.log2()
+            }
+        }
+
+        let mean = self
+            .samplers
+            .iter()
+            .map(|(_, r)| f(*r) - f(r - 1))
+            .sum::<f64>()
+            / self.samplers.len() as f64;
+
+        ((self.len as f64).log2() - mean).max(0.)
+    }
+}
+
+impl<T: Hash> Absorb<T> for EntropyEstimator {
+    /// Inserts the item into the estimator.
+    fn absorb(&mut self, item: T) {
+        self.insert(&item);
+    }
+}
+
+impl Fingerprinted for EntropyEstimator {
+    /// Describes the number of samplers and the seed, which determine the sampled positions.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("EntropyEstimator")
+            .with("samples", self.samplers.len() as u64)
+            .with("seed", self.seed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::CountedBag;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn empty_() {
+        let estimator = EntropyEstimator::new(8, 1);
+        assert!(estimator.is_empty());
+        assert_eq!(estimator.estimate(), 0.);
+    }
+
+    #[test]
+    fn constant_() {
+        let mut estimator = EntropyEstimator::new(16, 1);
+        for _ in 0..1_000 {
+            estimator.insert("a");
+        }
+        assert_eq!(estimator.len(), 1_000);
+        assert!(estimator.estimate() < 1e-9);
+
+        estimator.insert("b");
+        assert!(estimator.estimate() > 0.);
+    }
+
+    #[test]
+    fn skewed_() {
+        use rand::seq::SliceRandom;
+
+        // a zipf-like stream, compared to the exact entropy
+        let mut items: Vec<u32> = (1..=100u32)
+            .flat_map(|i| vec![i; 1_000 / i as usize])
+            .collect();
+        items.shuffle(&mut StdRng::seed_from_u64(3));
+        let exact = CountedBag::<u32>::from_keys(items.iter().copied()).entropy();
+
+        let mut estimator = EntropyEstimator::new(256, 7);
+        for item in items.iter() {
+            estimator.insert(item);
+        }
+
+        assert!((estimator.estimate() - exact).abs() < 0.1 * exact);
+    }
+
+    #[test]
+    #[should_panic]
+    fn zero_samples_() {
+        let _ = EntropyEstimator::new(0, 1);
+    }
+}
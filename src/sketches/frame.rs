@@ -0,0 +1,373 @@
+use crate::collections::{CountedBag, Mergeable};
+use crate::compress::{varint, VarintError};
+use std::{
+    error::Error,
+    fmt::{Display, Formatter},
+    hash::BuildHasher,
+};
+
+/// The version of the frame layout written by this crate.
+pub const FRAME_VERSION: u8 = 1;
+
+/// Summaries which can be shipped between processes as compact byte frames.
+///
+/// A frame starts with a two byte tag of the summary type and the [`FRAME_VERSION`],
+/// followed by the varint encoded content of the summary. Workers periodically send
+/// the frame of what they saw since the last report, and the aggregator folds these
+/// deltas into its own summary with [`Frame::apply_delta`].
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::{Frame, HyperLogLog};
+///
+/// let mut aggregator = HyperLogLog::new(10);
+///
+/// for worker in 0..3 {
+///     let mut delta = HyperLogLog::new(10);
+///     (0..100).for_each(|i| delta.insert(&(worker * 50 + i)));
+///     aggregator.apply_delta(&delta.to_frame()).unwrap();
+/// }
+///
+/// assert!((aggregator.estimate() - 200.).abs() < 10.);
+/// ```
+pub trait Frame: Sized {
+    /// Encodes the summary as a frame.
+    fn to_frame(&self) -> Vec<u8>;
+
+    /// Decodes a summary from a frame.
+    fn from_frame(frame: &[u8]) -> Result<Self, DecodeError>;
+
+    /// Merges the summary encoded by a frame into this one.
+    fn apply_delta(&mut self, frame: &[u8]) -> Result<(), DecodeError>;
+}
+
+/// The error returned when a frame cannot be decoded or applied, shared by all the summaries.
+/// Decoding never panics, whatever the bytes of the frame.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DecodeError {
+    /// The frame ends in the middle of a value.
+    Truncated,
+    /// The frame does not start with the tag of the expected type of summary.
+    BadMagic,
+    /// The frame was written with an unsupported layout version.
+    UnsupportedVersion(u8),
+    /// A parameter or a value is out of the range supported by the summary,
+    /// such as a precision which is too large or a count which does not fit in 32 bits.
+    Overflow,
+    /// The content of the frame is not valid for the summary.
+    Invalid,
+    /// The frame holds a summary whose parameters differ from the ones of the target.
+    Mismatch,
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Truncated => write!(f, "the frame is truncated"),
+            Self::BadMagic => write!(f, "the frame holds another type of summary"),
+            Self::UnsupportedVersion(v) => write!(f, "the frame version {v} is not supported"),
+            Self::Overflow => write!(f, "the frame holds a value out of range"),
+            Self::Invalid => write!(f, "the frame content is not valid"),
+            Self::Mismatch => write!(f, "the frame parameters do not match the summary"),
+        }
+    }
+}
+
+impl Error for DecodeError {}
+
+impl From<VarintError> for DecodeError {
+    fn from(err: VarintError) -> Self {
+        match err {
+            VarintError::Truncated => Self::Truncated,
+            VarintError::Overflow => Self::Overflow,
+        }
+    }
+}
+
+/// Builds a frame, starting with its header.
+pub(crate) struct FrameWriter {
+    bytes: Vec<u8>,
+}
+
+impl FrameWriter {
+    pub(crate) fn new(tag: [u8; 2]) -> Self {
+        let mut bytes = Vec::with_capacity(16);
+        bytes.extend_from_slice(&tag);
+        bytes.push(FRAME_VERSION);
+        Self { bytes }
+    }
+
+    pub(crate) fn byte(&mut self, value: u8) {
+        self.bytes.push(value);
+    }
+
+    pub(crate) fn varint(&mut self, value: u64) {
+        varint::encode_u64(value, &mut self.bytes);
+    }
+
+    pub(crate) fn bytes(&mut self, values: &[u8]) {
+        self.bytes.extend_from_slice(values);
+    }
+
+    pub(crate) fn finish(self) -> Vec<u8> {
+        self.bytes
+    }
+}
+
+/// Reads the content of a frame, after checking its header.
+#[derive(Clone)]
+pub(crate) struct FrameReader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> FrameReader<'a> {
+    pub(crate) fn new(tag: [u8; 2], frame: &'a [u8]) -> Result<Self, DecodeError> {
+        match frame {
+            [t0, t1, version, rest @ ..] => {
+                if [*t0, *t1] != tag {
+                    Err(DecodeError::BadMagic)
+                } else if *version != FRAME_VERSION {
+                    Err(DecodeError::UnsupportedVersion(*version))
+                } else {
+                    Ok(Self { bytes: rest })
+                }
+            }
+            _ => Err(DecodeError::Truncated),
+        }
+    }
+
+    pub(crate) fn byte(&mut self) -> Result<u8, DecodeError> {
+        let (first, rest) = self.bytes.split_first().ok_or(DecodeError::Truncated)?;
+        self.bytes = rest;
+        Ok(*first)
+    }
+
+    pub(crate) fn varint(&mut self) -> Result<u64, DecodeError> {
+        let (value, read) = varint::decode_u64(self.bytes)?;
+        self.bytes = &self.bytes[read..];
+        Ok(value)
+    }
+
+    /// Reads the next `len` bytes, failing before any allocation if the frame is shorter.
+    pub(crate) fn bytes(&mut self, len: usize) -> Result<&'a [u8], DecodeError> {
+        if len > self.bytes.len() {
+            return Err(DecodeError::Truncated);
+        }
+
+        let (first, rest) = self.bytes.split_at(len);
+        self.bytes = rest;
+        Ok(first)
+    }
+
+    /// Fails if the frame has bytes after its content.
+    pub(crate) fn finish(self) -> Result<(), DecodeError> {
+        if self.bytes.is_empty() {
+            Ok(())
+        } else {
+            Err(DecodeError::Invalid)
+        }
+    }
+}
+
+const COUNTED_BAG_TAG: [u8; 2] = *b"CB";
+
+/// A bag of integer keys is encoded as the number of keys, followed by the
+/// gaps between the sorted keys, each with its count.
+impl<S> Frame for CountedBag<u64, S>
+where
+    S: BuildHasher + Default,
+{
+    fn to_frame(&self) -> Vec<u8> {
+        let mut entries: Vec<(u64, u32)> = self.iter().map(|(k, c)| (*k, *c)).collect();
+        entries.sort_unstable();
+
+        let mut writer = FrameWriter::new(COUNTED_BAG_TAG);
+        writer.varint(entries.len() as u64);
+
+        let mut previous = 0;
+        for (key, count) in entries {
+            writer.varint(key - previous);
+            writer.varint(count as u64);
+            previous = key;
+        }
+
+        writer.finish()
+    }
+
+    fn from_frame(frame: &[u8]) -> Result<Self, DecodeError> {
+        let mut reader = FrameReader::new(COUNTED_BAG_TAG, frame)?;
+        let len = reader.varint()?;
+
+        let mut entries = Vec::new();
+        let mut total = 0_u32;
+        let mut key = 0_u64;
+        for i in 0..len {
+            let gap = reader.varint()?;
+            if i > 0 && gap == 0 {
+                return Err(DecodeError::Invalid);
+            }
+            key = key.checked_add(gap).ok_or(DecodeError::Overflow)?;
+
+            let count = u32::try_from(reader.varint()?).map_err(|_| DecodeError::Overflow)?;
+            if count == 0 {
+                return Err(DecodeError::Invalid);
+            }
+            total = total.checked_add(count).ok_or(DecodeError::Overflow)?;
+            entries.push((key, count));
+        }
+
+        reader.finish()?;
+        Ok(Self::from_iter(entries))
+    }
+
+    fn apply_delta(&mut self, frame: &[u8]) -> Result<(), DecodeError> {
+        let delta = Self::from_frame(frame)?;
+        if self.total().checked_add(delta.total()).is_none() {
+            return Err(DecodeError::Overflow);
+        }
+
+        self.merge_from(delta);
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn counted_bag_roundtrip_() {
+        let bag = CountedBag::<u64>::from_iter([(3, 2), (1_000, 1), (0, 7)]);
+        let frame = bag.to_frame();
+        assert_eq!(&frame[..3], [b'C', b'B', FRAME_VERSION]);
+        // the header, the length and three pairs of one byte varints but the 1000 gap
+        assert_eq!(frame.len(), 3 + 1 + 7);
+
+        let decoded = CountedBag::<u64>::from_frame(&frame).unwrap();
+        assert_eq!(decoded.get(&0), Some(&7));
+        assert_eq!(decoded.get(&1_000), Some(&1));
+        assert_eq!(decoded.total(), 10);
+
+        let empty = CountedBag::<u64>::new();
+        assert!(CountedBag::<u64>::from_frame(&empty.to_frame())
+            .unwrap()
+            .is_empty());
+    }
+
+    #[test]
+    fn counted_bag_delta_() {
+        let mut aggregator = CountedBag::<u64>::new();
+        for worker in 0..3_u64 {
+            let delta = CountedBag::<u64>::from_keys((0..10).map(|i| i * (worker + 1)));
+            aggregator.apply_delta(&delta.to_frame()).unwrap();
+        }
+
+        assert_eq!(aggregator.total(), 30);
+        assert_eq!(aggregator.get(&0), Some(&3));
+        assert_eq!(aggregator.get(&6), Some(&3));
+        assert_eq!(aggregator.get(&27), Some(&1));
+    }
+
+    #[test]
+    fn errors_() {
+        let bag = CountedBag::<u64>::from_iter([(3, 2), (5, 1)]);
+        let frame = bag.to_frame();
+
+        assert_eq!(
+            CountedBag::<u64>::from_frame(&frame[..2]).err(),
+            Some(DecodeError::Truncated)
+        );
+        assert_eq!(
+            CountedBag::<u64>::from_frame(&frame[..frame.len() - 1]).err(),
+            Some(DecodeError::Truncated)
+        );
+        assert_eq!(
+            CountedBag::<u64>::from_frame(b"XX\x01\x00").err(),
+            Some(DecodeError::BadMagic)
+        );
+        assert_eq!(
+            CountedBag::<u64>::from_frame(b"CB\x09\x00").err(),
+            Some(DecodeError::UnsupportedVersion(9))
+        );
+        // a repeated key, a zero count and trailing bytes
+        for invalid in [
+            &b"CB\x01\x02\x01\x01\x00\x01"[..],
+            b"CB\x01\x01\x01\x00",
+            b"CB\x01\x00\x00",
+        ] {
+            assert_eq!(
+                CountedBag::<u64>::from_frame(invalid).err(),
+                Some(DecodeError::Invalid)
+            );
+        }
+
+        let mut full = CountedBag::<u64>::from_iter([(1, u32::MAX)]);
+        assert_eq!(full.apply_delta(&frame).unwrap_err(), DecodeError::Overflow);
+        assert_eq!(full.total(), u32::MAX);
+    }
+
+    /// Decodes every truncation, every single byte change and an extension of a valid frame.
+    fn check_corruptions<T: Frame>(frame: &[u8]) {
+        assert!(T::from_frame(frame).is_ok());
+
+        for len in 0..frame.len() {
+            let err = T::from_frame(&frame[..len]).err();
+            assert_eq!(
+                err,
+                Some(DecodeError::Truncated),
+                "truncated to {len} bytes"
+            );
+        }
+
+        for idx in 0..frame.len() {
+            for value in (0..=u8::MAX).filter(|v| *v != frame[idx]) {
+                let mut corrupt = frame.to_vec();
+                corrupt[idx] = value;
+
+                // the content may still be valid, but decoding never panics
+                let err = T::from_frame(&corrupt).err();
+                match idx {
+                    0 | 1 => assert_eq!(err, Some(DecodeError::BadMagic)),
+                    2 => assert_eq!(err, Some(DecodeError::UnsupportedVersion(value))),
+                    _ => {}
+                }
+            }
+        }
+
+        let extended = [frame, &[0]].concat();
+        assert_eq!(T::from_frame(&extended).err(), Some(DecodeError::Invalid));
+    }
+
+    #[test]
+    fn corruptions_() {
+        use crate::sketches::{BloomFilter, CountMinSketch, HyperLogLog};
+
+        let bag = CountedBag::<u64>::from_iter([(3, 2), (1_000, 1), (u64::MAX, 300)]);
+        check_corruptions::<CountedBag<u64>>(&bag.to_frame());
+        check_corruptions::<CountedBag<u64>>(&CountedBag::<u64>::new().to_frame());
+
+        let mut hll = HyperLogLog::new(6);
+        (0..40).for_each(|i| hll.insert(&i));
+        check_corruptions::<HyperLogLog>(&hll.to_frame());
+
+        let mut filter = BloomFilter::new(61, 3).with_seed(u64::MAX);
+        (0..10).for_each(|i| filter.insert(&i));
+        check_corruptions::<BloomFilter>(&filter.to_frame());
+
+        let mut cms = CountMinSketch::new(7, 3).with_seed(5);
+        (0..30).for_each(|i| cms.insert_count(&i, i));
+        check_corruptions::<CountMinSketch>(&cms.to_frame());
+    }
+
+    #[quickcheck]
+    fn counted_bag_garbage_(content: Vec<u8>) -> bool {
+        // a valid header, so the content itself is decoded
+        let frame = [&[b'C', b'B', FRAME_VERSION][..], &content].concat();
+        match CountedBag::<u64>::from_frame(&frame) {
+            Ok(bag) => bag.total() == bag.iter().map(|(_, c)| *c).sum::<u32>(),
+            Err(_) => true,
+        }
+    }
+}
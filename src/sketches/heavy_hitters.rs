@@ -0,0 +1,588 @@
+use crate::collections::{Absorb, Mergeable};
+use crate::seeded::{assert_compatible, Fingerprint, Fingerprinted};
+use std::{
+    borrow::Borrow,
+    collections::{HashMap, VecDeque},
+    hash::Hash,
+};
+
+/// Reports the most frequent items among the last `window` events of a stream.
+///
+/// The window is split into equal blocks, each summarized by a
+/// [space-saving](https://www.cs.ucsb.edu/sites/default/files/documents/2005-23.pdf) counter
+/// which tracks at most `capacity` items. Blocks expire as a whole, so the oldest block
+/// may still hold a few events which already left the window.
+///
+/// The counts are upper bounds: the true count of an item in the window is never larger
+/// than the reported count, and never smaller than the reported count minus [`Self::error_bound`].
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::WindowedHeavyHitters;
+///
+/// let mut hitters = WindowedHeavyHitters::new(100, 4, 8);
+/// (0..1_000).for_each(|i| hitters.insert(if i < 500 { i % 3 } else { 10 + i % 2 }));
+///
+/// let top: Vec<_> = hitters.top_k(2).into_iter().map(|(key, _)| *key).collect();
+/// assert!(top.contains(&10) && top.contains(&11));
+/// assert_eq!(hitters.count_bounds(&0), (0, 0));
+/// ```
+#[derive(Clone, Debug)]
+pub struct WindowedHeavyHitters<K> {
+    window: usize,
+    num_blocks: usize,
+    capacity: usize,
+    events: usize,
+    // oldest block first, the last block is the one being filled
+    blocks: VecDeque<SpaceSaving<K>>,
+}
+
+impl<K> WindowedHeavyHitters<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Creates an empty summary over the last `window` events, split into `blocks` blocks
+    /// which track at most `capacity` items each.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `capacity` or `blocks` is zero, or if `window` is not a multiple of `blocks`.
+    pub fn new(window: usize, blocks: usize, capacity: usize) -> Self {
+        assert!(capacity > 0, "capacity must be positive");
+        assert!(
+            blocks > 0 && window > 0 && window.is_multiple_of(blocks),
+            "window must be a positive multiple of the number of blocks"
+        );
+
+        Self {
+            window,
+            num_blocks: blocks,
+            capacity,
+            events: 0,
+            blocks: VecDeque::with_capacity(blocks + 1),
+        }
+    }
+
+    /// Returns the number of events of the window.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Returns the maximum number of items tracked by each block.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Returns the number of events inserted so far, including the expired ones.
+    pub fn events(&self) -> usize {
+        self.events
+    }
+
+    /// Returns true if no event was inserted.
+    pub fn is_empty(&self) -> bool {
+        self.events == 0
+    }
+
+    fn block_len(&self) -> usize {
+        self.window / self.num_blocks
+    }
+
+    /// Inserts an event into the summary, expiring the blocks which left the window.
+    pub fn insert(&mut self, key: K) {
+        let block_len = self.block_len();
+        if self.blocks.back().is_none_or(|b| b.events >= block_len) {
+            self.blocks.push_back(SpaceSaving::new(self.capacity));
+        }
+
+        self.blocks.back_mut().unwrap().insert(key);
+        self.events += 1;
+        self.expire();
+    }
+
+    fn expire(&mut self) {
+        let current_full = self
+            .blocks
+            .back()
+            .is_some_and(|b| b.events >= self.block_len());
+        let keep = if current_full {
+            self.num_blocks
+        } else {
+            self.num_blocks + 1
+        };
+
+        while self.blocks.len() > keep {
+            self.blocks.pop_front();
+        }
+    }
+
+    /// Returns the blocks which lie entirely inside the window.
+    fn inner_blocks(&self) -> impl Iterator<Item = &SpaceSaving<K>> {
+        let skip = self.blocks.len().saturating_sub(self.num_blocks);
+        self.blocks.iter().skip(skip)
+    }
+
+    /// Returns the maximum difference between a reported count and the true count
+    /// of an item in the window.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::sketches::WindowedHeavyHitters;
+    ///
+    /// let mut hitters = WindowedHeavyHitters::new(10, 2, 4);
+    /// (0..10).for_each(|i| hitters.insert(i % 2));
+    /// assert_eq!(hitters.error_bound(), 0);
+    ///
+    /// hitters.insert(7);
+    /// assert_eq!(hitters.error_bound(), 5);
+    /// ```
+    pub fn error_bound(&self) -> usize {
+        let floors: usize = self.inner_blocks().map(|b| b.floor).sum();
+        let partial = if self.blocks.len() > self.num_blocks {
+            self.blocks[0].events
+        } else {
+            0
+        };
+        floors + partial
+    }
+
+    /// Returns the lower and the upper bound of the number of occurrences of a key in the window.
+    pub fn count_bounds<Q>(&self, key: &Q) -> (usize, usize)
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        let upper = self.blocks.iter().map(|b| b.upper(key)).sum();
+        let lower = self.inner_blocks().map(|b| b.lower(key)).sum();
+        (lower, upper)
+    }
+
+    /// Returns the `k` keys with the largest counts in the window, sorted by descending count.
+    /// The counts are upper bounds, as returned by [`Self::count_bounds`].
+    pub fn top_k(&self, k: usize) -> Vec<(&K, usize)> {
+        let mut counts: HashMap<&K, usize> = HashMap::new();
+        for block in &self.blocks {
+            for key in block.keys() {
+                counts.entry(key).or_default();
+            }
+        }
+
+        let mut counts: Vec<(&K, usize)> = counts
+            .into_keys()
+            .map(|key| (key, self.count_bounds(key).1))
+            .collect();
+        counts.sort_by(|(_, c1), (_, c2)| c2.cmp(c1));
+        counts.truncate(k);
+        counts
+    }
+}
+
+impl<K> Absorb<K> for WindowedHeavyHitters<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Inserts an event into the summary.
+    fn absorb(&mut self, key: K) {
+        self.insert(key);
+    }
+}
+
+impl<K> Fingerprinted for WindowedHeavyHitters<K> {
+    /// Describes the window, the number of blocks and the capacity of the summary.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("WindowedHeavyHitters")
+            .with("window", self.window as u64)
+            .with("blocks", self.num_blocks as u64)
+            .with("capacity", self.capacity as u64)
+    }
+}
+
+impl<K> Mergeable for WindowedHeavyHitters<K>
+where
+    K: Eq + Hash + Clone,
+{
+    /// Merges the summary of another stream into this one, aligning their most recent blocks,
+    /// so the result reports the heavy hitters over the last `window` events of each stream.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two summaries have different windows, blocks or capacities.
+    fn merge_from(&mut self, other: Self) {
+        assert_compatible(self, &other);
+
+        let len = self.blocks.len().max(other.blocks.len());
+        while self.blocks.len() < len {
+            self.blocks.push_front(SpaceSaving::new(self.capacity));
+        }
+
+        let offset = len - other.blocks.len();
+        for (i, block) in other.blocks.into_iter().enumerate() {
+            self.blocks[offset + i].merge_from(block);
+        }
+
+        // the blocks are kept as they are, the next insert expires the older ones
+        self.events += other.events;
+    }
+}
+
+/// A space-saving counter for a single block.
+///
+/// The counters are kept in a binary min-heap on their counts, indexed by their keys,
+/// so an insert, which may evict the smallest counter, takes logarithmic time.
+#[derive(Clone, Debug)]
+struct SpaceSaving<K> {
+    capacity: usize,
+    events: usize,
+    // the largest possible count of any untracked key
+    floor: usize,
+    // the tracked keys, each in a slot which it keeps until it is evicted
+    slots: Vec<Counter<K>>,
+    // the slots ordered as a min-heap on their counts
+    heap: Vec<usize>,
+    // the position of each slot in the heap
+    positions: Vec<usize>,
+    // the slot of each tracked key
+    index: HashMap<K, usize>,
+}
+
+/// A tracked key with its count and the overestimation error of the count.
+#[derive(Clone, Debug)]
+struct Counter<K> {
+    key: K,
+    count: usize,
+    error: usize,
+}
+
+impl<K> SpaceSaving<K>
+where
+    K: Eq + Hash + Clone,
+{
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            events: 0,
+            floor: 0,
+            slots: Vec::with_capacity(capacity),
+            heap: Vec::with_capacity(capacity),
+            positions: Vec::with_capacity(capacity),
+            index: HashMap::with_capacity(capacity),
+        }
+    }
+
+    fn insert(&mut self, key: K) {
+        self.events += 1;
+
+        if let Some(slot) = self.find(&key) {
+            self.slots[slot].count += 1;
+            self.sift_down(self.positions[slot]);
+            return;
+        }
+
+        if self.slots.len() < self.capacity {
+            self.track(key, self.floor + 1, self.floor);
+            return;
+        }
+
+        // the smallest counter gives its slot to the new key
+        let slot = self.heap[0];
+        let evicted = &self.slots[slot];
+        self.floor = self.floor.max(evicted.count);
+        self.index.remove(&evicted.key);
+
+        self.index.insert(key.clone(), slot);
+        self.slots[slot] = Counter {
+            key,
+            count: self.floor + 1,
+            error: self.floor,
+        };
+        self.sift_down(0);
+    }
+
+    /// Tracks a new key in a new slot.
+    fn track(&mut self, key: K, count: usize, error: usize) {
+        let slot = self.slots.len();
+        self.index.insert(key.clone(), slot);
+        self.slots.push(Counter { key, count, error });
+        self.positions.push(self.heap.len());
+        self.heap.push(slot);
+        self.sift_up(self.heap.len() - 1);
+    }
+
+    /// Returns the slot of a key, if it is tracked.
+    fn find<Q>(&self, key: &Q) -> Option<usize>
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.index.get(key).copied()
+    }
+
+    fn count_at(&self, pos: usize) -> usize {
+        self.slots[self.heap[pos]].count
+    }
+
+    fn swap(&mut self, i: usize, j: usize) {
+        self.heap.swap(i, j);
+        self.positions[self.heap[i]] = i;
+        self.positions[self.heap[j]] = j;
+    }
+
+    fn sift_up(&mut self, mut pos: usize) {
+        while pos > 0 {
+            let parent = (pos - 1) / 2;
+            if self.count_at(parent) <= self.count_at(pos) {
+                break;
+            }
+            self.swap(parent, pos);
+            pos = parent;
+        }
+    }
+
+    fn sift_down(&mut self, mut pos: usize) {
+        loop {
+            let (left, right) = (2 * pos + 1, 2 * pos + 2);
+            let mut smallest = pos;
+            if left < self.heap.len() && self.count_at(left) < self.count_at(smallest) {
+                smallest = left;
+            }
+            if right < self.heap.len() && self.count_at(right) < self.count_at(smallest) {
+                smallest = right;
+            }
+            if smallest == pos {
+                break;
+            }
+            self.swap(pos, smallest);
+            pos = smallest;
+        }
+    }
+
+    fn keys(&self) -> impl Iterator<Item = &K> {
+        self.slots.iter().map(|counter| &counter.key)
+    }
+
+    fn upper<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key)
+            .map_or(self.floor, |slot| self.slots[slot].count)
+    }
+
+    fn lower<Q>(&self, key: &Q) -> usize
+    where
+        K: Borrow<Q>,
+        Q: Hash + Eq + ?Sized,
+    {
+        self.find(key).map_or(0, |slot| {
+            let counter = &self.slots[slot];
+            counter.count - counter.error
+        })
+    }
+
+    fn merge_from(&mut self, other: Self) {
+        let (self_floor, other_floor) = (self.floor, other.floor);
+
+        let mut merged = Vec::with_capacity(self.slots.len() + other.slots.len());
+        let mut matched = vec![false; other.slots.len()];
+        for counter in std::mem::take(&mut self.slots) {
+            let (c, e) = match other.find(&counter.key) {
+                Some(slot) => {
+                    matched[slot] = true;
+                    (other.slots[slot].count, other.slots[slot].error)
+                }
+                None => (other_floor, other_floor),
+            };
+            merged.push((counter.key, counter.count + c, counter.error + e));
+        }
+        for (counter, _) in other.slots.into_iter().zip(matched).filter(|(_, m)| !m) {
+            merged.push((
+                counter.key,
+                counter.count + self_floor,
+                counter.error + self_floor,
+            ));
+        }
+
+        self.events += other.events;
+        self.floor = self_floor + other_floor;
+
+        if merged.len() > self.capacity {
+            let mut counts: Vec<usize> = merged.iter().map(|(_, c, _)| *c).collect();
+            counts.sort_unstable_by(|a, b| b.cmp(a));
+            let threshold = counts[self.capacity];
+
+            // drop every key at or below the largest dropped count, so the floor stays an upper bound
+            merged.retain(|(_, count, _)| *count > threshold);
+            self.floor = self.floor.max(threshold);
+        }
+
+        self.heap.clear();
+        self.positions.clear();
+        self.index.clear();
+        for (key, count, error) in merged {
+            self.track(key, count, error);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::merge_all;
+    use quickcheck_macros::quickcheck;
+    use rand::{rngs::StdRng, Rng, SeedableRng};
+
+    fn exact(events: &[u32], window: usize) -> HashMap<u32, usize> {
+        let mut counts = HashMap::new();
+        for e in &events[events.len().saturating_sub(window)..] {
+            *counts.entry(*e).or_default() += 1;
+        }
+        counts
+    }
+
+    #[test]
+    fn empty_() {
+        let hitters = WindowedHeavyHitters::<u32>::new(10, 2, 3);
+        assert!(hitters.is_empty());
+        assert_eq!(hitters.window(), 10);
+        assert_eq!(hitters.capacity(), 3);
+        assert!(hitters.top_k(3).is_empty());
+        assert_eq!(hitters.count_bounds(&1), (0, 0));
+        assert_eq!(hitters.error_bound(), 0);
+    }
+
+    #[test]
+    fn exact_within_capacity_() {
+        let mut hitters = WindowedHeavyHitters::new(6, 3, 4);
+        "aabbbcdd".chars().for_each(|c| hitters.insert(c));
+
+        // the window holds the blocks "bb", "bc" and "dd"
+        assert_eq!(hitters.events(), 8);
+        assert_eq!(hitters.count_bounds(&'b'), (3, 3));
+        assert_eq!(hitters.count_bounds(&'a'), (0, 0));
+        assert_eq!(hitters.top_k(1), [(&'b', 3)]);
+
+        // the block "bb" is partially expired
+        hitters.insert('d');
+        assert_eq!(hitters.count_bounds(&'b'), (1, 3));
+        assert_eq!(hitters.error_bound(), 2);
+
+        hitters.insert('d');
+        assert_eq!(hitters.count_bounds(&'d'), (4, 4));
+        assert_eq!(hitters.count_bounds(&'b'), (1, 1));
+        assert_eq!(hitters.error_bound(), 0);
+    }
+
+    #[test]
+    fn bounds_() {
+        let mut rng = StdRng::seed_from_u64(3);
+        let mut hitters = WindowedHeavyHitters::new(200, 5, 10);
+        let mut events = Vec::new();
+
+        for i in 0..2_000 {
+            // a heavy key which changes every 500 events, and a long tail
+            let e = if rng.gen_bool(0.3) {
+                1_000 + i / 500
+            } else {
+                rng.gen_range(0..100)
+            };
+            events.push(e);
+            hitters.insert(e);
+
+            if i % 97 == 0 {
+                let counts = exact(&events, 200);
+                for key in (0..100).chain(1_000..1_004) {
+                    let truth = counts.get(&key).copied().unwrap_or(0);
+                    let (lower, upper) = hitters.count_bounds(&key);
+                    assert!(
+                        lower <= truth && truth <= upper,
+                        "{key}: {lower} {truth} {upper}"
+                    );
+                    assert!(upper - truth <= hitters.error_bound());
+                }
+            }
+        }
+
+        assert_eq!(*hitters.top_k(1)[0].0, 1_003);
+    }
+
+    #[test]
+    fn merge_() {
+        let mut rng = StdRng::seed_from_u64(5);
+        let streams: Vec<Vec<u32>> = (0..4)
+            .map(|s| {
+                (0..300)
+                    .map(|_| {
+                        if rng.gen_bool(0.4) {
+                            500 + s
+                        } else {
+                            rng.gen_range(0..50)
+                        }
+                    })
+                    .collect()
+            })
+            .collect();
+
+        let shards = streams.iter().map(|events| {
+            let mut hitters = WindowedHeavyHitters::new(100, 4, 8);
+            events.iter().for_each(|e| hitters.insert(*e));
+            hitters
+        });
+        let merged = merge_all(shards).unwrap();
+        assert_eq!(merged.events(), 1_200);
+
+        let mut counts: HashMap<u32, usize> = HashMap::new();
+        for events in &streams {
+            for (key, count) in exact(events, 100) {
+                *counts.entry(key).or_default() += count;
+            }
+        }
+
+        for key in (0..50).chain(500..504) {
+            let truth = counts.get(&key).copied().unwrap_or(0);
+            let (lower, upper) = merged.count_bounds(&key);
+            assert!(
+                lower <= truth && truth <= upper,
+                "{key}: {lower} {truth} {upper}"
+            );
+        }
+
+        let mut top: Vec<u32> = merged.top_k(4).into_iter().map(|(k, _)| *k).collect();
+        top.sort_unstable();
+        assert_eq!(top, [500, 501, 502, 503]);
+    }
+
+    #[quickcheck]
+    fn space_saving_(events: Vec<u8>) -> bool {
+        let mut counter = SpaceSaving::new(4);
+        let mut counts: HashMap<u8, usize> = HashMap::new();
+        for e in &events {
+            counter.insert(*e % 16);
+            *counts.entry(*e % 16).or_default() += 1;
+        }
+
+        // the root of the heap is the smallest counter, and the counts add up to the events
+        let min = counter.slots.iter().map(|c| c.count).min();
+        let total: usize = counter.slots.iter().map(|c| c.count).sum();
+        min == counter.heap.first().map(|slot| counter.slots[*slot].count)
+            && total == events.len()
+            && (0..16).all(|key| {
+                let truth = counts.get(&key).copied().unwrap_or(0);
+                counter.lower(&key) <= truth && truth <= counter.upper(&key)
+            })
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_mismatch_() {
+        let mut hitters = WindowedHeavyHitters::<u32>::new(10, 2, 3);
+        hitters.merge_from(WindowedHeavyHitters::new(10, 5, 3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_blocks_() {
+        WindowedHeavyHitters::<u32>::new(10, 3, 3);
+    }
+}
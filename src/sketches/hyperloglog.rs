@@ -0,0 +1,576 @@
+use super::{DecodeError, Frame, FrameReader, FrameWriter};
+use crate::collections::{Absorb, Mergeable};
+use crate::seeded::{assert_compatible, stable_hash, Fingerprint, Fingerprinted};
+use std::hash::Hash;
+
+/// The smallest supported precision.
+pub const HLL_MIN_PRECISION: u8 = 4;
+/// The largest supported precision.
+pub const HLL_MAX_PRECISION: u8 = 16;
+
+/// A [HyperLogLog](https://en.wikipedia.org/wiki/HyperLogLog) sketch which estimates
+/// the number of distinct items in a stream.
+///
+/// The sketch uses `2^precision` one-byte registers and its relative standard error
+/// is about `1.04 / sqrt(2^precision)`. The items are hashed with the
+/// [stable hasher](crate::seeded::StableHasher) salted with the seed of the sketch,
+/// so the registers do not depend on the platform or the release.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::HyperLogLog;
+///
+/// let mut hll = HyperLogLog::new(12);
+/// for i in 0..1000 {
+///     hll.insert(&(i % 100));
+/// }
+///
+/// let estimate = hll.estimate();
+/// assert!((estimate - 100.).abs() < 5.);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HyperLogLog {
+    precision: u8,
+    registers: Vec<u8>,
+    seed: u64,
+}
+
+impl HyperLogLog {
+    /// Creates an empty sketch with `2^precision` registers.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the precision is not in the `4..=16` range.
+    pub fn new(precision: u8) -> Self {
+        assert!(
+            (HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&precision),
+            "precision must be between {HLL_MIN_PRECISION} and {HLL_MAX_PRECISION}"
+        );
+
+        Self {
+            precision,
+            registers: vec![0; 1 << precision],
+            seed: 0,
+        }
+    }
+
+    /// Returns an empty sketch with the same precision whose hash function is salted with `seed`.
+    /// Sketches with different seeds set different registers for the same items, so they cannot
+    /// be merged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::sketches::HyperLogLog;
+    ///
+    /// let mut hll = HyperLogLog::new(8).with_seed(7);
+    /// hll.insert("a");
+    /// assert_eq!(hll.estimate().round(), 1.);
+    /// assert_eq!(hll.seed(), 7);
+    /// ```
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::new(self.precision)
+        }
+    }
+
+    /// Returns the precision of the sketch.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns the seed of the hash function.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns true if no item was inserted into the sketch.
+    pub fn is_empty(&self) -> bool {
+        self.registers.iter().all(|r| *r == 0)
+    }
+
+    /// Inserts an item into the sketch.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use aabel_rs::sketches::HyperLogLog;
+    ///
+    /// let mut hll = HyperLogLog::new(8);
+    /// hll.insert("a");
+    /// hll.insert("a");
+    /// assert_eq!(hll.estimate().round(), 1.);
+    /// ```
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) {
+        self.insert_hash(stable_hash(item, self.seed));
+    }
+
+    /// Inserts an already computed 64 bit hash into the sketch.
+    pub fn insert_hash(&mut self, hash: u64) {
+        let p = self.precision as u32;
+        let idx = (hash >> (64 - p)) as usize;
+        // the remaining bits, with a sentinel bit so the rank is bounded
+        let rest = (hash << p) | (1 << (p - 1));
+        let rank = rest.leading_zeros() as u8 + 1;
+
+        if rank > self.registers[idx] {
+            self.registers[idx] = rank;
+        }
+    }
+
+    /// Returns the estimated number of distinct items inserted into the sketch.
+    pub fn estimate(&self) -> f64 {
+        estimate(
+            self.precision,
+            self.registers.iter().copied().filter(|r| *r > 0),
+        )
+    }
+}
+
+/// Returns the estimate of a sketch from its precision and its non-zero registers, in order.
+fn estimate<I: Iterator<Item = u8>>(precision: u8, ranks: I) -> f64 {
+    let len = 1_usize << precision;
+    let m = len as f64;
+    let alpha = match len {
+        16 => 0.673,
+        32 => 0.697,
+        64 => 0.709,
+        _ => 0.7213 / (1. + 1.079 / m),
+    };
+
+    let (sum, non_zero) = ranks.fold((0_f64, 0_usize), |(sum, non_zero), r| {
+        (sum + 2_f64.powi(-(r as i32)), non_zero + 1)
+    });
+    // each zero register adds 2^0 to the sum
+    let zeros = len - non_zero;
+    let sum = sum + zeros as f64;
+
+    let raw = alpha * m * m / sum;
+    if raw <= 2.5 * m && zeros > 0 {
+        // small range correction (linear counting)
+        m * (m / zeros as f64).ln()
+    } else {
+        raw
+    }
+}
+
+impl<T: Hash> Absorb<T> for HyperLogLog {
+    /// Inserts the item into the sketch.
+    fn absorb(&mut self, item: T) {
+        self.insert(&item);
+    }
+}
+
+impl Fingerprinted for HyperLogLog {
+    /// Describes the precision and the seed of the sketch, which determine the registers.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("HyperLogLog")
+            .with("precision", self.precision as u64)
+            .with("seed", self.seed)
+    }
+}
+
+impl Mergeable for HyperLogLog {
+    /// Merges another sketch into this one, so the result estimates the
+    /// number of distinct items of both streams.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two sketches have different precisions or seeds.
+    fn merge_from(&mut self, other: Self) {
+        assert_compatible(self, &other);
+
+        for (r, o) in self.registers.iter_mut().zip(other.registers) {
+            if o > *r {
+                *r = o;
+            }
+        }
+    }
+}
+
+const HLL_TAG: [u8; 2] = *b"HL";
+
+/// A sketch is encoded as its precision, its seed and the number of non-zero registers,
+/// followed by the gaps between the indices of these registers, each with its value.
+impl Frame for HyperLogLog {
+    fn to_frame(&self) -> Vec<u8> {
+        let mut writer = FrameWriter::new(HLL_TAG);
+        writer.byte(self.precision);
+        writer.varint(self.seed);
+
+        let non_zero = self.registers.iter().filter(|r| **r != 0).count();
+        writer.varint(non_zero as u64);
+
+        let mut previous = 0;
+        for (idx, r) in self.registers.iter().enumerate().filter(|(_, r)| **r != 0) {
+            writer.varint((idx - previous) as u64);
+            writer.byte(*r);
+            previous = idx;
+        }
+
+        writer.finish()
+    }
+
+    fn from_frame(frame: &[u8]) -> Result<Self, DecodeError> {
+        let registers = FrameRegisters::new(frame)?;
+        let mut hll = HyperLogLog::new(registers.precision).with_seed(registers.seed);
+        for register in registers {
+            let (idx, rank) = register?;
+            hll.registers[idx] = rank;
+        }
+
+        Ok(hll)
+    }
+
+    fn apply_delta(&mut self, frame: &[u8]) -> Result<(), DecodeError> {
+        let delta = Self::from_frame(frame)?;
+        if !self.is_compatible(&delta) {
+            return Err(DecodeError::Mismatch);
+        }
+
+        self.merge_from(delta);
+        Ok(())
+    }
+}
+
+/// An iterator over the non-zero registers of a frame, as `(index, rank)` pairs,
+/// which checks them as it goes and finally checks that nothing follows them.
+#[derive(Clone)]
+struct FrameRegisters<'a> {
+    reader: FrameReader<'a>,
+    precision: u8,
+    seed: u64,
+    remaining: u64,
+    // the index of the previous register
+    idx: Option<u64>,
+    done: bool,
+}
+
+impl<'a> FrameRegisters<'a> {
+    /// Reads the header of a frame, up to its registers.
+    fn new(frame: &'a [u8]) -> Result<Self, DecodeError> {
+        let mut reader = FrameReader::new(HLL_TAG, frame)?;
+
+        let precision = reader.byte()?;
+        if !(HLL_MIN_PRECISION..=HLL_MAX_PRECISION).contains(&precision) {
+            return Err(DecodeError::Overflow);
+        }
+
+        let seed = reader.varint()?;
+        let remaining = reader.varint()?;
+        if remaining > 1 << precision {
+            return Err(DecodeError::Invalid);
+        }
+
+        Ok(Self {
+            reader,
+            precision,
+            seed,
+            remaining,
+            idx: None,
+            done: false,
+        })
+    }
+
+    fn register(&mut self) -> Result<(usize, u8), DecodeError> {
+        let gap = self.reader.varint()?;
+        let idx = match self.idx {
+            Some(_) if gap == 0 => return Err(DecodeError::Invalid),
+            Some(previous) => previous.saturating_add(gap),
+            None => gap,
+        };
+        self.idx = Some(idx);
+
+        let rank = self.reader.byte()?;
+        let max_rank = 64 - self.precision + 1;
+        if idx >= 1 << self.precision || rank == 0 || rank > max_rank {
+            return Err(DecodeError::Invalid);
+        }
+
+        Ok((idx as usize, rank))
+    }
+}
+
+impl Iterator for FrameRegisters<'_> {
+    type Item = Result<(usize, u8), DecodeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+
+        let item = if self.remaining == 0 {
+            self.done = true;
+            self.reader.clone().finish().err().map(Err)
+        } else {
+            self.remaining -= 1;
+            Some(self.register())
+        };
+
+        if let Some(Err(_)) = item {
+            self.done = true;
+        }
+        item
+    }
+}
+
+/// A read-only view of a [`HyperLogLog`] sketch over its frame, which estimates the number
+/// of distinct items directly from the borrowed bytes, for example from a memory mapped file
+/// or a network buffer, without allocating the registers.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::{Frame, HllView, HyperLogLog};
+///
+/// let mut hll = HyperLogLog::new(10);
+/// (0..500).for_each(|i| hll.insert(&i));
+///
+/// let frame = hll.to_frame();
+/// let view = HllView::new(&frame).unwrap();
+/// assert_eq!(view.precision(), 10);
+/// assert_eq!(view.seed(), 0);
+/// assert_eq!(view.estimate(), hll.estimate());
+/// assert_eq!(view.to_owned(), hll);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct HllView<'a> {
+    frame: &'a [u8],
+    precision: u8,
+    seed: u64,
+}
+
+impl<'a> HllView<'a> {
+    /// Creates a view over a frame written by [`Frame::to_frame`], after checking all of it.
+    pub fn new(frame: &'a [u8]) -> Result<Self, DecodeError> {
+        let registers = FrameRegisters::new(frame)?;
+        let (precision, seed) = (registers.precision, registers.seed);
+        for register in registers {
+            register?;
+        }
+
+        Ok(Self {
+            frame,
+            precision,
+            seed,
+        })
+    }
+
+    /// Returns the precision of the sketch.
+    pub fn precision(&self) -> u8 {
+        self.precision
+    }
+
+    /// Returns the seed of the hash function.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the estimated number of distinct items inserted into the sketch.
+    pub fn estimate(&self) -> f64 {
+        let registers = FrameRegisters::new(self.frame).expect("the frame was checked");
+        estimate(
+            self.precision,
+            registers.filter_map(Result::ok).map(|(_, rank)| rank),
+        )
+    }
+
+    /// Copies the sketch into an owned [`HyperLogLog`].
+    pub fn to_owned(&self) -> HyperLogLog {
+        HyperLogLog::from_frame(self.frame).expect("the frame was checked")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::merge_all;
+    use crate::sketches::FRAME_VERSION;
+    use quickcheck_macros::quickcheck;
+
+    fn relative_error(estimate: f64, actual: f64) -> f64 {
+        (estimate - actual).abs() / actual
+    }
+
+    #[test]
+    fn empty_() {
+        let hll = HyperLogLog::new(10);
+        assert!(hll.is_empty());
+        assert_eq!(hll.estimate(), 0.);
+    }
+
+    #[test]
+    fn small_() {
+        let mut hll = HyperLogLog::new(10);
+        for i in 0..10 {
+            hll.insert(&i);
+        }
+        assert!(!hll.is_empty());
+        assert_eq!(hll.estimate().round(), 10.);
+    }
+
+    #[test]
+    fn large_() {
+        let mut hll = HyperLogLog::new(14);
+        for i in 0..100_000 {
+            hll.insert(&i);
+        }
+        assert!(relative_error(hll.estimate(), 100_000.) < 0.03);
+    }
+
+    #[test]
+    fn merge_() {
+        let mut xs = HyperLogLog::new(12);
+        let mut ys = HyperLogLog::new(12);
+        for i in 0..5_000 {
+            xs.insert(&i);
+            ys.insert(&(i + 2_500));
+        }
+
+        xs.merge_from(ys);
+        assert!(relative_error(xs.estimate(), 7_500.) < 0.05);
+    }
+
+    #[test]
+    fn merge_all_() {
+        let shards = (0..4).map(|s| {
+            let mut hll = HyperLogLog::new(12);
+            for i in 0..1_000 {
+                hll.insert(&(s * 1_000 + i));
+            }
+            hll
+        });
+
+        let merged = merge_all(shards).unwrap();
+        assert!(relative_error(merged.estimate(), 4_000.) < 0.05);
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_precision_() {
+        let mut xs = HyperLogLog::new(12);
+        xs.merge_from(HyperLogLog::new(10));
+    }
+
+    #[test]
+    fn seed_() {
+        let mut xs = HyperLogLog::new(10).with_seed(1);
+        let mut ys = HyperLogLog::new(10).with_seed(2);
+        (0..100).for_each(|i| {
+            xs.insert(&i);
+            ys.insert(&i);
+        });
+
+        assert_ne!(xs, ys);
+        assert!(!xs.is_compatible(&ys));
+        assert_eq!(xs.fingerprint().get("seed"), Some(1));
+        assert!((xs.estimate() - 100.).abs() < 5.);
+    }
+
+    #[test]
+    fn stable_() {
+        // the registers do not depend on the platform nor on the release
+        let mut hll = HyperLogLog::new(16);
+        hll.insert("a");
+        assert_eq!(hll.to_frame(), [b'H', b'L', 1, 16, 0, 1, 191, 50, 4]);
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_seed_() {
+        let mut xs = HyperLogLog::new(10);
+        xs.merge_from(HyperLogLog::new(10).with_seed(3));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_precision_() {
+        let _ = HyperLogLog::new(3);
+    }
+
+    #[test]
+    fn frame_() {
+        let mut hll = HyperLogLog::new(12);
+        (0..100).for_each(|i| hll.insert(&i));
+
+        let frame = hll.to_frame();
+        // the sparse encoding is much smaller than the 4096 registers
+        assert!(frame.len() < 300, "{}", frame.len());
+        assert_eq!(HyperLogLog::from_frame(&frame).unwrap(), hll);
+
+        let empty = HyperLogLog::new(4);
+        assert_eq!(empty.to_frame(), [b'H', b'L', 1, 4, 0, 0]);
+
+        let seeded = HyperLogLog::new(6).with_seed(300);
+        assert_eq!(HyperLogLog::from_frame(&seeded.to_frame()), Ok(seeded));
+        assert_eq!(HyperLogLog::from_frame(&empty.to_frame()).unwrap(), empty);
+    }
+
+    #[test]
+    fn frame_errors_() {
+        let mut hll = HyperLogLog::new(12);
+        hll.apply_delta(&HyperLogLog::new(12).to_frame()).unwrap();
+        assert_eq!(
+            hll.apply_delta(&HyperLogLog::new(10).to_frame()),
+            Err(DecodeError::Mismatch)
+        );
+        assert_eq!(
+            hll.apply_delta(&HyperLogLog::new(12).with_seed(1).to_frame()),
+            Err(DecodeError::Mismatch)
+        );
+
+        assert_eq!(
+            HyperLogLog::from_frame(b"HL\x01\x11\x00\x00"),
+            Err(DecodeError::Overflow)
+        );
+
+        // an index out of range and a rank out of range
+        for invalid in [
+            &b"HL\x01\x04\x00\x01\x10\x01"[..],
+            b"HL\x01\x04\x00\x01\x00\x3e",
+        ] {
+            assert_eq!(HyperLogLog::from_frame(invalid), Err(DecodeError::Invalid));
+        }
+        assert_eq!(
+            HyperLogLog::from_frame(b"HL\x01\x04\x00\x01\x00"),
+            Err(DecodeError::Truncated)
+        );
+    }
+
+    #[test]
+    fn view_() {
+        let mut hll = HyperLogLog::new(8);
+        (0..2000).for_each(|i| hll.insert(&i));
+
+        let frame = hll.to_frame();
+        let view = HllView::new(&frame).unwrap();
+        assert_eq!(view.estimate(), hll.estimate());
+        assert_eq!(view.to_owned(), hll);
+
+        let empty = HyperLogLog::new(4).to_frame();
+        assert_eq!(HllView::new(&empty).unwrap().estimate(), 0.0);
+
+        // trailing bytes and an out of range index are rejected up front
+        let trailing = [&frame[..], &[0]].concat();
+        assert_eq!(HllView::new(&trailing).err(), Some(DecodeError::Invalid));
+        assert_eq!(
+            HllView::new(b"HL\x01\x04\x00\x01\x10\x01").err(),
+            Some(DecodeError::Invalid)
+        );
+    }
+
+    #[quickcheck]
+    fn frame_garbage_(precision: u8, content: Vec<u8>) -> bool {
+        // a valid header and precision, so the registers themselves are decoded
+        let precision = HLL_MIN_PRECISION + precision % (HLL_MAX_PRECISION - HLL_MIN_PRECISION + 1);
+        let frame = [&[b'H', b'L', FRAME_VERSION, precision][..], &content].concat();
+        match HyperLogLog::from_frame(&frame) {
+            Ok(hll) => {
+                hll.precision() == precision && HyperLogLog::from_frame(&hll.to_frame()) == Ok(hll)
+            }
+            Err(_) => true,
+        }
+    }
+}
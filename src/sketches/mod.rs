@@ -0,0 +1,21 @@
+//! Probabilistic data structures which summarize large streams in bounded memory.
+
+#[cfg(feature = "arbitrary")]
+mod arbitrary;
+mod bloom;
+mod count_min;
+mod distinct_map;
+mod entropy;
+mod frame;
+mod heavy_hitters;
+mod hyperloglog;
+mod quotient;
+
+pub use bloom::*;
+pub use count_min::*;
+pub use distinct_map::*;
+pub use entropy::*;
+pub use frame::*;
+pub use heavy_hitters::*;
+pub use hyperloglog::*;
+pub use quotient::*;
@@ -0,0 +1,758 @@
+use crate::bits::{BVec, Bit};
+use crate::collections::Mergeable;
+use crate::seeded::{assert_compatible, stable_hash, Fingerprint, Fingerprinted};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt::{Display, Formatter},
+    hash::Hash,
+};
+
+/// The largest count kept in the counter of a slot. The counts from this one up are kept aside.
+const COUNTER_MAX: u8 = u8::MAX;
+
+/// The error returned when a [`CountingQuotientFilter`] has no free slot for a new fingerprint.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum QuotientFilterError {
+    /// All the slots of the filter but one are used.
+    Full,
+}
+
+impl Display for QuotientFilterError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Full => write!(f, "the quotient filter is full"),
+        }
+    }
+}
+
+impl Error for QuotientFilterError {}
+
+/// A counting [quotient filter](https://en.wikipedia.org/wiki/Quotient_filter), which answers
+/// approximate membership and count queries, and keeps an optional value with each fingerprint.
+///
+/// The fingerprint of an item is the top `q + r` bits of its hash: the `q` high bits,
+/// the quotient, select a home slot out of `2^q`, and the `r` low bits, the remainder,
+/// are stored in the slot. Fingerprints with the same quotient are kept together, sorted,
+/// in the slots which follow the home slot, with three metadata bits per slot to find them.
+///
+/// Each slot packs its remainder, its metadata bits and an 8-bit counter, so the filter
+/// takes `r + 11` bits per slot, and a single structure serves both the membership and
+/// the count queries of a Bloom filter and a count-min sketch, with counts which can be
+/// decremented. The counts which do not fit in a counter, and the values, are kept aside
+/// by fingerprint, so they only cost memory for the fingerprints which have them.
+///
+/// Two items with the same fingerprint share their count and their value, which happens
+/// with a probability of about `len / 2^(q + r)`, so the counts are upper bounds.
+///
+/// # Examples
+///
+/// ```
+/// use aabel_rs::sketches::CountingQuotientFilter;
+///
+/// let mut filter = CountingQuotientFilter::<&str>::new(8, 16);
+/// filter.insert("apple").unwrap();
+/// filter.insert("apple").unwrap();
+/// filter.insert("pear").unwrap();
+/// filter.set_value("pear", "green").unwrap();
+///
+/// assert_eq!(filter.count("apple"), 2);
+/// assert_eq!(filter.value("pear"), Some(&"green"));
+/// assert!(!filter.contains("plum"));
+///
+/// assert!(filter.remove("apple"));
+/// assert_eq!(filter.count("apple"), 1);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CountingQuotientFilter<V = ()> {
+    quotient_bits: u8,
+    remainder_bits: u8,
+    seed: u64,
+    len: usize,
+    // the slots which are the home slot of at least one fingerprint
+    occupied: BVec,
+    // the slots which hold the same quotient as the previous slot
+    continuation: BVec,
+    // the slots which are not the home slot of their fingerprint
+    shifted: BVec,
+    // the remainders of the slots, packed in words
+    remainders: Vec<u64>,
+    counters: Vec<u8>,
+    // the counts of the fingerprints whose counter is at `COUNTER_MAX`
+    overflows: HashMap<u64, u64>,
+    values: HashMap<u64, V>,
+}
+
+/// A fingerprint taken out of the slots, with its quotient.
+struct Entry {
+    quotient: usize,
+    remainder: u64,
+    counter: u8,
+}
+
+impl<V> CountingQuotientFilter<V> {
+    /// Creates an empty filter with `2^quotient_bits` slots and fingerprints of
+    /// `quotient_bits + remainder_bits` bits.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `quotient_bits` is not in the `1..=30` range, if `remainder_bits` is zero,
+    /// or if the fingerprints are longer than 64 bits.
+    pub fn new(quotient_bits: u8, remainder_bits: u8) -> Self {
+        assert!(
+            (1..=30).contains(&quotient_bits),
+            "the quotient bits must be between 1 and 30"
+        );
+        assert!(
+            remainder_bits > 0 && u32::from(quotient_bits) + u32::from(remainder_bits) <= 64,
+            "the remainder bits must be positive and the fingerprints at most 64 bits"
+        );
+
+        let num_slots = 1 << quotient_bits;
+        Self {
+            quotient_bits,
+            remainder_bits,
+            seed: 0,
+            len: 0,
+            occupied: BVec::with_length(num_slots),
+            continuation: BVec::with_length(num_slots),
+            shifted: BVec::with_length(num_slots),
+            remainders: vec![0; (num_slots * remainder_bits as usize).div_ceil(64)],
+            counters: vec![0; num_slots],
+            overflows: HashMap::new(),
+            values: HashMap::new(),
+        }
+    }
+
+    /// Returns an empty filter with the same sizes whose hash function is salted with `seed`.
+    /// Filters with different seeds store different fingerprints for the same items,
+    /// so they cannot be merged.
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self {
+            seed,
+            ..Self::new(self.quotient_bits, self.remainder_bits)
+        }
+    }
+
+    /// Returns the number of bits of the quotients.
+    pub fn quotient_bits(&self) -> u8 {
+        self.quotient_bits
+    }
+
+    /// Returns the number of bits of the remainders.
+    pub fn remainder_bits(&self) -> u8 {
+        self.remainder_bits
+    }
+
+    /// Returns the seed of the hash function.
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// Returns the number of distinct fingerprints in the filter.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Returns true if the filter holds no fingerprint.
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Returns the largest number of distinct fingerprints, one less than the number of slots.
+    pub fn capacity(&self) -> usize {
+        self.counters.len() - 1
+    }
+
+    /// Returns the sum of the counts of all the fingerprints.
+    pub fn total(&self) -> u64 {
+        let small = self
+            .counters
+            .iter()
+            .filter(|counter| **counter < COUNTER_MAX)
+            .map(|counter| u64::from(*counter))
+            .sum();
+        self.overflows
+            .values()
+            .fold(small, |total, count| total.saturating_add(*count))
+    }
+
+    /// Returns the quotient and the remainder of the fingerprint of an item.
+    fn split<T: Hash + ?Sized>(&self, item: &T) -> (usize, u64) {
+        let bits = u32::from(self.quotient_bits) + u32::from(self.remainder_bits);
+        let fingerprint = stable_hash(item, self.seed) >> (64 - bits);
+        let remainder = fingerprint & ((1 << self.remainder_bits) - 1);
+        ((fingerprint >> self.remainder_bits) as usize, remainder)
+    }
+
+    /// Returns the key of a fingerprint in the counts and the values kept aside.
+    fn key(&self, quotient: usize, remainder: u64) -> u64 {
+        ((quotient as u64) << self.remainder_bits) | remainder
+    }
+
+    fn next(&self, i: usize) -> usize {
+        (i + 1) & (self.counters.len() - 1)
+    }
+
+    fn prev(&self, i: usize) -> usize {
+        i.wrapping_sub(1) & (self.counters.len() - 1)
+    }
+
+    fn is_free(&self, i: usize) -> bool {
+        self.occupied.get_bit(i) == Bit::Zero
+            && self.continuation.get_bit(i) == Bit::Zero
+            && self.shifted.get_bit(i) == Bit::Zero
+    }
+
+    fn remainder(&self, i: usize) -> u64 {
+        let bits = self.remainder_bits as usize;
+        let (word, offset) = (i * bits / 64, i * bits % 64);
+
+        let mut remainder = self.remainders[word] >> offset;
+        if offset + bits > 64 {
+            remainder |= self.remainders[word + 1] << (64 - offset);
+        }
+        remainder & ((1 << bits) - 1)
+    }
+
+    fn set_remainder(&mut self, i: usize, remainder: u64) {
+        let bits = self.remainder_bits as usize;
+        let (word, offset) = (i * bits / 64, i * bits % 64);
+        let mask = (1 << bits) - 1;
+
+        self.remainders[word] &= !(mask << offset);
+        self.remainders[word] |= remainder << offset;
+        if offset + bits > 64 {
+            let high = mask >> (64 - offset);
+            self.remainders[word + 1] &= !high;
+            self.remainders[word + 1] |= remainder >> (64 - offset);
+        }
+    }
+
+    /// Returns the count of the fingerprint stored in the slot `i`.
+    fn count_at(&self, i: usize, key: u64) -> u64 {
+        match self.counters[i] {
+            COUNTER_MAX => self.overflows[&key],
+            counter => u64::from(counter),
+        }
+    }
+
+    fn set_count_at(&mut self, i: usize, key: u64, count: u64) {
+        match u8::try_from(count) {
+            Ok(counter) if counter < COUNTER_MAX => {
+                self.counters[i] = counter;
+                self.overflows.remove(&key);
+            }
+            _ => {
+                self.counters[i] = COUNTER_MAX;
+                self.overflows.insert(key, count);
+            }
+        }
+    }
+
+    /// Returns the first slot of the cluster which holds the slot `i`.
+    fn cluster_start(&self, mut i: usize) -> usize {
+        while self.shifted.get_bit(i) == Bit::One {
+            i = self.prev(i);
+        }
+        i
+    }
+
+    /// Returns the slots from `start` up to the next free slot, or up to the next cluster
+    /// when `cluster` is true, with the quotients of their fingerprints.
+    fn decode(&self, start: usize, cluster: bool) -> Vec<(usize, usize)> {
+        let mut decoded = Vec::new();
+        let (mut i, mut quotient) = (start, start);
+
+        while !self.is_free(i) {
+            if i != start {
+                if cluster && self.shifted.get_bit(i) == Bit::Zero {
+                    break;
+                }
+                if self.continuation.get_bit(i) == Bit::Zero {
+                    // the run of the next quotient whose home slot is occupied
+                    quotient = self.next(quotient);
+                    while self.occupied.get_bit(quotient) == Bit::Zero {
+                        quotient = self.next(quotient);
+                    }
+                }
+            }
+
+            decoded.push((i, quotient));
+            i = self.next(i);
+        }
+
+        decoded
+    }
+
+    /// Returns the slot of a fingerprint, if it is stored.
+    fn find(&self, quotient: usize, remainder: u64) -> Option<usize> {
+        if self.occupied.get_bit(quotient) == Bit::Zero {
+            return None;
+        }
+
+        // skips the runs of the cluster up to the run of the quotient
+        let mut home = self.cluster_start(quotient);
+        let mut i = home;
+        while home != quotient {
+            i = self.next(i);
+            while self.continuation.get_bit(i) == Bit::One {
+                i = self.next(i);
+            }
+            home = self.next(home);
+            while self.occupied.get_bit(home) == Bit::Zero {
+                home = self.next(home);
+            }
+        }
+
+        // the remainders of a run are sorted
+        loop {
+            match self.remainder(i).cmp(&remainder) {
+                std::cmp::Ordering::Equal => return Some(i),
+                std::cmp::Ordering::Greater => return None,
+                std::cmp::Ordering::Less => {}
+            }
+            i = self.next(i);
+            if self.continuation.get_bit(i) == Bit::Zero {
+                return None;
+            }
+        }
+    }
+
+    /// Takes the fingerprints out of the slots from `start` up to the next free slot.
+    fn take(&mut self, start: usize) -> Vec<Entry> {
+        let decoded = self.decode(start, false);
+
+        let entries: Vec<Entry> = decoded
+            .iter()
+            .map(|(i, quotient)| Entry {
+                quotient: *quotient,
+                remainder: self.remainder(*i),
+                counter: self.counters[*i],
+            })
+            .collect();
+        for (i, quotient) in decoded {
+            self.occupied.reset_bit(quotient);
+            self.continuation.reset_bit(i);
+            self.shifted.reset_bit(i);
+            self.set_remainder(i, 0);
+            self.counters[i] = 0;
+        }
+
+        entries
+    }
+
+    /// Lays out fingerprints sorted by quotient, from `start`, each run in its home slot or
+    /// right after the previous run.
+    fn put(&mut self, start: usize, entries: Vec<Entry>) {
+        let num_slots = self.counters.len();
+        let mut offset = 0;
+        let mut previous = None;
+
+        for entry in entries {
+            let home = (entry.quotient + num_slots - start) & (num_slots - 1);
+            let continuation = previous == Some(entry.quotient);
+            let at = if continuation {
+                offset
+            } else {
+                offset.max(home)
+            };
+
+            let i = (start + at) & (num_slots - 1);
+            self.set_remainder(i, entry.remainder);
+            self.counters[i] = entry.counter;
+            if continuation {
+                self.continuation.set_bit(i);
+            }
+            if at != home {
+                self.shifted.set_bit(i);
+            }
+            self.occupied.set_bit(entry.quotient);
+            offset = at + 1;
+            previous = Some(entry.quotient);
+        }
+    }
+
+    /// Adds a fingerprint with a count, or adds the count to the stored fingerprint,
+    /// and returns its slot.
+    fn upsert(
+        &mut self,
+        quotient: usize,
+        remainder: u64,
+        count: u64,
+    ) -> Result<usize, QuotientFilterError> {
+        let key = self.key(quotient, remainder);
+        if let Some(i) = self.find(quotient, remainder) {
+            let count = self.count_at(i, key).saturating_add(count);
+            self.set_count_at(i, key, count);
+            return Ok(i);
+        }
+        if self.len == self.capacity() {
+            return Err(QuotientFilterError::Full);
+        }
+
+        let start = self.cluster_start(quotient);
+        let mut entries = self.take(start);
+
+        let num_slots = self.counters.len();
+        let order = |q: usize, r: u64| ((q + num_slots - start) & (num_slots - 1), r);
+        let at = entries
+            .partition_point(|e| order(e.quotient, e.remainder) < order(quotient, remainder));
+        entries.insert(
+            at,
+            Entry {
+                quotient,
+                remainder,
+                counter: 0,
+            },
+        );
+
+        self.put(start, entries);
+        self.len += 1;
+
+        let i = self
+            .find(quotient, remainder)
+            .expect("the fingerprint was inserted");
+        self.set_count_at(i, key, count);
+        Ok(i)
+    }
+
+    /// Removes a stored fingerprint and returns its count and its value.
+    fn delete(&mut self, quotient: usize, remainder: u64) -> Option<(u64, Option<V>)> {
+        let key = self.key(quotient, remainder);
+        let count = self.count_at(self.find(quotient, remainder)?, key);
+
+        let start = self.cluster_start(quotient);
+        let mut entries = self.take(start);
+        entries.retain(|e| e.quotient != quotient || e.remainder != remainder);
+
+        self.put(start, entries);
+        self.len -= 1;
+        self.overflows.remove(&key);
+        Some((count, self.values.remove(&key)))
+    }
+
+    /// Returns the fingerprints of the filter, as `(quotient, remainder, slot)` triples.
+    fn fingerprints(&self) -> Vec<(usize, u64, usize)> {
+        (0..self.counters.len())
+            .filter(|i| !self.is_free(*i) && self.shifted.get_bit(*i) == Bit::Zero)
+            .flat_map(|start| self.decode(start, true))
+            .map(|(i, quotient)| (quotient, self.remainder(i), i))
+            .collect()
+    }
+
+    /// Inserts an item once and returns its new count.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`QuotientFilterError::Full`] if the item is new and the filter is full.
+    pub fn insert<T: Hash + ?Sized>(&mut self, item: &T) -> Result<u64, QuotientFilterError> {
+        self.insert_count(item, 1)
+    }
+
+    /// Inserts an item `count` times and returns its new count.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`QuotientFilterError::Full`] if the item is new and the filter is full.
+    pub fn insert_count<T: Hash + ?Sized>(
+        &mut self,
+        item: &T,
+        count: u64,
+    ) -> Result<u64, QuotientFilterError> {
+        let (quotient, remainder) = self.split(item);
+        let i = self.upsert(quotient, remainder, count)?;
+        Ok(self.count_at(i, self.key(quotient, remainder)))
+    }
+
+    /// Returns the count of an item, which is zero for an item which was never inserted,
+    /// and may be larger than the true count when another item has the same fingerprint.
+    pub fn count<T: Hash + ?Sized>(&self, item: &T) -> u64 {
+        let (quotient, remainder) = self.split(item);
+        self.find(quotient, remainder)
+            .map_or(0, |i| self.count_at(i, self.key(quotient, remainder)))
+    }
+
+    /// Returns true if the item may have been inserted, and false if it was certainly not.
+    pub fn contains<T: Hash + ?Sized>(&self, item: &T) -> bool {
+        let (quotient, remainder) = self.split(item);
+        self.find(quotient, remainder).is_some()
+    }
+
+    /// Removes one occurrence of an item and returns true, or returns false if the item is
+    /// missing. The fingerprint, with its value, is removed with its last occurrence.
+    pub fn remove<T: Hash + ?Sized>(&mut self, item: &T) -> bool {
+        let (quotient, remainder) = self.split(item);
+        let Some(i) = self.find(quotient, remainder) else {
+            return false;
+        };
+
+        let key = self.key(quotient, remainder);
+        match self.count_at(i, key) {
+            count @ 2.. => self.set_count_at(i, key, count - 1),
+            _ => {
+                self.delete(quotient, remainder);
+            }
+        }
+        true
+    }
+
+    /// Removes all the occurrences of an item, with its value, and returns its count.
+    pub fn remove_all<T: Hash + ?Sized>(&mut self, item: &T) -> u64 {
+        let (quotient, remainder) = self.split(item);
+        self.delete(quotient, remainder)
+            .map_or(0, |(count, _)| count)
+    }
+
+    /// Returns the value kept with an item, if it has one.
+    pub fn value<T: Hash + ?Sized>(&self, item: &T) -> Option<&V> {
+        let (quotient, remainder) = self.split(item);
+        self.values.get(&self.key(quotient, remainder))
+    }
+
+    /// Keeps a value with an item, inserting the item with a zero count if it is missing,
+    /// and returns the previous value.
+    ///
+    /// # Errors
+    ///
+    /// Fails with [`QuotientFilterError::Full`] if the item is new and the filter is full.
+    pub fn set_value<T: Hash + ?Sized>(
+        &mut self,
+        item: &T,
+        value: V,
+    ) -> Result<Option<V>, QuotientFilterError> {
+        let (quotient, remainder) = self.split(item);
+        self.upsert(quotient, remainder, 0)?;
+        Ok(self.values.insert(self.key(quotient, remainder), value))
+    }
+}
+
+impl<V> Fingerprinted for CountingQuotientFilter<V> {
+    /// Describes the sizes of the quotients and of the remainders, and the seed,
+    /// which determine the fingerprints.
+    fn fingerprint(&self) -> Fingerprint {
+        Fingerprint::new("CountingQuotientFilter")
+            .with("quotient_bits", self.quotient_bits as u64)
+            .with("remainder_bits", self.remainder_bits as u64)
+            .with("seed", self.seed)
+    }
+}
+
+impl<V> Mergeable for CountingQuotientFilter<V> {
+    /// Merges another filter into this one, adding the counts of the common fingerprints.
+    /// A fingerprint keeps its value in this filter, or takes the one of the other filter.
+    ///
+    /// # Panics
+    ///
+    /// Panics if the two filters have different sizes or seeds,
+    /// or if their distinct fingerprints do not fit in this filter.
+    fn merge_from(&mut self, mut other: Self) {
+        assert_compatible(self, &other);
+
+        for (quotient, remainder, i) in other.fingerprints() {
+            let key = other.key(quotient, remainder);
+            self.upsert(quotient, remainder, other.count_at(i, key))
+                .expect("the merged filter is full");
+
+            if let Some(value) = other.values.remove(&key) {
+                self.values.entry(key).or_insert(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::collections::merge_all;
+    use quickcheck_macros::quickcheck;
+
+    #[test]
+    fn empty_() {
+        let filter = CountingQuotientFilter::<()>::new(4, 8);
+        assert!(filter.is_empty());
+        assert_eq!(filter.capacity(), 15);
+        assert_eq!(filter.count("a"), 0);
+        assert!(!filter.contains("a"));
+        assert_eq!(filter.total(), 0);
+    }
+
+    #[test]
+    fn insert_remove_() {
+        let mut filter = CountingQuotientFilter::<()>::new(6, 10);
+        for i in 0..40u32 {
+            for _ in 0..=i % 3 {
+                filter.insert(&i).unwrap();
+            }
+        }
+
+        assert_eq!(filter.len(), 40);
+        assert_eq!(filter.total(), (0..40).map(|i| i % 3 + 1).sum::<u64>());
+        assert!((0..40u32).all(|i| filter.count(&i) == (i % 3 + 1) as u64));
+
+        assert!(filter.remove(&5));
+        assert_eq!(filter.count(&5), 2);
+        assert_eq!(filter.remove_all(&5), 2);
+        assert!(!filter.contains(&5));
+        assert!(!filter.remove(&5));
+        assert_eq!(filter.len(), 39);
+        assert!((0..40u32)
+            .filter(|i| *i != 5)
+            .all(|i| filter.count(&i) == (i % 3 + 1) as u64));
+    }
+
+    #[test]
+    fn large_counts_() {
+        let mut filter = CountingQuotientFilter::<()>::new(4, 20);
+        assert_eq!(filter.insert_count("a", 254), Ok(254));
+        assert_eq!(filter.insert("a"), Ok(255));
+        assert_eq!(filter.insert_count("b", u64::MAX), Ok(u64::MAX));
+        assert_eq!(filter.insert("b"), Ok(u64::MAX));
+        assert_eq!(filter.total(), u64::MAX);
+
+        assert!(filter.remove("a"));
+        assert_eq!(filter.count("a"), 254);
+        assert_eq!(filter.remove_all("b"), u64::MAX);
+        assert_eq!(filter.total(), 254);
+        assert!(filter.overflows.is_empty());
+    }
+
+    #[test]
+    fn remainders_() {
+        // remainders which straddle the words
+        let mut filter = CountingQuotientFilter::<()>::new(3, 61);
+        for i in 0..filter.counters.len() {
+            filter.set_remainder(i, (1 << 61) - 1 - i as u64);
+        }
+        filter.set_remainder(3, 5);
+        assert!((0..filter.counters.len())
+            .filter(|i| *i != 3)
+            .all(|i| filter.remainder(i) == (1 << 61) - 1 - i as u64));
+        assert_eq!(filter.remainder(3), 5);
+    }
+
+    #[test]
+    fn full_() {
+        let mut filter = CountingQuotientFilter::<()>::new(3, 16);
+        let mut items = 0u32..;
+        while filter.len() < filter.capacity() {
+            filter.insert(&items.next().unwrap()).unwrap();
+        }
+
+        let new = (0u32..).find(|i| !filter.contains(i)).unwrap();
+        assert_eq!(filter.insert(&new), Err(QuotientFilterError::Full));
+        assert_eq!(filter.set_value(&new, ()), Err(QuotientFilterError::Full));
+
+        // a stored item can still be counted
+        let stored = (0u32..).find(|i| filter.contains(i)).unwrap();
+        let count = filter.count(&stored);
+        assert_eq!(filter.insert(&stored), Ok(count + 1));
+    }
+
+    #[test]
+    fn values_() {
+        let mut filter = CountingQuotientFilter::<String>::new(5, 12);
+        assert_eq!(filter.set_value("a", "first".to_string()), Ok(None));
+        assert_eq!(filter.count("a"), 0);
+        assert!(filter.contains("a"));
+
+        filter.insert("a").unwrap();
+        assert_eq!(
+            filter.set_value("a", "second".to_string()),
+            Ok(Some("first".to_string()))
+        );
+        assert_eq!(filter.value("a").map(String::as_str), Some("second"));
+
+        // the value goes with the last occurrence
+        assert!(filter.remove("a"));
+        assert_eq!(filter.value("a"), None);
+        assert!(!filter.contains("a"));
+    }
+
+    #[test]
+    fn merge_() {
+        let shards = (0..3u32).map(|s| {
+            let mut filter = CountingQuotientFilter::<u32>::new(8, 12);
+            for i in 0..50u32 {
+                filter.insert(&(s * 25 + i)).unwrap();
+            }
+            filter.set_value(&(s * 25), s).unwrap();
+            filter
+        });
+
+        let merged = merge_all(shards).unwrap();
+        assert_eq!(merged.len(), 100);
+        assert_eq!(merged.total(), 150);
+        assert_eq!(merged.count(&30), 2);
+        // a missing value is taken from the other filter
+        assert_eq!(merged.value(&0), Some(&0));
+        assert_eq!(merged.value(&25), Some(&1));
+        assert_eq!(merged.value(&50), Some(&2));
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_sizes_() {
+        let mut xs = CountingQuotientFilter::<()>::new(4, 8);
+        xs.merge_from(CountingQuotientFilter::new(4, 9));
+    }
+
+    #[test]
+    #[should_panic]
+    fn merge_seed_() {
+        let mut xs = CountingQuotientFilter::<()>::new(4, 8);
+        xs.merge_from(CountingQuotientFilter::new(4, 8).with_seed(1));
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_bits_() {
+        CountingQuotientFilter::<()>::new(40, 30);
+    }
+
+    #[test]
+    #[should_panic]
+    fn new_long_fingerprints_() {
+        // the sum of the sizes does not fit in a byte
+        CountingQuotientFilter::<()>::new(30, 250);
+    }
+
+    #[quickcheck]
+    fn model_(ops: Vec<(bool, u8)>) -> bool {
+        // a small filter, so the runs wrap around and the clusters merge and split
+        let mut filter = CountingQuotientFilter::<()>::new(4, 6);
+        let mut model: HashMap<(usize, u64), u64> = HashMap::new();
+
+        for (insert, item) in ops {
+            let fingerprint = filter.split(&item);
+            if insert {
+                match filter.insert(&item) {
+                    Ok(count) => {
+                        let expected = model.entry(fingerprint).or_default();
+                        *expected += 1;
+                        if count != *expected {
+                            return false;
+                        }
+                    }
+                    Err(QuotientFilterError::Full) => {
+                        if model.len() != filter.capacity() || model.contains_key(&fingerprint) {
+                            return false;
+                        }
+                    }
+                }
+            } else if filter.remove(&item) != model.contains_key(&fingerprint) {
+                return false;
+            } else if let Some(count) = model.get_mut(&fingerprint) {
+                *count -= 1;
+                if *count == 0 {
+                    model.remove(&fingerprint);
+                }
+            }
+        }
+
+        filter.len() == model.len()
+            && (0..=255u8).all(|item| {
+                filter.count(&item) == model.get(&filter.split(&item)).copied().unwrap_or(0)
+            })
+    }
+}